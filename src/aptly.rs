@@ -12,58 +12,255 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use crate::archive::{self, PackageSource};
-use crate::deb::DistributionAlias;
+use crate::cli;
+use crate::common::{Project, SnapshotSource};
+use crate::deb::{DebianFamily, DistributionAlias};
+use crate::debedit;
 use crate::errors::BellhopError;
-use crate::{cli, common::Project};
+use crate::report;
+use crate::version::compare_versions;
+use chrono::NaiveDate;
 use clap::ArgMatches;
-use log::{debug, info};
-use std::collections::HashSet;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::iter;
+use std::mem;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
-use std::sync::OnceLock;
+use std::process::{Command, Output, Stdio};
+use std::slice;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
 
-const ALL_ARCHITECTURES_ARG: &str = "-architectures=amd64,arm64,armel,armhf,i386";
-const AMD64_ONLY_ARG: &str = "-architectures=amd64";
-const GPG_KEY_ID_ARG: &str = "-gpg-key=0A9AF2115F4687BD29803A206B73A36E6026DFCA";
+const ALL_ARCHITECTURES: &[&str] = &["amd64", "arm64", "armel", "armhf", "i386"];
+const AMD64_ONLY: &[&str] = &["amd64"];
+const DEFAULT_GPG_KEY_ID: &str = "0A9AF2115F4687BD29803A206B73A36E6026DFCA";
+
+const DEFAULT_APTLY_BIN: &str = "aptly";
 
 const TEMP_SNAPSHOT_SUFFIX: &str = "-bellhop-tmp";
+const REPO_SNAPSHOT_SUFFIX: &str = "-bellhop-repo";
 const IDENTICAL_SNAPSHOTS_MARKER: &str = "Snapshots are identical.";
+const DATABASE_LOCK_MARKER: &str = "unable to acquire database lock";
+pub const DEFAULT_LOCK_RETRIES: u32 = 3;
+const LOCK_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+pub fn gpg_key_id() -> String {
+    env::var("BELLHOP_GPG_KEY").unwrap_or_else(|_| DEFAULT_GPG_KEY_ID.to_string())
+}
 
 fn gpg_key_arg() -> String {
-    if let Ok(key_id) = env::var("BELLHOP_GPG_KEY") {
-        format!("-gpg-key={key_id}")
-    } else {
-        GPG_KEY_ID_ARG.to_string()
+    format!("-gpg-key={}", gpg_key_id())
+}
+
+/// Whether `gpg` has the secret key, an error means that `gpg` could not be run.
+/// With a keyring, only that keyring is searched for the key.
+pub fn has_gpg_secret_key(key_id: &str, keyring: Option<&Path>) -> io::Result<bool> {
+    let mut cmd = Command::new("gpg");
+    if let Some(keyring) = keyring {
+        cmd.arg("--no-default-keyring")
+            .arg("--keyring")
+            .arg(keyring);
+    }
+    let output = cmd.args(["--list-secret-keys", key_id]).output()?;
+    Ok(output.status.success())
+}
+
+/// aptly only finds out that the signing key is missing once it gets to signing, after it has
+/// done most of the work of a publish
+fn verify_gpg_key(keyring: Option<&Path>) -> Result<(), BellhopError> {
+    let key_id = gpg_key_id();
+    match has_gpg_secret_key(&key_id, keyring) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(BellhopError::GpgKeyNotFound { key_id }),
+        Err(e) => {
+            warn!("Could not run gpg to look up {key_id}: {e}");
+            Err(BellhopError::GpgKeyNotFound { key_id })
+        }
     }
 }
 
-static APTLY_AVAILABLE: OnceLock<bool> = OnceLock::new();
+/// Keyed by the `aptly` binary, `APTLY_BIN` can point at a different one later in the process
+static APTLY_AVAILABLE: Mutex<BTreeMap<String, bool>> = Mutex::new(BTreeMap::new());
+static REPO_PREFIX: OnceLock<String> = OnceLock::new();
+static CHANNEL: OnceLock<String> = OnceLock::new();
+static APTLY_CONFIG: OnceLock<PathBuf> = OnceLock::new();
+static LOCK_RETRIES: OnceLock<u32> = OnceLock::new();
+static RETRY_EXIT_CODES: OnceLock<Vec<i32>> = OnceLock::new();
+static STREAM_OUTPUT: OnceLock<bool> = OnceLock::new();
+
+/// Build agents sometimes have `aptly` installed outside of `PATH`
+pub fn aptly_bin() -> String {
+    env::var("APTLY_BIN")
+        .ok()
+        .filter(|bin| !bin.is_empty())
+        .unwrap_or_else(|| DEFAULT_APTLY_BIN.to_string())
+}
 
 pub fn check_aptly_available() -> Result<(), BellhopError> {
-    let available = APTLY_AVAILABLE.get_or_init(|| {
-        Command::new("aptly")
-            .arg("version")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-    });
-
-    if *available {
+    if is_aptly_available(&aptly_bin()) {
         Ok(())
     } else {
         Err(BellhopError::AptlyNotFound)
     }
 }
 
+/// Takes precedence over the `APTLY_CONFIG` environment variable. Only the first call in a process
+/// takes effect.
+pub fn set_aptly_config(path: &Path) -> Result<(), BellhopError> {
+    if !path.is_file() {
+        return Err(BellhopError::AptlyConfigNotFound {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let _ = APTLY_CONFIG.set(path.to_path_buf());
+    Ok(())
+}
+
+pub fn aptly_config() -> Option<PathBuf> {
+    APTLY_CONFIG
+        .get()
+        .cloned()
+        .or_else(|| env::var_os("APTLY_CONFIG").map(PathBuf::from))
+}
+
+/// Runs `aptly version` once per binary, later calls for the same binary use the cached result
+pub fn is_aptly_available(bin: &str) -> bool {
+    let mut cache = APTLY_AVAILABLE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    *cache.entry(bin.to_string()).or_insert_with(|| {
+        Command::new(bin)
+            .arg("version")
+            .output()
+            .is_ok_and(|o| o.status.success())
+    })
+}
+
 fn aptly_command() -> Command {
-    let mut cmd = Command::new("aptly");
-    if let Ok(config_path) = env::var("APTLY_CONFIG") {
-        cmd.arg(format!("-config={config_path}"));
+    let mut cmd = Command::new(aptly_bin());
+    if let Some(config_path) = aptly_config() {
+        cmd.arg(format!("-config={}", config_path.display()));
     }
     cmd
 }
 
+/// How many times a command that failed because another `aptly` process holds the database lock
+/// is retried. Only the first call in a process takes effect.
+pub fn set_lock_retries(retries: u32) {
+    let _ = LOCK_RETRIES.set(retries);
+}
+
+pub fn is_database_lock_failure(stderr: &str) -> bool {
+    stderr.to_lowercase().contains(DATABASE_LOCK_MARKER)
+}
+
+/// Exit codes of `aptly` that indicate a transient failure, commands that exit with them are
+/// retried like database lock failures. Only the first call in a process takes effect.
+pub fn set_retry_exit_codes(codes: Vec<i32>) {
+    let _ = RETRY_EXIT_CODES.set(codes);
+}
+
+/// Why a failed command is worth retrying, `None` when it is not
+fn transient_failure(output: &Output) -> Option<String> {
+    if is_database_lock_failure(&String::from_utf8_lossy(&output.stderr)) {
+        return Some("aptly database is locked by another process".to_string());
+    }
+    let code = output.status.code()?;
+    RETRY_EXIT_CODES
+        .get()
+        .is_some_and(|codes| codes.contains(&code))
+        .then(|| format!("aptly exited with code {code}"))
+}
+
+/// Whether the output of `aptly` is forwarded to stderr while it runs. Only the first call in a
+/// process takes effect.
+pub fn set_stream_output(stream: bool) {
+    let _ = STREAM_OUTPUT.set(stream);
+}
+
+/// Runs an `aptly` command, retrying with exponential backoff while the database is locked by
+/// another `aptly` process. Other failures are returned as is.
+fn run_aptly(cmd: &mut Command) -> io::Result<Output> {
+    report::record_action(command_line(cmd));
+    let retries = LOCK_RETRIES.get().copied().unwrap_or(DEFAULT_LOCK_RETRIES);
+    let mut attempt = 0;
+    let stream = STREAM_OUTPUT.get().copied().unwrap_or(false);
+    loop {
+        let output = if stream {
+            output_streaming(cmd)?
+        } else {
+            cmd.output()?
+        };
+        if output.status.success() || attempt >= retries {
+            return Ok(output);
+        }
+        let Some(reason) = transient_failure(&output) else {
+            return Ok(output);
+        };
+
+        let delay = LOCK_RETRY_BASE_DELAY * 2u32.pow(attempt);
+        attempt += 1;
+        warn!(
+            "{reason}, retrying in {}ms ({attempt}/{retries})",
+            delay.as_millis()
+        );
+        thread::sleep(delay);
+    }
+}
+
+/// The command as the run report shows it, without the configuration file argument
+fn command_line(cmd: &Command) -> String {
+    let args = cmd
+        .get_args()
+        .map(|arg| arg.to_string_lossy())
+        .filter(|arg| !arg.starts_with("-config="));
+    iter::once(Cow::Borrowed("aptly"))
+        .chain(args)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Like [`Command::output`] but every line is also forwarded to stderr as soon as it is printed.
+/// stdout goes to stderr as well, so that bellhop's own output stays machine-readable.
+fn output_streaming(cmd: &mut Command) -> io::Result<Output> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+
+    let stderr_forwarder = thread::spawn(move || forward_lines(stderr));
+    let stdout = forward_lines(stdout)?;
+    let stderr = stderr_forwarder
+        .join()
+        .map_err(|_| io::Error::other("aptly stderr forwarding thread panicked"))??;
+    let status = child.wait()?;
+
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+fn forward_lines(reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut reader = BufReader::new(reader);
+    let mut captured = Vec::new();
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        io::stderr().write_all(&line)?;
+        captured.extend_from_slice(&line);
+        line.clear();
+    }
+    Ok(captured)
+}
+
 fn check_aptly_output(output: Output, command: impl Into<String>) -> Result<Output, BellhopError> {
     if output.status.success() {
         Ok(output)
@@ -77,55 +274,497 @@ fn check_aptly_output(output: Output, command: impl Into<String>) -> Result<Outp
     }
 }
 
-pub fn add_package(
+/// What an add did, recorded in the run report of `deb add --json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AddSummary {
+    pub packages: Vec<AddedPackage>,
+    /// Distributions none of the steps failed for
+    pub distributions: Vec<String>,
+    /// Snapshots that were created or retaken, none with `--no-snapshot` or `--repo`
+    pub snapshots: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AddedPackage {
+    pub file: PathBuf,
+    /// Parsed from the file name, absent when it does not follow the `name_version_arch.deb` convention
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Repositories the package was added to, those that were skipped for already having it are left out
+    pub repos: Vec<String>,
+}
+
+impl AddedPackage {
+    fn new(deb_path: &Path, identity: Option<&PackageIdentity>) -> Self {
+        let (name, version) = match (
+            identity,
+            archive::extract_name_and_version_from_deb(deb_path),
+        ) {
+            (Some(identity), _) => (Some(identity.name.clone()), Some(identity.version.clone())),
+            (None, Ok((name, version))) => (Some(name), Some(version)),
+            (None, Err(_)) => (None, None),
+        };
+        Self {
+            file: deb_path.to_path_buf(),
+            name,
+            version,
+            repos: Vec::new(),
+        }
+    }
+}
+
+/// The name and version of a package given with `--package-name` and `--version`, for a file
+/// whose name does not follow the `name_version_arch.deb` convention
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageIdentity {
+    pub name: String,
+    pub version: String,
+}
+
+impl PackageIdentity {
+    /// Only a single package can be added under a given identity
+    fn for_packages(
+        cli_args: &ArgMatches,
+        deb_paths: &[&Path],
+    ) -> Result<Option<PackageIdentity>, BellhopError> {
+        let identity = cli::package_identity(cli_args);
+        if identity.is_some() && deb_paths.len() != 1 {
+            return Err(BellhopError::PackageIdentityNeedsSinglePackage {
+                count: deb_paths.len(),
+            });
+        }
+        Ok(identity)
+    }
+
+    fn name_and_version(&self) -> (String, String) {
+        (self.name.clone(), self.version.clone())
+    }
+}
+
+/// Adds the packages of every given file, then takes the snapshots once for all of them
+pub fn add_packages(
     cli_args: &ArgMatches,
-    package_file_path: &str,
+    package_file_paths: &[String],
     project: Project,
     target_releases: &[DistributionAlias],
+) -> Result<AddSummary, BellhopError> {
+    let package_sources = process_package_files(cli_args, package_file_paths, &project)?;
+    validate_packages(cli_args, &package_sources)?;
+    let mapped = map_architectures(cli_args, &package_sources)?;
+    let deb_paths: Vec<&Path> = mapped.paths.iter().map(PathBuf::as_path).collect();
+    let identity = PackageIdentity::for_packages(cli_args, &deb_paths)?;
+
+    let suffix = cli::suffix(cli_args);
+    let source = cli::snapshot_source(cli_args);
+    let newer_only = cli_args.get_flag("newer_only");
+    let if_not_present = cli_args.get_flag("if_not_present");
+    let publish_options = cli_args
+        .get_flag("publish")
+        .then(|| cli::publish_options(cli_args, project));
+    if let Some(options) = &publish_options {
+        // checked before anything is added rather than once the snapshots are taken
+        options.validate()?;
+    }
+
+    if newer_only {
+        info!(
+            "Adding {} packages unless the repositories have a newer version",
+            deb_paths.len()
+        );
+    } else {
+        info!("Adding {} packages", deb_paths.len());
+    }
+    let mut summary = AddSummary::default();
+    let mut distributions = PerDistribution::new(target_releases, cli::keep_going(cli_args));
+    for deb_path in deb_paths {
+        debug!("Processing: {}", deb_path.display());
+        let mut added = AddedPackage::new(deb_path, identity.as_ref());
+        distributions.run(|rel| {
+            let was_added = if newer_only {
+                add_newer_package_no_snapshot(&project, deb_path, identity.as_ref(), rel)?
+            } else if if_not_present {
+                add_missing_package_no_snapshot(&project, deb_path, identity.as_ref(), rel)?
+            } else {
+                add_single_package_no_snapshot(&project, deb_path, slice::from_ref(rel))?;
+                true
+            };
+            if was_added {
+                added.repos.push(repo_name(&project, rel));
+            }
+            Ok(())
+        })?;
+        summary.packages.push(added);
+    }
+
+    if cli::no_snapshot(cli_args) {
+        info!("Not taking snapshots, use 'snapshot take' once all packages are added");
+    } else {
+        update_snapshots(&project, &mut distributions, &suffix, source)?;
+        summary.snapshots = distributions
+            .remaining()
+            .iter()
+            .map(|rel| snapshot_name_with_suffix(&project, rel, &suffix))
+            .collect();
+        if let Some(options) = &publish_options {
+            let published_repos = publish_preflight(options)?;
+            distributions.run(|rel| {
+                run_snapshot_switch(&project, rel, &suffix, &published_repos, options)
+            })?;
+        }
+    }
+    summary.distributions = distributions
+        .remaining()
+        .iter()
+        .map(|rel| rel.release_name().to_string())
+        .collect();
+    distributions.finish()?;
+    Ok(summary)
+}
+
+/// Extracts the given package files and, with `--check-arch`, checks their architectures
+fn process_package_files(
+    cli_args: &ArgMatches,
+    package_file_paths: &[String],
+    project: &Project,
+) -> Result<Vec<PackageSource>, BellhopError> {
+    let paths: Vec<PathBuf> = package_file_paths.iter().map(PathBuf::from).collect();
+    if let Some(path) = paths.iter().find(|path| !path.exists()) {
+        return Err(BellhopError::PackageFileNotFound { path: path.clone() });
+    }
+
+    let options = cli::extraction_options(cli_args);
+    let mut package_sources = Vec::with_capacity(paths.len());
+    for path in &paths {
+        info!("Processing package file: {}", path.display());
+        package_sources.push(archive::process_package_file(path, &options)?);
+    }
+
+    let check_arch = cli_args
+        .try_get_one::<bool>("check_arch")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false);
+    if check_arch {
+        for deb_path in package_sources.iter().flat_map(PackageSource::deb_paths) {
+            check_package_architecture(project, deb_path)?;
+        }
+    }
+    Ok(package_sources)
+}
+
+/// Checks the contents of every binary package before any of them is added, unless `--no-validate` is given
+fn validate_packages(
+    cli_args: &ArgMatches,
+    package_sources: &[PackageSource],
 ) -> Result<(), BellhopError> {
-    let path = PathBuf::from(package_file_path);
-    if !path.exists() {
-        return Err(BellhopError::PackageFileNotFound { path });
+    if cli_args.get_flag("no_validate") {
+        return Ok(());
     }
+    package_sources
+        .iter()
+        .flat_map(PackageSource::deb_paths)
+        .filter(|path| !archive::is_source_package(&path.to_string_lossy()))
+        .try_for_each(archive::validate_deb)
+}
 
-    info!("Processing package file: {}", path.display());
-    let package_source = archive::process_package_file(&path)?;
+/// The package files to add, rewritten copies for those that `--arch-map` applies to
+struct MappedPackages {
+    paths: Vec<PathBuf>,
+    _temp_dir: Option<TempDir>,
+}
 
-    let suffix = cli::suffix(cli_args);
+fn map_architectures(
+    cli_args: &ArgMatches,
+    package_sources: &[PackageSource],
+) -> Result<MappedPackages, BellhopError> {
+    let deb_paths = package_sources.iter().flat_map(PackageSource::deb_paths);
+    let arch_map = cli::arch_map(cli_args);
+    if arch_map.is_empty() {
+        return Ok(MappedPackages {
+            paths: deb_paths.map(Path::to_path_buf).collect(),
+            _temp_dir: None,
+        });
+    }
 
-    match package_source {
-        PackageSource::SingleDeb(deb_path) => {
-            info!("Adding single .deb package");
-            add_single_package(cli_args, &deb_path, project, target_releases)?;
+    let temp_dir = TempDir::new()?;
+    let mut paths = Vec::new();
+    for deb_path in deb_paths {
+        if archive::is_source_package(&deb_path.to_string_lossy()) {
+            paths.push(deb_path.to_path_buf());
+            continue;
         }
-        PackageSource::Archive {
-            deb_files,
-            _temp_dir,
-        } => {
-            info!("Adding {} packages from archive", deb_files.len());
-            for deb_path in &deb_files {
-                debug!("Processing: {}", deb_path.display());
-                add_single_package_no_snapshot(&project, deb_path, target_releases)?;
+        match debedit::map_architecture(deb_path, &arch_map, temp_dir.path())? {
+            Some(rewritten) => {
+                info!(
+                    "Rewrote the architecture of {} as {}",
+                    deb_path.display(),
+                    rewritten.display()
+                );
+                paths.push(rewritten);
             }
-            update_snapshots_for_releases(&project, target_releases, &suffix)?;
+            None => paths.push(deb_path.to_path_buf()),
         }
     }
+    Ok(MappedPackages {
+        paths,
+        _temp_dir: Some(temp_dir),
+    })
+}
+
+/// Adds the packages to a repository given by name rather than derived from a distribution.
+/// Snapshot names are derived from distributions, so no snapshots are taken.
+pub fn add_packages_to_repo(
+    cli_args: &ArgMatches,
+    package_file_paths: &[String],
+    project: Project,
+    repo_name: &str,
+) -> Result<AddSummary, BellhopError> {
+    ensure_repo_exists(repo_name)?;
+    let package_sources = process_package_files(cli_args, package_file_paths, &project)?;
+    validate_packages(cli_args, &package_sources)?;
+    let mapped = map_architectures(cli_args, &package_sources)?;
+    let deb_paths: Vec<&Path> = mapped.paths.iter().map(PathBuf::as_path).collect();
+    let identity = PackageIdentity::for_packages(cli_args, &deb_paths)?;
+
+    let mut summary = AddSummary::default();
+    for deb_path in deb_paths {
+        info!(
+            "Adding package {} to repo '{repo_name}'",
+            deb_path.display()
+        );
+        repo_add(&project, deb_path, repo_name)?;
+        let mut added = AddedPackage::new(deb_path, identity.as_ref());
+        added.repos.push(repo_name.to_string());
+        summary.packages.push(added);
+    }
+    info!("Not taking snapshots of repo '{repo_name}', it is not tied to a distribution");
+    Ok(summary)
+}
+
+/// Removes packages from a repository given by name rather than derived from a distribution,
+/// without taking snapshots
+pub fn remove_from_repo(
+    cli_args: &ArgMatches,
+    project: Project,
+    repo_name: &str,
+) -> Result<(), BellhopError> {
+    ensure_repo_exists(repo_name)?;
+
+    let queries = if let Some(version) = cli_args.get_one::<String>("version") {
+        let package_name = cli_args
+            .get_one::<String>("package_name")
+            .map(|s| s.as_str());
+        vec![version_query(&project, version, package_name)?]
+    } else if let Some(package_file_path) = cli_args.get_one::<String>("package_file_path") {
+        let sources =
+            process_package_files(cli_args, slice::from_ref(package_file_path), &project)?;
+        let deb_paths: Vec<PathBuf> = sources
+            .iter()
+            .flat_map(PackageSource::deb_paths)
+            .map(Path::to_path_buf)
+            .collect();
+        if cli_args.get_flag("exact") {
+            let deb_paths: Vec<&Path> = deb_paths.iter().map(PathBuf::as_path).collect();
+            vec![exact_removal_query(&deb_paths)?]
+        } else {
+            let versions: BTreeSet<String> = archive::extract_versions_from_debs(&deb_paths)?
+                .into_iter()
+                .collect();
+            versions
+                .iter()
+                .map(|version| version_query(&project, version, None))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    } else if let Some(package_name) = cli_args.get_one::<String>("all_versions") {
+        vec![all_versions_query(&project, package_name)?]
+    } else {
+        return Err(BellhopError::MissingArgument {
+            argument: "version, package_file_path or all_versions".to_string(),
+        });
+    };
 
+    for query in &queries {
+        run_repo_remove_query(repo_name, query)?;
+    }
+    info!("Not taking snapshots of repo '{repo_name}', it is not tied to a distribution");
     Ok(())
 }
 
+fn ensure_repo_exists(repo_name: &str) -> Result<(), BellhopError> {
+    if list_repos()?.contains(repo_name) {
+        Ok(())
+    } else {
+        Err(BellhopError::RepoNotFound {
+            repo: repo_name.to_string(),
+        })
+    }
+}
+
+/// Adds the package only if the repository does not have this exact package yet, so that
+/// rerunning an add does not add it again. Returns whether it was added.
+fn add_missing_package_no_snapshot(
+    project: &Project,
+    deb_path: &Path,
+    identity: Option<&PackageIdentity>,
+    rel: &DistributionAlias,
+) -> Result<bool, BellhopError> {
+    let (name, version, arch) = match identity {
+        Some(identity) => {
+            let (name, version) = identity.name_and_version();
+            (name, version, debedit::architecture(deb_path)?)
+        }
+        None => archive::extract_package_key_from_deb(deb_path)?,
+    };
+    let query = package_key_query(&name, &version, &arch);
+
+    let repo_name = repo_name(project, rel);
+    if run_repo_search(&repo_name, &query)?.is_empty() {
+        run_repo_add(project, deb_path, &repo_name, rel)?;
+        Ok(true)
+    } else {
+        info!("{name} {version} ({arch}) is already present in repo '{repo_name}', skipping");
+        Ok(false)
+    }
+}
+
+/// The `aptly` query for exactly one package, packages of other architectures do not match
+pub fn package_key_query(name: &str, version: &str, architecture: &str) -> String {
+    format!("Name (= {name}), Version (= {version}), $Architecture (= {architecture})")
+}
+
+/// Adds the package only if the repository does not already have the same or a newer version of it.
+/// Returns whether it was added.
+fn add_newer_package_no_snapshot(
+    project: &Project,
+    deb_path: &Path,
+    identity: Option<&PackageIdentity>,
+    rel: &DistributionAlias,
+) -> Result<bool, BellhopError> {
+    let (name, version, arch) = match identity {
+        Some(identity) => {
+            let (name, version) = identity.name_and_version();
+            (name, version, debedit::architecture(deb_path)?)
+        }
+        None => archive::extract_package_key_from_deb(deb_path)?,
+    };
+
+    let repo_name = repo_name(project, rel);
+    let existing = run_repo_search(&repo_name, &newer_only_query(&name, &arch))?;
+    let newest = existing
+        .iter()
+        .map(|(_, v)| v)
+        .max_by(|a, b| compare_versions(a, b));
+
+    match newest {
+        Some(newest) if compare_versions(&version, newest) != Ordering::Greater => {
+            info!("Skipping {name} {version}: repo '{repo_name}' already has version {newest}");
+            Ok(false)
+        }
+        _ => {
+            run_repo_add(project, deb_path, &repo_name, rel)?;
+            Ok(true)
+        }
+    }
+}
+
+/// The `aptly` query for the versions `--newer-only` compares against: those of the same architecture
+/// and the architecture-independent ones. An `all` package is compared against every architecture.
+fn newer_only_query(name: &str, architecture: &str) -> String {
+    match architecture {
+        "all" => format!("Name (= {name})"),
+        _ => format!("Name (= {name}), ($Architecture (= {architecture}) | $Architecture (= all))"),
+    }
+}
+
 pub fn update_snapshots_for_releases(
     project: &Project,
     target_releases: &[DistributionAlias],
     suffix: &str,
+    source: SnapshotSource,
+) -> Result<(), BellhopError> {
+    let mut distributions = PerDistribution::new(target_releases, false);
+    update_snapshots(project, &mut distributions, suffix, source)?;
+    distributions.finish()
+}
+
+fn update_snapshots(
+    project: &Project,
+    distributions: &mut PerDistribution,
+    suffix: &str,
+    source: SnapshotSource,
 ) -> Result<(), BellhopError> {
     let existing_snapshots = list_snapshot_names()?;
     let published_repos = list_published_repos()?;
 
-    for rel in target_releases {
-        create_or_retake_snapshot(project, rel, suffix, &existing_snapshots, &published_repos)?;
+    distributions.run(|rel| {
+        create_or_retake_snapshot(
+            project,
+            rel,
+            suffix,
+            source,
+            &existing_snapshots,
+            &published_repos,
+        )
+    })
+}
+
+/// Runs the steps of a multi-distribution operation. By default the first failure is returned
+/// right away. With `keep_going`, a distribution that fails is skipped in later steps, the other
+/// distributions carry on, and the failures are reported together by [`PerDistribution::finish`].
+pub struct PerDistribution {
+    remaining: Vec<DistributionAlias>,
+    failures: Vec<(String, String)>,
+    keep_going: bool,
+}
+
+impl PerDistribution {
+    pub fn new(target_releases: &[DistributionAlias], keep_going: bool) -> Self {
+        Self {
+            remaining: target_releases.to_vec(),
+            failures: Vec::new(),
+            keep_going,
+        }
+    }
+
+    /// Runs a step for every distribution none of the earlier steps have failed for
+    pub fn run(
+        &mut self,
+        mut step: impl FnMut(&DistributionAlias) -> Result<(), BellhopError>,
+    ) -> Result<(), BellhopError> {
+        let mut succeeded = Vec::with_capacity(self.remaining.len());
+        for rel in mem::take(&mut self.remaining) {
+            match step(&rel) {
+                Ok(()) => succeeded.push(rel),
+                Err(e) if self.keep_going => {
+                    warn!(
+                        "'{}' failed, continuing with other distributions: {e}",
+                        rel.release_name()
+                    );
+                    self.failures
+                        .push((rel.release_name().to_string(), e.to_string()));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        self.remaining = succeeded;
+        Ok(())
+    }
+
+    /// The distributions none of the steps so far have failed for
+    pub fn remaining(&self) -> &[DistributionAlias] {
+        &self.remaining
+    }
+
+    pub fn finish(self) -> Result<(), BellhopError> {
+        if self.failures.is_empty() {
+            Ok(())
+        } else {
+            Err(BellhopError::MultipleFailures {
+                failures: self.failures,
+            })
+        }
     }
-    Ok(())
 }
 
 /// Package imports and hand-taken snapshots share this path so that an import never falls back to
@@ -134,59 +773,215 @@ fn create_or_retake_snapshot(
     project: &Project,
     rel: &DistributionAlias,
     suffix: &str,
+    source: SnapshotSource,
     existing_snapshots: &HashSet<String>,
     published_repos: &HashSet<String>,
 ) -> Result<(), BellhopError> {
     let repo_name = repo_name(project, rel);
     let snapshot_name = snapshot_name_with_suffix(project, rel, suffix);
 
+    let contents = match source {
+        SnapshotSource::Repo => SnapshotContents::Repo,
+        SnapshotSource::Published => {
+            let rel_path = rel_path_with_prefix(project, rel, rel.family());
+            match published_snapshot_name(published_repos, &rel_path, rel.release_name()) {
+                Some(base) => SnapshotContents::MergedWith(base),
+                None => {
+                    info!(
+                        "'{rel_path}' is not published yet, snapshotting repo '{repo_name}' alone"
+                    );
+                    SnapshotContents::Repo
+                }
+            }
+        }
+    };
+
     if existing_snapshots.contains(&snapshot_name) {
-        retake_snapshot(&snapshot_name, &repo_name, published_repos)
+        retake_snapshot(&snapshot_name, &repo_name, &contents, published_repos)
     } else {
-        run_snapshot_create_by_name(&snapshot_name, &repo_name)
+        build_snapshot(&snapshot_name, &repo_name, &contents)
     }
 }
 
-fn add_single_package(
-    cli_args: &ArgMatches,
+/// What a snapshot taken from a repository contains
+enum SnapshotContents {
+    /// Everything in the repository
+    Repo,
+    /// Every package of the given snapshot plus those of the repository, so packages removed from
+    /// the repository since that snapshot was published stay available
+    MergedWith(String),
+    /// Only the repository packages matching the given `aptly` query
+    Filtered(String),
+}
+
+fn build_snapshot(
+    snapshot_name: &str,
+    repo_name: &str,
+    contents: &SnapshotContents,
+) -> Result<(), BellhopError> {
+    if let SnapshotContents::Repo = contents {
+        return run_snapshot_create_by_name(snapshot_name, repo_name);
+    }
+
+    let repo_snapshot = format!("{snapshot_name}{REPO_SNAPSHOT_SUFFIX}");
+    run_snapshot_drop_by_name(&repo_snapshot);
+    run_snapshot_create_by_name(&repo_snapshot, repo_name)?;
+
+    let result = match contents {
+        SnapshotContents::MergedWith(base) => {
+            run_snapshot_merge(snapshot_name, base, &repo_snapshot)
+        }
+        SnapshotContents::Filtered(query) => {
+            run_snapshot_filter(&repo_snapshot, snapshot_name, query)
+        }
+        SnapshotContents::Repo => Ok(()),
+    };
+    run_snapshot_drop_by_name(&repo_snapshot);
+    result
+}
+
+pub fn add_single_package_no_snapshot(
+    project: &Project,
     deb_path: &Path,
+    target_releases: &[DistributionAlias],
+) -> Result<(), BellhopError> {
+    for rel in target_releases {
+        let repo_name = repo_name(project, rel);
+        run_repo_add(project, deb_path, &repo_name, rel)?;
+    }
+    Ok(())
+}
+
+pub fn remove_package(
+    cli_args: &ArgMatches,
+    version: &str,
     project: Project,
     target_releases: &[DistributionAlias],
 ) -> Result<(), BellhopError> {
+    let package_name = cli_args
+        .get_one::<String>("package_name")
+        .map(|s| s.as_str());
+    let query = version_query(&project, version, package_name)?;
     let suffix = cli::suffix(cli_args);
+    let source = cli::snapshot_source(cli_args);
 
     for rel in target_releases {
         let repo_name = repo_name(&project, rel);
-        run_repo_add(&project, deb_path, &repo_name, rel)?;
+        run_repo_remove_query(&repo_name, &query)?;
     }
-    update_snapshots_for_releases(&project, target_releases, &suffix)
+    update_snapshots_for_releases(&project, target_releases, &suffix, source)
 }
 
-pub fn add_single_package_no_snapshot(
+/// The `aptly` query for a version of the project's packages, or of a single package when
+/// its name is given
+pub fn version_query(
     project: &Project,
-    deb_path: &Path,
+    version: &str,
+    package_name: Option<&str>,
+) -> Result<String, BellhopError> {
+    if let Some(name) = package_name {
+        validate_package_name(name)?;
+        return Ok(format!("Name (= {name}), Version (= {version})"));
+    }
+
+    Ok(match project {
+        Project::RabbitMQ => format!("rabbitmq-server (= {version})"),
+        Project::Erlang => format!("Name (~ ^erlang), Version (= {version})"),
+        Project::CliTools => format!("Version (= {version})"),
+    })
+}
+
+/// Removes a version from the snapshots with the `--suffix` suffix by filtering them into new
+/// snapshots with the `--to` suffix. Neither the repositories nor the original snapshots change,
+/// so the new snapshots can be published as a hotfix. Nothing is filtered unless every original
+/// snapshot exists and none of the new ones do.
+pub fn remove_from_snapshots(
+    cli_args: &ArgMatches,
+    version: &str,
+    project: Project,
     target_releases: &[DistributionAlias],
 ) -> Result<(), BellhopError> {
+    let package_name = cli_args
+        .get_one::<String>("package_name")
+        .map(|s| s.as_str());
+    let query = version_query(&project, version, package_name)?;
+    let from_suffix = cli::suffix(cli_args);
+    let to_suffix =
+        cli_args
+            .get_one::<String>("to")
+            .ok_or_else(|| BellhopError::MissingArgument {
+                argument: "to".to_string(),
+            })?;
+
+    let existing_snapshots = list_snapshot_names()?;
+    let mut filters = Vec::new();
     for rel in target_releases {
-        let repo_name = repo_name(project, rel);
-        run_repo_add(project, deb_path, &repo_name, rel)?;
+        let source = snapshot_name_with_suffix(&project, rel, &from_suffix);
+        if !existing_snapshots.contains(&source) {
+            return Err(BellhopError::SourceSnapshotNotFound { snapshot: source });
+        }
+        let target = snapshot_name_with_suffix(&project, rel, to_suffix);
+        if existing_snapshots.contains(&target) {
+            return Err(BellhopError::SnapshotAlreadyExists { snapshot: target });
+        }
+        filters.push((source, target));
+    }
+
+    let exclusion = exclusion_query(&query);
+    for (source, target) in filters {
+        run_snapshot_filter(&source, &target, &exclusion)?;
     }
     Ok(())
 }
 
-pub fn remove_package(
+/// The `aptly` query for every package that does not match the given query
+pub fn exclusion_query(query: &str) -> String {
+    format!("!({query})")
+}
+
+/// Removes every version of the named package, e.g. to retire it. For Erlang the name is
+/// matched as a prefix, the same way other Erlang removals cover all of its packages.
+pub fn remove_all_versions(
     cli_args: &ArgMatches,
-    version: &str,
+    package_name: &str,
     project: Project,
     target_releases: &[DistributionAlias],
 ) -> Result<(), BellhopError> {
+    let query = all_versions_query(&project, package_name)?;
     let suffix = cli::suffix(cli_args);
+    let source = cli::snapshot_source(cli_args);
 
     for rel in target_releases {
         let repo_name = repo_name(&project, rel);
-        run_repo_remove(&project, version, &repo_name)?;
+        run_repo_remove_query(&repo_name, &query)?;
     }
-    update_snapshots_for_releases(&project, target_releases, &suffix)
+    update_snapshots_for_releases(&project, target_releases, &suffix, source)
+}
+
+pub fn all_versions_query(project: &Project, package_name: &str) -> Result<String, BellhopError> {
+    validate_package_name(package_name)?;
+
+    Ok(match project {
+        Project::Erlang => {
+            let pattern = package_name.replace('.', "\\.").replace('+', "\\+");
+            format!("Name (~ ^{pattern})")
+        }
+        Project::RabbitMQ | Project::CliTools => format!("Name (= {package_name})"),
+    })
+}
+
+/// Package names end up in `aptly` queries, so only the characters Debian allows in them are accepted
+fn validate_package_name(package_name: &str) -> Result<(), BellhopError> {
+    let is_valid = !package_name.is_empty()
+        && package_name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.'));
+    if !is_valid {
+        return Err(BellhopError::InvalidPackageName {
+            name: package_name.to_string(),
+        });
+    }
+    Ok(())
 }
 
 pub fn remove_package_from_archive(
@@ -201,9 +996,18 @@ pub fn remove_package_from_archive(
     }
 
     info!("Processing package file: {}", path.display());
-    let package_source = archive::process_package_file(&path)?;
+    let package_source = archive::process_package_file(&path, &cli::extraction_options(cli_args))?;
 
     let suffix = cli::suffix(cli_args);
+    let source = cli::snapshot_source(cli_args);
+
+    if cli_args.get_flag("exact") {
+        let query = exact_removal_query(&package_source.deb_paths())?;
+        for rel in target_releases {
+            run_repo_remove_query(&repo_name(&project, rel), &query)?;
+        }
+        return update_snapshots_for_releases(&project, target_releases, &suffix, source);
+    }
 
     match package_source {
         PackageSource::SingleDeb(deb_path) => {
@@ -225,56 +1029,531 @@ pub fn remove_package_from_archive(
             );
             for version in &unique_versions {
                 debug!("Removing version: {version}");
-                remove_single_package_no_snapshot(&project, version, target_releases)?;
+                remove_single_package_no_snapshot(&project, version, None, target_releases)?;
             }
-            update_snapshots_for_releases(&project, target_releases, &suffix)?;
+            update_snapshots_for_releases(&project, target_releases, &suffix, source)?;
         }
     }
 
     Ok(())
 }
 
-fn remove_single_package_no_snapshot(
+/// Removes exactly the packages of the given files, by the name and version in their control files,
+/// rather than every package of the project with one of their versions
+fn exact_removal_query(deb_paths: &[&Path]) -> Result<String, BellhopError> {
+    let packages = deb_paths
+        .iter()
+        .map(|deb_path| {
+            if archive::is_source_package(&deb_path.to_string_lossy()) {
+                archive::read_name_and_version_from_dsc(deb_path)
+            } else {
+                debedit::name_and_version(deb_path)
+            }
+        })
+        .collect::<Result<BTreeSet<_>, _>>()?;
+    Ok(exact_packages_query(&packages))
+}
+
+/// The `aptly` query for exactly the given `(name, version)` pairs
+pub fn exact_packages_query(packages: &BTreeSet<(String, String)>) -> String {
+    packages
+        .iter()
+        .map(|(name, version)| format!("(Name (= {name}), Version (= {version}))"))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Removes a version of the project's packages, or of a single package when its name is given
+pub fn remove_single_package_no_snapshot(
+    project: &Project,
+    version: &str,
+    package_name: Option<&str>,
+    target_releases: &[DistributionAlias],
+) -> Result<(), BellhopError> {
+    let query = version_query(project, version, package_name)?;
+    for rel in target_releases {
+        let repo_name = repo_name(project, rel);
+        run_repo_remove_query(&repo_name, &query)?;
+    }
+    Ok(())
+}
+
+/// Removes exactly one package, the same version built for other architectures is kept
+pub fn remove_package_key_no_snapshot(
     project: &Project,
+    name: &str,
     version: &str,
+    architecture: &str,
     target_releases: &[DistributionAlias],
 ) -> Result<(), BellhopError> {
+    let query = package_key_query(name, version, architecture);
     for rel in target_releases {
         let repo_name = repo_name(project, rel);
-        run_repo_remove(project, version, &repo_name)?;
+        run_repo_remove_query(&repo_name, &query)?;
     }
     Ok(())
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct PublishOptions {
+    /// Passes `-force-overwrite` to aptly
+    pub force_overwrite: bool,
+    /// Name of an endpoint from the `S3PublishEndpoints` section of the aptly configuration
+    pub endpoint: Option<String>,
+    /// Component to publish to, aptly uses `main` when none is given
+    pub component: Option<String>,
+    /// File with the signing key passphrase, so that signing does not prompt for it
+    pub passphrase_file: Option<PathBuf>,
+    /// Publishes under this family's prefix instead of the distribution's own family
+    pub family: Option<DebianFamily>,
+    /// aptly distribution names to publish under instead of the release names, e.g. `stable`
+    pub distribution_names: HashMap<DistributionAlias, String>,
+    /// Do not check that `gpg` has the signing key before publishing
+    pub skip_gpg_check: bool,
+    /// Publishes under this prefix instead of the computed one, `.` is aptly's root prefix
+    pub prefix: Option<String>,
+    /// A second aptly distribution name every snapshot is also published under, e.g. `stable`
+    pub also_distribution: Option<String>,
+    /// Drops the snapshots a switch replaced, unless they are still published elsewhere
+    pub cleanup_previous: bool,
+    /// GPG keyring aptly looks the signing key up in instead of the default one
+    pub keyring: Option<PathBuf>,
+}
+
+impl PublishOptions {
+    /// A missing passphrase file or keyring is reported before any publication is switched
+    pub fn validate(&self) -> Result<(), BellhopError> {
+        if let Some(prefix) = &self.prefix
+            && !is_valid_publish_prefix(prefix)
+        {
+            return Err(BellhopError::InvalidPublishPrefix {
+                prefix: prefix.clone(),
+            });
+        }
+        if let Some(path) = &self.passphrase_file
+            && !path.is_file()
+        {
+            return Err(BellhopError::PassphraseFileNotFound { path: path.clone() });
+        }
+        match &self.keyring {
+            Some(path) if !path.is_file() => {
+                Err(BellhopError::KeyringNotFound { path: path.clone() })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The publish prefix of the given distribution, without the storage part
+    pub fn rel_path_of(&self, project: &Project, rel: &DistributionAlias) -> String {
+        match &self.prefix {
+            Some(prefix) => prefix.clone(),
+            None => rel_path_with_prefix(project, rel, self.family_of(rel)),
+        }
+    }
+
+    /// The family component of the publish prefix for the given distribution
+    pub fn family_of(&self, rel: &DistributionAlias) -> DebianFamily {
+        self.family.unwrap_or_else(|| rel.family())
+    }
+
+    /// The aptly distribution name of a publication, the release name unless it was overridden
+    pub fn distribution_name_of(&self, rel: &DistributionAlias) -> &str {
+        self.distribution_names
+            .get(rel)
+            .map(String::as_str)
+            .unwrap_or_else(|| rel.release_name())
+    }
+
+    /// Every aptly distribution name a release is published under, the primary one first
+    pub fn distribution_names_of(&self, rel: &DistributionAlias) -> Vec<&str> {
+        let primary = self.distribution_name_of(rel);
+        let mut names = vec![primary];
+        match self.also_distribution.as_deref() {
+            Some(also) if also != primary => names.push(also),
+            _ => {}
+        }
+        names
+    }
+
+    /// Flags shared by `publish snapshot` and `publish switch`, except for the signing key
+    pub fn flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if let Some(path) = &self.keyring {
+            flags.push(format!("-keyring={}", path.display()));
+        }
+        if let Some(path) = &self.passphrase_file {
+            flags.push(format!("-passphrase-file={}", path.display()));
+            flags.push("-batch".to_string());
+        }
+        if self.force_overwrite {
+            flags.push("-force-overwrite".to_string());
+        }
+        if let Some(component) = &self.component {
+            flags.push(format!("-component={component}"));
+        }
+        flags
+    }
+}
+
 pub fn publish(
     project: Project,
     target_releases: &[DistributionAlias],
     suffix: &str,
+    options: &PublishOptions,
 ) -> Result<(), BellhopError> {
-    let published_repos = list_published_repos()?;
+    let published_repos = publish_preflight(options)?;
     for rel in target_releases {
-        run_snapshot_switch(&project, rel, suffix, &published_repos)?;
+        run_snapshot_switch(&project, rel, suffix, &published_repos, options)?;
     }
     Ok(())
 }
 
-pub fn list_snapshots(
+/// Checks the options and the signing key before anything is published, then lists the
+/// existing publications
+fn publish_preflight(options: &PublishOptions) -> Result<HashSet<String>, BellhopError> {
+    options.validate()?;
+    if !options.skip_gpg_check {
+        verify_gpg_key(options.keyring.as_deref())?;
+    }
+    list_published_repos()
+}
+
+/// Refreshes and re-signs the existing publications without switching their snapshots, e.g.
+/// after the signing key was rotated. Nothing is updated unless every publication exists.
+pub fn publish_update(
+    project: Project,
+    target_releases: &[DistributionAlias],
+    options: &PublishOptions,
+) -> Result<(), BellhopError> {
+    let published_repos = publish_preflight(options)?;
+
+    let mut updates = Vec::new();
+    for rel in target_releases {
+        let rel_path = publish_target(
+            &options.rel_path_of(&project, rel),
+            options.endpoint.as_deref(),
+        );
+        for distribution in options.distribution_names_of(rel) {
+            if !publication_exists(&published_repos, &rel_path, distribution) {
+                return Err(BellhopError::NothingPublished {
+                    distribution: distribution.to_string(),
+                    prefix: rel_path,
+                });
+            }
+            updates.push((distribution, rel_path.clone()));
+        }
+    }
+
+    for (distribution, rel_path) in updates {
+        info!("Updating the publication of '{distribution}' at '{rel_path}'");
+        let args = publish_update_args(distribution, &rel_path, &gpg_key_arg(), options);
+        let output = run_aptly(aptly_command().args(&args))?;
+        check_aptly_output(output, format!("aptly {}", args.join(" "))).map_err(|e| {
+            BellhopError::PublishFailed {
+                distribution: distribution.to_string(),
+                attempt: "updating the existing publication".to_string(),
+                source: Box::new(e),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Switches the publications to the snapshots a removal has just recreated, so that removed
+/// packages stop being served right away instead of with the next publish
+pub fn republish(
     project: Project,
     target_releases: &[DistributionAlias],
     suffix: &str,
+    options: &PublishOptions,
+) -> Result<(), BellhopError> {
+    info!("Republishing so that the removal takes effect");
+    publish(project, target_releases, suffix, options)
+}
+
+/// Switches the publications back to the snapshots taken with an earlier suffix. All snapshots
+/// are checked before anything is switched, so a missing one leaves every publication as it was.
+pub fn rollback(
+    project: Project,
+    target_releases: &[DistributionAlias],
+    to_suffix: &str,
+    options: &PublishOptions,
 ) -> Result<(), BellhopError> {
+    let existing_snapshots = list_snapshot_names()?;
     for rel in target_releases {
-        run_snapshot_show(&project, rel, suffix)?;
+        let snapshot = snapshot_name_with_suffix(&project, rel, to_suffix);
+        if !existing_snapshots.contains(&snapshot) {
+            return Err(BellhopError::SnapshotNotFound { snapshot });
+        }
+    }
+
+    info!("Rolling back to the snapshots with suffix '{to_suffix}'");
+    publish(project, target_releases, to_suffix, options)
+}
+
+/// Publishes a snapshot that only has the newest version of every package in the repository
+pub fn promote_latest(
+    project: Project,
+    target_releases: &[DistributionAlias],
+    suffix: &str,
+    remove_older: bool,
+    options: &PublishOptions,
+) -> Result<(), BellhopError> {
+    let published_repos = publish_preflight(options)?;
+    let existing_snapshots = list_snapshot_names()?;
+
+    for rel in target_releases {
+        let repo_name = repo_name(&project, rel);
+        let snapshot_name = snapshot_name_with_suffix(&project, rel, suffix);
+
+        let packages = run_repo_search(&repo_name, package_query(&project))?;
+        let newest = newest_versions(&packages);
+        if newest.is_empty() {
+            return Err(BellhopError::NoPackagesToPromote { repo: repo_name });
+        }
+
+        for (name, version) in &newest {
+            info!("Newest version of '{name}' in repo '{repo_name}' is {version}");
+            let has_older = packages
+                .iter()
+                .any(|(n, v)| n == name && compare_versions(v, version) == Ordering::Less);
+            if remove_older && has_older {
+                run_repo_remove_query(
+                    &repo_name,
+                    &format!("Name (= {name}), Version (<< {version})"),
+                )?;
+            }
+        }
+
+        let query = newest
+            .iter()
+            .map(|(name, version)| format!("(Name (= {name}), Version (= {version}))"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let contents = SnapshotContents::Filtered(query);
+
+        if existing_snapshots.contains(&snapshot_name) {
+            retake_snapshot(&snapshot_name, &repo_name, &contents, &published_repos)?;
+        } else {
+            build_snapshot(&snapshot_name, &repo_name, &contents)?;
+        }
+
+        run_snapshot_switch(&project, rel, suffix, &published_repos, options)?;
+    }
+    Ok(())
+}
+
+/// Picks the newest version of every package name, per the `dpkg` version ordering
+pub fn newest_versions(packages: &[(String, String)]) -> BTreeMap<String, String> {
+    let mut newest: BTreeMap<String, String> = BTreeMap::new();
+    for (name, version) in packages {
+        let is_newer = newest
+            .get(name)
+            .is_none_or(|current| compare_versions(version, current) == Ordering::Greater);
+        if is_newer {
+            newest.insert(name.clone(), version.clone());
+        }
+    }
+    newest
+}
+
+/// Parses `aptly` package keys such as `rabbitmq-server_4.1.3-1_all` into names and versions
+pub fn parse_package_keys(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().rsplitn(3, '_');
+            let _arch = parts.next()?;
+            let version = parts.next()?;
+            let name = parts.next()?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+pub fn list_snapshots(
+    snapshots: &[DistributionSnapshot],
+    package_filter: Option<&str>,
+) -> Result<(), BellhopError> {
+    for snapshot in snapshots {
+        run_snapshot_show(&snapshot.name, package_filter)?;
     }
     Ok(())
 }
 
+/// A snapshot of a distribution, selected by its suffix or by the date in its name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistributionSnapshot {
+    pub distribution: DistributionAlias,
+    pub name: String,
+}
+
+/// The snapshots of the distributions that have the given suffix
+pub fn snapshots_with_suffix(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    suffix: &str,
+) -> Vec<DistributionSnapshot> {
+    target_releases
+        .iter()
+        .map(|rel| DistributionSnapshot {
+            distribution: rel.clone(),
+            name: snapshot_name_with_suffix(project, rel, suffix),
+        })
+        .collect()
+}
+
+/// The snapshots of the distributions whose date suffix falls into the range, oldest first
+pub fn snapshots_in_date_range(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    date_format: &str,
+    range: &DateRange,
+) -> Result<Vec<DistributionSnapshot>, BellhopError> {
+    let snapshot_names = list_snapshot_names()?;
+    Ok(target_releases
+        .iter()
+        .flat_map(|rel| {
+            dated_snapshot_names(&snapshot_names, project, rel, date_format, range)
+                .into_iter()
+                .map(|name| DistributionSnapshot {
+                    distribution: rel.clone(),
+                    name,
+                })
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotPackages {
+    pub distribution: String,
+    pub snapshot: String,
+    /// Package keys, e.g. `rabbitmq-server_4.1.4-1_all`
+    pub packages: Vec<String>,
+}
+
+/// A snapshot as `aptly snapshot show -with-packages` describes it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SnapshotDetails {
+    pub name: String,
+    pub created_at: Option<String>,
+    pub description: Option<String>,
+    /// Package keys, e.g. `rabbitmq-server_4.1.4-1_all`
+    pub packages: Vec<String>,
+}
+
+/// `aptly snapshot show -with-packages` output for a snapshot given by its exact name,
+/// e.g. one produced by `snapshot orphans`
+pub fn snapshot_show_by_name(snapshot_name: &str) -> Result<String, BellhopError> {
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("show")
+            .arg("-with-packages")
+            .arg(snapshot_name),
+    )?;
+    let output = check_aptly_output(
+        output,
+        format!("aptly snapshot show -with-packages {snapshot_name}"),
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub fn parse_snapshot_details(output: &str) -> SnapshotDetails {
+    let mut details = SnapshotDetails::default();
+    let mut lines = output.lines();
+    for line in lines.by_ref() {
+        if line.starts_with("Packages:") {
+            break;
+        }
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match field {
+            "Name" => details.name = value,
+            "Created At" => details.created_at = Some(value),
+            "Description" => details.description = Some(value),
+            _ => {}
+        }
+    }
+    details.packages = lines
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    details
+}
+
+/// The packages of the snapshots, only those whose key contains `package_filter` when it is set
+pub fn snapshot_packages(
+    snapshots: &[DistributionSnapshot],
+    package_filter: Option<&str>,
+) -> Result<Vec<SnapshotPackages>, BellhopError> {
+    let mut packages_of = Vec::with_capacity(snapshots.len());
+    for snapshot in snapshots {
+        let packages = run_snapshot_packages(&snapshot.name)?
+            .into_iter()
+            .filter(|key| package_filter.is_none_or(|filter| key.contains(filter)))
+            .collect();
+        packages_of.push(SnapshotPackages {
+            distribution: snapshot.distribution.release_name().to_string(),
+            snapshot: snapshot.name.clone(),
+            packages,
+        });
+    }
+    Ok(packages_of)
+}
+
+/// `aptly snapshot show -with-packages` output with only the package lines that contain
+/// `filter`, the snapshot details before the package list are kept as they are
+pub fn filter_snapshot_packages(output: &str, filter: &str) -> String {
+    let mut in_packages = false;
+    let mut filtered = String::with_capacity(output.len());
+    for line in output.lines() {
+        if in_packages && !line.trim().is_empty() && !line.contains(filter) {
+            continue;
+        }
+        in_packages |= line.starts_with("Packages:");
+        filtered.push_str(line);
+        filtered.push('\n');
+    }
+    filtered
+}
+
 pub fn take_snapshot(
     project: Project,
     target_releases: &[DistributionAlias],
     suffix: &str,
+    source: SnapshotSource,
 ) -> Result<(), BellhopError> {
-    update_snapshots_for_releases(&project, target_releases, suffix)
+    update_snapshots_for_releases(&project, target_releases, suffix, source)
+}
+
+/// Copies the snapshot that is currently published for every distribution, e.g. to bookmark
+/// it for a rollback. Nothing is copied unless every distribution is published.
+pub fn take_snapshot_from_published(
+    project: Project,
+    target_releases: &[DistributionAlias],
+    suffix: &str,
+) -> Result<(), BellhopError> {
+    let published_repos = list_published_repos()?;
+
+    let mut copies = Vec::with_capacity(target_releases.len());
+    for rel in target_releases {
+        let rel_path = rel_path_with_prefix(&project, rel, rel.family());
+        let published = published_snapshot_name(&published_repos, &rel_path, rel.release_name())
+            .ok_or_else(|| BellhopError::NothingPublished {
+                distribution: rel.release_name().to_string(),
+                prefix: rel_path.clone(),
+            })?;
+        copies.push((snapshot_name_with_suffix(&project, rel, suffix), published));
+    }
+
+    for (snapshot_name, published) in &copies {
+        run_snapshot_copy(snapshot_name, published)?;
+    }
+    Ok(())
 }
 
 /// `aptly` cannot diff a snapshot against a repository, hence the temporary snapshot.
@@ -283,13 +1562,14 @@ pub fn take_snapshot(
 fn retake_snapshot(
     snapshot_name: &str,
     repo_name: &str,
+    contents: &SnapshotContents,
     published_repos: &HashSet<String>,
 ) -> Result<(), BellhopError> {
     let temp_name = format!("{snapshot_name}{TEMP_SNAPSHOT_SUFFIX}");
 
     // A temporary snapshot left behind by an interrupted run would block creation below
     run_snapshot_drop_by_name(&temp_name);
-    run_snapshot_create_by_name(&temp_name, repo_name)?;
+    build_snapshot(&temp_name, repo_name, contents)?;
 
     let identical = snapshots_are_identical(&temp_name, snapshot_name);
 
@@ -300,39 +1580,317 @@ fn retake_snapshot(
             info!("Snapshot '{snapshot_name}' already matches repo '{repo_name}', nothing to do");
             Ok(())
         }
-        Ok(false) => {
-            if is_snapshot_published(published_repos, snapshot_name) {
-                run_snapshot_drop_by_name(&temp_name);
-                return Err(BellhopError::PublishedSnapshotIsStale {
-                    snapshot: snapshot_name.to_string(),
-                    repo: repo_name.to_string(),
+        Ok(false) => {
+            if is_snapshot_published(published_repos, snapshot_name) {
+                run_snapshot_drop_by_name(&temp_name);
+                return Err(BellhopError::PublishedSnapshotIsStale {
+                    snapshot: snapshot_name.to_string(),
+                    repo: repo_name.to_string(),
+                });
+            }
+
+            info!("Snapshot '{snapshot_name}' is out of date, replacing it");
+            run_snapshot_drop_strictly(snapshot_name)?;
+            run_snapshot_rename(&temp_name, snapshot_name)?;
+            info!("Snapshot replaced successfully: {snapshot_name}");
+            Ok(())
+        }
+        Err(err) => {
+            run_snapshot_drop_by_name(&temp_name);
+            Err(err)
+        }
+    }
+}
+
+fn snapshots_are_identical(one: &str, other: &str) -> Result<bool, BellhopError> {
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("diff")
+            .arg(one)
+            .arg(other),
+    )?;
+
+    let output = check_aptly_output(output, format!("aptly snapshot diff {one} {other}"))?;
+
+    Ok(String::from_utf8_lossy(&output.stdout).contains(IDENTICAL_SNAPSHOTS_MARKER))
+}
+
+/// Extracts the snapshot name from an `aptly publish list` line such as
+/// `* prefix/bookworm [amd64] publishes {main: [snap-name]: Snapshot from local repo [repo-name]}`
+pub fn published_snapshot_name(
+    published_repos: &HashSet<String>,
+    prefix: &str,
+    distribution: &str,
+) -> Option<String> {
+    let location = format!("{prefix}/{distribution}");
+    published_repos
+        .iter()
+        .filter(|line| published_location(line) == Some(location.as_str()))
+        .find_map(|line| {
+            let (_, snapshot) = published_components(line).into_iter().next()?;
+            Some(snapshot.to_string())
+        })
+}
+
+/// The snapshot published under the given component, or under the first component when none
+/// is given
+pub fn published_component_snapshot(
+    published_repos: &HashSet<String>,
+    prefix: &str,
+    distribution: &str,
+    component: Option<&str>,
+) -> Option<String> {
+    let Some(component) = component else {
+        return published_snapshot_name(published_repos, prefix, distribution);
+    };
+    let location = format!("{prefix}/{distribution}");
+    published_repos
+        .iter()
+        .filter(|line| published_location(line) == Some(location.as_str()))
+        .flat_map(|line| published_components(line))
+        .find(|(name, _)| *name == component)
+        .map(|(_, snapshot)| snapshot.to_string())
+}
+
+/// The `(component, snapshot)` pairs of an `aptly publish list` line, e.g.
+/// `publishes {erlang: [snap-a]: ...}, {rabbitmq: [snap-b]: ...}`
+fn published_components(line: &str) -> Vec<(&str, &str)> {
+    let Some((_, sources)) = line.split_once("publishes {") else {
+        return Vec::new();
+    };
+    sources
+        .split('{')
+        .filter_map(|source| {
+            let (component, rest) = source.split_once(": [")?;
+            let (snapshot, _) = rest.split_once(']')?;
+            Some((component.trim(), snapshot))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanSnapshot {
+    pub name: String,
+    pub distribution: String,
+}
+
+/// Orphan snapshots, with a bounded date range only those whose date suffix falls into it
+pub fn find_orphan_snapshots(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    date_format: &str,
+    range: &DateRange,
+) -> Result<Vec<OrphanSnapshot>, BellhopError> {
+    let snapshot_names = list_snapshot_names()?;
+    let published_repos = list_published_repos()?;
+
+    Ok(target_releases
+        .iter()
+        .flat_map(|rel| {
+            orphan_snapshot_names(&snapshot_names, &published_repos, project, rel, date_format)
+                .into_iter()
+                .filter(|name| {
+                    range.is_unbounded()
+                        || snapshot_date(name, project, rel, date_format)
+                            .is_some_and(|date| range.contains(date))
+                })
+                .map(|name| OrphanSnapshot {
+                    name,
+                    distribution: rel.release_name().to_string(),
+                })
+        })
+        .collect())
+}
+
+/// Snapshots of the distribution that are neither published anywhere nor the one with
+/// the latest date suffix, in name order. Snapshots with other suffixes are only kept while published.
+pub fn orphan_snapshot_names(
+    snapshot_names: &HashSet<String>,
+    published_repos: &HashSet<String>,
+    project: &Project,
+    rel: &DistributionAlias,
+    date_format: &str,
+) -> Vec<String> {
+    let name_prefix = snapshot_name_with_suffix(project, rel, "");
+    let mut candidates: Vec<&String> = snapshot_names
+        .iter()
+        .filter(|name| name.starts_with(&name_prefix))
+        .collect();
+    candidates.sort();
+
+    let latest_dated = candidates
+        .iter()
+        .filter_map(|name| Some((snapshot_date(name, project, rel, date_format)?, *name)))
+        .max()
+        .map(|(_, name)| name);
+
+    candidates
+        .into_iter()
+        .filter(|name| Some(*name) != latest_dated && !is_snapshot_published(published_repos, name))
+        .cloned()
+        .collect()
+}
+
+/// A window of snapshot dates, both bounds are exclusive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub after: Option<NaiveDate>,
+    pub before: Option<NaiveDate>,
+}
+
+impl DateRange {
+    pub fn is_unbounded(&self) -> bool {
+        self.after.is_none() && self.before.is_none()
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.after.is_none_or(|after| date > after)
+            && self.before.is_none_or(|before| date < before)
+    }
+}
+
+/// The date in the suffix of a snapshot of the distribution, `None` for other snapshots and
+/// for suffixes that are not dates, e.g. `v1`
+pub fn snapshot_date(
+    snapshot_name: &str,
+    project: &Project,
+    rel: &DistributionAlias,
+    date_format: &str,
+) -> Option<NaiveDate> {
+    let suffix = snapshot_name.strip_prefix(&snapshot_name_with_suffix(project, rel, ""))?;
+    NaiveDate::parse_from_str(suffix, date_format).ok()
+}
+
+/// Snapshots of the distribution whose date suffix falls into the range, oldest first.
+/// Snapshots without a date suffix are left out.
+pub fn dated_snapshot_names(
+    snapshot_names: &HashSet<String>,
+    project: &Project,
+    rel: &DistributionAlias,
+    date_format: &str,
+    range: &DateRange,
+) -> Vec<String> {
+    let mut dated: Vec<(NaiveDate, &String)> = snapshot_names
+        .iter()
+        .filter_map(|name| Some((snapshot_date(name, project, rel, date_format)?, name)))
+        .filter(|(date, _)| range.contains(*date))
+        .collect();
+    dated.sort();
+    dated.into_iter().map(|(_, name)| name.clone()).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiff {
+    pub distribution: String,
+    pub from: String,
+    pub to: String,
+    #[serde(flatten)]
+    pub packages: PackageDiff,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PackageDiff {
+    /// Package keys, e.g. `rabbitmq-server_4.1.4-1_all`, of packages that only the newer snapshot has
+    pub added: Vec<String>,
+    /// Package keys of packages that only the older snapshot has
+    pub removed: Vec<String>,
+    /// Packages both snapshots have, but with different versions
+    pub changed: Vec<ChangedPackage>,
+}
+
+impl PackageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedPackage {
+    pub name: String,
+    pub architecture: String,
+    pub from: Vec<String>,
+    pub to: Vec<String>,
+}
+
+pub fn snapshot_diff(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    from_suffix: &str,
+    to_suffix: &str,
+) -> Result<Vec<SnapshotDiff>, BellhopError> {
+    let mut diffs = Vec::with_capacity(target_releases.len());
+    for rel in target_releases {
+        let from = snapshot_name_with_suffix(project, rel, from_suffix);
+        let to = snapshot_name_with_suffix(project, rel, to_suffix);
+        let packages =
+            diff_package_sets(&run_snapshot_packages(&from)?, &run_snapshot_packages(&to)?);
+        diffs.push(SnapshotDiff {
+            distribution: rel.release_name().to_string(),
+            from,
+            to,
+            packages,
+        });
+    }
+    Ok(diffs)
+}
+
+/// Packages are compared by name and architecture. A package whose versions differ between the
+/// two sets is reported as changed rather than as both added and removed.
+pub fn diff_package_sets(from: &[String], to: &[String]) -> PackageDiff {
+    let by_name_and_arch = |keys: &[String]| {
+        let mut packages: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+        for key in keys {
+            let mut parts = key.rsplitn(3, '_');
+            if let (Some(arch), Some(version), Some(name)) =
+                (parts.next(), parts.next(), parts.next())
+            {
+                packages
+                    .entry((name.to_string(), arch.to_string()))
+                    .or_default()
+                    .push(version.to_string());
+            }
+        }
+        for versions in packages.values_mut() {
+            versions.sort_by(|a, b| compare_versions(a, b));
+        }
+        packages
+    };
+    let from = by_name_and_arch(from);
+    let to = by_name_and_arch(to);
+    let keys = |(name, arch): &(String, String), versions: &[String]| -> Vec<String> {
+        versions
+            .iter()
+            .map(|version| format!("{name}_{version}_{arch}"))
+            .collect()
+    };
+
+    let mut diff = PackageDiff::default();
+    for (package, to_versions) in &to {
+        match from.get(package) {
+            None => diff.added.extend(keys(package, to_versions)),
+            Some(from_versions) if from_versions != to_versions => {
+                diff.changed.push(ChangedPackage {
+                    name: package.0.clone(),
+                    architecture: package.1.clone(),
+                    from: from_versions.clone(),
+                    to: to_versions.clone(),
                 });
             }
-
-            info!("Snapshot '{snapshot_name}' is out of date, replacing it");
-            run_snapshot_drop_strictly(snapshot_name)?;
-            run_snapshot_rename(&temp_name, snapshot_name)?;
-            info!("Snapshot replaced successfully: {snapshot_name}");
-            Ok(())
+            Some(_) => {}
         }
-        Err(err) => {
-            run_snapshot_drop_by_name(&temp_name);
-            Err(err)
+    }
+    for (package, from_versions) in &from {
+        if !to.contains_key(package) {
+            diff.removed.extend(keys(package, from_versions));
         }
     }
+    diff
 }
 
-fn snapshots_are_identical(one: &str, other: &str) -> Result<bool, BellhopError> {
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("diff")
-        .arg(one)
-        .arg(other)
-        .output()?;
-
-    let output = check_aptly_output(output, format!("aptly snapshot diff {one} {other}"))?;
-
-    Ok(String::from_utf8_lossy(&output.stdout).contains(IDENTICAL_SNAPSHOTS_MARKER))
+fn run_snapshot_packages(snapshot_name: &str) -> Result<Vec<String>, BellhopError> {
+    let output = snapshot_show_by_name(snapshot_name)?;
+    Ok(parse_snapshot_details(&output).packages)
 }
 
 fn is_snapshot_published(published_repos: &HashSet<String>, snapshot_name: &str) -> bool {
@@ -341,11 +1899,7 @@ fn is_snapshot_published(published_repos: &HashSet<String>, snapshot_name: &str)
 }
 
 fn list_snapshot_names() -> Result<HashSet<String>, BellhopError> {
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("list")
-        .arg("-raw")
-        .output()?;
+    let output = run_aptly(aptly_command().arg("snapshot").arg("list").arg("-raw"))?;
     let output = check_aptly_output(output, "aptly snapshot list -raw")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -367,12 +1921,43 @@ pub fn delete_snapshots(
     Ok(())
 }
 
+pub fn unpublish(
+    project: Project,
+    target_releases: &[DistributionAlias],
+    options: &PublishOptions,
+) -> Result<(), BellhopError> {
+    options.validate()?;
+    let published_repos = list_published_repos()?;
+    for rel in target_releases {
+        let rel_path = publish_target(
+            &options.rel_path_of(&project, rel),
+            options.endpoint.as_deref(),
+        );
+        for distribution in options.distribution_names_of(rel) {
+            if !publication_exists(&published_repos, &rel_path, distribution) {
+                info!("'{distribution}' is not published to '{rel_path}', nothing to drop");
+                continue;
+            }
+
+            info!("Dropping publication '{rel_path}/{distribution}'");
+            let output = run_aptly(
+                aptly_command()
+                    .arg("publish")
+                    .arg("drop")
+                    .arg(distribution)
+                    .arg(&rel_path),
+            )?;
+            check_aptly_output(
+                output,
+                format!("aptly publish drop {distribution} {rel_path}"),
+            )?;
+        }
+    }
+    Ok(())
+}
+
 pub fn list_repos() -> Result<HashSet<String>, BellhopError> {
-    let output = aptly_command()
-        .arg("repo")
-        .arg("list")
-        .arg("-raw")
-        .output()?;
+    let output = run_aptly(aptly_command().arg("repo").arg("list").arg("-raw"))?;
     let output = check_aptly_output(output, "aptly repo list -raw")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -385,15 +1970,122 @@ pub fn list_repos() -> Result<HashSet<String>, BellhopError> {
 
 pub fn create_repo(name: &str) -> Result<(), BellhopError> {
     info!("Creating repository '{name}'");
-    let output = aptly_command()
-        .arg("repo")
-        .arg("create")
-        .arg(name)
-        .output()?;
+    let output = run_aptly(aptly_command().arg("repo").arg("create").arg(name))?;
     check_aptly_output(output, format!("aptly repo create {name}"))?;
     Ok(())
 }
 
+/// What `aptly db cleanup` removed from the database and the package pool
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CleanupSummary {
+    pub packages_deleted: u64,
+    pub files_deleted: u64,
+    /// As formatted by aptly, e.g. `12.34 MiB`, absent when no files were deleted
+    pub space_freed: Option<String>,
+}
+
+pub fn db_cleanup() -> Result<CleanupSummary, BellhopError> {
+    info!("Removing the packages and files that nothing references");
+    let output = run_aptly(aptly_command().arg("db").arg("cleanup"))?;
+    let output = check_aptly_output(output, "aptly db cleanup")?;
+
+    // aptly reports its progress on either stream depending on the version
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(parse_db_cleanup_output(&format!("{stdout}\n{stderr}")))
+}
+
+/// Picks the counts out of lines such as `Deleting unreferenced files (5)...` and
+/// `Disk space freed: 12.34 MiB...`
+pub fn parse_db_cleanup_output(output: &str) -> CleanupSummary {
+    let count = |line: &str, prefix: &str| {
+        line.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_prefix('('))
+            .and_then(|rest| rest.split_once(')'))
+            .and_then(|(n, _)| n.parse::<u64>().ok())
+    };
+
+    let mut summary = CleanupSummary::default();
+    for line in output.lines().map(str::trim) {
+        if let Some(n) = count(line, "Deleting unreferenced packages ") {
+            summary.packages_deleted = n;
+        } else if let Some(n) = count(line, "Deleting unreferenced files ") {
+            summary.files_deleted = n;
+        } else if let Some(freed) = line.strip_prefix("Disk space freed:") {
+            summary.space_freed = Some(freed.trim().trim_end_matches('.').to_string());
+        }
+    }
+    summary
+}
+
+/// Brings the database back into a consistent state after a crash or an interrupted run
+pub fn db_recover() -> Result<(), BellhopError> {
+    info!("Recovering the aptly database");
+    let output = run_aptly(aptly_command().arg("db").arg("recover"))?;
+    check_aptly_output(output, "aptly db recover")?;
+    Ok(())
+}
+
+pub fn mirror_snapshot_name(mirror_name: &str, suffix: &str) -> String {
+    format!("snap-mirror-{mirror_name}-{suffix}")
+}
+
+/// Without components, aptly mirrors every component the upstream distribution has
+pub fn mirror_create_args(
+    name: &str,
+    url: &str,
+    distribution: &str,
+    components: &[String],
+    architectures: &[String],
+) -> Vec<String> {
+    let mut args = vec!["mirror".to_string(), "create".to_string()];
+    if !architectures.is_empty() {
+        args.push(format!("-architectures={}", architectures.join(",")));
+    }
+    args.extend([name.to_string(), url.to_string(), distribution.to_string()]);
+    args.extend(components.iter().cloned());
+    args
+}
+
+pub fn mirror_create(
+    name: &str,
+    url: &str,
+    distribution: &str,
+    components: &[String],
+    architectures: &[String],
+) -> Result<(), BellhopError> {
+    info!("Creating mirror '{name}' of {url} {distribution}");
+    let args = mirror_create_args(name, url, distribution, components, architectures);
+    let output = run_aptly(aptly_command().args(&args))?;
+    check_aptly_output(output, format!("aptly {}", args.join(" ")))?;
+    Ok(())
+}
+
+pub fn mirror_update(name: &str) -> Result<(), BellhopError> {
+    info!("Updating mirror '{name}'");
+    let output = run_aptly(aptly_command().arg("mirror").arg("update").arg(name))?;
+    check_aptly_output(output, format!("aptly mirror update {name}"))?;
+    Ok(())
+}
+
+pub fn mirror_snapshot(name: &str, snapshot_name: &str) -> Result<(), BellhopError> {
+    info!("Creating snapshot '{snapshot_name}' from mirror '{name}'");
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("create")
+            .arg(snapshot_name)
+            .arg("from")
+            .arg("mirror")
+            .arg(name),
+    )?;
+    check_aptly_output(
+        output,
+        format!("aptly snapshot create {snapshot_name} from mirror {name}"),
+    )?;
+    Ok(())
+}
+
 pub fn expected_repos() -> Vec<(Project, String)> {
     let mut repos = Vec::with_capacity(16);
     for dist in DistributionAlias::all() {
@@ -408,36 +2100,135 @@ pub fn expected_repos() -> Vec<(Project, String)> {
     repos
 }
 
-pub fn repo_name(project: &Project, rel: &DistributionAlias) -> String {
-    match project {
-        Project::RabbitMQ => {
-            format!("repo-rabbitmq-server-{rel}")
-        }
-        Project::Erlang => {
-            format!("repo-rabbitmq-erlang-{rel}")
-        }
-        Project::CliTools => {
-            format!("repo-rabbitmq-cli-{rel}")
-        }
+/// How the repositories in aptly compare to [`expected_repos`], every list is sorted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RepositoryStatus {
+    pub expected: Vec<String>,
+    pub present: Vec<String>,
+    pub missing: Vec<String>,
+    pub unexpected: Vec<String>,
+}
+
+pub fn repository_status(existing: &HashSet<String>) -> RepositoryStatus {
+    let mut expected: Vec<String> = expected_repos().into_iter().map(|(_, repo)| repo).collect();
+    expected.sort();
+
+    let (present, missing) = expected
+        .iter()
+        .cloned()
+        .partition(|repo| existing.contains(repo));
+    let mut unexpected: Vec<String> = existing
+        .iter()
+        .filter(|repo| !expected.contains(repo))
+        .cloned()
+        .collect();
+    unexpected.sort();
+
+    RepositoryStatus {
+        expected,
+        present,
+        missing,
+        unexpected,
     }
 }
 
+pub fn repo_name(project: &Project, rel: &DistributionAlias) -> String {
+    format!("repo-{}-{rel}", name_stem(project))
+}
+
 pub fn snapshot_name_with_suffix(
     project: &Project,
     rel: &DistributionAlias,
     suffix: &str,
 ) -> String {
-    let prefix = project_prefix(project);
+    let prefix = name_stem(project);
 
     format!("snap-{}-{}-{}", prefix, rel.release_name(), suffix)
 }
 
-pub fn rel_path_with_prefix(project: &Project, rel: &DistributionAlias) -> String {
-    let prefix = project_prefix(project);
-    format!("{}/{}/{}", prefix, rel.family_name(), rel.release_name())
+/// `family` is usually the distribution's own family but can be overridden so that Debian and
+/// Ubuntu packages are served under a single prefix
+pub fn rel_path_with_prefix(
+    project: &Project,
+    rel: &DistributionAlias,
+    family: DebianFamily,
+) -> String {
+    let prefix = name_stem(project);
+    format!("{}/{}/{}", prefix, family, rel.release_name())
+}
+
+/// A relative path of non-empty segments with no `..` and no storage part, or `.` for the root
+pub fn is_valid_publish_prefix(prefix: &str) -> bool {
+    if prefix == "." {
+        return true;
+    }
+    prefix.split('/').all(|segment| {
+        !segment.is_empty()
+            && segment != "."
+            && segment != ".."
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    })
+}
+
+/// The prefix argument of aptly publish commands, e.g. `s3:endpoint:rabbitmq-server/debian/bookworm`
+/// for an S3 endpoint
+pub fn publish_target(rel_path: &str, endpoint: Option<&str>) -> String {
+    match endpoint {
+        Some(endpoint) => format!("s3:{endpoint}:{rel_path}"),
+        None => rel_path.to_string(),
+    }
+}
+
+/// Replaces the project-specific component of repository, snapshot and publishing names,
+/// e.g. `rabbitmq-server` in `repo-rabbitmq-server-bookworm`. Only the first call in a process
+/// takes effect.
+pub fn set_repo_prefix(prefix: &str) -> Result<(), BellhopError> {
+    if !is_valid_name_component(prefix) {
+        return Err(BellhopError::InvalidRepoPrefix {
+            prefix: prefix.to_string(),
+        });
+    }
+
+    let _ = REPO_PREFIX.set(prefix.to_string());
+    Ok(())
+}
+
+/// Inserts a channel, e.g. `nightly`, into repository, snapshot and publishing names, so that
+/// several package streams can share an aptly instance. Only the first call in a process
+/// takes effect.
+pub fn set_channel(channel: &str) -> Result<(), BellhopError> {
+    if !is_valid_name_component(channel) {
+        return Err(BellhopError::InvalidChannel {
+            channel: channel.to_string(),
+        });
+    }
+
+    let _ = CHANNEL.set(channel.to_string());
+    Ok(())
+}
+
+fn is_valid_name_component(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// The project prefix followed by the channel, if any, e.g. `rabbitmq-server-nightly`
+fn name_stem(project: &Project) -> String {
+    match CHANNEL.get() {
+        Some(channel) => format!("{}-{channel}", project_prefix(project)),
+        None => project_prefix(project).to_string(),
+    }
 }
 
 pub fn project_prefix(project: &Project) -> &'static str {
+    if let Some(prefix) = REPO_PREFIX.get() {
+        return prefix;
+    }
+
     match project {
         Project::RabbitMQ => "rabbitmq-server",
         Project::Erlang => "rabbitmq-erlang",
@@ -445,93 +2236,250 @@ pub fn project_prefix(project: &Project) -> &'static str {
     }
 }
 
+/// `None` means aptly's default, that is, every architecture of the package
+pub fn architectures(project: &Project) -> Option<&'static [&'static str]> {
+    match project {
+        Project::RabbitMQ => Some(ALL_ARCHITECTURES),
+        Project::CliTools => Some(AMD64_ONLY),
+        Project::Erlang => None,
+    }
+}
+
+pub fn architectures_arg(project: &Project) -> Option<String> {
+    architectures(project).map(|archs| format!("-architectures={}", archs.join(",")))
+}
+
+/// Packages for the `all` architecture and source packages are compatible with every repository
+pub fn check_package_architecture(project: &Project, deb_path: &Path) -> Result<(), BellhopError> {
+    let file_name = deb_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if archive::is_source_package(file_name) {
+        return Ok(());
+    }
+    let architecture = archive::extract_architecture_from_filename(file_name)?;
+
+    match architectures(project) {
+        Some(allowed) if architecture != "all" && !allowed.contains(&architecture.as_str()) => {
+            Err(BellhopError::IncompatibleArchitecture {
+                package: file_name.to_string(),
+                architecture,
+                allowed: allowed.join(", "),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
 fn run_repo_add(
     project: &Project,
     package_file_path: &Path,
     repo_name: &str,
     rel: &DistributionAlias,
 ) -> Result<(), BellhopError> {
-    let path_str = package_file_path.display();
-    info!("Adding package {path_str} to repo '{repo_name}' for distribution '{rel}'");
-
-    let arch_arg = match project {
-        Project::RabbitMQ => Some(ALL_ARCHITECTURES_ARG),
-        Project::CliTools => Some(AMD64_ONLY_ARG),
-        Project::Erlang => None,
-    };
+    info!(
+        "Adding package {} to repo '{repo_name}' for distribution '{rel}'",
+        package_file_path.display()
+    );
+    repo_add(project, package_file_path, repo_name)
+}
 
-    let output = aptly_command()
-        .arg("repo")
-        .arg("add")
-        .args(arch_arg)
-        .arg(repo_name)
-        .arg(package_file_path)
-        .output()?;
+fn repo_add(
+    project: &Project,
+    package_file_path: &Path,
+    repo_name: &str,
+) -> Result<(), BellhopError> {
+    let path_str = package_file_path.display();
+    let arch_arg = architectures_arg(project);
+
+    let output = run_aptly(
+        aptly_command()
+            .arg("repo")
+            .arg("add")
+            .args(arch_arg)
+            .arg(repo_name)
+            .arg(package_file_path),
+    )?;
     check_aptly_output(output, format!("aptly repo add {repo_name} {path_str}"))?;
 
     debug!("Package added successfully");
     Ok(())
 }
 
-fn run_repo_remove(project: &Project, version: &str, repo_name: &str) -> Result<(), BellhopError> {
-    let query = match project {
-        Project::RabbitMQ => format!("rabbitmq-server (= {version})"),
-        Project::Erlang => format!("Name (~ ^erlang), Version (= {version})"),
-        Project::CliTools => format!("Version (= {version})"),
-    };
+/// Matches every package of a project, regardless of version
+fn package_query(project: &Project) -> &'static str {
+    match project {
+        Project::RabbitMQ => "rabbitmq-server",
+        Project::Erlang => "Name (~ ^erlang)",
+        Project::CliTools => "Name (~ .)",
+    }
+}
+
+/// Checks that the repository of every distribution has a package of the `expected` version
+pub fn verify_imported_version(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    expected: &str,
+) -> Result<(), BellhopError> {
+    for rel in target_releases {
+        let repo_name = repo_name(project, rel);
+        let packages = run_repo_search(&repo_name, package_query(project))?;
+        if !packages
+            .iter()
+            .any(|(_, version)| matches_expected_version(version, expected))
+        {
+            return Err(BellhopError::ExpectedVersionNotFound {
+                version: expected.to_string(),
+                repo: repo_name,
+            });
+        }
+        info!("Repo '{repo_name}' has version {expected}");
+    }
+    Ok(())
+}
+
+/// The version itself, or the version with a Debian revision, e.g. `4.2.3-1` or
+/// `27.3-1~noble` for `4.2.3` and `27.3`
+pub fn matches_expected_version(version: &str, expected: &str) -> bool {
+    version
+        .strip_prefix(expected)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+/// `aptly repo search` exits with an error when nothing matches, which is not a failure here
+fn run_repo_search(repo_name: &str, query: &str) -> Result<Vec<(String, String)>, BellhopError> {
+    let output = run_aptly(
+        aptly_command()
+            .arg("repo")
+            .arg("search")
+            .arg(repo_name)
+            .arg(query),
+    )?;
+
+    if !output.status.success() && String::from_utf8_lossy(&output.stderr).contains("no results") {
+        return Ok(Vec::new());
+    }
+    let output = check_aptly_output(output, format!("aptly repo search {repo_name} {query}"))?;
+
+    Ok(parse_package_keys(&String::from_utf8_lossy(&output.stdout)))
+}
 
+fn run_repo_remove_query(repo_name: &str, query: &str) -> Result<(), BellhopError> {
     info!("Removing packages matching query '{query}' from repo '{repo_name}'");
 
-    let output = aptly_command()
-        .arg("repo")
-        .arg("remove")
-        .arg(repo_name)
-        .arg(&query)
-        .output()?;
+    let output = run_aptly(
+        aptly_command()
+            .arg("repo")
+            .arg("remove")
+            .arg(repo_name)
+            .arg(query),
+    )?;
 
     check_aptly_output(output, format!("aptly repo remove {repo_name} {query}"))?;
     Ok(())
 }
 
 fn run_snapshot_show(
-    project: &Project,
-    rel: &DistributionAlias,
-    suffix: &str,
+    snapshot_name: &str,
+    package_filter: Option<&str>,
 ) -> Result<(), BellhopError> {
-    let snapshot_name = snapshot_name_with_suffix(project, rel, suffix);
+    let stdout = snapshot_show_by_name(snapshot_name)?;
+    match package_filter {
+        Some(filter) => print!("{}", filter_snapshot_packages(&stdout, filter)),
+        None => print!("{stdout}"),
+    }
 
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("show")
-        .arg("-with-packages")
-        .arg(&snapshot_name)
-        .output()?;
+    Ok(())
+}
 
-    let output = check_aptly_output(
+fn run_snapshot_create_by_name(snapshot_name: &str, repo_name: &str) -> Result<(), BellhopError> {
+    info!("Creating snapshot '{snapshot_name}' from repo '{repo_name}'");
+
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("create")
+            .arg(snapshot_name)
+            .arg("from")
+            .arg("repo")
+            .arg(repo_name),
+    )?;
+
+    check_aptly_output(
         output,
-        format!("aptly snapshot show -with-packages {snapshot_name}"),
+        format!("aptly snapshot create {snapshot_name} from repo {repo_name}"),
     )?;
 
-    print!("{}", String::from_utf8_lossy(&output.stdout));
+    info!("Snapshot created successfully: {snapshot_name}");
+    Ok(())
+}
+
+fn run_snapshot_copy(snapshot_name: &str, source: &str) -> Result<(), BellhopError> {
+    info!("Creating snapshot '{snapshot_name}' as a copy of '{source}'");
 
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("merge")
+            .arg(snapshot_name)
+            .arg(source),
+    )?;
+    check_aptly_output(
+        output,
+        format!("aptly snapshot merge {snapshot_name} {source}"),
+    )?;
+
+    info!("Snapshot created successfully: {snapshot_name}");
     Ok(())
 }
 
-fn run_snapshot_create_by_name(snapshot_name: &str, repo_name: &str) -> Result<(), BellhopError> {
-    info!("Creating snapshot '{snapshot_name}' from repo '{repo_name}'");
+fn run_snapshot_merge(
+    snapshot_name: &str,
+    base: &str,
+    repo_snapshot: &str,
+) -> Result<(), BellhopError> {
+    info!("Creating snapshot '{snapshot_name}' by merging '{base}' with '{repo_snapshot}'");
 
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("create")
-        .arg(snapshot_name)
-        .arg("from")
-        .arg("repo")
-        .arg(repo_name)
-        .output()?;
+    // -no-remove keeps every version from both sources instead of letting the repo win
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("merge")
+            .arg("-no-remove")
+            .arg(snapshot_name)
+            .arg(base)
+            .arg(repo_snapshot),
+    )?;
 
     check_aptly_output(
         output,
-        format!("aptly snapshot create {snapshot_name} from repo {repo_name}"),
+        format!("aptly snapshot merge -no-remove {snapshot_name} {base} {repo_snapshot}"),
+    )?;
+
+    info!("Snapshot created successfully: {snapshot_name}");
+    Ok(())
+}
+
+fn run_snapshot_filter(
+    source_name: &str,
+    snapshot_name: &str,
+    query: &str,
+) -> Result<(), BellhopError> {
+    info!("Creating snapshot '{snapshot_name}' from '{source_name}' filtered by '{query}'");
+
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("filter")
+            .arg(source_name)
+            .arg(snapshot_name)
+            .arg(query),
+    )?;
+
+    check_aptly_output(
+        output,
+        format!("aptly snapshot filter {source_name} {snapshot_name} {query}"),
     )?;
 
     info!("Snapshot created successfully: {snapshot_name}");
@@ -539,12 +2487,13 @@ fn run_snapshot_create_by_name(snapshot_name: &str, repo_name: &str) -> Result<(
 }
 
 fn run_snapshot_rename(old_name: &str, new_name: &str) -> Result<(), BellhopError> {
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("rename")
-        .arg(old_name)
-        .arg(new_name)
-        .output()?;
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("rename")
+            .arg(old_name)
+            .arg(new_name),
+    )?;
 
     check_aptly_output(
         output,
@@ -556,12 +2505,13 @@ fn run_snapshot_rename(old_name: &str, new_name: &str) -> Result<(), BellhopErro
 /// `aptly` refuses to drop a published snapshot even with `-force`, which only overrides
 /// snapshots referenced by other snapshots.
 fn run_snapshot_drop_strictly(snapshot_name: &str) -> Result<(), BellhopError> {
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("drop")
-        .arg("-force")
-        .arg(snapshot_name)
-        .output()?;
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("drop")
+            .arg("-force")
+            .arg(snapshot_name),
+    )?;
 
     check_aptly_output(
         output,
@@ -574,12 +2524,13 @@ fn run_snapshot_drop_strictly(snapshot_name: &str) -> Result<(), BellhopError> {
 fn run_snapshot_drop_by_name(snapshot_name: &str) {
     debug!("Dropping snapshot '{snapshot_name}'");
 
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("drop")
-        .arg("-force")
-        .arg(snapshot_name)
-        .output();
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("drop")
+            .arg("-force")
+            .arg(snapshot_name),
+    );
 
     if let Ok(out) = output
         && !out.status.success()
@@ -603,12 +2554,13 @@ fn run_snapshot_drop(
     // Drop is allowed to fail (snapshot may not exist)
     // Use -force to allow dropping published snapshots
     // Ignore all errors including IO errors
-    let output = aptly_command()
-        .arg("snapshot")
-        .arg("drop")
-        .arg("-force")
-        .arg(&snapshot_name)
-        .output();
+    let output = run_aptly(
+        aptly_command()
+            .arg("snapshot")
+            .arg("drop")
+            .arg("-force")
+            .arg(&snapshot_name),
+    );
 
     if let Ok(out) = output {
         if !out.status.success() {
@@ -625,16 +2577,108 @@ fn run_snapshot_drop(
 }
 
 fn list_published_repos() -> Result<HashSet<String>, BellhopError> {
-    let output = aptly_command().arg("publish").arg("list").output()?;
+    let output = run_aptly(aptly_command().arg("publish").arg("list"))?;
     let output = check_aptly_output(output, "aptly publish list")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     Ok(stdout.lines().map(|l| l.to_string()).collect())
 }
 
-fn is_repo_published(published_repos: &HashSet<String>, prefix: &str, distribution: &str) -> bool {
-    let search_pattern = format!("{prefix}/{distribution}");
-    published_repos.iter().any(|p| p.contains(&search_pattern))
+/// `prefix` must include the storage, e.g. `s3:endpoint:`, of a publication that is not local,
+/// since a local publication and an S3 one can share the same path
+pub fn publication_exists(
+    published_repos: &HashSet<String>,
+    prefix: &str,
+    distribution: &str,
+) -> bool {
+    let location = format!("{prefix}/{distribution}");
+    published_repos
+        .iter()
+        .any(|line| published_location(line) == Some(location.as_str()))
+}
+
+/// Whether the publication at `prefix/distribution` has the given component
+pub fn publication_has_component(
+    published_repos: &HashSet<String>,
+    prefix: &str,
+    distribution: &str,
+    component: &str,
+) -> bool {
+    published_component_snapshot(published_repos, prefix, distribution, Some(component)).is_some()
+}
+
+/// The `[storage:]prefix/distribution` part of an `aptly publish list` line
+fn published_location(line: &str) -> Option<&str> {
+    line.trim_start()
+        .strip_prefix("* ")?
+        .split_whitespace()
+        .next()
+}
+
+/// `publish switch` replaces the snapshot of an existing publication,
+/// `publish snapshot` creates the publication
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishMethod {
+    Switch,
+    Snapshot,
+}
+
+impl PublishMethod {
+    fn description(self) -> &'static str {
+        match self {
+            PublishMethod::Switch => "switching the existing publication",
+            PublishMethod::Snapshot => "initial publish",
+        }
+    }
+}
+
+pub fn publish_args(
+    method: PublishMethod,
+    distribution: &str,
+    rel_path: &str,
+    snapshot_name: &str,
+    gpg_key: &str,
+    options: &PublishOptions,
+) -> Vec<String> {
+    let mut flags = vec![gpg_key.to_string()];
+    flags.extend(options.flags());
+
+    let mut args = vec!["publish".to_string()];
+    match method {
+        PublishMethod::Switch => {
+            args.push("switch".to_string());
+            args.extend(flags);
+            args.extend([distribution, rel_path, snapshot_name].map(String::from));
+        }
+        PublishMethod::Snapshot => {
+            args.extend(["snapshot", "-distribution", distribution].map(String::from));
+            args.extend(flags);
+            args.extend([snapshot_name, rel_path].map(String::from));
+        }
+    }
+    args
+}
+
+/// `aptly publish update` has no `-component` flag, it updates every component of a publication
+pub fn publish_update_args(
+    distribution: &str,
+    rel_path: &str,
+    gpg_key: &str,
+    options: &PublishOptions,
+) -> Vec<String> {
+    let mut args = vec![
+        "publish".to_string(),
+        "update".to_string(),
+        gpg_key.to_string(),
+    ];
+    args.extend(
+        options
+            .flags()
+            .into_iter()
+            .filter(|flag| !flag.starts_with("-component=")),
+    );
+    args.extend([distribution, rel_path].map(String::from));
+    args
 }
 
 fn run_snapshot_switch(
@@ -642,58 +2686,111 @@ fn run_snapshot_switch(
     rel: &DistributionAlias,
     suffix: &str,
     published_repos: &HashSet<String>,
+    options: &PublishOptions,
 ) -> Result<(), BellhopError> {
     let snapshot_name = snapshot_name_with_suffix(project, rel, suffix);
-    let rel_path = rel_path_with_prefix(project, rel);
-
-    info!("Publishing snapshot '{snapshot_name}' to '{rel_path}'");
-
-    let gpg_key = gpg_key_arg();
-
-    if is_repo_published(published_repos, &rel_path, rel.release_name()) {
-        let output = aptly_command()
-            .arg("publish")
-            .arg("switch")
-            .arg(&gpg_key)
-            .arg(rel.release_name())
-            .arg(&rel_path)
-            .arg(&snapshot_name)
-            .output()?;
-
-        check_aptly_output(
-            output,
-            format!(
-                "aptly publish switch {} {} {} {}",
-                gpg_key,
-                rel.release_name(),
-                rel_path,
-                snapshot_name
-            ),
-        )?;
+    let rel_path = publish_target(
+        &options.rel_path_of(project, rel),
+        options.endpoint.as_deref(),
+    );
+
+    let distributions = options.distribution_names_of(rel);
+    // the snapshots to clean up are the ones published before the switch
+    let replaced: Vec<String> = if options.cleanup_previous {
+        distributions
+            .iter()
+            .filter_map(|distribution| {
+                published_component_snapshot(
+                    published_repos,
+                    &rel_path,
+                    distribution,
+                    options.component.as_deref(),
+                )
+            })
+            .collect()
     } else {
-        debug!("Publication does not exist, using 'publish snapshot' instead of 'switch'");
+        Vec::new()
+    };
 
-        let output = aptly_command()
-            .arg("publish")
-            .arg("snapshot")
-            .arg("-distribution")
-            .arg(rel.release_name())
-            .arg(&gpg_key)
-            .arg(&snapshot_name)
-            .arg(&rel_path)
-            .output()?;
-
-        check_aptly_output(
-            output,
-            format!(
-                "aptly publish snapshot -distribution {} {} {} {}",
-                rel.release_name(),
-                gpg_key,
-                snapshot_name,
-                rel_path
-            ),
-        )?;
+    for (i, distribution) in distributions.iter().copied().enumerate() {
+        info!("Publishing snapshot '{snapshot_name}' to '{rel_path}' as '{distribution}'");
+
+        let method = if publication_exists(published_repos, &rel_path, distribution) {
+            // aptly can only switch the components a publication already has
+            if let Some(component) = options.component.as_deref()
+                && !publication_has_component(published_repos, &rel_path, distribution, component)
+            {
+                return Err(BellhopError::ComponentNotPublished {
+                    distribution: distribution.to_string(),
+                    prefix: rel_path.clone(),
+                    component: component.to_string(),
+                });
+            }
+            PublishMethod::Switch
+        } else {
+            debug!("Publication does not exist, using 'publish snapshot' instead of 'switch'");
+            PublishMethod::Snapshot
+        };
+        let args = publish_args(
+            method,
+            distribution,
+            &rel_path,
+            &snapshot_name,
+            &gpg_key_arg(),
+            options,
+        );
+
+        // a failed additional publication is reported under its own name
+        let failed_distribution = if i == 0 {
+            rel.release_name()
+        } else {
+            distribution
+        };
+        let output = run_aptly(aptly_command().args(&args))?;
+        check_aptly_output(output, format!("aptly {}", args.join(" "))).map_err(|e| {
+            BellhopError::PublishFailed {
+                distribution: failed_distribution.to_string(),
+                attempt: method.description().to_string(),
+                source: Box::new(e),
+            }
+        })?;
     }
 
+    let switched: Vec<String> = distributions
+        .iter()
+        .map(|distribution| format!("{rel_path}/{distribution}"))
+        .collect();
+    drop_replaced_snapshots(&replaced, &snapshot_name, &switched, published_repos);
     Ok(())
 }
+
+/// Drops the snapshots a switch has replaced. The publication has already been switched at this
+/// point, so a snapshot that cannot be dropped is only reported.
+fn drop_replaced_snapshots(
+    replaced: &[String],
+    active_snapshot: &str,
+    switched_locations: &[String],
+    published_repos: &HashSet<String>,
+) {
+    let mut dropped = HashSet::new();
+    for snapshot_name in replaced {
+        if snapshot_name == active_snapshot || !dropped.insert(snapshot_name.as_str()) {
+            continue;
+        }
+        let search_pattern = format!("[{snapshot_name}]");
+        let published_elsewhere = published_repos.iter().any(|line| {
+            line.contains(&search_pattern)
+                && published_location(line)
+                    .is_none_or(|location| !switched_locations.iter().any(|s| s == location))
+        });
+        if published_elsewhere {
+            info!("Keeping snapshot '{snapshot_name}', it is still published elsewhere");
+            continue;
+        }
+
+        info!("Dropping the previously published snapshot '{snapshot_name}'");
+        if let Err(e) = run_snapshot_drop_strictly(snapshot_name) {
+            warn!("Could not drop the previously published snapshot '{snapshot_name}': {e}");
+        }
+    }
+}
@@ -11,16 +11,47 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use crate::common::sync_file_and_parent;
 use crate::errors::BellhopError;
+use crate::gh::releases::glob_match;
+use crate::progress;
 use flate2::read::GzDecoder;
 use log::{debug, info};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{self, Display, Formatter};
 use std::fs::{self, File};
-use std::io::{self, Read};
-use std::path::{Path, PathBuf};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
 use tar::Archive;
 use tempfile::TempDir;
 use zip::ZipArchive;
 
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZIP_MAGIC: &[u8] = b"PK";
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+pub(crate) const AR_MAGIC: &[u8] = b"!<arch>\n";
+/// Size of the header that precedes every member of an `ar` archive
+pub(crate) const AR_MEMBER_HEADER_LEN: usize = 60;
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+
+/// A gzip member has a 10 byte header and an 8 byte trailer
+const MIN_GZIP_LEN: u64 = 18;
+/// A tar archive has at least one 512 byte header block
+const MIN_TAR_LEN: u64 = 512;
+/// The end of central directory record of a zip archive is 22 bytes long
+const MIN_ZIP_LEN: u64 = 22;
+/// How many levels of subdirectories are searched for packages and nested archives
+const MAX_SEARCH_DEPTH: usize = 2;
+/// How many levels of zip archives inside a zip archive are extracted
+const MAX_NESTED_ZIP_DEPTH: usize = 3;
+/// zip archives store the target of a symlink as its contents, `PATH_MAX` on Linux
+const MAX_SYMLINK_TARGET_LENGTH: u64 = 4096;
+/// Package files that are sometimes shipped alongside or instead of `.deb`s
+const UNSUPPORTED_PACKAGE_FORMATS: &[&str] = &[".deb.sig", ".ddeb", ".rpm"];
+
 pub enum PackageSource {
     SingleDeb(PathBuf),
     Archive {
@@ -29,67 +60,429 @@ pub enum PackageSource {
     },
 }
 
-pub fn process_package_file(package_file_path: &Path) -> Result<PackageSource, BellhopError> {
+impl PackageSource {
+    pub fn deb_paths(&self) -> Vec<&Path> {
+        match self {
+            PackageSource::SingleDeb(path) => vec![path.as_path()],
+            PackageSource::Archive { deb_files, .. } => {
+                deb_files.iter().map(PathBuf::as_path).collect()
+            }
+        }
+    }
+}
+
+/// How a package file is interpreted. `Auto` goes by the file extension, then by the file contents.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum InputFormat {
+    #[default]
+    Auto,
+    Deb,
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(InputFormat::Auto),
+            "deb" => Ok(InputFormat::Deb),
+            "tar" => Ok(InputFormat::Tar),
+            "tar.gz" | "tgz" => Ok(InputFormat::TarGz),
+            "zip" => Ok(InputFormat::Zip),
+            _ => Err(format!("Unsupported input format: {s}")),
+        }
+    }
+}
+
+impl Display for InputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InputFormat::Auto => write!(f, "auto"),
+            InputFormat::Deb => write!(f, "deb"),
+            InputFormat::Tar => write!(f, "tar"),
+            InputFormat::TarGz => write!(f, "tar.gz"),
+            InputFormat::Zip => write!(f, "zip"),
+        }
+    }
+}
+
+pub const DEFAULT_MAX_EXTRACTED_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+pub const DEFAULT_MAX_ENTRIES: u64 = 100_000;
+
+#[derive(Debug, Clone)]
+pub struct ExtractionOptions {
+    /// Flush every extracted .deb file and its directory to disk before it is handed to aptly
+    pub fsync: bool,
+    pub input_format: InputFormat,
+    /// Upper bound on the total size of extracted files, nested archives included
+    pub max_extracted_bytes: u64,
+    /// Upper bound on the number of archive entries, nested archives included
+    pub max_entries: u64,
+    /// Only keep packages for these architectures (and `all`)
+    pub only_architectures: Option<Vec<String>>,
+    /// Also add `.ddeb` debug symbol packages, which are skipped otherwise
+    pub include_ddeb: bool,
+    /// Skip the packages in an archive whose file name matches one of these glob patterns
+    pub exclude: Vec<String>,
+    /// Also add `.dsc` source packages, aptly picks up the tarballs they list from the same directory
+    pub with_source: bool,
+    /// Replace symlinks with copies of their targets, which must be within the extraction
+    /// directory. Symlinks are skipped otherwise.
+    pub follow_symlinks: bool,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            fsync: false,
+            input_format: InputFormat::default(),
+            max_extracted_bytes: DEFAULT_MAX_EXTRACTED_BYTES,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            only_architectures: None,
+            include_ddeb: false,
+            exclude: Vec::new(),
+            with_source: false,
+            follow_symlinks: false,
+        }
+    }
+}
+
+/// Running totals of an extraction, checked against the limits in [`ExtractionOptions`]
+/// so that an archive bomb is stopped before it fills up the disk
+struct ExtractionBudget<'a> {
+    archive_path: &'a Path,
+    max_bytes: u64,
+    max_entries: u64,
+    bytes: u64,
+    entries: u64,
+}
+
+impl<'a> ExtractionBudget<'a> {
+    fn new(archive_path: &'a Path, options: &ExtractionOptions) -> Self {
+        Self {
+            archive_path,
+            max_bytes: options.max_extracted_bytes,
+            max_entries: options.max_entries,
+            bytes: 0,
+            entries: 0,
+        }
+    }
+
+    fn count_entry(&mut self) -> Result<(), BellhopError> {
+        self.entries += 1;
+        if self.entries > self.max_entries {
+            return Err(self.too_large(format!("{} entries", self.max_entries)));
+        }
+        Ok(())
+    }
+
+    fn copy<R: Read, W: Write>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> Result<(), BellhopError> {
+        let remaining = self.max_bytes - self.bytes;
+        // Reading one byte past the limit tells an entry that fits exactly from one that does not
+        let copied = match io::copy(&mut reader.take(remaining.saturating_add(1)), writer) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(truncated_archive(self.archive_path));
+            }
+            result => result?,
+        };
+        if copied > remaining {
+            return Err(self.too_large(format!("{} bytes", self.max_bytes)));
+        }
+        self.bytes += copied;
+        Ok(())
+    }
+
+    fn too_large(&self, limit: String) -> BellhopError {
+        BellhopError::ArchiveTooLarge {
+            path: self.archive_path.to_path_buf(),
+            limit,
+        }
+    }
+}
+
+pub fn process_package_file(
+    package_file_path: &Path,
+    options: &ExtractionOptions,
+) -> Result<PackageSource, BellhopError> {
     let file_name = package_file_path
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("");
 
+    let format = match options.input_format {
+        InputFormat::Auto if options.include_ddeb && is_ddeb(file_name) => {
+            debug!("Detected .ddeb file: {}", package_file_path.display());
+            return Ok(PackageSource::SingleDeb(package_file_path.to_path_buf()));
+        }
+        InputFormat::Auto if options.with_source && is_source_package(file_name) => {
+            debug!("Detected .dsc file: {}", package_file_path.display());
+            return Ok(PackageSource::SingleDeb(package_file_path.to_path_buf()));
+        }
+        InputFormat::Auto => detect_input_format(package_file_path, file_name)?,
+        forced => {
+            info!(
+                "Treating {} as {forced} as requested",
+                package_file_path.display()
+            );
+            forced
+        }
+    };
+
+    check_archive_length(package_file_path, format)?;
+    let source = match format {
+        InputFormat::TarGz => extract_tar_gz(package_file_path, options)?,
+        InputFormat::Tar => extract_tar(package_file_path, options)?,
+        InputFormat::Zip => extract_zip(package_file_path, options)?,
+        InputFormat::Deb => named_as_deb(package_file_path, file_name)?,
+        InputFormat::Auto => PackageSource::SingleDeb(package_file_path.to_path_buf()),
+    };
+
+    match &options.only_architectures {
+        Some(allowed) => keep_architectures(source, package_file_path, allowed),
+        None => Ok(source),
+    }
+}
+
+fn keep_architectures(
+    source: PackageSource,
+    package_file_path: &Path,
+    allowed: &[String],
+) -> Result<PackageSource, BellhopError> {
+    let no_debs_left = || BellhopError::NoDebFilesInArchive {
+        path: package_file_path.to_path_buf(),
+    };
+
+    match source {
+        PackageSource::SingleDeb(path) => {
+            if filter_by_architecture(vec![path.clone()], allowed).is_empty() {
+                return Err(no_debs_left());
+            }
+            Ok(PackageSource::SingleDeb(path))
+        }
+        PackageSource::Archive {
+            deb_files,
+            _temp_dir,
+        } => {
+            let deb_files = filter_by_architecture(deb_files, allowed);
+            if deb_files.is_empty() {
+                return Err(no_debs_left());
+            }
+            Ok(PackageSource::Archive {
+                deb_files,
+                _temp_dir,
+            })
+        }
+    }
+}
+
+/// Packages for `all` are always allowed, packages without an architecture in their file name never are
+pub fn is_allowed_architecture(file_name: &str, allowed: &[String]) -> bool {
+    extract_architecture_from_filename(file_name)
+        .is_ok_and(|arch| arch == "all" || allowed.contains(&arch))
+}
+
+/// Source packages are kept, they are not built for any architecture
+pub fn filter_by_architecture(deb_files: Vec<PathBuf>, allowed: &[String]) -> Vec<PathBuf> {
+    deb_files
+        .into_iter()
+        .filter(|path| {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let keep = is_source_package(file_name) || is_allowed_architecture(file_name, allowed);
+            if !keep {
+                info!("Skipping {file_name}: not built for {}", allowed.join(", "));
+            }
+            keep
+        })
+        .collect()
+}
+
+pub fn exclude_matching(deb_files: Vec<PathBuf>, patterns: &[String]) -> Vec<PathBuf> {
+    deb_files
+        .into_iter()
+        .filter(|path| {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            match patterns.iter().find(|p| glob_match(p, file_name)) {
+                Some(pattern) => {
+                    info!("Skipping {file_name}: excluded by '{pattern}'");
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect()
+}
+
+/// Goes by the file extension, then by the leading bytes of the file. Returns `Auto`
+/// when neither is recognized.
+fn detect_input_format(
+    package_file_path: &Path,
+    file_name: &str,
+) -> Result<InputFormat, BellhopError> {
     let file_name_lower = file_name.to_lowercase();
 
     if file_name_lower.ends_with(".deb") {
         debug!("Detected .deb file: {}", package_file_path.display());
-        return Ok(PackageSource::SingleDeb(package_file_path.to_path_buf()));
+        return Ok(InputFormat::Deb);
     }
 
     if file_name_lower.ends_with(".tar.gz") || file_name_lower.ends_with(".tgz") {
         info!("Detected .tar.gz archive: {}", package_file_path.display());
-        return extract_tar_gz(package_file_path);
+        return Ok(InputFormat::TarGz);
     }
 
     if file_name_lower.ends_with(".tar") {
         info!("Detected .tar archive: {}", package_file_path.display());
-        return extract_tar(package_file_path);
+        return Ok(InputFormat::Tar);
     }
 
     if file_name_lower.ends_with(".zip") {
         info!("Detected .zip archive: {}", package_file_path.display());
-        return extract_zip(package_file_path);
+        return Ok(InputFormat::Zip);
     }
 
-    debug!("Assuming .deb file: {}", package_file_path.display());
-    Ok(PackageSource::SingleDeb(package_file_path.to_path_buf()))
+    sniff_input_format(package_file_path)
+}
+
+fn sniff_input_format(package_file_path: &Path) -> Result<InputFormat, BellhopError> {
+    let mut header = Vec::with_capacity(TAR_MAGIC_OFFSET + TAR_MAGIC.len());
+    File::open(package_file_path)?
+        .take((TAR_MAGIC_OFFSET + TAR_MAGIC.len()) as u64)
+        .read_to_end(&mut header)?;
+
+    let unsupported = |format: &str| BellhopError::UnsupportedArchiveFormat {
+        path: package_file_path.to_path_buf(),
+        format: format.to_string(),
+    };
+
+    let format = if header.starts_with(GZIP_MAGIC) {
+        InputFormat::TarGz
+    } else if header.starts_with(ZIP_MAGIC) {
+        InputFormat::Zip
+    } else if header.starts_with(XZ_MAGIC) {
+        return Err(unsupported("xz"));
+    } else if header.starts_with(ZSTD_MAGIC) {
+        return Err(unsupported("zstd"));
+    } else if header.starts_with(AR_MAGIC) {
+        InputFormat::Deb
+    } else if header.get(TAR_MAGIC_OFFSET..) == Some(TAR_MAGIC) {
+        InputFormat::Tar
+    } else {
+        debug!("Assuming .deb file: {}", package_file_path.display());
+        return Ok(InputFormat::Auto);
+    };
+
+    info!(
+        "Detected {format} content in {}",
+        package_file_path.display()
+    );
+    Ok(format)
 }
 
-fn extract_tar_gz(archive_path: &Path) -> Result<PackageSource, BellhopError> {
+/// Tells an empty or cut off download apart from an archive that is corrupted in other ways,
+/// which would otherwise fail with a cryptic decoder error
+fn check_archive_length(path: &Path, format: InputFormat) -> Result<(), BellhopError> {
+    let min_len = match format {
+        InputFormat::TarGz => MIN_GZIP_LEN,
+        InputFormat::Tar => MIN_TAR_LEN,
+        InputFormat::Zip => MIN_ZIP_LEN,
+        InputFormat::Deb | InputFormat::Auto => return Ok(()),
+    };
+
+    if fs::metadata(path)?.len() < min_len {
+        return Err(truncated_archive(path));
+    }
+    Ok(())
+}
+
+fn truncated_archive(path: &Path) -> BellhopError {
+    BellhopError::EmptyOrTruncatedArchive {
+        path: path.to_path_buf(),
+    }
+}
+
+/// aptly skips files without a .deb extension, so a package without one gets
+/// a temporary copy with that extension
+fn named_as_deb(package_file_path: &Path, file_name: &str) -> Result<PackageSource, BellhopError> {
+    if file_name.to_lowercase().ends_with(".deb") {
+        return Ok(PackageSource::SingleDeb(package_file_path.to_path_buf()));
+    }
+
+    let temp_dir = TempDir::new()?;
+    let deb_path = temp_dir.path().join(format!("{file_name}.deb"));
+    fs::copy(package_file_path, &deb_path)?;
+
+    Ok(PackageSource::Archive {
+        deb_files: vec![deb_path],
+        _temp_dir: temp_dir,
+    })
+}
+
+fn extract_tar_gz(
+    archive_path: &Path,
+    options: &ExtractionOptions,
+) -> Result<PackageSource, BellhopError> {
     let file = File::open(archive_path)?;
     let decoder = GzDecoder::new(file);
     let archive = Archive::new(decoder);
 
-    extract_and_find_debs(archive, archive_path)
+    extract_and_find_debs(archive, archive_path, options)
 }
 
-fn extract_tar(archive_path: &Path) -> Result<PackageSource, BellhopError> {
+fn extract_tar(
+    archive_path: &Path,
+    options: &ExtractionOptions,
+) -> Result<PackageSource, BellhopError> {
     let file = File::open(archive_path)?;
     let archive = Archive::new(file);
 
-    extract_and_find_debs(archive, archive_path)
+    extract_and_find_debs(archive, archive_path, options)
 }
 
-fn extract_zip(archive_path: &Path) -> Result<PackageSource, BellhopError> {
-    let file = File::open(archive_path)?;
-    let mut archive =
-        ZipArchive::new(file).map_err(|e| BellhopError::ArchiveExtractionFailed(e.to_string()))?;
-
+fn extract_zip(
+    archive_path: &Path,
+    options: &ExtractionOptions,
+) -> Result<PackageSource, BellhopError> {
     let temp_dir = TempDir::new()?;
     let extract_path = temp_dir.path();
 
     info!("Extracting ZIP archive to: {}", extract_path.display());
 
+    let mut budget = ExtractionBudget::new(archive_path, options);
+    unpack_zip_entries(
+        archive_path,
+        extract_path,
+        options.follow_symlinks,
+        &mut budget,
+    )?;
+
+    finalize_archive_extraction(temp_dir, archive_path, options, &mut budget)
+}
+
+fn unpack_zip_entries(
+    zip_path: &Path,
+    dest: &Path,
+    follow_symlinks: bool,
+    budget: &mut ExtractionBudget,
+) -> Result<(), BellhopError> {
+    let file = File::open(zip_path)?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| BellhopError::ArchiveExtractionFailed(e.to_string()))?;
+
     // Due to a zip crate limitation,
     // all files are created with default permissions (0666 & umask).
 
+    let mut symlinks = Vec::new();
+    let bar = progress::entries_bar(archive.len() as u64, "Extracting");
     for i in 0..archive.len() {
+        bar.inc(1);
+        budget.count_entry()?;
         let mut entry = archive
             .by_index(i)
             .map_err(|e| BellhopError::ArchiveExtractionFailed(e.to_string()))?;
@@ -99,13 +492,25 @@ fn extract_zip(archive_path: &Path) -> Result<PackageSource, BellhopError> {
             continue;
         };
 
-        // Skip symlinks for security
+        // Skip symlinks for security, unless they are to be followed
         if entry.is_symlink() {
-            debug!("Skipping symlink: {}", entry_name.display());
+            if follow_symlinks {
+                let mut target = String::new();
+                entry
+                    .by_ref()
+                    .take(MAX_SYMLINK_TARGET_LENGTH)
+                    .read_to_string(&mut target)?;
+                symlinks.push(PendingSymlink {
+                    entry_path: entry_name,
+                    target: PathBuf::from(target),
+                });
+            } else {
+                debug!("Skipping symlink: {}", entry_name.display());
+            }
             continue;
         }
 
-        let outpath = extract_path.join(entry_name);
+        let outpath = dest.join(entry_name);
 
         if entry.is_dir() {
             fs::create_dir_all(&outpath)?;
@@ -114,16 +519,18 @@ fn extract_zip(archive_path: &Path) -> Result<PackageSource, BellhopError> {
                 fs::create_dir_all(parent)?;
             }
             let mut outfile = File::create(&outpath)?;
-            io::copy(&mut entry, &mut outfile)?;
+            budget.copy(&mut entry, &mut outfile)?;
         }
     }
+    bar.finish_and_clear();
 
-    finalize_archive_extraction(temp_dir, archive_path)
+    copy_symlink_targets(dest, symlinks, budget)
 }
 
 fn extract_and_find_debs<R: Read>(
     mut archive: Archive<R>,
     archive_path: &Path,
+    options: &ExtractionOptions,
 ) -> Result<PackageSource, BellhopError> {
     let temp_dir = TempDir::new()?;
     let extract_path = temp_dir.path();
@@ -133,21 +540,177 @@ fn extract_and_find_debs<R: Read>(
     archive.set_unpack_xattrs(false);
 
     info!("Extracting archive to: {}", extract_path.display());
-    archive
-        .unpack(extract_path)
-        .map_err(|e| BellhopError::ArchiveExtractionFailed(e.to_string()))?;
+    let mut budget = ExtractionBudget::new(archive_path, options);
+    unpack_tar_entries(
+        &mut archive,
+        extract_path,
+        options.follow_symlinks,
+        &mut budget,
+    )?;
+
+    finalize_archive_extraction(temp_dir, archive_path, options, &mut budget)
+}
+
+/// Unlike `Archive::unpack`, only writes regular files and directories, and only within `dest`
+fn unpack_tar_entries<R: Read>(
+    archive: &mut Archive<R>,
+    dest: &Path,
+    follow_symlinks: bool,
+    budget: &mut ExtractionBudget,
+) -> Result<(), BellhopError> {
+    let archive_path = budget.archive_path;
+    let to_error = |e: io::Error| {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            truncated_archive(archive_path)
+        } else {
+            BellhopError::ArchiveExtractionFailed(e.to_string())
+        }
+    };
+
+    let mut symlinks = Vec::new();
+    for entry in archive.entries().map_err(to_error)? {
+        budget.count_entry()?;
+        let mut entry = entry.map_err(to_error)?;
+        let entry_path = entry.path().map_err(to_error)?.into_owned();
+
+        if !is_enclosed(&entry_path) {
+            debug!("Skipping entry with unsafe path: {}", entry_path.display());
+            continue;
+        }
+
+        let outpath = dest.join(&entry_path);
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_dir() {
+            fs::create_dir_all(&outpath)?;
+        } else if entry_type.is_file() || entry_type.is_contiguous() {
+            if let Some(parent) = outpath.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut outfile = File::create(&outpath)?;
+            budget.copy(&mut entry, &mut outfile)?;
+        } else if follow_symlinks
+            && entry_type.is_symlink()
+            && let Some(target) = entry.link_name().map_err(to_error)?
+        {
+            symlinks.push(PendingSymlink {
+                entry_path,
+                target: target.into_owned(),
+            });
+        } else {
+            // Skip symlinks, hard links and special files for security
+            debug!("Skipping {:?} entry: {}", entry_type, entry_path.display());
+        }
+    }
+
+    copy_symlink_targets(dest, symlinks, budget)
+}
+
+/// A symlink entry of an archive, to be replaced with a copy of its target once every other
+/// entry is extracted
+struct PendingSymlink {
+    entry_path: PathBuf,
+    target: PathBuf,
+}
+
+/// Copies rather than links, so that nothing outside of `dest` can be reached through the
+/// extracted files. A symlink that points outside of `dest` is an error, one whose target
+/// is not a file extracted from the archive is skipped.
+fn copy_symlink_targets(
+    dest: &Path,
+    symlinks: Vec<PendingSymlink>,
+    budget: &mut ExtractionBudget,
+) -> Result<(), BellhopError> {
+    for symlink in symlinks {
+        let Some(target) = resolve_symlink_target(&symlink.entry_path, &symlink.target) else {
+            return Err(BellhopError::SymlinkOutsideArchive {
+                path: budget.archive_path.to_path_buf(),
+                entry: symlink.entry_path,
+                target: symlink.target,
+            });
+        };
+        let target_path = dest.join(&target);
+        if !target_path.is_file() {
+            debug!(
+                "Skipping symlink to something other than an extracted file: {} -> {}",
+                symlink.entry_path.display(),
+                symlink.target.display()
+            );
+            continue;
+        }
 
-    finalize_archive_extraction(temp_dir, archive_path)
+        debug!(
+            "Copying {} to symlink {}",
+            target.display(),
+            symlink.entry_path.display()
+        );
+        let outpath = dest.join(&symlink.entry_path);
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut outfile = File::create(&outpath)?;
+        budget.copy(&mut File::open(&target_path)?, &mut outfile)?;
+    }
+    Ok(())
+}
+
+/// The target of a symlink relative to the extraction directory, `None` when it is absolute or
+/// leaves the extraction directory
+pub fn resolve_symlink_target(entry_path: &Path, target: &Path) -> Option<PathBuf> {
+    let base = entry_path.parent().unwrap_or(Path::new(""));
+    let mut resolved = PathBuf::new();
+    for component in base.join(target).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!resolved.as_os_str().is_empty()).then_some(resolved)
+}
+
+/// Relative paths without any `..` components cannot point outside of the extraction directory
+fn is_enclosed(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        && path.components().any(|c| matches!(c, Component::Normal(_)))
 }
 
 fn finalize_archive_extraction(
     temp_dir: TempDir,
     archive_path: &Path,
+    options: &ExtractionOptions,
+    budget: &mut ExtractionBudget,
 ) -> Result<PackageSource, BellhopError> {
-    extract_nested_tar_archives(temp_dir.path())?;
+    extract_nested_zip_archives(temp_dir.path(), options.follow_symlinks, budget)?;
+    extract_nested_tar_archives(
+        temp_dir.path(),
+        options.with_source,
+        options.follow_symlinks,
+        budget,
+    )?;
 
-    let deb_files = find_deb_files(temp_dir.path())?;
+    let deb_files = dedup_deb_files(find_deb_files(temp_dir.path(), options)?);
 
+    if deb_files.is_empty() {
+        let found = find_unsupported_package_formats(temp_dir.path())?;
+        if !found.is_empty() {
+            return Err(BellhopError::OnlyUnsupportedPackagesInArchive {
+                path: archive_path.to_path_buf(),
+                found,
+            });
+        }
+        return Err(BellhopError::NoDebFilesInArchive {
+            path: archive_path.to_path_buf(),
+        });
+    }
+
+    let deb_files = exclude_matching(deb_files, &options.exclude);
     if deb_files.is_empty() {
         return Err(BellhopError::NoDebFilesInArchive {
             path: archive_path.to_path_buf(),
@@ -157,6 +720,9 @@ fn finalize_archive_extraction(
     info!("Found {} .deb files in archive", deb_files.len());
     for deb in &deb_files {
         debug!("  - {}", deb.display());
+        if options.fsync {
+            sync_file_and_parent(deb)?;
+        }
     }
 
     Ok(PackageSource::Archive {
@@ -165,10 +731,62 @@ fn finalize_archive_extraction(
     })
 }
 
-fn extract_nested_tar_archives(dir: &Path) -> Result<(), BellhopError> {
+/// Zip archives are extracted next to themselves and removed, level by level, so that a zip of
+/// per-distribution zips is handled. Nesting deeper than [`MAX_NESTED_ZIP_DEPTH`] is an error.
+fn extract_nested_zip_archives(
+    dir: &Path,
+    follow_symlinks: bool,
+    budget: &mut ExtractionBudget,
+) -> Result<(), BellhopError> {
+    for _ in 0..MAX_NESTED_ZIP_DEPTH {
+        let zip_archives = find_zip_archives(dir)?;
+        if zip_archives.is_empty() {
+            return Ok(());
+        }
+
+        for zip_path in zip_archives {
+            info!("Extracting nested archive: {}", zip_path.display());
+            let parent_dir = zip_path.parent().ok_or_else(|| {
+                BellhopError::ArchiveExtractionFailed("Invalid zip path".to_string())
+            })?;
+            unpack_zip_entries(&zip_path, parent_dir, follow_symlinks, budget)?;
+            fs::remove_file(&zip_path)?;
+        }
+    }
+
+    if find_zip_archives(dir)?.is_empty() {
+        Ok(())
+    } else {
+        Err(BellhopError::ArchiveNestedTooDeeply {
+            path: budget.archive_path.to_path_buf(),
+            limit: MAX_NESTED_ZIP_DEPTH,
+        })
+    }
+}
+
+fn find_zip_archives(dir: &Path) -> Result<Vec<PathBuf>, BellhopError> {
+    find_files(dir, |path| {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.to_lowercase().ends_with(".zip"))
+    })
+}
+
+/// With `with_source`, the tarballs of source packages are left as they are for aptly to pick up
+fn extract_nested_tar_archives(
+    dir: &Path,
+    with_source: bool,
+    follow_symlinks: bool,
+    budget: &mut ExtractionBudget,
+) -> Result<(), BellhopError> {
     let tar_archives = find_tar_archives(dir)?;
 
     for tar_path in tar_archives {
+        if with_source && is_source_tarball(&tar_path) {
+            debug!("Keeping source tarball: {}", tar_path.display());
+            continue;
+        }
+
         info!("Extracting nested archive: {}", tar_path.display());
 
         let file_name = tar_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -178,11 +796,11 @@ fn extract_nested_tar_archives(dir: &Path) -> Result<(), BellhopError> {
             let file = File::open(&tar_path)?;
             let decoder = GzDecoder::new(file);
             let mut archive = Archive::new(decoder);
-            extract_tar_to_same_dir(&mut archive, &tar_path)?;
+            extract_tar_to_same_dir(&mut archive, &tar_path, follow_symlinks, budget)?;
         } else if file_name_lower.ends_with(".tar") {
             let file = File::open(&tar_path)?;
             let mut archive = Archive::new(file);
-            extract_tar_to_same_dir(&mut archive, &tar_path)?;
+            extract_tar_to_same_dir(&mut archive, &tar_path, follow_symlinks, budget)?;
         }
 
         fs::remove_file(&tar_path)?;
@@ -194,55 +812,124 @@ fn extract_nested_tar_archives(dir: &Path) -> Result<(), BellhopError> {
 fn extract_tar_to_same_dir<R: Read>(
     archive: &mut Archive<R>,
     tar_path: &Path,
+    follow_symlinks: bool,
+    budget: &mut ExtractionBudget,
 ) -> Result<(), BellhopError> {
     let parent_dir = tar_path
         .parent()
         .ok_or_else(|| BellhopError::ArchiveExtractionFailed("Invalid tar path".to_string()))?;
 
-    archive.set_preserve_permissions(false);
-    archive.set_preserve_mtime(false);
-    archive.set_unpack_xattrs(false);
+    unpack_tar_entries(archive, parent_dir, follow_symlinks, budget)
+}
 
-    archive
-        .unpack(parent_dir)
-        .map_err(|e| BellhopError::ArchiveExtractionFailed(e.to_string()))?;
+fn find_tar_archives(dir: &Path) -> Result<Vec<PathBuf>, BellhopError> {
+    find_files(dir, |path| {
+        path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+            let lower = n.to_lowercase();
+            lower.ends_with(".tar.gz") || lower.ends_with(".tgz") || lower.ends_with(".tar")
+        })
+    })
+}
 
-    Ok(())
+/// Drops files with the same package name, version and architecture as an earlier one,
+/// e.g. when an archive has a package both at the top level and in a nested directory.
+/// Files closer to the top level come first.
+pub fn dedup_deb_files(mut deb_files: Vec<PathBuf>) -> Vec<PathBuf> {
+    deb_files.sort_by(|a, b| {
+        a.components()
+            .count()
+            .cmp(&b.components().count())
+            .then_with(|| a.cmp(b))
+    });
+
+    let mut seen = HashSet::new();
+    deb_files
+        .into_iter()
+        .filter(|path| {
+            let Some(key) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| extract_package_key_from_filename(n).ok())
+            else {
+                return true;
+            };
+
+            let is_new = seen.insert(key);
+            if !is_new {
+                info!("Skipping duplicate package: {}", path.display());
+            }
+            is_new
+        })
+        .collect()
 }
 
-fn find_tar_archives(dir: &Path) -> Result<Vec<PathBuf>, BellhopError> {
-    let mut tar_files = Vec::new();
+fn find_deb_files(root: &Path, options: &ExtractionOptions) -> Result<Vec<PathBuf>, BellhopError> {
+    find_files(root, |path| {
+        path.extension().is_some_and(|ext| {
+            ext == "deb"
+                || (options.include_ddeb && ext == "ddeb")
+                || (options.with_source && ext == "dsc")
+        })
+    })
+}
 
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        let file_type = entry.file_type()?;
+pub fn is_source_package(file_name: &str) -> bool {
+    file_name.to_lowercase().ends_with(".dsc")
+}
 
-        if file_type.is_file()
-            && path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
-                let lower = n.to_lowercase();
-                lower.ends_with(".tar.gz") || lower.ends_with(".tgz") || lower.ends_with(".tar")
-            })
-        {
-            tar_files.push(path);
-        }
-    }
+/// The upstream `.orig.tar.*` (or `.orig-component.tar.*`) and the `.debian.tar.*` tarballs
+/// listed in a `.dsc` file
+fn is_source_tarball(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| {
+            let lower = name.to_lowercase();
+            lower.contains(".orig.tar.")
+                || lower.contains(".debian.tar.")
+                || lower.contains(".orig-")
+        })
+}
+
+fn is_ddeb(file_name: &str) -> bool {
+    file_name.to_lowercase().ends_with(".ddeb")
+}
+
+/// `package_version_arch` of a `.deb` or a `.ddeb` file name
+fn strip_package_extension(filename: &str) -> Option<&str> {
+    filename
+        .strip_suffix(".deb")
+        .or_else(|| filename.strip_suffix(".ddeb"))
+}
 
-    Ok(tar_files)
+/// The distinct package formats other than `.deb`, e.g. `.rpm`, found under `root`
+fn find_unsupported_package_formats(root: &Path) -> Result<Vec<String>, BellhopError> {
+    let files = find_files(root, |path| unsupported_package_format(path).is_some())?;
+    let formats: BTreeSet<&str> = files
+        .iter()
+        .filter_map(|path| unsupported_package_format(path))
+        .collect();
+    Ok(formats.into_iter().map(String::from).collect())
 }
 
-fn find_deb_files(root: &Path) -> Result<Vec<PathBuf>, BellhopError> {
-    const MAX_DEPTH: usize = 2;
+fn unsupported_package_format(path: &Path) -> Option<&'static str> {
+    let file_name = path.file_name()?.to_str()?;
+    UNSUPPORTED_PACKAGE_FORMATS
+        .iter()
+        .find(|format| file_name.ends_with(*format))
+        .copied()
+}
 
-    let mut deb_files = Vec::new();
+/// Walks `root` down to `MAX_SEARCH_DEPTH` levels of subdirectories
+fn find_files(root: &Path, matches: impl Fn(&Path) -> bool) -> Result<Vec<PathBuf>, BellhopError> {
+    let mut files = Vec::new();
     let mut to_visit = vec![(root.to_path_buf(), 0)];
 
     while let Some((dir, depth)) = to_visit.pop() {
-        if depth > MAX_DEPTH {
+        if depth > MAX_SEARCH_DEPTH {
             debug!(
                 "Skipping directory at depth {} (max: {}): {}",
                 depth,
-                MAX_DEPTH,
+                MAX_SEARCH_DEPTH,
                 dir.display()
             );
             continue;
@@ -253,18 +940,94 @@ fn find_deb_files(root: &Path) -> Result<Vec<PathBuf>, BellhopError> {
             let path = entry.path();
             let file_type = entry.file_type()?;
 
-            if file_type.is_file() && path.extension().is_some_and(|ext| ext == "deb") {
-                deb_files.push(path);
+            if file_type.is_file() && matches(&path) {
+                files.push(path);
             } else if file_type.is_dir() {
                 to_visit.push((path, depth + 1));
             }
         }
     }
 
-    Ok(deb_files)
+    Ok(files)
+}
+
+/// Checks that a `.deb` file is an `ar` archive with the `debian-binary`, `control.tar.*`
+/// and `data.tar.*` members, so that e.g. an HTML error page saved as a `.deb` is reported
+/// by name instead of failing inside `aptly repo add`. Only the member headers are read.
+pub fn validate_deb(deb_path: &Path) -> Result<(), BellhopError> {
+    let invalid = |reason: &str| BellhopError::InvalidDebFile {
+        path: deb_path.to_path_buf(),
+        reason: reason.to_string(),
+    };
+
+    let mut file = File::open(deb_path)?;
+    let file_len = file.metadata()?.len();
+    let mut magic = [0u8; AR_MAGIC.len()];
+    if file.read_exact(&mut magic).is_err() || magic != AR_MAGIC {
+        return Err(invalid("not an ar archive"));
+    }
+
+    let mut members = Vec::new();
+    let mut offset = AR_MAGIC.len() as u64;
+    while offset < file_len {
+        let mut header = [0u8; AR_MEMBER_HEADER_LEN];
+        file.read_exact(&mut header)
+            .map_err(|_| invalid("truncated member header"))?;
+        let name = String::from_utf8_lossy(&header[..16])
+            .trim_end()
+            .trim_end_matches('/')
+            .to_string();
+        let size: u64 = String::from_utf8_lossy(&header[48..58])
+            .trim()
+            .parse()
+            .map_err(|_| invalid("malformed member header"))?;
+
+        // members are padded to an even length
+        offset += AR_MEMBER_HEADER_LEN as u64 + size + size % 2;
+        if offset - size % 2 > file_len {
+            return Err(invalid(&format!("member '{name}' is truncated")));
+        }
+        members.push(name);
+        file.seek(SeekFrom::Start(offset))?;
+    }
+
+    if members.first().map(String::as_str) != Some("debian-binary") {
+        return Err(invalid("the first member is not debian-binary"));
+    }
+    for prefix in ["control.tar", "data.tar"] {
+        if !members.iter().any(|member| member.starts_with(prefix)) {
+            return Err(invalid(&format!("no {prefix}.* member")));
+        }
+    }
+    Ok(())
+}
+
+/// Source package name and version from the `Source:` and `Version:` fields of a `.dsc` file,
+/// which may be signed
+pub fn read_name_and_version_from_dsc(dsc_path: &Path) -> Result<(String, String), BellhopError> {
+    let contents = fs::read_to_string(dsc_path)?;
+    let field = |name: &str| {
+        contents.lines().find_map(|line| {
+            line.strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        })
+    };
+
+    match (field("Source"), field("Version")) {
+        (Some(name), Some(version)) => Ok((name, version)),
+        _ => Err(BellhopError::MalformedDscFile {
+            path: dsc_path.to_path_buf(),
+        }),
+    }
 }
 
 pub fn extract_version_from_deb(deb_path: &Path) -> Result<String, BellhopError> {
+    if is_source_package(&deb_path.to_string_lossy()) {
+        return read_name_and_version_from_dsc(deb_path).map(|(_, version)| version);
+    }
     let file_name = deb_path
         .file_name()
         .and_then(|n| n.to_str())
@@ -280,29 +1043,103 @@ pub fn extract_version_from_deb(deb_path: &Path) -> Result<String, BellhopError>
 pub fn extract_versions_from_debs(deb_files: &[PathBuf]) -> Result<Vec<String>, BellhopError> {
     deb_files
         .iter()
-        .map(|deb_path| {
-            let file_name = deb_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .ok_or_else(|| {
-                    BellhopError::ArchiveExtractionFailed(format!(
-                        "Invalid .deb filename: {}",
-                        deb_path.display()
-                    ))
-                })?;
-            extract_version_from_filename(file_name)
-        })
+        .map(|deb_path| extract_version_from_deb(deb_path))
         .collect()
 }
 
+pub fn extract_name_and_version_from_deb(
+    deb_path: &Path,
+) -> Result<(String, String), BellhopError> {
+    if is_source_package(&deb_path.to_string_lossy()) {
+        return read_name_and_version_from_dsc(deb_path);
+    }
+    let file_name = deb_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| {
+            BellhopError::ArchiveExtractionFailed(format!(
+                "Invalid .deb filename: {}",
+                deb_path.display()
+            ))
+        })?;
+    extract_name_and_version_from_filename(file_name)
+}
+
+pub fn extract_name_and_version_from_filename(
+    filename: &str,
+) -> Result<(String, String), BellhopError> {
+    let version = extract_version_from_filename(filename)?;
+    let name = strip_package_extension(filename)
+        .unwrap_or(filename)
+        .rsplitn(3, '_')
+        .nth(2)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| BellhopError::MalformedDebFilename {
+            filename: filename.to_string(),
+        })?;
+
+    Ok((name.to_string(), version))
+}
+
+/// Package name, version and architecture of a package file, `source` for a `.dsc` file
+pub fn extract_package_key_from_deb(
+    deb_path: &Path,
+) -> Result<(String, String, String), BellhopError> {
+    if is_source_package(&deb_path.to_string_lossy()) {
+        let (name, version) = read_name_and_version_from_dsc(deb_path)?;
+        return Ok((name, version, "source".to_string()));
+    }
+    let file_name = deb_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    extract_package_key_from_filename(file_name)
+}
+
+/// Package name, version and architecture of a `package_version_arch.deb` file
+pub fn extract_package_key_from_filename(
+    filename: &str,
+) -> Result<(String, String, String), BellhopError> {
+    let (name, version) = extract_name_and_version_from_filename(filename)?;
+    let arch = strip_package_extension(filename)
+        .unwrap_or(filename)
+        .rsplit('_')
+        .next()
+        .unwrap_or_default();
+
+    Ok((name, version, arch.to_string()))
+}
+
+/// Architecture of a `package_version_arch.deb` file
+pub fn extract_architecture_from_filename(filename: &str) -> Result<String, BellhopError> {
+    let parts: Vec<&str> = strip_package_extension(filename)
+        .ok_or_else(|| BellhopError::InvalidDebFilename {
+            filename: filename.to_string(),
+        })?
+        .split('_')
+        .collect();
+
+    match parts.as_slice() {
+        [name, version, arch] if !name.is_empty() && !version.is_empty() && !arch.is_empty() => {
+            Ok(arch.to_string())
+        }
+        _ => Err(BellhopError::MalformedDebFilename {
+            filename: filename.to_string(),
+        }),
+    }
+}
+
+/// Version of a `package_version_arch.deb` file. An epoch colon that was percent-encoded
+/// as `%3a`, e.g. in `erlang-base_1%3a27.3.4.6-1_amd64.deb`, is decoded, so the version
+/// matches the one aptly stores
 pub fn extract_version_from_filename(filename: &str) -> Result<String, BellhopError> {
-    if !filename.ends_with(".deb") {
+    let Some(stem) = strip_package_extension(filename) else {
         return Err(BellhopError::InvalidDebFilename {
             filename: filename.to_string(),
         });
-    }
+    };
 
-    let parts: Vec<&str> = filename.trim_end_matches(".deb").rsplitn(3, '_').collect();
+    let parts: Vec<&str> = stem.rsplitn(3, '_').collect();
 
     if parts.len() < 3 {
         return Err(BellhopError::MalformedDebFilename {
@@ -317,5 +1154,5 @@ pub fn extract_version_from_filename(filename: &str) -> Result<String, BellhopEr
         });
     }
 
-    Ok(version.to_string())
+    Ok(version.replace("%3a", ":").replace("%3A", ":"))
 }
@@ -13,11 +13,27 @@
 // limitations under the License.
 #![allow(dead_code)]
 
-use crate::common::Project;
-use crate::deb::DistributionAlias;
+use crate::aptly::{DEFAULT_LOCK_RETRIES, DateRange, PackageIdentity, PublishOptions};
+use crate::archive::{
+    DEFAULT_MAX_ENTRIES, DEFAULT_MAX_EXTRACTED_BYTES, ExtractionOptions, InputFormat,
+};
+use crate::common::{OutputFormat, Project, SnapshotSource};
+use crate::deb::{DebianFamily, DistributionAlias};
 use crate::errors::BellhopError;
-use chrono::Local;
-use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use crate::gh::DEFAULT_HTTP_TIMEOUT;
+use crate::gh::downloads::DEFAULT_DOWNLOAD_JOBS;
+use crate::lock::{self, DEFAULT_LOCK_TIMEOUT};
+use chrono::{Local, NaiveDate};
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command, value_parser};
+use log::LevelFilter;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DISTRIBUTION_ORDER_ENV_VAR: &str = "BELLHOP_DISTRIBUTION_ORDER";
 
 pub fn parser() -> Command {
     Command::new("bellhop")
@@ -25,35 +41,228 @@ pub fn parser() -> Command {
         .about("Puts input .deb and .rpm packages into the right places")
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("PATH")
+                .global(true)
+                .help("aptly configuration file to use, takes precedence over $APTLY_CONFIG"),
+        )
+        .arg(
+            Arg::new("aptly_lock_retries")
+                .long("aptly-lock-retries")
+                .value_name("COUNT")
+                .value_parser(value_parser!(u32))
+                .global(true)
+                .help("How many times to retry an aptly command while another aptly process holds the database lock, or that exited with one of --retry-aptly-exit-codes [default: 3]"),
+        )
+        .arg(
+            Arg::new("retry_aptly_exit_codes")
+                .long("retry-aptly-exit-codes")
+                .value_name("CODES")
+                .value_parser(value_parser!(u8).range(1..))
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .global(true)
+                .help("Comma-separated aptly exit codes that indicate a transient failure, commands that exit with them are retried like database lock failures, e.g. 1,2"),
+        )
+        .arg(
+            Arg::new("lockfile")
+                .long("lockfile")
+                .value_name("PATH")
+                .global(true)
+                .help("Lock file that keeps bellhop runs that change the aptly database from overlapping [default: bellhop.lock in the aptly root directory]"),
+        )
+        .arg(
+            Arg::new("lock_timeout")
+                .long("lock-timeout")
+                .value_name("SECONDS")
+                .value_parser(value_parser!(u64))
+                .global(true)
+                .help("How long to wait for another bellhop run to release the lock [default: 60]"),
+        )
+        .arg(
+            Arg::new("stream_aptly")
+                .long("stream-aptly")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help("Forward the output of aptly to stderr as it runs instead of only reporting it on failure"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .conflicts_with("verbose")
+                .help("Only log warnings and errors, do not display progress bars"),
+        )
+        .arg(
+            Arg::new("print_plan")
+                .long("print-plan")
+                .visible_alias("dry-run")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help("Print the aptly commands that would be run instead of running them, does not require aptly"),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help("End every run with a JSON report of its outcome on stderr and produce JSON output where supported"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(ArgAction::Count)
+                .global(true)
+                .help("Log more details: -v traces bellhop, -vv also debugs dependencies, -vvv traces everything"),
+        )
         .subcommand(rabbitmq_group())
         .subcommand(erlang_group())
         .subcommand(cli_tools_group())
         .subcommand(repositories_group())
+        .subcommand(mirror_group())
         .subcommand(watch_command())
+        .subcommand(
+            Command::new("doctor")
+                .about("Check that aptly, its configuration and the signing key are usable"),
+        )
+        .subcommand(
+            Command::new("env")
+                .about("Print bellhop, aptly and gpg versions and the aptly configuration in use, e.g. for bug reports"),
+        )
+        .subcommand(
+            Command::new("clean")
+                .about("Delete the packages and pool files that dropped snapshots and repositories left behind (aptly db cleanup)")
+                .arg(
+                    Arg::new("recover")
+                        .long("recover")
+                        .action(ArgAction::SetTrue)
+                        .help("Run 'aptly db recover' first, e.g. after an interrupted run"),
+                )
+                .arg(yes_arg()),
+        )
+        .subcommand(
+            Command::new("distributions")
+                .about("List the supported distributions, their families and whether Erlang packages are built for them")
+                .arg(format_arg()),
+        )
 }
 
 pub fn distributions(
     cli_args: &ArgMatches,
     project: Project,
 ) -> Result<Vec<DistributionAlias>, BellhopError> {
-    if cli_args.get_flag("all") {
-        match project {
-            Project::Erlang => Ok(DistributionAlias::erlang_supported().to_vec()),
-            Project::RabbitMQ | Project::CliTools => Ok(DistributionAlias::all().to_vec()),
-        }
+    let resolved = if cli_args.get_flag("all") {
+        let all = match project {
+            Project::Erlang => DistributionAlias::erlang_supported(),
+            Project::RabbitMQ | Project::CliTools => DistributionAlias::all(),
+        };
+        except_distributions(all, &excluded_distributions(cli_args)?)?
+    } else if let Some(path) = cli_args.get_one::<PathBuf>("dist_file") {
+        read_distribution_file(path)?
     } else {
         cli_args
             .get_many::<String>("distributions")
             .ok_or_else(|| BellhopError::MissingArgument {
                 argument: "distributions".to_string(),
             })?
-            .map(|s| {
-                s.as_str()
-                    .parse::<DistributionAlias>()
-                    .map_err(|_| BellhopError::InvalidDistribution { alias: s.clone() })
-            })
-            .collect()
+            .map(|s| parse_distribution(s))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    Ok(order_distributions(
+        resolved,
+        &distribution_order(cli_args)?,
+    ))
+}
+
+fn excluded_distributions(cli_args: &ArgMatches) -> Result<Vec<DistributionAlias>, BellhopError> {
+    cli_args
+        .get_many::<String>("except")
+        .into_iter()
+        .flatten()
+        .map(|s| parse_distribution(s))
+        .collect()
+}
+
+/// `all` without the `excluded` distributions, excluding every one of them is an error
+pub fn except_distributions(
+    all: &[DistributionAlias],
+    excluded: &[DistributionAlias],
+) -> Result<Vec<DistributionAlias>, BellhopError> {
+    let remaining: Vec<DistributionAlias> = all
+        .iter()
+        .filter(|rel| !excluded.contains(rel))
+        .cloned()
+        .collect();
+    if remaining.is_empty() {
+        return Err(BellhopError::NoDistributionsLeft);
+    }
+    Ok(remaining)
+}
+
+pub fn read_distribution_file(path: &Path) -> Result<Vec<DistributionAlias>, BellhopError> {
+    if !path.is_file() {
+        return Err(BellhopError::DistributionFileNotFound {
+            path: path.to_path_buf(),
+        });
+    }
+    let distributions = parse_distribution_list(&fs::read_to_string(path)?)?;
+    if distributions.is_empty() {
+        return Err(BellhopError::MissingArgument {
+            argument: "distributions".to_string(),
+        });
     }
+    Ok(distributions)
+}
+
+/// Newline- or comma-separated aliases, lines starting with `#` are comments
+pub fn parse_distribution_list(contents: &str) -> Result<Vec<DistributionAlias>, BellhopError> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(parse_distribution)
+        .collect()
+}
+
+/// Distributions listed in `order` go first, in that order. The rest keep their relative order.
+pub fn order_distributions(
+    mut distributions: Vec<DistributionAlias>,
+    order: &[DistributionAlias],
+) -> Vec<DistributionAlias> {
+    distributions.sort_by_key(|d| order.iter().position(|o| o == d).unwrap_or(order.len()));
+    distributions
+}
+
+pub fn distribution_order(cli_args: &ArgMatches) -> Result<Vec<DistributionAlias>, BellhopError> {
+    if let Some(values) = cli_args.get_many::<String>("distribution_order") {
+        return values.map(|s| parse_distribution(s)).collect();
+    }
+
+    match env::var(DISTRIBUTION_ORDER_ENV_VAR) {
+        Ok(value) => value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(parse_distribution)
+            .collect(),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn parse_distribution(s: &str) -> Result<DistributionAlias, BellhopError> {
+    s.parse::<DistributionAlias>()
+        .map_err(|_| BellhopError::InvalidDistribution {
+            alias: s.to_string(),
+        })
 }
 
 pub fn distributions_for_all_projects(
@@ -62,21 +271,467 @@ pub fn distributions_for_all_projects(
     distributions(cli_args, Project::RabbitMQ)
 }
 
+/// Snapshots taken without an explicit `--suffix` are named after the current date in this format
+pub const DEFAULT_SUFFIX_DATE_FORMAT: &str = "%d-%b-%y";
+
 pub fn suffix(cli_args: &ArgMatches) -> String {
     let now = Local::now();
-    let default = now.format("%d-%b-%y").to_string();
+    let default = now.format(suffix_date_format(cli_args)).to_string();
 
     cli_args
-        .get_one::<String>("suffix")
+        .try_get_one::<String>("suffix")
+        .ok()
+        .flatten()
         .cloned()
         .unwrap_or(default)
 }
 
+pub fn suffix_date_format(cli_args: &ArgMatches) -> &str {
+    cli_args
+        .try_get_one::<String>("suffix_date_format")
+        .ok()
+        .flatten()
+        .map(|s| s.as_str())
+        .unwrap_or(DEFAULT_SUFFIX_DATE_FORMAT)
+}
+
+/// Parses `alias=name`, e.g. `bookworm=stable`
+pub fn parse_distribution_name(value: &str) -> Result<(DistributionAlias, String), String> {
+    let (alias, name) = value
+        .split_once('=')
+        .ok_or_else(|| "must be in the alias=name format, e.g. bookworm=stable".to_string())?;
+    if name.is_empty() {
+        return Err("the distribution name must not be empty".to_string());
+    }
+    let alias = parse_distribution(alias).map_err(|e| e.to_string())?;
+    Ok((alias, name.to_string()))
+}
+
+pub fn parse_arch_map(value: &str) -> Result<(String, String), String> {
+    let (from, to) = value
+        .split_once('=')
+        .ok_or_else(|| "must be in the from=to format, e.g. all=amd64".to_string())?;
+    let is_architecture = |arch: &str| {
+        !arch.is_empty() && arch.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    if !is_architecture(from) || !is_architecture(to) {
+        return Err(format!("'{value}' does not map an architecture to another"));
+    }
+    Ok((from.to_string(), to.to_string()))
+}
+
+pub fn parse_watch_map(value: &str) -> Result<(String, Project), String> {
+    let (dir_name, project) = value
+        .split_once('=')
+        .ok_or_else(|| "must be in the dir=project format, e.g. server=rabbitmq".to_string())?;
+    if dir_name.is_empty() || dir_name == "." || dir_name == ".." || dir_name.contains(['/', '\\'])
+    {
+        return Err(format!(
+            "'{dir_name}' is not the name of a subdirectory of the watched root"
+        ));
+    }
+    Ok((dir_name.to_string(), project.parse()?))
+}
+
+fn parse_additional_distribution_name(value: &str) -> Result<String, String> {
+    if value.is_empty() {
+        return Err("the distribution name must not be empty".to_string());
+    }
+    Ok(value.to_string())
+}
+
+fn parse_github_component(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    if value.contains('/') {
+        return Err("must not contain '/'".to_string());
+    }
+    Ok(value.to_string())
+}
+
+fn parse_download_jobs(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(jobs) if jobs > 0 => Ok(jobs),
+        _ => Err("must be a positive number".to_string()),
+    }
+}
+
+/// Formatting a date with an invalid format string fails at formatting time, so the
+/// format is tried out on a sample date
+pub fn parse_suffix_date_format(format: &str) -> Result<String, String> {
+    let sample = NaiveDate::from_ymd_opt(2025, 8, 4).expect("sample date should be valid");
+    let mut formatted = String::new();
+    write!(formatted, "{}", sample.format(format))
+        .map_err(|_| format!("'{format}' is not a valid date format"))?;
+    if formatted.is_empty() {
+        return Err("the date format must not be empty".to_string());
+    }
+    Ok(format.to_string())
+}
+
+pub fn extraction_options(cli_args: &ArgMatches) -> ExtractionOptions {
+    ExtractionOptions {
+        fsync: cli_args
+            .try_get_one::<bool>("fsync")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false),
+        input_format: cli_args
+            .get_one::<String>("input_format")
+            .and_then(|s| s.parse::<InputFormat>().ok())
+            .unwrap_or_default(),
+        max_extracted_bytes: cli_args
+            .try_get_one::<u64>("max_extracted_bytes")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(DEFAULT_MAX_EXTRACTED_BYTES),
+        max_entries: cli_args
+            .try_get_one::<u64>("max_entries")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(DEFAULT_MAX_ENTRIES),
+        only_architectures: only_architectures(cli_args),
+        include_ddeb: cli_args
+            .try_get_one::<bool>("include_ddeb")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false),
+        exclude: cli_args
+            .try_get_many::<String>("exclude")
+            .ok()
+            .flatten()
+            .map(|patterns| patterns.cloned().collect())
+            .unwrap_or_default(),
+        with_source: cli_args
+            .try_get_one::<bool>("with_source")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false),
+        follow_symlinks: cli_args
+            .try_get_one::<bool>("follow_symlinks")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false),
+    }
+}
+
+pub fn http_timeout(cli_args: &ArgMatches) -> Duration {
+    cli_args
+        .try_get_one::<u64>("http_timeout")
+        .ok()
+        .flatten()
+        .map(|secs| Duration::from_secs(*secs))
+        .unwrap_or(DEFAULT_HTTP_TIMEOUT)
+}
+
+/// With `--from-stdin`, the paths are read from standard input, one per line
+pub fn package_file_paths(cli_args: &ArgMatches) -> Result<Vec<String>, BellhopError> {
+    let paths: Vec<String> = if cli_args.get_flag("from_stdin") {
+        read_package_file_paths(io::stdin().lock())?
+    } else {
+        cli_args
+            .get_many::<String>("package_file_path")
+            .map(|paths| paths.cloned().collect())
+            .unwrap_or_default()
+    };
+
+    if paths.is_empty() {
+        return Err(BellhopError::MissingArgument {
+            argument: "package_file_path".to_string(),
+        });
+    }
+    Ok(paths)
+}
+
+/// Blank lines are skipped so that the output of e.g. `find` can be piped in as is
+pub fn read_package_file_paths(reader: impl BufRead) -> io::Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let path = line.trim();
+        if !path.is_empty() {
+            paths.push(path.to_string());
+        }
+    }
+    Ok(paths)
+}
+
+/// Whether a multi-distribution operation carries on with the other distributions after one fails
+pub fn keep_going(cli_args: &ArgMatches) -> bool {
+    cli_args
+        .try_get_one::<bool>("continue")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+}
+
+pub fn no_snapshot(cli_args: &ArgMatches) -> bool {
+    cli_args
+        .try_get_one::<bool>("no_snapshot")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+}
+
+pub fn download_jobs(cli_args: &ArgMatches) -> usize {
+    cli_args
+        .try_get_one::<usize>("download_jobs")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(DEFAULT_DOWNLOAD_JOBS)
+}
+
+pub fn max_download_size(cli_args: &ArgMatches) -> Option<u64> {
+    cli_args
+        .try_get_one::<u64>("max_download_size")
+        .ok()
+        .flatten()
+        .copied()
+}
+
+pub fn arch_map(cli_args: &ArgMatches) -> Vec<(String, String)> {
+    cli_args
+        .try_get_many::<(String, String)>("arch_map")
+        .ok()
+        .flatten()
+        .map(|mappings| mappings.cloned().collect())
+        .unwrap_or_default()
+}
+
+pub fn watch_map(cli_args: &ArgMatches) -> Vec<(String, Project)> {
+    cli_args
+        .try_get_many::<(String, Project)>("watch_map")
+        .ok()
+        .flatten()
+        .map(|mappings| mappings.cloned().collect())
+        .unwrap_or_default()
+}
+
+/// The name and version given with `deb add --package-name` and `--version`
+pub fn package_identity(cli_args: &ArgMatches) -> Option<PackageIdentity> {
+    let name = cli_args
+        .try_get_one::<String>("package_name")
+        .ok()
+        .flatten()?;
+    let version = cli_args.try_get_one::<String>("version").ok().flatten()?;
+    Some(PackageIdentity {
+        name: name.clone(),
+        version: version.clone(),
+    })
+}
+
+pub fn only_architectures(cli_args: &ArgMatches) -> Option<Vec<String>> {
+    cli_args
+        .try_get_many::<String>("only_arch")
+        .ok()
+        .flatten()
+        .map(|archs| archs.cloned().collect())
+}
+
+pub fn publish_options(cli_args: &ArgMatches, project: Project) -> PublishOptions {
+    let string = |id: &str| cli_args.try_get_one::<String>(id).ok().flatten().cloned();
+    let flag = |id: &str| {
+        cli_args
+            .try_get_one::<bool>(id)
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(false)
+    };
+
+    let component = string("component")
+        .or_else(|| flag("component_by_project").then(|| project.component().to_string()));
+    PublishOptions {
+        force_overwrite: flag("force_overwrite"),
+        endpoint: string("publish_endpoint"),
+        component,
+        passphrase_file: string("signing_passphrase_file").map(PathBuf::from),
+        keyring: string("keyring").map(PathBuf::from),
+        family: cli_args
+            .try_get_one::<DebianFamily>("family")
+            .ok()
+            .flatten()
+            .copied(),
+        distribution_names: cli_args
+            .try_get_many::<(DistributionAlias, String)>("distribution_name")
+            .ok()
+            .flatten()
+            .map(|names| names.cloned().collect())
+            .unwrap_or_default(),
+        skip_gpg_check: no_gpg_check(cli_args),
+        prefix: string("publish_prefix"),
+        also_distribution: string("also_distribution"),
+        cleanup_previous: flag("cleanup_previous"),
+    }
+}
+
+pub fn no_gpg_check(cli_args: &ArgMatches) -> bool {
+    cli_args
+        .try_get_one::<bool>("no_gpg_check")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+}
+
+pub fn aptly_config(cli_args: &ArgMatches) -> Option<&str> {
+    cli_args.get_one::<String>("config").map(|s| s.as_str())
+}
+
+pub fn print_plan(cli_args: &ArgMatches) -> bool {
+    cli_args.get_flag("print_plan")
+}
+
+pub fn aptly_lock_retries(cli_args: &ArgMatches) -> u32 {
+    cli_args
+        .get_one::<u32>("aptly_lock_retries")
+        .copied()
+        .unwrap_or(DEFAULT_LOCK_RETRIES)
+}
+
+pub fn retry_aptly_exit_codes(cli_args: &ArgMatches) -> Vec<i32> {
+    cli_args
+        .get_many::<u8>("retry_aptly_exit_codes")
+        .map(|codes| codes.map(|code| i32::from(*code)).collect())
+        .unwrap_or_default()
+}
+
+pub fn lockfile(cli_args: &ArgMatches) -> Option<PathBuf> {
+    cli_args
+        .try_get_one::<String>("lockfile")
+        .ok()
+        .flatten()
+        .map(PathBuf::from)
+        .or_else(lock::default_lock_path)
+}
+
+pub fn lock_timeout(cli_args: &ArgMatches) -> Duration {
+    cli_args
+        .try_get_one::<u64>("lock_timeout")
+        .ok()
+        .flatten()
+        .map(|secs| Duration::from_secs(*secs))
+        .unwrap_or(DEFAULT_LOCK_TIMEOUT)
+}
+
+pub fn stream_aptly(cli_args: &ArgMatches) -> bool {
+    cli_args.get_flag("stream_aptly")
+}
+
+pub fn quiet(cli_args: &ArgMatches) -> bool {
+    cli_args.get_flag("quiet")
+}
+
+/// Log levels for all crates and for bellhop itself, respectively
+pub fn log_levels(cli_args: &ArgMatches) -> (LevelFilter, LevelFilter) {
+    if quiet(cli_args) {
+        return (LevelFilter::Warn, LevelFilter::Warn);
+    }
+
+    match cli_args.get_count("verbose") {
+        0 => (LevelFilter::Info, LevelFilter::Debug),
+        1 => (LevelFilter::Info, LevelFilter::Trace),
+        2 => (LevelFilter::Debug, LevelFilter::Trace),
+        _ => (LevelFilter::Trace, LevelFilter::Trace),
+    }
+}
+
+pub fn repo_prefix(cli_args: &ArgMatches) -> Option<&str> {
+    cli_args
+        .get_one::<String>("repo_prefix")
+        .map(|s| s.as_str())
+}
+
+pub fn channel(cli_args: &ArgMatches) -> Option<&str> {
+    cli_args.get_one::<String>("channel").map(|s| s.as_str())
+}
+
+pub fn snapshot_source(cli_args: &ArgMatches) -> SnapshotSource {
+    cli_args
+        .get_one::<String>("snapshot_from")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_default()
+}
+
+pub fn output_format(cli_args: &ArgMatches) -> OutputFormat {
+    if json(cli_args) {
+        return OutputFormat::Json;
+    }
+    cli_args
+        .get_one::<String>("format")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_default()
+}
+
+pub fn json(cli_args: &ArgMatches) -> bool {
+    cli_args
+        .try_get_one::<bool>("json")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+}
+
 fn deb_group() -> Command {
     Command::new("deb")
         .about("Manage .deb packages")
         .arg_required_else_help(true)
         .subcommands(package_operation_subcommands())
+        .subcommand(promote_latest_subcommand())
+        .subcommand(unpublish_subcommand())
+        .subcommand(rollback_subcommand())
+}
+
+fn unpublish_subcommand() -> Command {
+    add_publication_location_args(add_distribution_args(
+        Command::new("unpublish")
+            .about("Drop the publications of the repositories, the repositories and snapshots are kept")
+            .arg(yes_arg()),
+        false,
+    ))
+}
+
+fn rollback_subcommand() -> Command {
+    add_publication_args(add_distribution_args(
+        Command::new("rollback")
+            .about("Switch the publications back to the snapshots taken with an earlier suffix")
+            .arg(
+                Arg::new("to")
+                    .long("to")
+                    .value_name("SUFFIX")
+                    .required(true)
+                    .help("Suffix of the snapshots to publish again"),
+            )
+            .arg(yes_arg()),
+        false,
+    ))
+}
+
+fn promote_latest_subcommand() -> Command {
+    add_publication_args(add_distribution_args(
+        Command::new("promote-latest")
+            .about(
+                "Publish a snapshot with only the newest version of each package in the repository",
+            )
+            .arg(
+                Arg::new("remove_older")
+                    .long("remove-older")
+                    .action(ArgAction::SetTrue)
+                    .help("Also remove older versions from the repository"),
+            ),
+        true,
+    ))
 }
 
 fn deb_group_with_github_import() -> Command {
@@ -84,25 +739,132 @@ fn deb_group_with_github_import() -> Command {
 }
 
 fn import_from_github_subcommand() -> Command {
-    add_distribution_args(
+    let cmd = add_distribution_args(
         Command::new("import-from-github")
             .about("Import .deb packages from a GitHub release")
             .arg(
                 Arg::new("github_release_url")
                     .long("github-release-url")
                     .value_name("URL")
-                    .help("GitHub release URL, e.g. https://github.com/owner/repo/releases/tag/v1.0")
-                    .required(true),
+                    .help("GitHub release URL, e.g. https://github.com/owner/repo/releases/tag/v1.0, or a repository URL with --tag-pattern"),
+            )
+            .arg(
+                Arg::new("owner")
+                    .long("owner")
+                    .value_name("OWNER")
+                    .value_parser(parse_github_component)
+                    .requires("github_repo")
+                    .help("Owner of the GitHub repository, an alternative to --github-release-url together with --repo and --tag"),
+            )
+            .arg(
+                Arg::new("github_repo")
+                    .long("repo")
+                    .value_name("REPO")
+                    .value_parser(parse_github_component)
+                    .requires("owner")
+                    .help("Name of the GitHub repository, used with --owner"),
+            )
+            .arg(
+                Arg::new("tag")
+                    .long("tag")
+                    .value_name("TAG")
+                    .value_parser(parse_github_component)
+                    .requires("owner")
+                    .conflicts_with("tag_pattern")
+                    .help("Tag of the release to import, used with --owner and --repo"),
+            )
+            .group(
+                ArgGroup::new("release")
+                    .args(["github_release_url", "owner"])
+                    .required(true)
+                    .multiple(false),
+            )
+            .arg(
+                Arg::new("tag_pattern")
+                    .long("tag-pattern")
+                    .value_name("GLOB")
+                    .help("Import the release with the highest tag matching this glob, e.g. 'v4.2.*', instead of a specific release"),
+            )
+            .arg(
+                Arg::new("include_prereleases")
+                    .long("include-prereleases")
+                    .action(ArgAction::SetTrue)
+                    .requires("tag_pattern")
+                    .help("Consider prereleases when selecting a release with --tag-pattern"),
             )
             .arg(
                 Arg::new("pattern")
                     .long("pattern")
                     .value_name("GLOB")
-                    .help("Glob pattern to filter release assets (default: *amd64*.deb for cli-tools, *.deb for rabbitmq)")
+                    .help("Glob pattern to filter release assets, {distribution} is replaced with each target distribution to import distribution-specific assets (default: *amd64*.deb for cli-tools, *.deb for rabbitmq, *{distribution}*.deb for erlang)")
                     .required(false),
-            ),
+            )
+            .arg(
+                Arg::new("check_only")
+                    .long("check-only")
+                    .action(ArgAction::SetTrue)
+                    .help("Only verify that the release exists and has matching assets, do not download or import anything"),
+            )
+            .arg(
+                Arg::new("download_only")
+                    .long("download-only")
+                    .action(ArgAction::SetTrue)
+                    .requires("keep_downloads")
+                    .conflicts_with("check_only")
+                    .help("Download the matching assets to --dest-dir and print their paths, do not import them (aptly is not needed)"),
+            )
+            .arg(
+                Arg::new("expect_version")
+                    .long("expect-version")
+                    .value_name("VERSION")
+                    .conflicts_with_all(["check_only", "download_only"])
+                    .help("Fail unless every repository has a package of this version after the import, e.g. 4.2.3 (matches 4.2.3-1) or 4.2.3-1"),
+            )
+            .arg(
+                Arg::new("release_notes_file")
+                    .long("release-notes-file")
+                    .value_name("PATH")
+                    .help("Write the release notes (the release body) to this file"),
+            )
+            .arg(
+                Arg::new("keep_downloads")
+                    .long("keep-downloads")
+                    .visible_alias("dest-dir")
+                    .value_name("DIR")
+                    .help("Download the assets to this directory and keep them there, assets that are already there are not downloaded again"),
+            )
+            .arg(only_arch_arg())
+            .arg(
+                Arg::new("http_timeout")
+                    .long("http-timeout")
+                    .value_name("SECONDS")
+                    .value_parser(value_parser!(u64).range(1..))
+                    .help("Give up on a GitHub API request or an asset download that takes longer than this [default: 600]"),
+            )
+            .arg(
+                Arg::new("download_jobs")
+                    .long("download-jobs")
+                    .value_name("N")
+                    .value_parser(parse_download_jobs)
+                    .help("Download up to this many assets at a time [default: 4]"),
+            )
+            .arg(
+                Arg::new("max_download_size")
+                    .long("max-download-size")
+                    .value_name("BYTES")
+                    .value_parser(value_parser!(u64).range(1..))
+                    .help("Refuse to download assets larger than this, and abort downloads that exceed their declared size [default: no limit]"),
+            )
+            .arg(
+                Arg::new("no_cache")
+                    .long("no-cache")
+                    .action(ArgAction::SetTrue)
+                    .help("Always fetch the release metadata from the GitHub API instead of using a recently cached copy"),
+            )
+            .arg(fsync_arg()),
         true,
-    )
+    );
+    add_snapshot_source_arg(cmd)
 }
 
 fn rpm_group() -> Command {
@@ -116,6 +878,8 @@ fn rabbitmq_group() -> Command {
     Command::new("rabbitmq")
         .about("Manage RabbitMQ packages")
         .arg_required_else_help(true)
+        .arg(repo_prefix_arg())
+        .arg(channel_arg())
         .subcommands([
             deb_group_with_github_import(),
             rpm_group(),
@@ -127,16 +891,40 @@ fn erlang_group() -> Command {
     Command::new("erlang")
         .about("Manage Erlang packages")
         .arg_required_else_help(true)
-        .subcommands([deb_group(), rpm_group(), snapshot_group()])
+        .arg(repo_prefix_arg())
+        .arg(channel_arg())
+        .subcommands([
+            deb_group_with_github_import(),
+            rpm_group(),
+            snapshot_group(),
+        ])
 }
 
 fn cli_tools_group() -> Command {
     Command::new("cli-tools")
         .about("Manage CLI tools packages (rabbitmqadmin, rabbitmq-lqt)")
         .arg_required_else_help(true)
+        .arg(repo_prefix_arg())
+        .arg(channel_arg())
         .subcommands([deb_group_with_github_import(), snapshot_group()])
 }
 
+fn channel_arg() -> Arg {
+    Arg::new("channel")
+        .long("channel")
+        .value_name("NAME")
+        .global(true)
+        .help("Keeps a separate package stream, e.g. 'nightly', by adding it to repository, snapshot and publishing names, e.g. 'repo-rabbitmq-server-nightly-bookworm'")
+}
+
+fn repo_prefix_arg() -> Arg {
+    Arg::new("repo_prefix")
+        .long("repo-prefix")
+        .value_name("PREFIX")
+        .global(true)
+        .help("Overrides the project component of repository, snapshot and publishing names, e.g. 'rabbitmq-server' in 'repo-rabbitmq-server-bookworm'")
+}
+
 fn repositories_group() -> Command {
     Command::new("repositories")
         .about("Manage aptly repositories")
@@ -146,6 +934,76 @@ fn repositories_group() -> Command {
                 .visible_alias("setup")
                 .about("Create all expected aptly repositories (idempotent)"),
         )
+        .subcommand(
+            Command::new("list")
+                .visible_alias("status")
+                .about("List the expected aptly repositories and whether they exist")
+                .arg(format_arg()),
+        )
+}
+
+fn mirror_group() -> Command {
+    let name_arg = Arg::new("name")
+        .long("name")
+        .value_name("NAME")
+        .required(true)
+        .help("Name of the aptly mirror");
+
+    Command::new("mirror")
+        .about("Seed repositories from upstream apt repositories")
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("create")
+                .about("Create an aptly mirror of an upstream apt repository")
+                .arg(name_arg.clone())
+                .arg(
+                    Arg::new("url")
+                        .long("url")
+                        .value_name("URL")
+                        .required(true)
+                        .help("Upstream repository URL, e.g. https://deb.nodesource.com/node_22.x"),
+                )
+                .arg(
+                    Arg::new("distribution")
+                        .long("distribution")
+                        .value_name("DISTRIBUTION")
+                        .required(true)
+                        .help("Upstream distribution to mirror, e.g. nodistro"),
+                )
+                .arg(
+                    Arg::new("components")
+                        .long("components")
+                        .value_name("COMPONENTS")
+                        .value_delimiter(',')
+                        .help("A comma-separated list of components to mirror [default: all of them]"),
+                )
+                .arg(
+                    Arg::new("architectures")
+                        .long("architectures")
+                        .value_name("ARCHITECTURES")
+                        .value_delimiter(',')
+                        .help("A comma-separated list of architectures to mirror [default: all of them]"),
+                ),
+        )
+        .subcommand(
+            Command::new("update")
+                .about("Download the upstream changes of a mirror and snapshot it")
+                .arg(name_arg)
+                .arg(
+                    Arg::new("suffix")
+                        .long("suffix")
+                        .value_name("SUFFIX")
+                        .help("Snapshot name suffix, defaults to today's date"),
+                )
+                .arg(suffix_date_format_arg().conflicts_with("suffix")),
+        )
+}
+
+pub fn strings(cli_args: &ArgMatches, id: &str) -> Vec<String> {
+    cli_args
+        .get_many::<String>(id)
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default()
 }
 
 fn snapshot_group() -> Command {
@@ -162,7 +1020,7 @@ fn add_distribution_args(cmd: Command, include_suffix: bool) -> Command {
                 .short('a')
                 .long("all")
                 .action(ArgAction::SetTrue)
-                .conflicts_with("distributions")
+                .conflicts_with_all(["distributions", "dist_file"])
                 .help("Add the package to all distributions"),
         )
         .arg(
@@ -176,29 +1034,269 @@ fn add_distribution_args(cmd: Command, include_suffix: bool) -> Command {
                 .action(ArgAction::Append)
                 .help("A comma-separated list of distributions to add the package to"),
         )
+        .arg(
+            Arg::new("except")
+                .long("except")
+                .value_name("DISTRIBUTIONS")
+                // the distribution group is required, so this leaves only --all
+                .conflicts_with_all(["distributions", "dist_file"])
+                .num_args(1..)
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("A comma-separated list of distributions to leave out of --all"),
+        )
+        .arg(
+            Arg::new("dist_file")
+                .long("dist-file")
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with_all(["all", "distributions"])
+                .help("Read the distributions from a file, one per line or comma-separated"),
+        )
+        .arg(
+            Arg::new("distribution_order")
+                .long("distribution-order")
+                .value_name("DISTRIBUTIONS")
+                .num_args(1..)
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("A comma-separated list of distributions to process first, in this order (default: $BELLHOP_DISTRIBUTION_ORDER)"),
+        )
         .group(
             ArgGroup::new("distribution")
-                .args(["all", "distributions"])
+                .args(["all", "distributions", "dist_file"])
                 .required(true)
                 .multiple(false),
         );
 
     if include_suffix {
-        cmd = cmd.arg(
-            Arg::new("suffix")
-                .long("suffix")
-                .value_name("NAME")
-                .help("Snapshot suffix name, e.g. a date in the %d-%b-%y format, such as 04-Aug-25")
-                .required(false),
-        );
+        cmd = cmd
+            .arg(
+                Arg::new("suffix")
+                    .long("suffix")
+                    .value_name("NAME")
+                    .help("Snapshot suffix name, e.g. a date in the %d-%b-%y format, such as 04-Aug-25")
+                    .required(false),
+            )
+            .arg(suffix_date_format_arg().conflicts_with("suffix"));
     }
 
     cmd
 }
 
-fn snapshot_subcommands() -> [Command; 3] {
-    let list_cmd = add_distribution_args(Command::new("list").about("List snapshots"), true);
-    let create_cmd = add_distribution_args(Command::new("take").about("Take a snapshot"), true);
+fn input_format_arg() -> Arg {
+    Arg::new("input_format")
+        .long("input-format")
+        .value_name("FORMAT")
+        .value_parser(["auto", "deb", "tar", "tar.gz", "tgz", "zip"])
+        .default_value("auto")
+        .help("How to interpret the package file, 'auto' goes by its extension")
+}
+
+fn max_extracted_bytes_arg() -> Arg {
+    Arg::new("max_extracted_bytes")
+        .long("max-extracted-bytes")
+        .value_name("BYTES")
+        .value_parser(value_parser!(u64))
+        .help("Abort if the files extracted from an archive add up to more than this many bytes [default: 10 GiB]")
+}
+
+fn max_entries_arg() -> Arg {
+    Arg::new("max_entries")
+        .long("max-entries")
+        .value_name("COUNT")
+        .value_parser(value_parser!(u64))
+        .help("Abort if an archive has more than this many entries [default: 100000]")
+}
+
+fn follow_symlinks_arg() -> Arg {
+    Arg::new("follow_symlinks")
+        .long("follow-symlinks")
+        .action(ArgAction::SetTrue)
+        .help("Extract symlinks in archives as copies of their targets instead of skipping them, symlinks that point outside of the archive are an error")
+}
+
+fn only_arch_arg() -> Arg {
+    Arg::new("only_arch")
+        .long("only-arch")
+        .value_name("ARCHITECTURES")
+        .num_args(1..)
+        .value_delimiter(',')
+        .action(ArgAction::Append)
+        .help("A comma-separated list of architectures to keep, other packages are skipped (packages for 'all' are always kept)")
+}
+
+fn include_ddeb_arg() -> Arg {
+    Arg::new("include_ddeb")
+        .long("include-ddeb")
+        .action(ArgAction::SetTrue)
+        .help("Also add .ddeb debug symbol packages, by default only .deb files are added")
+}
+
+fn no_gpg_check_arg() -> Arg {
+    Arg::new("no_gpg_check")
+        .long("no-gpg-check")
+        .action(ArgAction::SetTrue)
+        .help("Do not check that gpg has the signing key before publishing")
+}
+
+fn yes_arg() -> Arg {
+    Arg::new("yes")
+        .short('y')
+        .long("yes")
+        .action(ArgAction::SetTrue)
+        .help("Do not ask for confirmation")
+}
+
+fn fsync_arg() -> Arg {
+    Arg::new("fsync")
+        .long("fsync")
+        .action(ArgAction::SetTrue)
+        .help("Flush downloaded and extracted .deb files to disk before handing them to aptly")
+}
+
+/// Where `deb publish` publishes to, for the commands that find or drop publications
+fn add_publication_location_args(cmd: Command) -> Command {
+    cmd
+        .arg(
+            Arg::new("publish_endpoint")
+                .long("publish-endpoint")
+                .value_name("NAME")
+                .help("Publish to this endpoint from the S3PublishEndpoints section of the aptly configuration"),
+        )
+        .arg(
+            Arg::new("family")
+                .long("family")
+                .value_name("FAMILY")
+                .value_parser(|s: &str| s.parse::<DebianFamily>())
+                .help("Publish under the 'debian' or 'ubuntu' prefix regardless of the distribution's own family"),
+        )
+        .arg(
+            Arg::new("distribution_name")
+                .long("distribution-name")
+                .value_name("ALIAS=NAME")
+                .value_parser(parse_distribution_name)
+                .action(ArgAction::Append)
+                .help("Publish a distribution under another aptly distribution name, e.g. bookworm=stable (can be repeated)"),
+        )
+        .arg(
+            Arg::new("publish_prefix")
+                .long("publish-prefix")
+                .value_name("PREFIX")
+                .conflicts_with("family")
+                .help("Publish under this prefix instead of <project>/<family>/<release>, '.' publishes at the root"),
+        )
+        .arg(
+            Arg::new("also_distribution")
+                .long("also-distribution")
+                .value_name("NAME")
+                .value_parser(parse_additional_distribution_name)
+                .help("Also publish the same snapshot under this aptly distribution name, e.g. stable"),
+        )
+}
+
+/// The flags of `deb publish`, for the commands that publish as well
+fn add_publication_args(cmd: Command) -> Command {
+    add_publication_location_args(cmd)
+        .arg(
+            Arg::new("force_overwrite")
+                .long("force-overwrite")
+                .action(ArgAction::SetTrue)
+                .help("Overwrite files in the published pool that have the same name but different contents"),
+        )
+        .arg(
+            Arg::new("component")
+                .long("component")
+                .value_name("NAME")
+                .help("Publish the packages under this component instead of 'main'"),
+        )
+        .arg(
+            Arg::new("component_by_project")
+                .long("component-by-project")
+                .action(ArgAction::SetTrue)
+                .help("Publish under the project's own component, rabbitmq, erlang or cli, unless --component is given"),
+        )
+        .arg(
+            Arg::new("signing_passphrase_file")
+                .long("signing-passphrase-file")
+                .value_name("PATH")
+                .help("Read the signing key passphrase from this file instead of prompting for it"),
+        )
+        .arg(
+            Arg::new("keyring")
+                .long("keyring")
+                .value_name("PATH")
+                .help("Look the signing key up in this GPG keyring file instead of the default keyring"),
+        )
+        .arg(no_gpg_check_arg())
+}
+
+/// Adds `--repo`, which takes the place of the distribution arguments along with the ones
+/// that only make sense for distributions, e.g. snapshot suffixes
+fn add_direct_repo_arg(cmd: Command, conflicts: &[&'static str]) -> Command {
+    cmd.arg(
+        Arg::new("repo")
+            .long("repo")
+            .value_name("NAME")
+            .conflicts_with_all(["all", "distributions", "dist_file", "except", "suffix"])
+            .conflicts_with_all(conflicts)
+            .help("Target this aptly repository as is instead of the distributions' repositories, no snapshots are taken"),
+    )
+    .mut_group("distribution", |group| group.arg("repo"))
+}
+
+pub fn direct_repo(cli_args: &ArgMatches) -> Option<&str> {
+    cli_args
+        .try_get_one::<String>("repo")
+        .ok()
+        .flatten()
+        .map(String::as_str)
+}
+
+fn add_snapshot_source_arg(cmd: Command) -> Command {
+    cmd.arg(
+        Arg::new("snapshot_from")
+            .long("snapshot-from")
+            .value_name("SOURCE")
+            .value_parser(["repo", "published"])
+            .default_value("repo")
+            .help("What new snapshots are based on: the repository, or the currently published snapshot merged with the repository"),
+    )
+}
+
+fn snapshot_subcommands() -> [Command; 6] {
+    let list_cmd = add_distribution_args(
+        add_date_range_args(
+            Command::new("list")
+                .about("List snapshots")
+                .arg(Arg::new("grep").long("grep").value_name("SUBSTRING").help(
+                    "Only list the packages whose key, e.g. rabbitmq-server_4.1.4-1_all, contains this",
+                ))
+                .arg(format_arg()),
+        )
+        .mut_arg("after", |arg| arg.conflicts_with("suffix"))
+        .mut_arg("before", |arg| arg.conflicts_with("suffix")),
+        true,
+    );
+    let show_cmd = Command::new("show")
+        .about("Show the details and packages of a snapshot given by its exact name")
+        .arg(
+            Arg::new("name")
+                .value_name("NAME")
+                .required(true)
+                .help("Snapshot name, e.g. snap-rabbitmq-server-bookworm-04-Aug-25"),
+        )
+        .arg(format_arg());
+    let create_cmd = add_snapshot_source_arg(add_distribution_args(
+        Command::new("take").about("Take a snapshot").arg(
+            Arg::new("from_published")
+                .long("from-published")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("snapshot_from")
+                .help("Copy the currently published snapshot instead of snapshotting the repository, e.g. to keep it for a rollback"),
+        ),
+        true,
+    ));
     let delete_cmd = add_distribution_args(
         Command::new("delete")
             .about("Delete a snapshot")
@@ -206,11 +1304,106 @@ fn snapshot_subcommands() -> [Command; 3] {
         true,
     );
 
-    [list_cmd, create_cmd, delete_cmd]
+    let orphans_cmd = add_distribution_args(
+        add_date_range_args(
+            Command::new("orphans")
+                .about("List snapshots that are neither published nor the latest dated snapshot of their distribution")
+                .arg(format_arg())
+                .arg(suffix_date_format_arg()),
+        ),
+        false,
+    );
+
+    let diff_cmd = add_distribution_args(
+        Command::new("diff")
+            .about("Compare the packages of two snapshots of every distribution")
+            .arg(
+                Arg::new("from")
+                    .long("from")
+                    .value_name("SUFFIX")
+                    .required(true)
+                    .help("Suffix of the older snapshot"),
+            )
+            .arg(
+                Arg::new("to")
+                    .long("to")
+                    .value_name("SUFFIX")
+                    .required(true)
+                    .help("Suffix of the newer snapshot"),
+            )
+            .arg(format_arg()),
+        false,
+    );
+
+    [
+        list_cmd,
+        show_cmd,
+        create_cmd,
+        delete_cmd,
+        orphans_cmd,
+        diff_cmd,
+    ]
+}
+
+/// `--after` and `--before` select snapshots by the date in their suffix, snapshots with other
+/// suffixes are left out
+fn add_date_range_args(cmd: Command) -> Command {
+    cmd.arg(
+        Arg::new("after")
+            .long("after")
+            .value_name("DATE")
+            .value_parser(parse_snapshot_date)
+            .help("Only snapshots dated after this date, e.g. 04-Aug-25 or 2025-08-04"),
+    )
+    .arg(
+        Arg::new("before")
+            .long("before")
+            .value_name("DATE")
+            .value_parser(parse_snapshot_date)
+            .help("Only snapshots dated before this date, e.g. 04-Aug-25 or 2025-08-04"),
+    )
+}
+
+/// Accepts the default snapshot suffix format, e.g. `04-Aug-25`, and ISO 8601 dates
+pub fn parse_snapshot_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, DEFAULT_SUFFIX_DATE_FORMAT)
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .map_err(|_| format!("'{value}' is not a date like 04-Aug-25 or 2025-08-04"))
+}
+
+pub fn date_range(cli_args: &ArgMatches) -> DateRange {
+    let date = |id: &str| {
+        cli_args
+            .try_get_one::<NaiveDate>(id)
+            .ok()
+            .flatten()
+            .copied()
+    };
+    DateRange {
+        after: date("after"),
+        before: date("before"),
+    }
+}
+
+fn suffix_date_format_arg() -> Arg {
+    Arg::new("suffix_date_format")
+        .long("suffix-date-format")
+        .value_name("FORMAT")
+        .value_parser(parse_suffix_date_format)
+        .help("chrono format of the date used as the snapshot suffix when --suffix is not given, e.g. %Y-%m-%d [default: %d-%b-%y]")
+}
+
+fn format_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .value_name("FORMAT")
+        .value_parser(["text", "json"])
+        .default_value("text")
+        .help("Output format")
 }
 
 fn package_operation_subcommands() -> [Command; 3] {
-    let add_cmd = add_distribution_args(
+    let add_cmd = add_snapshot_source_arg(add_distribution_args(
         Command::new("add")
             .about("Add a package to one or multiple distributions")
             .arg(
@@ -218,21 +1411,130 @@ fn package_operation_subcommands() -> [Command; 3] {
                     .short('p')
                     .long("package-file-path")
                     .value_name("PATH")
-                    .help("Binary package file path")
-                    .required(true),
+                    .num_args(1..)
+                    .action(ArgAction::Append)
+                    .help("Binary package file paths (.deb, .zip, .tar.gz), the snapshots are taken once for all of them")
+                    .required_unless_present("from_stdin"),
+            )
+            .arg(
+                Arg::new("from_stdin")
+                    .long("from-stdin")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("package_file_path")
+                    .help("Read package file paths from standard input, one per line"),
+            )
+            .arg(input_format_arg())
+            .arg(max_extracted_bytes_arg())
+            .arg(max_entries_arg())
+            .arg(follow_symlinks_arg())
+            .arg(only_arch_arg())
+            .arg(include_ddeb_arg())
+            .arg(
+                Arg::new("exclude")
+                    .long("exclude")
+                    .value_name("GLOB")
+                    .action(ArgAction::Append)
+                    .help("Skip the packages in an archive whose file name matches this glob, e.g. '*-dbg_*', can be repeated"),
+            )
+            .arg(
+                Arg::new("with_source")
+                    .long("with-source")
+                    .action(ArgAction::SetTrue)
+                    .help("Also add .dsc source packages, their .orig.tar.* and .debian.tar.* files must be next to them"),
+            )
+            .arg(fsync_arg())
+            .arg(
+                Arg::new("newer_only")
+                    .long("newer-only")
+                    .action(ArgAction::SetTrue)
+                    .help("Skip packages whose repository already has the same or a newer version"),
+            )
+            .arg(
+                Arg::new("if_not_present")
+                    .long("if-not-present")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("newer_only")
+                    .help("Skip packages that the repository already has, e.g. when rerunning a failed job"),
+            )
+            .arg(
+                Arg::new("no_snapshot")
+                    .long("no-snapshot")
+                    .visible_alias("repo-only")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with_all(["suffix", "suffix_date_format"])
+                    .help("Only add the packages to the repositories, take the snapshots later with 'snapshot take'"),
+            )
+            .arg(
+                Arg::new("continue")
+                    .long("continue")
+                    .action(ArgAction::SetTrue)
+                    .help("Carry on with the other distributions when one fails, the failures are reported at the end"),
+            )
+            .arg(
+                Arg::new("exit_on_first_error")
+                    .long("exit-on-first-error")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("continue")
+                    .help("Stop at the first distribution that fails, this is the default"),
+            )
+            .arg(
+                Arg::new("no_validate")
+                    .long("no-validate")
+                    .action(ArgAction::SetTrue)
+                    .help("Do not check that every .deb file is a Debian package before adding it"),
+            )
+            .arg(
+                Arg::new("arch_map")
+                    .long("arch-map")
+                    .value_name("FROM=TO")
+                    .value_parser(parse_arch_map)
+                    .action(ArgAction::Append)
+                    .help("Rewrite the Architecture field of packages built for FROM to TO before adding them, e.g. all=amd64 (can be repeated)"),
+            )
+            .arg(
+                Arg::new("check_arch")
+                    .long("check-arch")
+                    .action(ArgAction::SetTrue)
+                    .help("Fail before adding anything if a package's architecture is not one of the repository architectures"),
+            )
+            .arg(
+                Arg::new("publish")
+                    .long("publish")
+                    .visible_alias("publish-after-add")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("no_snapshot")
+                    .help("Publish the new snapshots right after taking them, with the same suffix"),
+            )
+            .arg(
+                Arg::new("package_name")
+                    .long("package-name")
+                    .value_name("PACKAGE_NAME")
+                    .requires("version")
+                    .help("Name of the package, for a single .deb file whose name does not follow the name_version_arch.deb convention"),
+            )
+            .arg(
+                Arg::new("version")
+                    .long("version")
+                    .value_name("VERSION")
+                    .requires("package_name")
+                    .help("Version of the package, for a single .deb file whose name does not follow the name_version_arch.deb convention"),
             ),
         true,
+    ));
+    let add_cmd = add_direct_repo_arg(
+        add_publication_args(add_cmd),
+        &["newer_only", "if_not_present", "no_snapshot", "publish"],
     );
 
-    let remove_cmd = add_distribution_args(
+    let remove_cmd = add_snapshot_source_arg(add_distribution_args(
         Command::new("remove")
             .about("Remove a .deb package from one or multiple distributions")
             .arg(
                 Arg::new("version")
-                    .short('v')
+                    .short('V')
                     .long("version")
                     .value_name("VERSION")
-                    .conflicts_with("package_file_path")
+                    .conflicts_with_all(["package_file_path", "all_versions"])
                     .help("Version of the package to remove"),
             )
             .arg(
@@ -240,24 +1542,86 @@ fn package_operation_subcommands() -> [Command; 3] {
                     .short('p')
                     .long("package-file-path")
                     .value_name("PATH")
-                    .conflicts_with("version")
+                    .conflicts_with_all(["version", "all_versions"])
                     .help("Package file path (.deb, .zip, .tar.gz)"),
             )
+            .arg(input_format_arg().requires("package_file_path"))
+            .arg(max_extracted_bytes_arg().requires("package_file_path"))
+            .arg(max_entries_arg().requires("package_file_path"))
+            .arg(follow_symlinks_arg().requires("package_file_path"))
+            .arg(
+                Arg::new("package_name")
+                    .long("package-name")
+                    .value_name("PACKAGE_NAME")
+                    .requires("version")
+                    .conflicts_with_all(["package_file_path", "all_versions"])
+                    .help("Only remove the package with this exact name, e.g. erlang-base, instead of all of the project's packages of that version"),
+            )
+            .arg(
+                Arg::new("all_versions")
+                    .long("all-versions")
+                    .value_name("PACKAGE_NAME")
+                    .conflicts_with_all(["version", "package_file_path"])
+                    .help("Remove every version of the named package (for Erlang, of every package whose name starts with it)"),
+            )
+            .arg(
+                Arg::new("exact")
+                    .long("exact")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with_all(["version", "all_versions"])
+                    .help("Only remove the packages of the file, by the name and version in their control files, instead of every package of the project with one of their versions"),
+            )
+            .arg(
+                Arg::new("republish")
+                    .long("republish")
+                    .action(ArgAction::SetTrue)
+                    .help("Publish the recreated snapshots so that the removal takes effect immediately"),
+            )
+            .arg(
+                Arg::new("snapshot_only")
+                    .long("snapshot-only")
+                    .action(ArgAction::SetTrue)
+                    .requires_all(["version", "suffix", "to"])
+                    .conflicts_with("republish")
+                    .help("Leave the repository as it is and write copies of the --suffix snapshots without the version, named with the --to suffix"),
+            )
+            .arg(
+                Arg::new("to")
+                    .long("to")
+                    .value_name("SUFFIX")
+                    .requires("snapshot_only")
+                    .help("Suffix of the snapshots --snapshot-only writes"),
+            )
             .group(
                 ArgGroup::new("input")
-                    .args(["version", "package_file_path"])
+                    .args(["version", "package_file_path", "all_versions"])
                     .required(true)
                     .multiple(false),
             ),
         true,
-    );
+    ));
+    let remove_cmd = add_direct_repo_arg(add_publication_args(remove_cmd), &["republish"]);
 
-    let publish_cmd = add_distribution_args(
-        Command::new("publish").about(
-            "Regenerates all repositories from recent snapshots (created by the 'add' command)",
-        ),
+    let publish_cmd = add_publication_args(add_distribution_args(
+        Command::new("publish")
+            .about(
+                "Regenerates all repositories from recent snapshots (created by the 'add' command)",
+            )
+            .arg(
+                Arg::new("cleanup_previous")
+                    .long("cleanup-previous")
+                    .action(ArgAction::SetTrue)
+                    .help("Drop the snapshots that were published before the switch, unless they are still published elsewhere"),
+            )
+            .arg(
+                Arg::new("update_only")
+                    .long("update-only")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("cleanup_previous")
+                    .help("Refresh and re-sign the existing publications (aptly publish update) without switching snapshots, e.g. after a key rotation"),
+            ),
         true,
-    );
+    ));
 
     [add_cmd, remove_cmd, publish_cmd]
 }
@@ -270,8 +1634,35 @@ fn watch_command() -> Command {
                 Arg::new("root")
                     .long("root")
                     .value_name("PATH")
-                    .help("Root directory containing project subdirectories (rabbitmq-server/, rabbitmq-erlang/, rabbitmq-cli/)")
+                    .help("Root directory containing project subdirectories (rabbitmq-server/, rabbitmq-erlang/, rabbitmq-cli/ unless --watch-map is given)")
                     .required(true),
+            )
+            .arg(
+                Arg::new("watch_removals")
+                    .long("watch-removals")
+                    .action(ArgAction::SetTrue)
+                    .help("Remove a package version from the repositories when its .deb file is deleted"),
+            )
+            .arg(
+                Arg::new("watch_publish")
+                    .long("watch-publish")
+                    .action(ArgAction::SetTrue)
+                    .help("Publish the snapshots that are taken once the watched directories are quiet"),
+            )
+            .arg(
+                Arg::new("watch_map")
+                    .long("watch-map")
+                    .value_name("DIR=PROJECT")
+                    .value_parser(parse_watch_map)
+                    .action(ArgAction::Append)
+                    .help("Watch this subdirectory of the root for packages of the project (rabbitmq, erlang or cli-tools) instead of the default subdirectories, e.g. server=rabbitmq (can be repeated)"),
+            )
+            .arg(
+                Arg::new("once")
+                    .long("once")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("watch_removals")
+                    .help("Import the .deb files that are already in the watched directories, then exit instead of watching"),
             ),
         false,
     )
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Project {
@@ -29,3 +33,93 @@ impl Display for Project {
         }
     }
 }
+
+impl FromStr for Project {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rabbitmq" => Ok(Project::RabbitMQ),
+            "erlang" => Ok(Project::Erlang),
+            "cli-tools" => Ok(Project::CliTools),
+            _ => Err(format!("Unsupported project: {s}")),
+        }
+    }
+}
+
+impl Project {
+    /// The component the project publishes under with `--component-by-project`, so that
+    /// several projects can share a publish prefix
+    pub fn component(&self) -> &'static str {
+        match self {
+            Project::RabbitMQ => "rabbitmq",
+            Project::Erlang => "erlang",
+            Project::CliTools => "cli",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SnapshotSource {
+    #[default]
+    Repo,
+    Published,
+}
+
+impl FromStr for SnapshotSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "repo" => Ok(SnapshotSource::Repo),
+            "published" => Ok(SnapshotSource::Published),
+            _ => Err(format!("Unsupported snapshot source: {s}")),
+        }
+    }
+}
+
+impl Display for SnapshotSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotSource::Repo => write!(f, "repo"),
+            SnapshotSource::Published => write!(f, "published"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unsupported output format: {s}")),
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Syncing the directory as well makes sure the directory entry of a new file survives a crash
+pub fn sync_file_and_parent(path: &Path) -> io::Result<()> {
+    File::open(path)?.sync_all()?;
+    if let Some(parent) = path.parent() {
+        File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,329 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::archive::{self, AR_MAGIC, AR_MEMBER_HEADER_LEN};
+use crate::errors::BellhopError;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use tar::{Archive, Builder};
+
+const CONTROL_MEMBER_PREFIX: &str = "control.tar";
+const ARCHITECTURE_FIELD: &str = "Architecture";
+const PACKAGE_FIELD: &str = "Package";
+const VERSION_FIELD: &str = "Version";
+
+struct ArMember {
+    name: String,
+    /// The raw header, so that the timestamp, owner and mode of a member are kept
+    header: [u8; AR_MEMBER_HEADER_LEN],
+    data: Vec<u8>,
+}
+
+/// The value of a field of the control file, e.g. `Architecture`
+pub fn control_field(deb_path: &Path, field: &str) -> Result<Option<String>, BellhopError> {
+    let control = read_deb_control(deb_path)?;
+    Ok(field_value(&control, field).map(str::to_string))
+}
+
+/// The `Package` and `Version` fields of the control file, the version includes the epoch if any
+pub fn name_and_version(deb_path: &Path) -> Result<(String, String), BellhopError> {
+    let control = read_deb_control(deb_path)?;
+    let field = |name: &str| {
+        field_value(&control, name)
+            .map(str::to_string)
+            .ok_or_else(|| {
+                rewrite_failed(deb_path, format!("the control file has no {name} field"))
+            })
+    };
+    Ok((field(PACKAGE_FIELD)?, field(VERSION_FIELD)?))
+}
+
+/// The architecture the control file declares, for packages whose file name does not tell
+pub fn architecture(deb_path: &Path) -> Result<String, BellhopError> {
+    control_field(deb_path, ARCHITECTURE_FIELD)?
+        .ok_or_else(|| rewrite_failed(deb_path, "the control file has no Architecture field"))
+}
+
+/// Writes a copy of the package to `dest_dir` with the `Architecture` field changed to the
+/// target of the first mapping whose source matches it, e.g. for a package built for `all` that
+/// a repository only accepts as `amd64`. Only `control.tar.*` is repacked, `data.tar.*` is copied
+/// as it is. The copy is named after the new architecture. Returns `None` when no mapping applies.
+pub fn map_architecture(
+    deb_path: &Path,
+    arch_map: &[(String, String)],
+    dest_dir: &Path,
+) -> Result<Option<PathBuf>, BellhopError> {
+    let mut members = read_ar_members(deb_path)?;
+    let control_member = find_control_member(deb_path, &members)?;
+    let control = read_control_file(deb_path, control_member)?;
+
+    let Some(arch) = field_value(&control, ARCHITECTURE_FIELD) else {
+        return Err(rewrite_failed(
+            deb_path,
+            "the control file has no Architecture field",
+        ));
+    };
+    let Some((_, to)) = arch_map.iter().find(|(from, _)| from == arch) else {
+        return Ok(None);
+    };
+
+    let rewritten = replace_field(&control, ARCHITECTURE_FIELD, to);
+    let index = members
+        .iter()
+        .position(|m| m.name.starts_with(CONTROL_MEMBER_PREFIX))
+        .expect("the control member was found above");
+    let repacked = repack_control_member(deb_path, &members[index], &rewritten)?;
+    set_member_data(&mut members[index], repacked);
+
+    let dest_path = dest_dir.join(rewritten_file_name(deb_path, to));
+    fs::write(&dest_path, write_ar_members(&members))?;
+    Ok(Some(dest_path))
+}
+
+fn rewritten_file_name(deb_path: &Path, arch: &str) -> String {
+    let file_name = deb_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match archive::extract_package_key_from_filename(&file_name) {
+        Ok((name, version, _)) => format!("{name}_{version}_{arch}.deb"),
+        Err(_) => file_name,
+    }
+}
+
+fn rewrite_failed(deb_path: &Path, reason: impl Into<String>) -> BellhopError {
+    BellhopError::DebRewriteFailed {
+        path: deb_path.to_path_buf(),
+        reason: reason.into(),
+    }
+}
+
+fn read_deb_control(deb_path: &Path) -> Result<String, BellhopError> {
+    let members = read_ar_members(deb_path)?;
+    let control_member = find_control_member(deb_path, &members)?;
+    read_control_file(deb_path, control_member)
+}
+
+fn read_ar_members(deb_path: &Path) -> Result<Vec<ArMember>, BellhopError> {
+    let bytes = fs::read(deb_path)?;
+    let invalid = |reason: &str| rewrite_failed(deb_path, reason);
+    if !bytes.starts_with(AR_MAGIC) {
+        return Err(invalid("not an ar archive"));
+    }
+
+    let mut members = Vec::new();
+    let mut offset = AR_MAGIC.len();
+    while offset < bytes.len() {
+        let header: [u8; AR_MEMBER_HEADER_LEN] = bytes
+            .get(offset..offset + AR_MEMBER_HEADER_LEN)
+            .and_then(|h| h.try_into().ok())
+            .ok_or_else(|| invalid("truncated member header"))?;
+        let name = String::from_utf8_lossy(&header[..16])
+            .trim_end()
+            .trim_end_matches('/')
+            .to_string();
+        let size: usize = String::from_utf8_lossy(&header[48..58])
+            .trim()
+            .parse()
+            .map_err(|_| invalid("malformed member header"))?;
+
+        let start = offset + AR_MEMBER_HEADER_LEN;
+        let data = bytes
+            .get(start..start + size)
+            .ok_or_else(|| invalid("truncated member"))?
+            .to_vec();
+        members.push(ArMember { name, header, data });
+        // members are padded to an even length
+        offset = start + size + size % 2;
+    }
+    Ok(members)
+}
+
+fn write_ar_members(members: &[ArMember]) -> Vec<u8> {
+    let mut bytes = AR_MAGIC.to_vec();
+    for member in members {
+        bytes.extend_from_slice(&member.header);
+        bytes.extend_from_slice(&member.data);
+        if member.data.len() % 2 == 1 {
+            bytes.push(b'\n');
+        }
+    }
+    bytes
+}
+
+fn set_member_data(member: &mut ArMember, data: Vec<u8>) {
+    let size = format!("{:<10}", data.len());
+    member.header[48..58].copy_from_slice(size.as_bytes());
+    member.data = data;
+}
+
+fn find_control_member<'a>(
+    deb_path: &Path,
+    members: &'a [ArMember],
+) -> Result<&'a ArMember, BellhopError> {
+    members
+        .iter()
+        .find(|m| m.name.starts_with(CONTROL_MEMBER_PREFIX))
+        .ok_or_else(|| rewrite_failed(deb_path, "no control.tar member"))
+}
+
+fn read_control_file(deb_path: &Path, member: &ArMember) -> Result<String, BellhopError> {
+    let tar = decompress(deb_path, member)?;
+    let mut archive = Archive::new(tar.as_slice());
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if is_control_file(&entry.path()?) {
+            let mut control = String::new();
+            entry.read_to_string(&mut control)?;
+            return Ok(control);
+        }
+    }
+    Err(rewrite_failed(
+        deb_path,
+        "no control file in the control archive",
+    ))
+}
+
+/// Copies every entry of the control archive with its original header, except for the control
+/// file, which gets the given contents
+fn repack_control_member(
+    deb_path: &Path,
+    member: &ArMember,
+    control: &str,
+) -> Result<Vec<u8>, BellhopError> {
+    let tar = decompress(deb_path, member)?;
+    let mut archive = Archive::new(tar.as_slice());
+    let mut builder = Builder::new(Vec::new());
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let mut header = entry.header().clone();
+        if is_control_file(&entry.path()?) {
+            header.set_size(control.len() as u64);
+            header.set_cksum();
+            builder.append(&header, control.as_bytes())?;
+        } else {
+            builder.append(&header, &mut entry)?;
+        }
+    }
+    let tar = builder.into_inner()?;
+    compress(deb_path, member, tar)
+}
+
+fn is_control_file(path: &Path) -> bool {
+    path.strip_prefix(".").unwrap_or(path) == Path::new("control")
+}
+
+fn decompress(deb_path: &Path, member: &ArMember) -> Result<Vec<u8>, BellhopError> {
+    match compression_suffix(&member.name) {
+        "" => Ok(member.data.clone()),
+        ".gz" => {
+            let mut tar = Vec::new();
+            GzDecoder::new(member.data.as_slice()).read_to_end(&mut tar)?;
+            Ok(tar)
+        }
+        ".xz" => pipe_through(deb_path, "xz", &["-dc"], &member.data),
+        ".zst" => pipe_through(deb_path, "zstd", &["-dcq"], &member.data),
+        other => Err(unsupported_compression(deb_path, other)),
+    }
+}
+
+fn compress(deb_path: &Path, member: &ArMember, tar: Vec<u8>) -> Result<Vec<u8>, BellhopError> {
+    match compression_suffix(&member.name) {
+        "" => Ok(tar),
+        ".gz" => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&tar)?;
+            Ok(encoder.finish()?)
+        }
+        ".xz" => pipe_through(deb_path, "xz", &["-c"], &tar),
+        ".zst" => pipe_through(deb_path, "zstd", &["-cq"], &tar),
+        other => Err(unsupported_compression(deb_path, other)),
+    }
+}
+
+fn compression_suffix(member_name: &str) -> &str {
+    member_name
+        .strip_prefix(CONTROL_MEMBER_PREFIX)
+        .unwrap_or(member_name)
+}
+
+fn unsupported_compression(deb_path: &Path, suffix: &str) -> BellhopError {
+    rewrite_failed(
+        deb_path,
+        format!("unsupported control archive compression '{suffix}'"),
+    )
+}
+
+/// There is no xz or zstd implementation among the dependencies, the tools that `dpkg` itself
+/// depends on are used instead
+fn pipe_through(
+    deb_path: &Path,
+    tool: &str,
+    args: &[&str],
+    input: &[u8],
+) -> Result<Vec<u8>, BellhopError> {
+    let failed = |e: String| rewrite_failed(deb_path, format!("'{tool}' failed: {e}"));
+    let mut child = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_vec();
+    let writer = thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output()?;
+    writer
+        .join()
+        .map_err(|_| failed("the input could not be written".to_string()))??;
+
+    if !output.status.success() {
+        return Err(failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// The value of a top-level field, field names are case-insensitive
+pub fn field_value<'a>(control: &'a str, field: &str) -> Option<&'a str> {
+    control.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (!line.starts_with([' ', '\t']) && name.eq_ignore_ascii_case(field)).then(|| value.trim())
+    })
+}
+
+/// Replaces the value of a top-level field, keeping every other line as it is
+pub fn replace_field(control: &str, field: &str, value: &str) -> String {
+    control
+        .split_inclusive('\n')
+        .map(|line| match line.split_once(':') {
+            Some((name, _))
+                if !line.starts_with([' ', '\t']) && name.eq_ignore_ascii_case(field) =>
+            {
+                let ending = if line.ends_with('\n') { "\n" } else { "" };
+                format!("{name}: {value}{ending}")
+            }
+            _ => line.to_string(),
+        })
+        .collect()
+}
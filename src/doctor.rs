@@ -0,0 +1,238 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::aptly;
+use serde_json::Value;
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// aptly falls back to this file when no configuration file is specified
+const DEFAULT_CONFIG_FILE_NAME: &str = ".aptly.conf";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// Something that can be worked around, e.g. a key that is only needed for signing
+    Warning,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub details: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, details: impl Into<String>) -> Self {
+        Check {
+            name,
+            status,
+            details: details.into(),
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.status == Status::Failed
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let marker = match self.status {
+            Status::Ok => "[ok]  ",
+            Status::Warning => "[warn]",
+            Status::Failed => "[FAIL]",
+        };
+        write!(f, "{marker} {}: {}", self.name, self.details)
+    }
+}
+
+pub fn run_checks() -> Vec<Check> {
+    let mut checks = vec![check_aptly()];
+
+    let (config_check, config) = check_config(config_path().as_deref());
+    checks.push(config_check);
+    if let Some(config) = config {
+        checks.push(check_root_dir(&config));
+    }
+
+    checks.push(check_gpg_key(&aptly::gpg_key_id()));
+    checks
+}
+
+/// A paste-able summary of the environment for bug reports. Unlike the checks,
+/// it never fails: tools that cannot be run are reported as "not found".
+pub fn environment_report() -> Vec<(&'static str, String)> {
+    let config = config_path();
+    let root_dir = aptly_root_dir().map(|dir| dir.display().to_string());
+
+    vec![
+        ("bellhop", env!("CARGO_PKG_VERSION").to_string()),
+        ("aptly", tool_version(&aptly::aptly_bin(), "version")),
+        ("gpg", tool_version("gpg", "--version")),
+        (
+            "aptly configuration",
+            config.map_or("not set".to_string(), |path| path.display().to_string()),
+        ),
+        (
+            "aptly root directory",
+            root_dir.unwrap_or_else(|| "unknown".to_string()),
+        ),
+    ]
+}
+
+/// The `rootDir` of the aptly configuration in use, `None` when the configuration cannot be read
+pub fn aptly_root_dir() -> Option<PathBuf> {
+    let contents = fs::read_to_string(config_path()?).ok()?;
+    let config = serde_json::from_str::<Value>(&contents).ok()?;
+    Some(expand_home(config.get("rootDir")?.as_str()?))
+}
+
+fn tool_version(bin: &str, arg: &str) -> String {
+    match Command::new(bin).arg(arg).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        Ok(output) => format!("'{bin} {arg}' exited with {}", output.status),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => "not found".to_string(),
+        Err(e) => format!("could not run '{bin}': {e}"),
+    }
+}
+
+fn check_aptly() -> Check {
+    let bin = aptly::aptly_bin();
+    match Command::new(&bin).arg("version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            Check::new("aptly", Status::Ok, version.lines().next().unwrap_or(&bin))
+        }
+        Ok(output) => Check::new(
+            "aptly",
+            Status::Failed,
+            format!("'{bin} version' exited with {}", output.status),
+        ),
+        Err(e) => Check::new(
+            "aptly",
+            Status::Failed,
+            format!("could not run '{bin}': {e}"),
+        ),
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    aptly::aptly_config()
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(DEFAULT_CONFIG_FILE_NAME)))
+}
+
+fn check_config(path: Option<&Path>) -> (Check, Option<Value>) {
+    let Some(path) = path else {
+        return (
+            Check::new(
+                "configuration",
+                Status::Failed,
+                "neither --config, $APTLY_CONFIG nor $HOME is set",
+            ),
+            None,
+        );
+    };
+
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            return (
+                Check::new(
+                    "configuration",
+                    Status::Failed,
+                    format!("could not read {}: {e}", path.display()),
+                ),
+                None,
+            );
+        }
+    };
+
+    match serde_json::from_str::<Value>(&contents) {
+        Ok(config) => (
+            Check::new("configuration", Status::Ok, path.display().to_string()),
+            Some(config),
+        ),
+        Err(e) => (
+            Check::new(
+                "configuration",
+                Status::Failed,
+                format!("{} is not valid JSON: {e}", path.display()),
+            ),
+            None,
+        ),
+    }
+}
+
+fn check_root_dir(config: &Value) -> Check {
+    let Some(root_dir) = config.get("rootDir").and_then(Value::as_str) else {
+        return Check::new(
+            "root directory",
+            Status::Failed,
+            "the configuration does not have a rootDir",
+        );
+    };
+    let root_dir = expand_home(root_dir);
+
+    // aptly creates a missing root directory, so the closest existing ancestor must be writable
+    let Some(existing) = root_dir.ancestors().find(|p| p.exists()) else {
+        return Check::new(
+            "root directory",
+            Status::Failed,
+            format!("{} does not exist", root_dir.display()),
+        );
+    };
+    match tempfile::tempfile_in(existing) {
+        Ok(_) => Check::new("root directory", Status::Ok, root_dir.display().to_string()),
+        Err(e) => Check::new(
+            "root directory",
+            Status::Failed,
+            format!("{} is not writable: {e}", existing.display()),
+        ),
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Only publishing needs the key, so a missing key is a warning
+fn check_gpg_key(key_id: &str) -> Check {
+    match aptly::has_gpg_secret_key(key_id, None) {
+        Ok(true) => Check::new("signing key", Status::Ok, key_id.to_string()),
+        Ok(false) => Check::new(
+            "signing key",
+            Status::Warning,
+            format!("{key_id} is not in the GPG keyring, publishing will fail"),
+        ),
+        Err(e) => Check::new(
+            "signing key",
+            Status::Warning,
+            format!("could not run gpg to look up {key_id}: {e}"),
+        ),
+    }
+}
@@ -11,6 +11,8 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use clap::error::ErrorKind;
+use serde_json::{Value, json};
 use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -27,9 +29,59 @@ pub enum BellhopError {
     #[error("Package file does not exist at {path}")]
     PackageFileNotFound { path: PathBuf },
 
+    #[error(
+        "GPG signing key {key_id} is not in the keyring, import it, set BELLHOP_GPG_KEY or pass --no-gpg-check"
+    )]
+    GpgKeyNotFound { key_id: String },
+
+    #[error("Signing passphrase file does not exist at {path}")]
+    PassphraseFileNotFound { path: PathBuf },
+
+    #[error("GPG keyring does not exist at {path}")]
+    KeyringNotFound { path: PathBuf },
+
+    #[error("Distribution file does not exist at {path}")]
+    DistributionFileNotFound { path: PathBuf },
+
+    #[error("Nothing is published for '{distribution}' at '{prefix}'")]
+    NothingPublished {
+        distribution: String,
+        prefix: String,
+    },
+
+    #[error(
+        "'{distribution}' is published at '{prefix}' without the '{component}' component, aptly cannot add a component to an existing publication"
+    )]
+    ComponentNotPublished {
+        distribution: String,
+        prefix: String,
+        component: String,
+    },
+
+    #[error("No package of version {version} in repo '{repo}' after the import")]
+    ExpectedVersionNotFound { version: String, repo: String },
+
+    #[error("Repository '{repo}' does not exist")]
+    RepoNotFound { repo: String },
+
+    #[error("Snapshot '{snapshot}' does not exist, nothing to roll back to")]
+    SnapshotNotFound { snapshot: String },
+
+    #[error("Snapshot '{snapshot}' does not exist, there is nothing to remove packages from")]
+    SourceSnapshotNotFound { snapshot: String },
+
+    #[error("Snapshot '{snapshot}' already exists, pick another suffix with --to")]
+    SnapshotAlreadyExists { snapshot: String },
+
     #[error("Invalid distribution alias: {alias}")]
     InvalidDistribution { alias: String },
 
+    #[error("Erlang packages are not built for {distribution}")]
+    ErlangNotSupported { distribution: String },
+
+    #[error("--except leaves none of the distributions selected by --all")]
+    NoDistributionsLeft,
+
     #[error("Required argument '{argument}' is missing")]
     MissingArgument { argument: String },
 
@@ -47,47 +99,228 @@ pub enum BellhopError {
         stderr: String,
     },
 
+    #[error("Failed to publish '{distribution}' ({attempt}): {source}")]
+    PublishFailed {
+        distribution: String,
+        attempt: String,
+        source: Box<BellhopError>,
+    },
+
+    #[error("{} distribution(s) failed:\n{}", .failures.len(), describe_failures(.failures))]
+    MultipleFailures { failures: Vec<(String, String)> },
+
+    #[error("Another bellhop run holds the lock at {path}, try again once it is done")]
+    AlreadyRunning { path: PathBuf },
+
+    #[error("{action}: not confirmed, pass --yes to proceed without a prompt")]
+    NotConfirmed { action: String },
+
+    #[error("{failed} environment check(s) failed")]
+    DoctorChecksFailed { failed: usize },
+
     #[error("Run into an I/O error when executing aptly: {0}")]
     IoError(#[from] io::Error),
 
     #[error("No .deb files found in archive: {path}")]
     NoDebFilesInArchive { path: PathBuf },
 
+    #[error("No .deb files found in archive, only unsupported package formats ({}): {path}", .found.join(", "))]
+    OnlyUnsupportedPackagesInArchive { path: PathBuf, found: Vec<String> },
+
+    #[error("Archive is empty or truncated, e.g. by an interrupted download: {path}")]
+    EmptyOrTruncatedArchive { path: PathBuf },
+
     #[error("Failed to extract archive: {0}")]
     ArchiveExtractionFailed(String),
 
+    #[error("Archive exceeds the extraction limit of {limit}: {path}")]
+    ArchiveTooLarge { path: PathBuf, limit: String },
+
+    #[error("Archive has zip archives nested more than {limit} levels deep: {path}")]
+    ArchiveNestedTooDeeply { path: PathBuf, limit: usize },
+
+    #[error("Symlink {entry} in {path} points outside of the archive: {target}")]
+    SymlinkOutsideArchive {
+        path: PathBuf,
+        entry: PathBuf,
+        target: PathBuf,
+    },
+
+    #[error("Unsupported archive format ({format}): {path}")]
+    UnsupportedArchiveFormat { path: PathBuf, format: String },
+
+    #[error(
+        "{package} is built for {architecture}, which is not one of the repository architectures ({allowed})"
+    )]
+    IncompatibleArchitecture {
+        package: String,
+        architecture: String,
+        allowed: String,
+    },
+
     #[error("Not a .deb file: {filename}")]
     InvalidDebFilename { filename: String },
 
+    #[error("Not a valid Debian package ({reason}), pass --no-validate to add it anyway: {path}")]
+    InvalidDebFile { path: PathBuf, reason: String },
+
+    #[error("Cannot process the control file of {path}: {reason}")]
+    DebRewriteFailed { path: PathBuf, reason: String },
+
+    #[error("--package-name and --version describe a single package, but {count} were given")]
+    PackageIdentityNeedsSinglePackage { count: usize },
+
     #[error("Malformed .deb filename (expected format: package_version_arch.deb): {filename}")]
     MalformedDebFilename { filename: String },
 
+    #[error("Source package description at {path} has no Source: or Version: field")]
+    MalformedDscFile { path: PathBuf },
+
     #[error(
-        "aptly executable not found. Please install aptly first: https://www.aptly.info/download/"
+        "aptly executable not found. Please install aptly first (https://www.aptly.info/download/) or point APTLY_BIN at it"
     )]
     AptlyNotFound,
 
+    #[error("aptly configuration file does not exist at {path}")]
+    AptlyConfigNotFound { path: PathBuf },
+
     #[error("Invalid GitHub release URL: {url}")]
     InvalidGitHubReleaseUrl { url: String },
 
     #[error("GitHub API request failed: {message}")]
     GitHubApiFailed { message: String },
 
+    #[error("No release with a tag matching '{pattern}'")]
+    NoMatchingRelease { pattern: String },
+
     #[error("No assets matching pattern '{pattern}' in the GitHub release")]
     NoAssetsInRelease { pattern: String },
 
     #[error("Failed to download {url}: {message}")]
     DownloadFailed { url: String, message: String },
 
+    #[error("Release asset {name} is {size} bytes, over the download limit of {limit} bytes")]
+    AssetTooLarge { name: String, size: u64, limit: u64 },
+
     #[error("Watcher error: {0}")]
     WatcherError(String),
 
+    #[error(
+        "Invalid package name '{name}': only lowercase ASCII letters, digits, '+', '-' and '.' are allowed"
+    )]
+    InvalidPackageName { name: String },
+
+    #[error("--print-plan is not supported for '{command}'")]
+    PlanNotSupported { command: String },
+
+    #[error("No packages to promote in repository '{repo}'")]
+    NoPackagesToPromote { repo: String },
+
+    #[error(
+        "Invalid repository prefix '{prefix}': only ASCII letters, digits, '-', '_' and '.' are allowed"
+    )]
+    InvalidRepoPrefix { prefix: String },
+
+    #[error(
+        "Invalid publish prefix '{prefix}': expected '.' or a relative path without '..' segments"
+    )]
+    InvalidPublishPrefix { prefix: String },
+
+    #[error(
+        "Invalid channel '{channel}': only ASCII letters, digits, '-', '_' and '.' are allowed"
+    )]
+    InvalidChannel { channel: String },
+
     #[error(
         "Snapshot '{snapshot}' already exists, its contents differ from repository '{repo}', and it is currently published. Replacing it would alter an already published repository. Re-run the same command with --suffix NAME to write a separate snapshot, then publish it with 'publish --suffix NAME'."
     )]
     PublishedSnapshotIsStale { snapshot: String, repo: String },
 }
 
+fn describe_failures(failures: &[(String, String)]) -> String {
+    failures
+        .iter()
+        .map(|(distribution, error)| format!("  {distribution}: {error}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl BellhopError {
+    /// A stable machine-readable name of the error, used in JSON output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BellhopError::UnknownCommand { .. } => "unknown_command",
+            BellhopError::PackageFileNotFound { .. } => "package_file_not_found",
+            BellhopError::PassphraseFileNotFound { .. } => "passphrase_file_not_found",
+            BellhopError::KeyringNotFound { .. } => "keyring_not_found",
+            BellhopError::DistributionFileNotFound { .. } => "distribution_file_not_found",
+            BellhopError::GpgKeyNotFound { .. } => "gpg_key_not_found",
+            BellhopError::InvalidDistribution { .. } => "invalid_distribution",
+            BellhopError::NoDistributionsLeft => "no_distributions_left",
+            BellhopError::NothingPublished { .. } => "nothing_published",
+            BellhopError::ComponentNotPublished { .. } => "component_not_published",
+            BellhopError::RepoNotFound { .. } => "repo_not_found",
+            BellhopError::ExpectedVersionNotFound { .. } => "expected_version_not_found",
+            BellhopError::SnapshotNotFound { .. } => "snapshot_not_found",
+            BellhopError::SourceSnapshotNotFound { .. } => "source_snapshot_not_found",
+            BellhopError::SnapshotAlreadyExists { .. } => "snapshot_already_exists",
+            BellhopError::ErlangNotSupported { .. } => "erlang_not_supported",
+            BellhopError::MissingArgument { .. } => "missing_argument",
+            BellhopError::AptlyCommandFailed { .. } => "aptly_command_failed",
+            BellhopError::AptlyNonZeroExit { .. } => "aptly_non_zero_exit",
+            BellhopError::PublishFailed { .. } => "publish_failed",
+            BellhopError::MultipleFailures { .. } => "multiple_failures",
+            BellhopError::AlreadyRunning { .. } => "already_running",
+            BellhopError::NotConfirmed { .. } => "not_confirmed",
+            BellhopError::DoctorChecksFailed { .. } => "doctor_checks_failed",
+            BellhopError::IoError(_) => "io_error",
+            BellhopError::NoDebFilesInArchive { .. } => "no_deb_files_in_archive",
+            BellhopError::OnlyUnsupportedPackagesInArchive { .. } => {
+                "only_unsupported_packages_in_archive"
+            }
+            BellhopError::EmptyOrTruncatedArchive { .. } => "empty_or_truncated_archive",
+            BellhopError::ArchiveExtractionFailed(_) => "archive_extraction_failed",
+            BellhopError::ArchiveTooLarge { .. } => "archive_too_large",
+            BellhopError::ArchiveNestedTooDeeply { .. } => "archive_nested_too_deeply",
+            BellhopError::SymlinkOutsideArchive { .. } => "symlink_outside_archive",
+            BellhopError::UnsupportedArchiveFormat { .. } => "unsupported_archive_format",
+            BellhopError::IncompatibleArchitecture { .. } => "incompatible_architecture",
+            BellhopError::InvalidDebFilename { .. } => "invalid_deb_filename",
+            BellhopError::PackageIdentityNeedsSinglePackage { .. } => {
+                "package_identity_needs_single_package"
+            }
+            BellhopError::MalformedDebFilename { .. } => "malformed_deb_filename",
+            BellhopError::InvalidDebFile { .. } => "invalid_deb_file",
+            BellhopError::DebRewriteFailed { .. } => "deb_rewrite_failed",
+            BellhopError::MalformedDscFile { .. } => "malformed_dsc_file",
+            BellhopError::AptlyNotFound => "aptly_not_found",
+            BellhopError::AptlyConfigNotFound { .. } => "aptly_config_not_found",
+            BellhopError::InvalidGitHubReleaseUrl { .. } => "invalid_github_release_url",
+            BellhopError::GitHubApiFailed { .. } => "github_api_failed",
+            BellhopError::NoMatchingRelease { .. } => "no_matching_release",
+            BellhopError::NoAssetsInRelease { .. } => "no_assets_in_release",
+            BellhopError::DownloadFailed { .. } => "download_failed",
+            BellhopError::AssetTooLarge { .. } => "asset_too_large",
+            BellhopError::WatcherError(_) => "watcher_error",
+            BellhopError::InvalidPackageName { .. } => "invalid_package_name",
+            BellhopError::PlanNotSupported { .. } => "plan_not_supported",
+            BellhopError::NoPackagesToPromote { .. } => "no_packages_to_promote",
+            BellhopError::InvalidRepoPrefix { .. } => "invalid_repo_prefix",
+            BellhopError::InvalidPublishPrefix { .. } => "invalid_publish_prefix",
+            BellhopError::InvalidChannel { .. } => "invalid_channel",
+            BellhopError::PublishedSnapshotIsStale { .. } => "published_snapshot_is_stale",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "error": self.kind(),
+            "message": self.to_string(),
+            "exit_code": i32::from(map_error_to_exit_code(self)),
+        })
+    }
+}
+
 #[repr(i32)]
 pub enum ExitCode {
     Ok = 0,
@@ -102,25 +335,71 @@ impl From<ExitCode> for i32 {
     }
 }
 
+/// clap exits with 2 on its own, argument errors use the same code as other usage errors instead
+pub fn map_clap_error_to_exit_code(kind: ErrorKind) -> ExitCode {
+    match kind {
+        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => ExitCode::Ok,
+        _ => ExitCode::Usage,
+    }
+}
+
 pub fn map_error_to_exit_code(error: &BellhopError) -> ExitCode {
     match error {
         BellhopError::UnknownCommand { .. } => ExitCode::Usage,
+        BellhopError::PlanNotSupported { .. } => ExitCode::Usage,
         BellhopError::MissingArgument { .. } => ExitCode::Usage,
         BellhopError::InvalidDistribution { .. } => ExitCode::DataErr,
+        BellhopError::NothingPublished { .. } => ExitCode::DataErr,
+        BellhopError::ComponentNotPublished { .. } => ExitCode::DataErr,
+        BellhopError::RepoNotFound { .. } => ExitCode::DataErr,
+        BellhopError::ExpectedVersionNotFound { .. } => ExitCode::DataErr,
+        BellhopError::SnapshotNotFound { .. } => ExitCode::DataErr,
+        BellhopError::SourceSnapshotNotFound { .. } => ExitCode::DataErr,
+        BellhopError::SnapshotAlreadyExists { .. } => ExitCode::DataErr,
+        BellhopError::ErlangNotSupported { .. } => ExitCode::DataErr,
         BellhopError::PackageFileNotFound { .. } => ExitCode::DataErr,
+        BellhopError::PassphraseFileNotFound { .. } => ExitCode::DataErr,
+        BellhopError::KeyringNotFound { .. } => ExitCode::DataErr,
+        BellhopError::DistributionFileNotFound { .. } => ExitCode::DataErr,
+        BellhopError::GpgKeyNotFound { .. } => ExitCode::DataErr,
         BellhopError::NoDebFilesInArchive { .. } => ExitCode::DataErr,
+        BellhopError::OnlyUnsupportedPackagesInArchive { .. } => ExitCode::DataErr,
+        BellhopError::ArchiveTooLarge { .. } => ExitCode::DataErr,
+        BellhopError::ArchiveNestedTooDeeply { .. } => ExitCode::DataErr,
+        BellhopError::SymlinkOutsideArchive { .. } => ExitCode::DataErr,
+        BellhopError::EmptyOrTruncatedArchive { .. } => ExitCode::DataErr,
+        BellhopError::UnsupportedArchiveFormat { .. } => ExitCode::DataErr,
+        BellhopError::IncompatibleArchitecture { .. } => ExitCode::DataErr,
         BellhopError::InvalidDebFilename { .. } => ExitCode::DataErr,
+        BellhopError::PackageIdentityNeedsSinglePackage { .. } => ExitCode::Usage,
         BellhopError::MalformedDebFilename { .. } => ExitCode::DataErr,
+        BellhopError::InvalidDebFile { .. } => ExitCode::DataErr,
+        BellhopError::DebRewriteFailed { .. } => ExitCode::DataErr,
+        BellhopError::MalformedDscFile { .. } => ExitCode::DataErr,
         BellhopError::AptlyCommandFailed { .. } => ExitCode::Software,
         BellhopError::AptlyNonZeroExit { .. } => ExitCode::Software,
+        BellhopError::PublishFailed { .. } => ExitCode::Software,
+        BellhopError::MultipleFailures { .. } => ExitCode::Software,
+        BellhopError::AlreadyRunning { .. } => ExitCode::Software,
+        BellhopError::NotConfirmed { .. } => ExitCode::Usage,
+        BellhopError::NoDistributionsLeft => ExitCode::Usage,
+        BellhopError::DoctorChecksFailed { .. } => ExitCode::Software,
         BellhopError::IoError(_) => ExitCode::Software,
         BellhopError::ArchiveExtractionFailed(_) => ExitCode::Software,
         BellhopError::AptlyNotFound => ExitCode::Software,
+        BellhopError::AptlyConfigNotFound { .. } => ExitCode::DataErr,
         BellhopError::InvalidGitHubReleaseUrl { .. } => ExitCode::DataErr,
         BellhopError::GitHubApiFailed { .. } => ExitCode::Software,
+        BellhopError::NoMatchingRelease { .. } => ExitCode::DataErr,
         BellhopError::NoAssetsInRelease { .. } => ExitCode::DataErr,
         BellhopError::DownloadFailed { .. } => ExitCode::Software,
+        BellhopError::AssetTooLarge { .. } => ExitCode::DataErr,
         BellhopError::WatcherError(_) => ExitCode::Software,
         BellhopError::PublishedSnapshotIsStale { .. } => ExitCode::DataErr,
+        BellhopError::InvalidRepoPrefix { .. } => ExitCode::DataErr,
+        BellhopError::InvalidPublishPrefix { .. } => ExitCode::DataErr,
+        BellhopError::InvalidChannel { .. } => ExitCode::DataErr,
+        BellhopError::NoPackagesToPromote { .. } => ExitCode::DataErr,
+        BellhopError::InvalidPackageName { .. } => ExitCode::DataErr,
     }
 }
@@ -11,50 +11,143 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use crate::common::sync_file_and_parent;
 use crate::errors::BellhopError;
+use crate::gh::describe_http_error;
 use crate::gh::releases::ReleaseAsset;
+use crate::progress;
+use indicatif::MultiProgress;
 use log::info;
 use reqwest::blocking::Client;
-use std::fs::File;
-use std::io;
+use std::fs::{self, File};
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
 
+pub const DEFAULT_DOWNLOAD_JOBS: usize = 4;
+
+/// Downloads up to `jobs` assets at a time. The returned paths follow the order of `assets`.
+/// After the first failure no new downloads are started and that failure is returned.
+///
+/// With a `max_size`, nothing is downloaded when an asset is declared to be larger than that,
+/// and a download that streams more bytes than the asset's declared size is aborted.
 pub fn download_assets(
     client: &Client,
     assets: &[ReleaseAsset],
     dest_dir: &Path,
+    fsync: bool,
+    jobs: usize,
+    max_size: Option<u64>,
 ) -> Result<Vec<PathBuf>, BellhopError> {
-    let mut paths = Vec::with_capacity(assets.len());
-
-    for asset in assets {
-        let dest_path = dest_dir.join(&asset.name);
-        info!("Downloading {} ({} bytes)", asset.name, asset.size);
-
-        let mut response = client
-            .get(&asset.browser_download_url)
-            .header("User-Agent", "bellhop")
-            .send()
-            .map_err(|e| BellhopError::DownloadFailed {
-                url: asset.browser_download_url.clone(),
-                message: e.to_string(),
-            })?;
-
-        if !response.status().is_success() {
-            return Err(BellhopError::DownloadFailed {
-                url: asset.browser_download_url.clone(),
-                message: format!("HTTP status {}", response.status()),
+    if let Some(limit) = max_size
+        && let Some(asset) = assets.iter().find(|asset| asset.size > limit)
+    {
+        return Err(BellhopError::AssetTooLarge {
+            name: asset.name.clone(),
+            size: asset.size,
+            limit,
+        });
+    }
+
+    let next = AtomicUsize::new(0);
+    let paths = Mutex::new(vec![None; assets.len()]);
+    let failure = Mutex::new(None);
+    let bars = MultiProgress::new();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, assets.len().max(1)) {
+            scope.spawn(|| {
+                while lock(&failure).is_none() {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(asset) = assets.get(i) else {
+                        break;
+                    };
+                    let limited = max_size.is_some();
+                    match download_asset(client, asset, dest_dir, fsync, limited, &bars) {
+                        Ok(path) => lock(&paths)[i] = Some(path),
+                        Err(e) => {
+                            lock(&failure).get_or_insert(e);
+                        }
+                    }
+                }
             });
         }
+    });
+
+    if let Some(e) = failure.into_inner().unwrap_or_else(PoisonError::into_inner) {
+        return Err(e);
+    }
+    Ok(paths
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+        .into_iter()
+        .flatten()
+        .collect())
+}
 
-        let mut file = File::create(&dest_path)?;
-        io::copy(&mut response, &mut file).map_err(|e| BellhopError::DownloadFailed {
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn download_asset(
+    client: &Client,
+    asset: &ReleaseAsset,
+    dest_dir: &Path,
+    fsync: bool,
+    limited: bool,
+    bars: &MultiProgress,
+) -> Result<PathBuf, BellhopError> {
+    let dest_path = dest_dir.join(&asset.name);
+    if fs::metadata(&dest_path).is_ok_and(|m| m.is_file() && m.len() == asset.size) {
+        info!("{} is already downloaded, skipping", asset.name);
+        return Ok(dest_path);
+    }
+    info!("Downloading {} ({} bytes)", asset.name, asset.size);
+
+    let mut response = client
+        .get(&asset.browser_download_url)
+        .header("User-Agent", "bellhop")
+        .send()
+        .map_err(|e| BellhopError::DownloadFailed {
             url: asset.browser_download_url.clone(),
-            message: e.to_string(),
+            message: describe_http_error(&e, &asset.browser_download_url),
         })?;
 
-        info!("Downloaded {}", asset.name);
-        paths.push(dest_path);
+    if !response.status().is_success() {
+        return Err(BellhopError::DownloadFailed {
+            url: asset.browser_download_url.clone(),
+            message: format!("HTTP status {}", response.status()),
+        });
+    }
+
+    let bar = bars.add(progress::bytes_bar(asset.size, &asset.name));
+    let mut file = File::create(&dest_path)?;
+    let mut body = bar.wrap_read(&mut response);
+    let copied = if limited {
+        // Reading one byte past the declared size tells an overrun from a complete download
+        io::copy(&mut (&mut body).take(asset.size + 1), &mut file)
+    } else {
+        io::copy(&mut body, &mut file)
+    }
+    .map_err(|e| BellhopError::DownloadFailed {
+        url: asset.browser_download_url.clone(),
+        message: e.to_string(),
+    })?;
+    bar.finish_and_clear();
+    if limited && copied > asset.size {
+        drop(file);
+        fs::remove_file(&dest_path)?;
+        return Err(BellhopError::DownloadFailed {
+            url: asset.browser_download_url.clone(),
+            message: format!("received more than the declared {} bytes", asset.size),
+        });
+    }
+    if fsync {
+        sync_file_and_parent(&dest_path)?;
     }
 
-    Ok(paths)
+    info!("Downloaded {}", asset.name);
+    Ok(dest_path)
 }
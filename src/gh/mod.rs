@@ -15,6 +15,32 @@ pub mod downloads;
 pub mod releases;
 
 use crate::errors::BellhopError;
+use reqwest::blocking::Client;
+use std::time::Duration;
+
+/// Generous enough for large assets on a slow connection, the bound is there so that
+/// a stalled connection does not hang a CI job forever
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const MAX_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The client both release metadata requests and asset downloads go through
+pub fn http_client(timeout: Duration) -> Result<Client, BellhopError> {
+    Client::builder()
+        .timeout(timeout)
+        .connect_timeout(timeout.min(MAX_CONNECT_TIMEOUT))
+        .build()
+        .map_err(|e| BellhopError::GitHubApiFailed {
+            message: format!("Failed to set up an HTTP client: {e}"),
+        })
+}
+
+fn describe_http_error(e: &reqwest::Error, url: &str) -> String {
+    if e.is_timeout() {
+        format!("request to {url} timed out")
+    } else {
+        e.to_string()
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GitHubRelease {
@@ -59,3 +85,25 @@ pub fn parse_release_url(url: &str) -> Result<GitHubRelease, BellhopError> {
         tag: tag.to_string(),
     })
 }
+
+/// `{owner}/{repo}` of a repository URL, e.g. https://github.com/owner/repo, a trailing
+/// `/releases` is accepted as well
+pub fn parse_repository_url(url: &str) -> Result<(String, String), BellhopError> {
+    let url = url.trim().trim_end_matches('/');
+    let invalid = || BellhopError::InvalidGitHubReleaseUrl {
+        url: url.to_string(),
+    };
+
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .ok_or_else(invalid)?;
+    let path = path.strip_suffix("/releases").unwrap_or(path);
+
+    match path.split('/').collect::<Vec<_>>()[..] {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => {
+            Ok((owner.to_string(), repo.to_string()))
+        }
+        _ => Err(invalid()),
+    }
+}
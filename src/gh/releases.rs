@@ -12,38 +12,219 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use crate::errors::BellhopError;
-use crate::gh::GitHubRelease;
+use crate::gh::{GitHubRelease, describe_http_error};
+use crate::version::compare_versions;
+use log::{debug, warn};
 use reqwest::blocking::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{env, fs};
 
-#[derive(Debug, Deserialize)]
+const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+const RELEASES_PER_PAGE: usize = 100;
+
+/// Long enough for a scripted series of imports from one release, short enough to pick up
+/// assets uploaded to a release after it was created
+pub const RELEASE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Replaced with a distribution name in asset patterns, e.g. `*{distribution}*.deb`
+pub const DISTRIBUTION_PLACEHOLDER: &str = "{distribution}";
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct ReleaseAsset {
     pub name: String,
     pub browser_download_url: String,
     pub size: u64,
 }
 
+/// Release metadata kept for provenance, i.e. a record of what release a package set came from
 #[derive(Debug, Deserialize)]
-struct ReleaseResponse {
-    assets: Vec<ReleaseAsset>,
+pub struct ReleaseDetails {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// The release a run imported from, recorded in the run report of `import-from-github --json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub owner: String,
+    pub repo: String,
+    pub tag: String,
+    pub name: Option<String>,
+    pub published_at: Option<String>,
+    /// Names of the assets that matched the pattern, none of them are imported with `--check-only`
+    pub assets: Vec<String>,
+}
+
+/// An entry of a repository's release list
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseSummary {
+    pub tag_name: String,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// Honors `GITHUB_API_URL`, which GitHub Actions sets, so GitHub Enterprise Server works as well
+pub fn github_api_url() -> String {
+    env::var("GITHUB_API_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| DEFAULT_GITHUB_API_URL.to_string())
+}
+
+/// `$XDG_CACHE_HOME/bellhop`, or `~/.cache/bellhop` when `XDG_CACHE_HOME` is not set
+pub fn cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|dir| dir.join("bellhop"))
 }
 
-pub fn fetch_release_assets(
+pub fn release_cache_path(release: &GitHubRelease) -> Option<PathBuf> {
+    cache_dir().map(|dir| {
+        dir.join("releases")
+            .join(&release.owner)
+            .join(&release.repo)
+            .join(format!("{}.json", release.tag.replace('/', "_")))
+    })
+}
+
+/// Release metadata is served from the on-disk cache when it is younger than [`RELEASE_CACHE_TTL`]
+pub fn fetch_release_details(
     client: &Client,
     release: &GitHubRelease,
-) -> Result<Vec<ReleaseAsset>, BellhopError> {
+    use_cache: bool,
+) -> Result<ReleaseDetails, BellhopError> {
+    let cache_path = release_cache_path(release).filter(|_| use_cache);
+    if let Some(details) = cache_path.as_deref().and_then(read_cached_release) {
+        return Ok(details);
+    }
+
+    let body = fetch_release_json(client, release)?;
+    let details = parse_release_details(&body)?;
+
+    if let Some(path) = cache_path {
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, &body));
+        if let Err(e) = written {
+            warn!(
+                "Could not cache release metadata at {}: {e}",
+                path.display()
+            );
+        }
+    }
+    Ok(details)
+}
+
+fn read_cached_release(path: &Path) -> Option<ReleaseDetails> {
+    let age = fs::metadata(path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > RELEASE_CACHE_TTL {
+        return None;
+    }
+
+    let details = fs::read_to_string(path)
+        .ok()
+        .and_then(|body| parse_release_details(&body).ok())?;
+    debug!("Using cached release metadata from {}", path.display());
+    Some(details)
+}
+
+pub fn parse_release_details(body: &str) -> Result<ReleaseDetails, BellhopError> {
+    serde_json::from_str(body).map_err(|e| BellhopError::GitHubApiFailed {
+        message: format!("Failed to parse GitHub API response: {e}"),
+    })
+}
+
+fn fetch_release_json(client: &Client, release: &GitHubRelease) -> Result<String, BellhopError> {
     let api_url = format!(
-        "https://api.github.com/repos/{}/{}/releases/tags/{}",
-        release.owner, release.repo, release.tag
+        "{}/repos/{}/{}/releases/tags/{}",
+        github_api_url(),
+        release.owner,
+        release.repo,
+        release.tag
     );
+    fetch_json(client, &api_url)
+}
+
+/// All releases of a repository, page by page
+pub fn fetch_releases(
+    client: &Client,
+    owner: &str,
+    repo: &str,
+) -> Result<Vec<ReleaseSummary>, BellhopError> {
+    let mut releases = Vec::new();
+    for page in 1.. {
+        let api_url = format!(
+            "{}/repos/{owner}/{repo}/releases?per_page={RELEASES_PER_PAGE}&page={page}",
+            github_api_url()
+        );
+        let body = fetch_json(client, &api_url)?;
+        let batch: Vec<ReleaseSummary> =
+            serde_json::from_str(&body).map_err(|e| BellhopError::GitHubApiFailed {
+                message: format!("Failed to parse GitHub API response: {e}"),
+            })?;
+
+        let last_page = batch.len() < RELEASES_PER_PAGE;
+        releases.extend(batch);
+        if last_page {
+            break;
+        }
+    }
+    debug!("Found {} releases of {owner}/{repo}", releases.len());
+    Ok(releases)
+}
+
+/// The release with the highest tag that matches `tag_pattern`. Drafts are never selected,
+/// prereleases only on request.
+pub fn select_release<'a>(
+    releases: &'a [ReleaseSummary],
+    tag_pattern: &str,
+    include_prereleases: bool,
+) -> Option<&'a ReleaseSummary> {
+    releases
+        .iter()
+        .filter(|r| !r.draft && (include_prereleases || !r.prerelease))
+        .filter(|r| glob_match(tag_pattern, &r.tag_name))
+        .max_by(|a, b| compare_versions(&comparable_version(a), &comparable_version(b)))
+}
 
+/// The version a release tag stands for, e.g. `4.2.3` for `v4.2.3`. Debian packages of it
+/// usually add a revision, e.g. `4.2.3-1`.
+pub fn version_from_tag(tag: &str) -> &str {
+    tag.strip_prefix('v')
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(tag)
+}
+
+/// A tag as a Debian version, e.g. `v4.2.0-rc.1` of a prerelease becomes `4.2.0~rc.1`
+/// so that it sorts before `v4.2.0`
+fn comparable_version(release: &ReleaseSummary) -> String {
+    let version = version_from_tag(&release.tag_name);
+    if release.prerelease {
+        version.replacen('-', "~", 1)
+    } else {
+        version.to_string()
+    }
+}
+
+fn fetch_json(client: &Client, api_url: &str) -> Result<String, BellhopError> {
     let response = client
-        .get(&api_url)
+        .get(api_url)
         .header("User-Agent", "bellhop")
         .header("Accept", "application/vnd.github+json")
         .send()
         .map_err(|e| BellhopError::GitHubApiFailed {
-            message: e.to_string(),
+            message: describe_http_error(&e, api_url),
         })?;
 
     if !response.status().is_success() {
@@ -56,12 +237,12 @@ pub fn fetch_release_assets(
         });
     }
 
-    let release_data: ReleaseResponse =
-        response.json().map_err(|e| BellhopError::GitHubApiFailed {
-            message: format!("Failed to parse GitHub API response: {e}"),
-        })?;
-
-    Ok(release_data.assets)
+    response.text().map_err(|e| BellhopError::GitHubApiFailed {
+        message: format!(
+            "Failed to read GitHub API response: {}",
+            describe_http_error(&e, api_url)
+        ),
+    })
 }
 
 pub fn filter_assets(assets: Vec<ReleaseAsset>, pattern: &str) -> Vec<ReleaseAsset> {
@@ -71,6 +252,14 @@ pub fn filter_assets(assets: Vec<ReleaseAsset>, pattern: &str) -> Vec<ReleaseAss
         .collect()
 }
 
+pub fn is_per_distribution_pattern(pattern: &str) -> bool {
+    pattern.contains(DISTRIBUTION_PLACEHOLDER)
+}
+
+pub fn pattern_for_distribution(pattern: &str, distribution: &str) -> String {
+    pattern.replace(DISTRIBUTION_PLACEHOLDER, distribution)
+}
+
 pub fn glob_match(pattern: &str, name: &str) -> bool {
     let parts: Vec<&str> = pattern.split('*').collect();
 
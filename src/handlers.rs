@@ -16,137 +16,561 @@ use log::info;
 use reqwest::blocking::Client;
 use tempfile::TempDir;
 
-use std::path::Path;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
-use crate::common::Project;
+use crate::common::{OutputFormat, Project};
+use crate::deb::DistributionAlias;
 use crate::errors::BellhopError;
-use crate::gh::{self, downloads, releases};
-use crate::{aptly, cli, watcher};
+use crate::gh::releases::{ImportSummary, ReleaseAsset, ReleaseDetails};
+use crate::gh::{self, GitHubRelease, downloads, releases};
+use crate::watcher::{WatchOptions, WatchedDirectories};
+use crate::{aptly, archive, cli, doctor, plan, report, watcher};
+
+/// Unlike other handlers, does not require aptly to be installed
+pub fn print_plan(
+    group: &str,
+    command: &str,
+    cli_args: &ArgMatches,
+    project: Project,
+) -> Result<(), BellhopError> {
+    for step in plan::command_plan(group, command, cli_args, project)? {
+        println!("{step}");
+    }
+    Ok(())
+}
 
 pub fn add(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
-    let package_file_path = cli_args
-        .get_one::<String>("package_file_path")
-        .ok_or_else(|| BellhopError::MissingArgument {
-            argument: "package_file_path".to_string(),
-        })?;
-
-    let target_releases = cli::distributions(cli_args, project)?;
+    let package_file_paths = cli::package_file_paths(cli_args)?;
+    let summary = if let Some(repo) = cli::direct_repo(cli_args) {
+        aptly::add_packages_to_repo(cli_args, &package_file_paths, project, repo)?
+    } else {
+        let target_releases = target_releases(cli_args, project)?;
+        aptly::add_packages(cli_args, &package_file_paths, project, &target_releases)?
+    };
 
-    aptly::add_package(cli_args, package_file_path, project, &target_releases)
+    if cli::json(cli_args) {
+        report::record_summary(&summary)?;
+    }
+    Ok(())
 }
 
 pub fn remove(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
-    let target_releases = cli::distributions(cli_args, project)?;
+    if let Some(repo) = cli::direct_repo(cli_args) {
+        return aptly::remove_from_repo(cli_args, project, repo);
+    }
+    let target_releases = target_releases(cli_args, project)?;
+    let republish = cli_args.get_flag("republish");
+    let options = cli::publish_options(cli_args, project);
+    if republish {
+        // checked before anything is removed rather than once the snapshots are recreated
+        options.validate()?;
+    }
 
-    if let Some(version) = cli_args.get_one::<String>("version") {
-        aptly::remove_package(cli_args, version, project, &target_releases)
+    if let Some(version) = cli_args.get_one::<String>("version")
+        && cli_args.get_flag("snapshot_only")
+    {
+        aptly::remove_from_snapshots(cli_args, version, project, &target_releases)?;
+    } else if let Some(version) = cli_args.get_one::<String>("version") {
+        aptly::remove_package(cli_args, version, project, &target_releases)?;
     } else if let Some(package_file_path) = cli_args.get_one::<String>("package_file_path") {
-        aptly::remove_package_from_archive(cli_args, package_file_path, project, &target_releases)
+        aptly::remove_package_from_archive(cli_args, package_file_path, project, &target_releases)?;
+    } else if let Some(package_name) = cli_args.get_one::<String>("all_versions") {
+        aptly::remove_all_versions(cli_args, package_name, project, &target_releases)?;
     } else {
-        Err(BellhopError::MissingArgument {
-            argument: "version or package_file_path".to_string(),
-        })
+        return Err(BellhopError::MissingArgument {
+            argument: "version, package_file_path or all_versions".to_string(),
+        });
+    }
+
+    if republish {
+        aptly::republish(project, &target_releases, &cli::suffix(cli_args), &options)?;
     }
+    Ok(())
 }
 
 pub fn publish(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
-    let target_releases = cli::distributions(cli_args, project)?;
+    let target_releases = target_releases(cli_args, project)?;
     let suffix = cli::suffix(cli_args);
 
-    aptly::publish(project, &target_releases, &suffix)
+    let options = cli::publish_options(cli_args, project);
+    if cli_args.get_flag("update_only") {
+        return aptly::publish_update(project, &target_releases, &options);
+    }
+
+    aptly::publish(project, &target_releases, &suffix, &options)
 }
 
-pub fn list_snapshots(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
+pub fn unpublish(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
-    let target_releases = cli::distributions(cli_args, project)?;
+    let target_releases = target_releases(cli_args, project)?;
+    let names: Vec<&str> = target_releases.iter().map(|r| r.release_name()).collect();
+    confirm(
+        cli_args,
+        &format!("Drop the publications of {}", names.join(", ")),
+    )?;
+
+    aptly::unpublish(
+        project,
+        &target_releases,
+        &cli::publish_options(cli_args, project),
+    )
+}
+
+pub fn rollback(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let target_releases = target_releases(cli_args, project)?;
+    let to_suffix = required_string(cli_args, "to")?;
+    let names: Vec<&str> = target_releases.iter().map(|r| r.release_name()).collect();
+    confirm(
+        cli_args,
+        &format!(
+            "Publish the '{to_suffix}' snapshots of {} again",
+            names.join(", ")
+        ),
+    )?;
+
+    aptly::rollback(
+        project,
+        &target_releases,
+        to_suffix,
+        &cli::publish_options(cli_args, project),
+    )
+}
+
+/// Destructive commands proceed with `--yes` or when the user confirms on an interactive terminal
+fn confirm(cli_args: &ArgMatches, action: &str) -> Result<(), BellhopError> {
+    if cli_args.get_flag("yes") {
+        return Ok(());
+    }
+
+    let not_confirmed = || BellhopError::NotConfirmed {
+        action: action.to_string(),
+    };
+    if !io::stdin().is_terminal() {
+        return Err(not_confirmed());
+    }
+
+    eprint!("{action}? [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(not_confirmed()),
+    }
+}
+
+pub fn promote_latest(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let target_releases = target_releases(cli_args, project)?;
     let suffix = cli::suffix(cli_args);
+    let remove_older = cli_args.get_flag("remove_older");
+    let options = cli::publish_options(cli_args, project);
+
+    aptly::promote_latest(project, &target_releases, &suffix, remove_older, &options)
+}
+
+pub fn list_snapshots(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let target_releases = target_releases(cli_args, project)?;
+    let range = cli::date_range(cli_args);
+    let snapshots = if range.is_unbounded() {
+        aptly::snapshots_with_suffix(&project, &target_releases, &cli::suffix(cli_args))
+    } else {
+        let date_format = cli::suffix_date_format(cli_args);
+        aptly::snapshots_in_date_range(&project, &target_releases, date_format, &range)?
+    };
+
+    let package_filter = cli_args.get_one::<String>("grep").map(|s| s.as_str());
+
+    match cli::output_format(cli_args) {
+        OutputFormat::Json => {
+            let snapshots = aptly::snapshot_packages(&snapshots, package_filter)?;
+            let json = serde_json::to_string_pretty(&snapshots).map_err(io::Error::from)?;
+            println!("{json}");
+            Ok(())
+        }
+        OutputFormat::Text => {
+            if snapshots.is_empty() {
+                info!("No snapshots in the date range");
+            }
+            aptly::list_snapshots(&snapshots, package_filter)
+        }
+    }
+}
+
+pub fn show_snapshot(cli_args: &ArgMatches) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let name = required_string(cli_args, "name")?;
+    let output = aptly::snapshot_show_by_name(name)?;
+
+    match cli::output_format(cli_args) {
+        OutputFormat::Json => {
+            let details = aptly::parse_snapshot_details(&output);
+            let json = serde_json::to_string_pretty(&details).map_err(io::Error::from)?;
+            println!("{json}");
+        }
+        OutputFormat::Text => print!("{output}"),
+    }
+    Ok(())
+}
+
+pub fn orphan_snapshots(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let target_releases = target_releases(cli_args, project)?;
+    let date_format = cli::suffix_date_format(cli_args);
+    let range = cli::date_range(cli_args);
+    let orphans = aptly::find_orphan_snapshots(&project, &target_releases, date_format, &range)?;
 
-    aptly::list_snapshots(project, &target_releases, &suffix)
+    match cli::output_format(cli_args) {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&orphans).map_err(io::Error::from)?;
+            println!("{json}");
+        }
+        OutputFormat::Text => {
+            if orphans.is_empty() {
+                info!("No orphan snapshots found");
+            }
+            for orphan in &orphans {
+                println!("{}", orphan.name);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn diff_snapshots(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let target_releases = target_releases(cli_args, project)?;
+    let from = required_string(cli_args, "from")?;
+    let to = required_string(cli_args, "to")?;
+    let diffs = aptly::snapshot_diff(&project, &target_releases, from, to)?;
+
+    match cli::output_format(cli_args) {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&diffs).map_err(io::Error::from)?;
+            println!("{json}");
+        }
+        OutputFormat::Text => {
+            for diff in &diffs {
+                println!("{} -> {}", diff.from, diff.to);
+                if diff.packages.is_empty() {
+                    println!("  no changes");
+                }
+                for key in &diff.packages.added {
+                    println!("  + {key}");
+                }
+                for key in &diff.packages.removed {
+                    println!("  - {key}");
+                }
+                for changed in &diff.packages.changed {
+                    println!(
+                        "  ~ {} ({}): {} -> {}",
+                        changed.name,
+                        changed.architecture,
+                        changed.from.join(", "),
+                        changed.to.join(", ")
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
 pub fn take_snapshots(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
-    let target_releases = cli::distributions(cli_args, project)?;
+    let target_releases = target_releases(cli_args, project)?;
     let suffix = cli::suffix(cli_args);
+    if cli_args.get_flag("from_published") {
+        return aptly::take_snapshot_from_published(project, &target_releases, &suffix);
+    }
+    let source = cli::snapshot_source(cli_args);
 
-    aptly::take_snapshot(project, &target_releases, &suffix)
+    aptly::take_snapshot(project, &target_releases, &suffix, source)
 }
 
 pub fn delete_snapshots(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
-    let target_releases = cli::distributions(cli_args, project)?;
+    let target_releases = target_releases(cli_args, project)?;
     let suffix = cli::suffix(cli_args);
 
     aptly::delete_snapshots(project, &target_releases, &suffix)
 }
 
 pub fn import_from_github(cli_args: &ArgMatches, project: Project) -> Result<(), BellhopError> {
-    aptly::check_aptly_available()?;
-
-    let url = cli_args
-        .get_one::<String>("github_release_url")
-        .ok_or_else(|| BellhopError::MissingArgument {
-            argument: "github_release_url".to_string(),
-        })?;
+    let check_only = cli_args.get_flag("check_only");
+    let download_only = cli_args.get_flag("download_only");
+    if !check_only && !download_only {
+        aptly::check_aptly_available()?;
+    }
 
     let default_pattern = match project {
         Project::CliTools => "*amd64*.deb",
-        Project::RabbitMQ | Project::Erlang => "*.deb",
+        Project::RabbitMQ => "*.deb",
+        Project::Erlang => "*{distribution}*.deb",
     };
     let pattern = cli_args
         .get_one::<String>("pattern")
         .map(|s| s.as_str())
         .unwrap_or(default_pattern);
 
-    let target_releases = cli::distributions(cli_args, project)?;
+    let target_releases = target_releases(cli_args, project)?;
+    if project == Project::Erlang
+        && let Some(rel) = target_releases
+            .iter()
+            .find(|rel| !DistributionAlias::erlang_supported().contains(rel))
+    {
+        return Err(BellhopError::ErlangNotSupported {
+            distribution: rel.release_name().to_string(),
+        });
+    }
     let suffix = cli::suffix(cli_args);
+    let source = cli::snapshot_source(cli_args);
 
-    let release = gh::parse_release_url(url)?;
+    let client = gh::http_client(cli::http_timeout(cli_args))?;
+    let release = release_to_import(cli_args, &client)?;
     info!(
-        "Fetching release assets for {}/{} tag {}",
-        release.owner, release.repo, release.tag
+        "Fetching release assets for {}/{} tag {} (version {})",
+        release.owner,
+        release.repo,
+        release.tag,
+        releases::version_from_tag(&release.tag)
     );
 
-    let client = Client::new();
-    let assets = releases::fetch_release_assets(&client, &release)?;
-    let filtered = releases::filter_assets(assets, pattern);
-
-    if filtered.is_empty() {
-        return Err(BellhopError::NoAssetsInRelease {
-            pattern: pattern.to_string(),
-        });
+    let details =
+        releases::fetch_release_details(&client, &release, !cli_args.get_flag("no_cache"))?;
+    log_release_details(&release, &details);
+    if let Some(path) = cli_args.get_one::<String>("release_notes_file") {
+        fs::write(path, details.body.as_deref().unwrap_or(""))?;
+        info!("Wrote release notes to {path}");
+    }
+    let batches = asset_batches(
+        details.assets,
+        pattern,
+        &target_releases,
+        cli::only_architectures(cli_args),
+    )?;
+    if cli::json(cli_args) {
+        report::record_summary(&ImportSummary {
+            owner: release.owner.clone(),
+            repo: release.repo.clone(),
+            tag: release.tag.clone(),
+            name: details.name,
+            published_at: details.published_at,
+            assets: batches
+                .iter()
+                .flat_map(|batch| &batch.assets)
+                .map(|asset| asset.name.clone())
+                .collect(),
+        })?;
     }
 
     info!(
         "Found {} matching assets (pattern: '{pattern}')",
-        filtered.len()
+        batches
+            .iter()
+            .map(|batch| batch.assets.len())
+            .sum::<usize>()
     );
 
-    let temp_dir = TempDir::new()?;
-    let downloaded = downloads::download_assets(&client, &filtered, temp_dir.path())?;
+    if check_only {
+        for asset in batches.iter().flat_map(|batch| &batch.assets) {
+            info!("  - {} ({} bytes)", asset.name, asset.size);
+        }
+        info!("Check only mode, nothing was downloaded or imported");
+        return Ok(());
+    }
+
+    // Kept until the packages are added
+    let mut temp_dir = None;
+    let download_dir = match cli_args.get_one::<String>("keep_downloads") {
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            PathBuf::from(dir)
+        }
+        None => temp_dir.insert(TempDir::new()?).path().to_path_buf(),
+    };
 
-    for deb_path in &downloaded {
-        aptly::add_single_package_no_snapshot(&project, deb_path, &target_releases)?;
+    let mut imported = 0;
+    for batch in &batches {
+        let downloaded = downloads::download_assets(
+            &client,
+            &batch.assets,
+            &download_dir,
+            cli_args.get_flag("fsync"),
+            cli::download_jobs(cli_args),
+            cli::max_download_size(cli_args),
+        )?;
+
+        if download_only {
+            for path in &downloaded {
+                println!("{}", path.display());
+            }
+            continue;
+        }
+        for deb_path in &downloaded {
+            aptly::add_single_package_no_snapshot(&project, deb_path, &batch.releases)?;
+        }
+        imported += downloaded.len();
     }
-    aptly::update_snapshots_for_releases(&project, &target_releases, &suffix)?;
+    if download_only {
+        return Ok(());
+    }
+    if let Some(expected) = cli_args.get_one::<String>("expect_version") {
+        aptly::verify_imported_version(&project, &target_releases, expected)?;
+    }
+    aptly::update_snapshots_for_releases(&project, &target_releases, &suffix, source)?;
 
     info!(
-        "Imported {} packages into {} distributions",
-        downloaded.len(),
+        "Imported {imported} packages from {}/{} {} into {} distributions",
+        release.owner,
+        release.repo,
+        release.tag,
         target_releases.len()
     );
     Ok(())
 }
 
+/// Release assets and the distributions they are imported into
+struct AssetBatch {
+    releases: Vec<DistributionAlias>,
+    assets: Vec<ReleaseAsset>,
+}
+
+/// A pattern with a `{distribution}` placeholder selects the assets of every distribution
+/// separately, any other pattern selects assets that go into all of them
+fn asset_batches(
+    assets: Vec<ReleaseAsset>,
+    pattern: &str,
+    target_releases: &[DistributionAlias],
+    only_architectures: Option<Vec<String>>,
+) -> Result<Vec<AssetBatch>, BellhopError> {
+    let select = |pattern: &str| {
+        let mut selected = releases::filter_assets(assets.clone(), pattern);
+        if let Some(allowed) = &only_architectures {
+            selected.retain(|asset| archive::is_allowed_architecture(&asset.name, allowed));
+        }
+        if selected.is_empty() {
+            return Err(BellhopError::NoAssetsInRelease {
+                pattern: pattern.to_string(),
+            });
+        }
+        Ok(selected)
+    };
+
+    if !releases::is_per_distribution_pattern(pattern) {
+        return Ok(vec![AssetBatch {
+            releases: target_releases.to_vec(),
+            assets: select(pattern)?,
+        }]);
+    }
+    target_releases
+        .iter()
+        .map(|rel| {
+            Ok(AssetBatch {
+                releases: vec![rel.clone()],
+                assets: select(&releases::pattern_for_distribution(
+                    pattern,
+                    rel.release_name(),
+                ))?,
+            })
+        })
+        .collect()
+}
+
+/// The release given by `--github-release-url` or by `--owner`, `--repo` and `--tag`, or the
+/// newest one matching `--tag-pattern`
+fn release_to_import(
+    cli_args: &ArgMatches,
+    client: &Client,
+) -> Result<GitHubRelease, BellhopError> {
+    let tag_pattern = cli_args.get_one::<String>("tag_pattern");
+    let include_prereleases = cli_args.get_flag("include_prereleases");
+
+    if let Some(url) = cli_args.get_one::<String>("github_release_url") {
+        return match tag_pattern {
+            Some(tag_pattern) => {
+                let (owner, repo) = gh::parse_repository_url(url)?;
+                newest_matching_release(client, owner, repo, tag_pattern, include_prereleases)
+            }
+            None => gh::parse_release_url(url),
+        };
+    }
+
+    let owner = required_string(cli_args, "owner")?.to_string();
+    let repo = required_string(cli_args, "github_repo")?.to_string();
+    match (cli_args.get_one::<String>("tag"), tag_pattern) {
+        (Some(tag), _) => Ok(GitHubRelease {
+            owner,
+            repo,
+            tag: tag.clone(),
+        }),
+        (None, Some(tag_pattern)) => {
+            newest_matching_release(client, owner, repo, tag_pattern, include_prereleases)
+        }
+        (None, None) => Err(BellhopError::MissingArgument {
+            argument: "tag or tag_pattern".to_string(),
+        }),
+    }
+}
+
+fn newest_matching_release(
+    client: &Client,
+    owner: String,
+    repo: String,
+    tag_pattern: &str,
+    include_prereleases: bool,
+) -> Result<GitHubRelease, BellhopError> {
+    let all = releases::fetch_releases(client, &owner, &repo)?;
+    let selected =
+        releases::select_release(&all, tag_pattern, include_prereleases).ok_or_else(|| {
+            BellhopError::NoMatchingRelease {
+                pattern: tag_pattern.to_string(),
+            }
+        })?;
+    info!(
+        "Selected release {} (tag pattern: '{tag_pattern}')",
+        selected.tag_name
+    );
+
+    Ok(GitHubRelease {
+        owner,
+        repo,
+        tag: selected.tag_name.clone(),
+    })
+}
+
+fn log_release_details(release: &GitHubRelease, details: &ReleaseDetails) {
+    info!(
+        "Release: {} ({}/{} {})",
+        details.name.as_deref().unwrap_or(&release.tag),
+        release.owner,
+        release.repo,
+        release.tag
+    );
+    if let Some(published_at) = &details.published_at {
+        info!("Published at: {published_at}");
+    }
+}
+
 pub fn setup_repositories() -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
@@ -170,6 +594,176 @@ pub fn setup_repositories() -> Result<(), BellhopError> {
     Ok(())
 }
 
+pub fn list_repositories(cli_args: &ArgMatches) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let status = aptly::repository_status(&aptly::list_repos()?);
+    match cli::output_format(cli_args) {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&status).map_err(io::Error::from)?;
+            println!("{json}");
+        }
+        OutputFormat::Text => {
+            for repo in &status.present {
+                println!("{repo}");
+            }
+            for repo in &status.missing {
+                println!("{repo} (missing)");
+            }
+            for repo in &status.unexpected {
+                println!("{repo} (not managed by bellhop)");
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn clean(cli_args: &ArgMatches) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+    confirm(
+        cli_args,
+        "Delete the packages and pool files that no repository, mirror or snapshot references",
+    )?;
+
+    if cli_args.get_flag("recover") {
+        aptly::db_recover()?;
+    }
+    let summary = aptly::db_cleanup()?;
+
+    if cli::json(cli_args) {
+        let json = serde_json::to_string(&summary).map_err(io::Error::from)?;
+        println!("{json}");
+    } else {
+        println!(
+            "Deleted {} packages and {} files, freed {}",
+            summary.packages_deleted,
+            summary.files_deleted,
+            summary.space_freed.as_deref().unwrap_or("no disk space")
+        );
+    }
+    Ok(())
+}
+
+pub fn mirror_create(cli_args: &ArgMatches) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let name = required_string(cli_args, "name")?;
+    aptly::mirror_create(
+        name,
+        required_string(cli_args, "url")?,
+        required_string(cli_args, "distribution")?,
+        &cli::strings(cli_args, "components"),
+        &cli::strings(cli_args, "architectures"),
+    )?;
+    info!("Created mirror '{name}', run 'bellhop mirror update --name {name}' to download it");
+    Ok(())
+}
+
+pub fn mirror_update(cli_args: &ArgMatches) -> Result<(), BellhopError> {
+    aptly::check_aptly_available()?;
+
+    let name = required_string(cli_args, "name")?;
+    let snapshot_name = aptly::mirror_snapshot_name(name, &cli::suffix(cli_args));
+    aptly::mirror_update(name)?;
+    aptly::mirror_snapshot(name, &snapshot_name)?;
+    info!("Mirror '{name}' is up to date, its packages are in snapshot '{snapshot_name}'");
+    Ok(())
+}
+
+/// The distributions to operate on, recorded for the run report
+fn target_releases(
+    cli_args: &ArgMatches,
+    project: Project,
+) -> Result<Vec<DistributionAlias>, BellhopError> {
+    let target_releases = cli::distributions(cli_args, project)?;
+    report::record_distributions(&target_releases);
+    Ok(target_releases)
+}
+
+fn required_string<'a>(cli_args: &'a ArgMatches, id: &str) -> Result<&'a str, BellhopError> {
+    cli_args
+        .get_one::<String>(id)
+        .map(|s| s.as_str())
+        .ok_or_else(|| BellhopError::MissingArgument {
+            argument: id.to_string(),
+        })
+}
+
+pub fn environment(cli_args: &ArgMatches) -> Result<(), BellhopError> {
+    let report = doctor::environment_report();
+    if cli::json(cli_args) {
+        let object: serde_json::Map<String, serde_json::Value> = report
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.into()))
+            .collect();
+        println!("{}", serde_json::Value::Object(object));
+    } else {
+        for (name, value) in report {
+            println!("{name}: {value}");
+        }
+    }
+    Ok(())
+}
+
+pub fn list_distributions(cli_args: &ArgMatches) -> Result<(), BellhopError> {
+    let erlang_supported = DistributionAlias::erlang_supported();
+    match cli::output_format(cli_args) {
+        OutputFormat::Json => {
+            let distributions: Vec<serde_json::Value> = DistributionAlias::all()
+                .iter()
+                .map(|rel| {
+                    serde_json::json!({
+                        "name": rel.release_name(),
+                        "family": rel.family_name(),
+                        "erlang_supported": erlang_supported.contains(rel),
+                    })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&distributions).map_err(io::Error::from)?;
+            println!("{json}");
+        }
+        OutputFormat::Text => {
+            println!("{:<12} {:<8} erlang", "distribution", "family");
+            for rel in DistributionAlias::all() {
+                let erlang = if erlang_supported.contains(rel) {
+                    "yes"
+                } else {
+                    "no"
+                };
+                println!(
+                    "{:<12} {:<8} {erlang}",
+                    rel.release_name(),
+                    rel.family_name()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn doctor() -> Result<(), BellhopError> {
+    let checks = doctor::run_checks();
+    for check in &checks {
+        println!("{check}");
+    }
+
+    let failed = checks.iter().filter(|c| c.is_failure()).count();
+    if failed > 0 {
+        return Err(BellhopError::DoctorChecksFailed { failed });
+    }
+    Ok(())
+}
+
+/// `--watch-map` replaces the default directories
+fn watch_directories(cli_args: &ArgMatches) -> WatchedDirectories {
+    let mappings = cli::watch_map(cli_args);
+    if mappings.is_empty() {
+        WatchedDirectories::default()
+    } else {
+        WatchedDirectories::new(mappings)
+    }
+}
+
 pub fn watch(cli_args: &ArgMatches) -> Result<(), BellhopError> {
     aptly::check_aptly_available()?;
 
@@ -180,6 +774,16 @@ pub fn watch(cli_args: &ArgMatches) -> Result<(), BellhopError> {
         })?;
 
     let target_releases = cli::distributions_for_all_projects(cli_args)?;
-
-    watcher::watch_directory(Path::new(root), &target_releases, None)
+    report::record_distributions(&target_releases);
+
+    let options = WatchOptions {
+        watch_removals: cli_args.get_flag("watch_removals"),
+        publish: cli_args.get_flag("watch_publish"),
+        max_events: None,
+        once: cli_args.get_flag("once"),
+        directories: watch_directories(cli_args),
+        lockfile: cli::lockfile(cli_args),
+        lock_timeout: cli::lock_timeout(cli_args),
+    };
+    watcher::watch_directory(Path::new(root), &target_releases, options)
 }
@@ -18,7 +18,14 @@ pub mod archive;
 pub mod cli;
 pub mod common;
 pub mod deb;
+pub mod debedit;
+pub mod doctor;
 pub mod errors;
 pub mod gh;
 pub mod handlers;
+pub mod lock;
+pub mod plan;
+pub mod progress;
+pub mod report;
+pub mod version;
 pub mod watcher;
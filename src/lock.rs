@@ -0,0 +1,77 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::doctor;
+use crate::errors::BellhopError;
+use log::{debug, info};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+const LOCK_FILE_NAME: &str = "bellhop.lock";
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Keeps other bellhop runs from changing the same aptly database, which aptly does not guard
+/// against well enough. The lock is released when this is dropped.
+#[derive(Debug)]
+pub struct RunLock {
+    _file: File,
+}
+
+/// `bellhop.lock` in the aptly root directory, so that runs against different aptly databases
+/// do not wait for each other
+pub fn default_lock_path() -> Option<PathBuf> {
+    doctor::aptly_root_dir().map(|dir| dir.join(LOCK_FILE_NAME))
+}
+
+/// Waits for up to `timeout` for another run to release the lock
+pub fn acquire(path: &Path, timeout: Duration) -> Result<RunLock, BellhopError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+
+    let started = Instant::now();
+    let mut waiting = false;
+    loop {
+        match file.try_lock() {
+            Ok(()) => {
+                debug!("Acquired the run lock at {}", path.display());
+                return Ok(RunLock { _file: file });
+            }
+            Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                if !waiting {
+                    waiting = true;
+                    info!(
+                        "Another bellhop run holds {}, waiting for up to {}s",
+                        path.display(),
+                        timeout.as_secs()
+                    );
+                }
+                thread::sleep(LOCK_POLL_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(BellhopError::AlreadyRunning {
+                    path: path.to_path_buf(),
+                });
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
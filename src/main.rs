@@ -17,53 +17,112 @@ mod archive;
 mod cli;
 mod common;
 mod deb;
+mod debedit;
+mod doctor;
 mod errors;
 mod gh;
 mod handlers;
+mod lock;
+mod plan;
+mod progress;
+mod report;
+mod version;
 mod watcher;
 
 use common::Project;
-use errors::{BellhopError, ExitCode, map_error_to_exit_code};
+use errors::{BellhopError, ExitCode, map_clap_error_to_exit_code, map_error_to_exit_code};
+use lock::RunLock;
+use log::debug;
 use std::io;
+use std::path::Path;
 use std::process;
 
-fn setup_logging() -> Result<(), fern::InitError> {
+fn setup_logging(cli_args: &clap::ArgMatches) -> Result<(), fern::InitError> {
+    let (level, bellhop_level) = cli::log_levels(cli_args);
     fern::Dispatch::new()
         .format(|out, message, record| out.finish(format_args!("[{}] {}", record.level(), message)))
-        .level(log::LevelFilter::Info)
-        .level_for("bellhop", log::LevelFilter::Debug)
+        .level(level)
+        .level_for("bellhop", bellhop_level)
         .chain(io::stderr())
         .apply()?;
     Ok(())
 }
 
 fn main() {
-    if let Err(e) = setup_logging() {
+    let parser = cli::parser();
+    let cli_args = match parser.try_get_matches() {
+        Ok(cli_args) => cli_args,
+        Err(err) => {
+            let _ = err.print();
+            process::exit(map_clap_error_to_exit_code(err.kind()).into());
+        }
+    };
+
+    if let Err(e) = setup_logging(&cli_args) {
         eprintln!("Failed to initialize logging: {e}");
     }
 
-    let parser = cli::parser();
-    let cli_args = parser.get_matches();
-
-    let exit_code = match run(&cli_args) {
-        Ok(_) => ExitCode::Ok,
-        Err(err) => {
-            eprintln!("Error: {err}");
-            map_error_to_exit_code(&err)
+    let result = run(&cli_args);
+    if cli::json(&cli_args) {
+        let report = report::run_report(&command_name(&cli_args), &result);
+        match serde_json::to_string(&report) {
+            Ok(json) => eprintln!("{json}"),
+            Err(e) => eprintln!("Failed to serialize the run report: {e}"),
         }
+    } else if let Err(err) = &result {
+        eprintln!("Error: {err}");
+    }
+    let exit_code = match &result {
+        Ok(_) => ExitCode::Ok,
+        Err(err) => map_error_to_exit_code(err),
     };
 
     process::exit(exit_code.into());
 }
 
+/// The subcommands that were invoked, e.g. `rabbitmq deb add`
+fn command_name(cli_args: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = cli_args;
+    while let Some((name, args)) = current.subcommand() {
+        names.push(name);
+        current = args;
+    }
+    names.join(" ")
+}
+
 fn run(cli_args: &clap::ArgMatches) -> Result<(), BellhopError> {
+    progress::set_quiet(cli::quiet(cli_args));
+    aptly::set_lock_retries(cli::aptly_lock_retries(cli_args));
+    aptly::set_retry_exit_codes(cli::retry_aptly_exit_codes(cli_args));
+    aptly::set_stream_output(cli::stream_aptly(cli_args));
+
+    if let Some(config_path) = cli::aptly_config(cli_args) {
+        aptly::set_aptly_config(Path::new(config_path))?;
+    }
+
     if let Some((first_level, first_level_args)) = cli_args.subcommand() {
         if first_level == "watch" {
             return handlers::watch(first_level_args);
         }
+        if first_level == "doctor" {
+            return handlers::doctor();
+        }
+        if first_level == "env" {
+            return handlers::environment(first_level_args);
+        }
+        if first_level == "clean" {
+            let _lock = acquire_run_lock(first_level_args)?;
+            return handlers::clean(first_level_args);
+        }
+        if first_level == "distributions" {
+            return handlers::list_distributions(first_level_args);
+        }
 
         if let Some((second_level, second_level_args)) = first_level_args.subcommand() {
-            if let Some(result) = dispatch_admin_command(first_level, second_level) {
+            if let Some(result) =
+                dispatch_admin_command(first_level, second_level, second_level_args)
+            {
                 return result;
             }
 
@@ -75,14 +134,49 @@ fn run(cli_args: &clap::ArgMatches) -> Result<(), BellhopError> {
     Ok(())
 }
 
+/// Read-only commands do not wait for the run lock
+fn changes_aptly_database(second_level: &str, third_level: &str) -> bool {
+    matches!(
+        (second_level, third_level),
+        (
+            "deb",
+            "add"
+                | "remove"
+                | "publish"
+                | "promote-latest"
+                | "unpublish"
+                | "rollback"
+                | "import-from-github"
+        ) | ("snapshot", "take" | "delete")
+    )
+}
+
+fn acquire_run_lock(cli_args: &clap::ArgMatches) -> Result<Option<RunLock>, BellhopError> {
+    match cli::lockfile(cli_args) {
+        Some(path) => lock::acquire(&path, cli::lock_timeout(cli_args)).map(Some),
+        None => {
+            debug!("The aptly root directory is unknown, not taking the run lock");
+            Ok(None)
+        }
+    }
+}
+
 fn dispatch_admin_command(
     first_level: &str,
     second_level: &str,
+    second_level_args: &clap::ArgMatches,
 ) -> Option<Result<(), BellhopError>> {
-    match (first_level, second_level) {
-        ("repositories", "set-up") => Some(handlers::setup_repositories()),
-        _ => None,
-    }
+    let handler: fn(&clap::ArgMatches) -> Result<(), BellhopError> =
+        match (first_level, second_level) {
+            ("repositories", "list") => {
+                return Some(handlers::list_repositories(second_level_args));
+            }
+            ("repositories", "set-up") => |_| handlers::setup_repositories(),
+            ("mirror", "create") => handlers::mirror_create,
+            ("mirror", "update") => handlers::mirror_update,
+            _ => return None,
+        };
+    Some(acquire_run_lock(second_level_args).and_then(|_lock| handler(second_level_args)))
 }
 
 fn dispatch_command(
@@ -91,27 +185,45 @@ fn dispatch_command(
     third_level: &str,
     third_level_args: &clap::ArgMatches,
 ) -> Result<(), BellhopError> {
-    let project = match first_level {
-        "rabbitmq" => Project::RabbitMQ,
-        "erlang" => Project::Erlang,
-        "cli-tools" => Project::CliTools,
-        _ => {
-            return Err(BellhopError::UnknownCommand {
-                first: first_level.to_string(),
-                second: second_level.to_string(),
-                third: third_level.to_string(),
-            });
-        }
+    let project: Project = first_level
+        .parse()
+        .map_err(|_| BellhopError::UnknownCommand {
+            first: first_level.to_string(),
+            second: second_level.to_string(),
+            third: third_level.to_string(),
+        })?;
+
+    if let Some(prefix) = cli::repo_prefix(third_level_args) {
+        aptly::set_repo_prefix(prefix)?;
+    }
+    if let Some(channel) = cli::channel(third_level_args) {
+        aptly::set_channel(channel)?;
+    }
+
+    if cli::print_plan(third_level_args) {
+        return handlers::print_plan(second_level, third_level, third_level_args, project);
+    }
+
+    let _lock = if changes_aptly_database(second_level, third_level) {
+        acquire_run_lock(third_level_args)?
+    } else {
+        None
     };
 
     match (second_level, third_level) {
         ("deb", "add") => handlers::add(third_level_args, project),
         ("deb", "remove") => handlers::remove(third_level_args, project),
         ("deb", "publish") => handlers::publish(third_level_args, project),
+        ("deb", "promote-latest") => handlers::promote_latest(third_level_args, project),
+        ("deb", "unpublish") => handlers::unpublish(third_level_args, project),
+        ("deb", "rollback") => handlers::rollback(third_level_args, project),
         ("deb", "import-from-github") => handlers::import_from_github(third_level_args, project),
         ("snapshot", "take") => handlers::take_snapshots(third_level_args, project),
         ("snapshot", "delete") => handlers::delete_snapshots(third_level_args, project),
         ("snapshot", "list") => handlers::list_snapshots(third_level_args, project),
+        ("snapshot", "show") => handlers::show_snapshot(third_level_args),
+        ("snapshot", "orphans") => handlers::orphan_snapshots(third_level_args, project),
+        ("snapshot", "diff") => handlers::diff_snapshots(third_level_args, project),
         _ => Err(BellhopError::UnknownCommand {
             first: first_level.to_string(),
             second: second_level.to_string(),
@@ -0,0 +1,374 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::aptly::{self, PublishOptions};
+use crate::archive::{self, PackageSource};
+use crate::cli;
+use crate::common::{Project, SnapshotSource};
+use crate::deb::DistributionAlias;
+use crate::errors::BellhopError;
+use clap::ArgMatches;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// The `aptly` commands a bellhop command would run. Plans are built without running `aptly`,
+/// so they can be previewed where it is not installed.
+pub fn command_plan(
+    group: &str,
+    command: &str,
+    cli_args: &ArgMatches,
+    project: Project,
+) -> Result<Vec<String>, BellhopError> {
+    if cli::direct_repo(cli_args).is_some() {
+        return Err(BellhopError::PlanNotSupported {
+            command: format!("{group} {command} --repo"),
+        });
+    }
+    let target_releases = cli::distributions(cli_args, project)?;
+    let suffix = cli::suffix(cli_args);
+
+    let mut steps = match (group, command) {
+        ("deb", "add") => add_steps(cli_args, &project, &target_releases)?,
+        ("deb", "remove") if cli_args.get_flag("snapshot_only") => {
+            return snapshot_only_remove_steps(cli_args, &project, &target_releases, &suffix);
+        }
+        ("deb", "remove") => remove_steps(cli_args, &project, &target_releases)?,
+        ("deb", "publish") => {
+            let options = cli::publish_options(cli_args, project);
+            if cli_args.get_flag("update_only") {
+                return Ok(publish_update_steps(&project, &target_releases, &options));
+            }
+            return Ok(publish_steps(&project, &target_releases, &suffix, &options));
+        }
+        ("deb", "rollback") => {
+            let to_suffix =
+                cli_args
+                    .get_one::<String>("to")
+                    .ok_or_else(|| BellhopError::MissingArgument {
+                        argument: "to".to_string(),
+                    })?;
+            let options = cli::publish_options(cli_args, project);
+            return Ok(publish_steps(
+                &project,
+                &target_releases,
+                to_suffix,
+                &options,
+            ));
+        }
+        ("snapshot", "take") if cli_args.get_flag("from_published") => {
+            return Ok(target_releases
+                .iter()
+                .map(|rel| {
+                    format!(
+                        "aptly snapshot merge {} <the snapshot published at {}/{}>",
+                        aptly::snapshot_name_with_suffix(&project, rel, &suffix),
+                        aptly::rel_path_with_prefix(&project, rel, rel.family()),
+                        rel.release_name()
+                    )
+                })
+                .collect());
+        }
+        ("snapshot", "take") => Vec::new(),
+        ("snapshot", "delete") => {
+            return Ok(target_releases
+                .iter()
+                .map(|rel| {
+                    format!(
+                        "aptly snapshot drop -force {}",
+                        aptly::snapshot_name_with_suffix(&project, rel, &suffix)
+                    )
+                })
+                .collect());
+        }
+        _ => {
+            return Err(BellhopError::PlanNotSupported {
+                command: format!("{group} {command}"),
+            });
+        }
+    };
+
+    if cli::no_snapshot(cli_args) {
+        return Ok(steps);
+    }
+    steps.extend(snapshot_steps(
+        &project,
+        &target_releases,
+        &suffix,
+        cli::snapshot_source(cli_args),
+    ));
+    Ok(steps)
+}
+
+fn add_steps(
+    cli_args: &ArgMatches,
+    project: &Project,
+    target_releases: &[DistributionAlias],
+) -> Result<Vec<String>, BellhopError> {
+    let newer_only = cli_args.get_flag("newer_only");
+    let if_not_present = cli_args.get_flag("if_not_present");
+    let mut deb_names = Vec::new();
+    for package_file_path in required_paths(cli_args)? {
+        deb_names.extend(deb_file_names(&package_file_path, cli_args)?);
+    }
+
+    let mut steps = Vec::new();
+    for deb_name in &deb_names {
+        for rel in target_releases {
+            let mut args = vec!["aptly repo add".to_string()];
+            args.extend(aptly::architectures_arg(project));
+            args.push(aptly::repo_name(project, rel));
+            args.push(deb_name.clone());
+            if newer_only {
+                args.push("(unless the repository has the same or a newer version)".to_string());
+            } else if if_not_present {
+                args.push("(unless the repository already has it)".to_string());
+            }
+            steps.push(args.join(" "));
+        }
+    }
+    Ok(steps)
+}
+
+fn remove_steps(
+    cli_args: &ArgMatches,
+    project: &Project,
+    target_releases: &[DistributionAlias],
+) -> Result<Vec<String>, BellhopError> {
+    let package_name = cli_args
+        .get_one::<String>("package_name")
+        .map(|s| s.as_str());
+
+    let queries = if let Some(version) = cli_args.get_one::<String>("version") {
+        vec![aptly::version_query(project, version, package_name)?]
+    } else if let Some(name) = cli_args.get_one::<String>("all_versions") {
+        vec![aptly::all_versions_query(project, name)?]
+    } else {
+        let package_file_path = required_path(cli_args)?;
+        if cli_args.get_flag("exact") {
+            return exact_remove_steps(&package_file_path, cli_args, project, target_releases);
+        }
+        let versions: BTreeSet<String> = deb_file_names(&package_file_path, cli_args)?
+            .iter()
+            .map(|name| archive::extract_version_from_filename(name))
+            .collect::<Result<_, _>>()?;
+        versions
+            .iter()
+            .map(|version| aptly::version_query(project, version, None))
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut steps = Vec::new();
+    for query in &queries {
+        for rel in target_releases {
+            steps.push(format!(
+                "aptly repo remove {} '{query}'",
+                aptly::repo_name(project, rel)
+            ));
+        }
+    }
+    Ok(steps)
+}
+
+/// `--snapshot-only` leaves the repositories alone, so no snapshots are taken afterwards
+fn snapshot_only_remove_steps(
+    cli_args: &ArgMatches,
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    from_suffix: &str,
+) -> Result<Vec<String>, BellhopError> {
+    let version =
+        cli_args
+            .get_one::<String>("version")
+            .ok_or_else(|| BellhopError::MissingArgument {
+                argument: "version".to_string(),
+            })?;
+    let to_suffix =
+        cli_args
+            .get_one::<String>("to")
+            .ok_or_else(|| BellhopError::MissingArgument {
+                argument: "to".to_string(),
+            })?;
+    let package_name = cli_args
+        .get_one::<String>("package_name")
+        .map(|s| s.as_str());
+    let exclusion = aptly::exclusion_query(&aptly::version_query(project, version, package_name)?);
+
+    Ok(target_releases
+        .iter()
+        .map(|rel| {
+            format!(
+                "aptly snapshot filter {} {} '{exclusion}'",
+                aptly::snapshot_name_with_suffix(project, rel, from_suffix),
+                aptly::snapshot_name_with_suffix(project, rel, to_suffix)
+            )
+        })
+        .collect())
+}
+
+/// The plan reads package names and versions from the file names, the removal itself reads them
+/// from the control files
+fn exact_remove_steps(
+    package_file_path: &Path,
+    cli_args: &ArgMatches,
+    project: &Project,
+    target_releases: &[DistributionAlias],
+) -> Result<Vec<String>, BellhopError> {
+    let packages: BTreeSet<(String, String)> = deb_file_names(package_file_path, cli_args)?
+        .iter()
+        .map(|name| {
+            let file_name = Path::new(name)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            archive::extract_name_and_version_from_filename(&file_name)
+        })
+        .collect::<Result<_, _>>()?;
+    let query = aptly::exact_packages_query(&packages);
+    Ok(target_releases
+        .iter()
+        .map(|rel| {
+            format!(
+                "aptly repo remove {} '{query}'",
+                aptly::repo_name(project, rel)
+            )
+        })
+        .collect())
+}
+
+fn snapshot_steps(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    suffix: &str,
+    source: SnapshotSource,
+) -> Vec<String> {
+    target_releases
+        .iter()
+        .map(|rel| {
+            let note = match source {
+                SnapshotSource::Repo => "",
+                SnapshotSource::Published => ", merged with the currently published snapshot",
+            };
+            format!(
+                "aptly snapshot create {} from repo {} (updated in place if it already exists{note})",
+                aptly::snapshot_name_with_suffix(project, rel, suffix),
+                aptly::repo_name(project, rel)
+            )
+        })
+        .collect()
+}
+
+fn publish_steps(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    suffix: &str,
+    options: &PublishOptions,
+) -> Vec<String> {
+    let flags: String = options.flags().iter().map(|f| format!("{f} ")).collect();
+    target_releases
+        .iter()
+        .flat_map(|rel| {
+            let rel_path =
+                aptly::publish_target(&options.rel_path_of(project, rel), options.endpoint.as_deref());
+            let snapshot = aptly::snapshot_name_with_suffix(project, rel, suffix);
+            let flags = &flags;
+            let cleanup = options.cleanup_previous.then(|| {
+                format!("aptly snapshot drop -force <the snapshot previously published at {rel_path}> (unless it is {snapshot} or still published elsewhere)")
+            });
+            options
+                .distribution_names_of(rel)
+                .into_iter()
+                .map(move |distribution| {
+                    format!(
+                        "aptly publish switch {flags}{distribution} {rel_path} {snapshot} (or 'publish snapshot' if it is not published yet)"
+                    )
+                })
+                .chain(cleanup)
+        })
+        .collect()
+}
+
+fn publish_update_steps(
+    project: &Project,
+    target_releases: &[DistributionAlias],
+    options: &PublishOptions,
+) -> Vec<String> {
+    let flags: String = options
+        .flags()
+        .iter()
+        .filter(|f| !f.starts_with("-component="))
+        .map(|f| format!("{f} "))
+        .collect();
+    target_releases
+        .iter()
+        .flat_map(|rel| {
+            let rel_path = aptly::publish_target(
+                &options.rel_path_of(project, rel),
+                options.endpoint.as_deref(),
+            );
+            let flags = &flags;
+            options
+                .distribution_names_of(rel)
+                .into_iter()
+                .map(move |distribution| {
+                    format!("aptly publish update {flags}{distribution} {rel_path}")
+                })
+        })
+        .collect()
+}
+
+fn required_paths(cli_args: &ArgMatches) -> Result<Vec<PathBuf>, BellhopError> {
+    let paths: Vec<PathBuf> = cli::package_file_paths(cli_args)?
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    if let Some(path) = paths.iter().find(|path| !path.exists()) {
+        return Err(BellhopError::PackageFileNotFound { path: path.clone() });
+    }
+    Ok(paths)
+}
+
+fn required_path(cli_args: &ArgMatches) -> Result<PathBuf, BellhopError> {
+    let path = cli_args
+        .get_one::<String>("package_file_path")
+        .map(PathBuf::from)
+        .ok_or_else(|| BellhopError::MissingArgument {
+            argument: "package_file_path".to_string(),
+        })?;
+    if !path.exists() {
+        return Err(BellhopError::PackageFileNotFound { path });
+    }
+    Ok(path)
+}
+
+/// Archives are extracted to find the packages in them, the extracted files are discarded
+fn deb_file_names(
+    package_file_path: &Path,
+    cli_args: &ArgMatches,
+) -> Result<Vec<String>, BellhopError> {
+    let file_name = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+
+    Ok(
+        match archive::process_package_file(package_file_path, &cli::extraction_options(cli_args))?
+        {
+            PackageSource::SingleDeb(path) => vec![path.display().to_string()],
+            PackageSource::Archive { deb_files, .. } => {
+                deb_files.iter().map(|path| file_name(path)).collect()
+            }
+        },
+    )
+}
@@ -0,0 +1,52 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+static QUIET: OnceLock<bool> = OnceLock::new();
+
+/// Disables progress bars for the rest of the process. Only the first call takes effect.
+pub fn set_quiet(quiet: bool) {
+    let _ = QUIET.set(quiet);
+}
+
+/// Progress bars are only drawn for interactive sessions, so that CI logs stay clean
+pub fn is_enabled() -> bool {
+    !QUIET.get().copied().unwrap_or(false) && io::stderr().is_terminal()
+}
+
+pub fn bytes_bar(total: u64, message: &str) -> ProgressBar {
+    new_bar(
+        total,
+        message,
+        "{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec})",
+    )
+}
+
+pub fn entries_bar(total: u64, message: &str) -> ProgressBar {
+    new_bar(total, message, "{msg} [{bar:40}] {pos}/{len} entries")
+}
+
+fn new_bar(total: u64, message: &str, template: &str) -> ProgressBar {
+    if !is_enabled() {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::new(total).with_message(message.to_string());
+    if let Ok(style) = ProgressStyle::with_template(template) {
+        bar.set_style(style.progress_chars("=> "));
+    }
+    bar
+}
@@ -0,0 +1,110 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::Serialize;
+use serde_json::Value;
+use std::io;
+use std::sync::{Mutex, PoisonError};
+
+use crate::deb::DistributionAlias;
+use crate::errors::{BellhopError, ExitCode, map_error_to_exit_code};
+
+/// What handlers recorded during the run so far
+struct Recorded {
+    distributions: Vec<String>,
+    actions: Vec<String>,
+    summary: Option<Value>,
+}
+
+static RECORDED: Mutex<Recorded> = Mutex::new(Recorded {
+    distributions: Vec::new(),
+    actions: Vec::new(),
+    summary: None,
+});
+
+/// The outcome of a run, printed by `--json` as the last line on stderr for every command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunReport {
+    /// The subcommands, e.g. `rabbitmq deb add`
+    pub command: String,
+    pub status: RunStatus,
+    /// Distributions the command resolved, in processing order
+    pub distributions: Vec<String>,
+    /// The `aptly` commands that were run, in order
+    pub actions: Vec<String>,
+    pub exit_code: i32,
+    /// The error object of [`BellhopError::to_json`] with its `error`, `message` and `exit_code`
+    /// fields, `null` for successful runs
+    pub error: Option<Value>,
+    /// What the command did, e.g. the added packages of `deb add`, `null` for commands
+    /// that do not record one
+    pub summary: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+}
+
+/// Distributions that were already recorded are not repeated
+pub fn record_distributions(releases: &[DistributionAlias]) {
+    let mut recorded = RECORDED.lock().unwrap_or_else(PoisonError::into_inner);
+    for rel in releases {
+        let name = rel.release_name().to_string();
+        if !recorded.distributions.contains(&name) {
+            recorded.distributions.push(name);
+        }
+    }
+}
+
+pub fn record_action(action: impl Into<String>) {
+    RECORDED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .actions
+        .push(action.into());
+}
+
+/// Replaces the summary recorded earlier, if any
+pub fn record_summary(summary: &impl Serialize) -> Result<(), BellhopError> {
+    let summary = serde_json::to_value(summary).map_err(io::Error::from)?;
+    RECORDED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .summary = Some(summary);
+    Ok(())
+}
+
+/// The report of everything recorded so far, with the outcome of the run
+pub fn run_report(command: &str, result: &Result<(), BellhopError>) -> RunReport {
+    let recorded = RECORDED.lock().unwrap_or_else(PoisonError::into_inner);
+    let (status, exit_code, error) = match result {
+        Ok(()) => (RunStatus::Succeeded, ExitCode::Ok, None),
+        Err(err) => (
+            RunStatus::Failed,
+            map_error_to_exit_code(err),
+            Some(err.to_json()),
+        ),
+    };
+    RunReport {
+        command: command.to_string(),
+        status,
+        distributions: recorded.distributions.clone(),
+        actions: recorded.actions.clone(),
+        exit_code: exit_code.into(),
+        error,
+        summary: recorded.summary.clone(),
+    }
+}
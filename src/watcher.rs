@@ -11,15 +11,21 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use crate::aptly;
-use crate::common::Project;
+use crate::aptly::{self, PublishOptions};
+use crate::archive;
+use crate::cli::DEFAULT_SUFFIX_DATE_FORMAT;
+use crate::common::{Project, SnapshotSource};
 use crate::deb::DistributionAlias;
 use crate::errors::BellhopError;
+use crate::lock::{self, RunLock};
+use chrono::Local;
 use log::{debug, error, info, warn};
 use notify::{Event, EventKind, RecursiveMode, Watcher};
 use std::fs;
-use std::path::Path;
-use std::sync::mpsc;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
 
 const RABBITMQ_SERVER_DIR: &str = "rabbitmq-server";
 const RABBITMQ_ERLANG_DIR: &str = "rabbitmq-erlang";
@@ -34,16 +40,150 @@ pub fn project_for_directory(dir_name: &str) -> Option<Project> {
     }
 }
 
-fn subdirectories() -> [&'static str; 3] {
-    [RABBITMQ_SERVER_DIR, RABBITMQ_ERLANG_DIR, RABBITMQ_CLI_DIR]
+/// Watched subdirectory names and the projects their packages are imported into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedDirectories(Vec<(String, Project)>);
+
+impl Default for WatchedDirectories {
+    /// The built-in directories of [`project_for_directory`]
+    fn default() -> Self {
+        Self::new(
+            [RABBITMQ_SERVER_DIR, RABBITMQ_ERLANG_DIR, RABBITMQ_CLI_DIR]
+                .into_iter()
+                .filter_map(|name| Some((name.to_string(), project_for_directory(name)?))),
+        )
+    }
+}
+
+impl WatchedDirectories {
+    /// A later mapping of the same directory replaces an earlier one
+    pub fn new(mappings: impl IntoIterator<Item = (String, Project)>) -> Self {
+        let mut directories: Vec<(String, Project)> = Vec::new();
+        for (dir_name, project) in mappings {
+            match directories.iter_mut().find(|(name, _)| *name == dir_name) {
+                Some(existing) => existing.1 = project,
+                None => directories.push((dir_name, project)),
+            }
+        }
+        WatchedDirectories(directories)
+    }
+
+    pub fn project_for(&self, dir_name: &str) -> Option<Project> {
+        self.0
+            .iter()
+            .find(|(name, _)| name == dir_name)
+            .map(|(_, project)| *project)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+/// How long the watched directories must stay quiet before the snapshots are refreshed, so that
+/// files copied in together end up in a single snapshot
+const QUIET_PERIOD: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default)]
+pub struct WatchOptions {
+    /// Deleting a package file removes that package version from the repositories
+    pub watch_removals: bool,
+    /// Publishes the refreshed snapshots
+    pub publish: bool,
+    /// Stops after this many files were imported or removed
+    pub max_events: Option<usize>,
+    /// Imports the files that are already in the watched directories and stops instead of
+    /// waiting for new ones
+    pub once: bool,
+    /// The subdirectories to watch, `rabbitmq-server`, `rabbitmq-erlang` and `rabbitmq-cli`
+    /// by default
+    pub directories: WatchedDirectories,
+    /// The run lock, taken for every event and snapshot refresh so that other bellhop runs can
+    /// change the aptly database in between. No lock is taken when this is `None`.
+    pub lockfile: Option<PathBuf>,
+    /// How long to wait for another bellhop run to release the run lock
+    pub lock_timeout: Duration,
+}
+
+impl WatchOptions {
+    fn lock(&self) -> Result<Option<RunLock>, BellhopError> {
+        self.lockfile
+            .as_deref()
+            .map(|path| lock::acquire(path, self.lock_timeout))
+            .transpose()
+    }
+}
+
+/// The distributions of every project that packages were added to or removed from since the
+/// snapshots were last refreshed
+#[derive(Debug, Default)]
+struct Batch {
+    touched: Vec<(Project, Vec<DistributionAlias>)>,
+}
+
+impl Batch {
+    fn touch(&mut self, project: Project, releases: &[DistributionAlias]) {
+        let index = match self.touched.iter().position(|(p, _)| *p == project) {
+            Some(index) => index,
+            None => {
+                self.touched.push((project, Vec::new()));
+                self.touched.len() - 1
+            }
+        };
+        let touched = &mut self.touched[index].1;
+        for rel in releases {
+            if !touched.contains(rel) {
+                touched.push(rel.clone());
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.touched.is_empty()
+    }
+
+    /// Takes new snapshots of, and with `publish` publishes, every touched distribution.
+    /// Failures are logged so that the watcher keeps running, only the run lock is required.
+    fn refresh(&mut self, options: &WatchOptions) -> Result<(), BellhopError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let _lock = options.lock()?;
+        let suffix = Local::now().format(DEFAULT_SUFFIX_DATE_FORMAT).to_string();
+        for (project, releases) in mem::take(&mut self.touched) {
+            info!(
+                "Refreshing the snapshots of {project} for {} distributions",
+                releases.len()
+            );
+            let result = aptly::update_snapshots_for_releases(
+                &project,
+                &releases,
+                &suffix,
+                SnapshotSource::default(),
+            )
+            .and_then(|()| {
+                if options.publish {
+                    aptly::publish(project, &releases, &suffix, &PublishOptions::default())
+                } else {
+                    Ok(())
+                }
+            });
+            if let Err(e) = result {
+                error!("Failed to refresh the snapshots of {project}: {e}");
+            }
+        }
+        Ok(())
+    }
 }
 
+/// Imports, and with `watch_removals` removes, packages as their files change. The snapshots of
+/// the affected distributions are refreshed once no event has arrived for [`QUIET_PERIOD`].
 pub fn watch_directory(
     root: &Path,
     target_releases: &[DistributionAlias],
-    max_events: Option<usize>,
+    options: WatchOptions,
 ) -> Result<(), BellhopError> {
-    for subdir in subdirectories() {
+    for subdir in options.directories.names() {
         let dir_path = root.join(subdir);
         if !dir_path.exists() {
             info!("Creating watched directory: {}", dir_path.display());
@@ -51,6 +191,10 @@ pub fn watch_directory(
         }
     }
 
+    if options.once {
+        return import_existing_files(root, target_releases, &options);
+    }
+
     info!("Watching {} for .deb files", root.display());
     info!("Targeting {} distributions", target_releases.len());
 
@@ -59,7 +203,7 @@ pub fn watch_directory(
     let mut watcher =
         notify::recommended_watcher(tx).map_err(|e| BellhopError::WatcherError(e.to_string()))?;
 
-    for subdir in subdirectories() {
+    for subdir in options.directories.names() {
         let dir_path = root.join(subdir);
         watcher
             .watch(&dir_path, RecursiveMode::NonRecursive)
@@ -68,33 +212,62 @@ pub fn watch_directory(
     }
 
     let mut events_processed = 0;
+    let mut batch = Batch::default();
 
-    if max_events == Some(0) {
+    if options.max_events == Some(0) {
         return Ok(());
     }
 
-    for event_result in rx {
+    loop {
+        let event_result = if batch.is_empty() {
+            match rx.recv() {
+                Ok(event_result) => event_result,
+                Err(_) => break,
+            }
+        } else {
+            match rx.recv_timeout(QUIET_PERIOD) {
+                Ok(event_result) => event_result,
+                Err(RecvTimeoutError::Timeout) => {
+                    batch.refresh(&options)?;
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        };
+
         match event_result {
             Ok(event) => {
                 debug!("Filesystem event: {event:?}");
 
-                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
-                    continue;
-                }
+                type Handler = fn(
+                    &Path,
+                    &[DistributionAlias],
+                    &WatchedDirectories,
+                    &mut Batch,
+                ) -> Option<bool>;
+                let handler: Handler = match event.kind {
+                    EventKind::Create(_) | EventKind::Modify(_) => handle_file_event,
+                    EventKind::Remove(_) if options.watch_removals => handle_removal_event,
+                    _ => continue,
+                };
 
-                for path in &event.paths {
-                    if let Some(handled) = handle_file_event(path, target_releases) {
-                        if handled {
+                {
+                    let _lock = options.lock()?;
+                    for path in &event.paths {
+                        if handler(path, target_releases, &options.directories, &mut batch)
+                            == Some(true)
+                        {
                             events_processed += 1;
                         }
                     }
                 }
 
-                if let Some(max) = max_events {
-                    if events_processed >= max {
-                        info!("Reached max events ({max}), stopping watcher");
-                        return Ok(());
-                    }
+                if let Some(max) = options.max_events
+                    && events_processed >= max
+                {
+                    info!("Reached max events ({max}), stopping watcher");
+                    batch.refresh(&options)?;
+                    return Ok(());
                 }
             }
             Err(e) => {
@@ -103,6 +276,44 @@ pub fn watch_directory(
         }
     }
 
+    batch.refresh(&options)?;
+    Ok(())
+}
+
+/// Imports the files in the watched directories in name order, as if each of them had just
+/// been created
+fn import_existing_files(
+    root: &Path,
+    target_releases: &[DistributionAlias],
+    options: &WatchOptions,
+) -> Result<(), BellhopError> {
+    info!("Importing the files in {} once", root.display());
+    let mut paths = Vec::new();
+    for subdir in options.directories.names() {
+        for entry in fs::read_dir(root.join(subdir))? {
+            paths.push(entry?.path());
+        }
+    }
+    paths.sort();
+
+    let mut events_processed = 0;
+    let mut batch = Batch::default();
+    for path in &paths {
+        if let Some(max) = options.max_events
+            && events_processed >= max
+        {
+            info!("Reached max events ({max}), stopping");
+            break;
+        }
+        let _lock = options.lock()?;
+        if handle_file_event(path, target_releases, &options.directories, &mut batch) == Some(true)
+        {
+            events_processed += 1;
+        }
+    }
+    info!("Imported {events_processed} files");
+
+    batch.refresh(options)?;
     Ok(())
 }
 
@@ -120,36 +331,55 @@ pub fn releases_for_project<'a>(
         .collect()
 }
 
-fn handle_file_event(path: &Path, target_releases: &[DistributionAlias]) -> Option<bool> {
-    if !path.is_file() {
-        return None;
-    }
-
-    let extension = path.extension()?.to_str()?;
+/// The project a `.deb` path in a watched directory belongs to and the distributions that
+/// apply to it, `Err` with the event outcome when the path is to be ignored
+fn watched_package(
+    path: &Path,
+    target_releases: &[DistributionAlias],
+    directories: &WatchedDirectories,
+) -> Result<(Project, Vec<DistributionAlias>), Option<bool>> {
+    let extension = path.extension().and_then(|e| e.to_str()).ok_or(None)?;
     if extension != "deb" {
         warn!("Ignoring non-.deb file: {}", path.display());
-        return Some(false);
+        return Err(Some(false));
     }
 
-    let parent = path.parent()?;
-    let dir_name = parent.file_name()?.to_str()?;
+    let dir_name = path
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|n| n.to_str())
+        .ok_or(None)?;
 
-    let project = match project_for_directory(dir_name) {
-        Some(p) => p,
-        None => {
-            warn!(
-                "Ignoring file in unknown subdirectory '{}': {}",
-                dir_name,
-                path.display()
-            );
-            return Some(false);
-        }
+    let Some(project) = directories.project_for(dir_name) else {
+        warn!(
+            "Ignoring file in unknown subdirectory '{}': {}",
+            dir_name,
+            path.display()
+        );
+        return Err(Some(false));
     };
 
-    let applicable: Vec<DistributionAlias> = releases_for_project(&project, target_releases)
+    let applicable = releases_for_project(&project, target_releases)
         .into_iter()
         .cloned()
         .collect();
+    Ok((project, applicable))
+}
+
+fn handle_file_event(
+    path: &Path,
+    target_releases: &[DistributionAlias],
+    directories: &WatchedDirectories,
+    batch: &mut Batch,
+) -> Option<bool> {
+    if !path.is_file() {
+        return None;
+    }
+
+    let (project, applicable) = match watched_package(path, target_releases, directories) {
+        Ok(package) => package,
+        Err(outcome) => return outcome,
+    };
 
     let filename = path.file_name()?.to_str()?;
     info!(
@@ -162,6 +392,7 @@ fn handle_file_event(path: &Path, target_releases: &[DistributionAlias]) -> Opti
     match aptly::add_single_package_no_snapshot(&project, path, &applicable) {
         Ok(()) => {
             info!("Successfully imported {filename}");
+            batch.touch(project, &applicable);
             Some(true)
         }
         Err(e) => {
@@ -170,3 +401,42 @@ fn handle_file_event(path: &Path, target_releases: &[DistributionAlias]) -> Opti
         }
     }
 }
+
+/// The file is gone, so the package name, version and architecture come from its file name
+fn handle_removal_event(
+    path: &Path,
+    target_releases: &[DistributionAlias],
+    directories: &WatchedDirectories,
+    batch: &mut Batch,
+) -> Option<bool> {
+    let (project, applicable) = match watched_package(path, target_releases, directories) {
+        Ok(package) => package,
+        Err(outcome) => return outcome,
+    };
+
+    let filename = path.file_name()?.to_str()?;
+    let (name, version, arch) = match archive::extract_package_key_from_filename(filename) {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Ignoring removal of {filename}: {e}");
+            return Some(false);
+        }
+    };
+    info!(
+        "Removing {name} {version} ({arch}) from {} for {} distributions",
+        project,
+        applicable.len()
+    );
+
+    match aptly::remove_package_key_no_snapshot(&project, &name, &version, &arch, &applicable) {
+        Ok(()) => {
+            info!("Successfully removed {name} {version} ({arch})");
+            batch.touch(project, &applicable);
+            Some(true)
+        }
+        Err(e) => {
+            error!("Failed to remove {name} {version} ({arch}): {e}");
+            Some(false)
+        }
+    }
+}
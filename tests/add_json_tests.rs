@@ -0,0 +1,164 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use serde_json::Value;
+use std::error::Error;
+use std::fs::File;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use tar::Builder;
+use tempfile::TempDir;
+use test_helpers::*;
+
+const DEBS: [&str; 2] = [
+    "rabbitmq-server_4.1.3-1_all.deb",
+    "rabbitmq-server_4.1.4-1_all.deb",
+];
+
+fn create_tar_archive_with_debs() -> Result<(PathBuf, TempDir), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    for deb in DEBS {
+        builder.append_path_with_name(test_package_path(deb), deb)?;
+    }
+    builder.finish()?;
+    Ok((archive_path, temp_dir))
+}
+
+/// The summary of the run report on the last line of stderr, stdout is left empty
+fn summary(output: &Output) -> Result<Value, Box<dyn Error>> {
+    assert!(
+        output.stdout.is_empty(),
+        "nothing should be printed on stdout"
+    );
+    let stderr = String::from_utf8(output.stderr.clone())?;
+    let last_line = stderr.lines().last().ok_or("stderr should not be empty")?;
+    let report: Value = serde_json::from_str(last_line)?;
+    Ok(report["summary"].clone())
+}
+
+fn versions(summary: &Value) -> Vec<&str> {
+    let mut versions: Vec<&str> = summary["packages"]
+        .as_array()
+        .expect("packages should be an array")
+        .iter()
+        .filter_map(|package| package["version"].as_str())
+        .collect();
+    versions.sort();
+    versions
+}
+
+#[test]
+fn test_add_reports_a_json_summary() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let (archive_path, _temp_dir) = create_tar_archive_with_debs()?;
+
+    let output = stub
+        .bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm,noble"])
+        .args(["--suffix", "v1", "--json", "-p"])
+        .arg(&archive_path)
+        .output()?;
+    assert!(output.status.success());
+
+    let summary = summary(&output)?;
+    assert_eq!(versions(&summary), ["4.1.3-1", "4.1.4-1"]);
+    assert_eq!(
+        summary["distributions"],
+        serde_json::json!(["bookworm", "noble"])
+    );
+    assert_eq!(
+        summary["snapshots"],
+        serde_json::json!([
+            "snap-rabbitmq-server-bookworm-v1",
+            "snap-rabbitmq-server-noble-v1"
+        ])
+    );
+    for package in summary["packages"].as_array().unwrap() {
+        assert_eq!(package["name"], "rabbitmq-server");
+        assert_eq!(
+            package["repos"],
+            serde_json::json!([
+                "repo-rabbitmq-server-bookworm",
+                "repo-rabbitmq-server-noble"
+            ])
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_add_json_summary_without_snapshots() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    let output = stub
+        .bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--no-snapshot"])
+        .args(["--json", "-p"])
+        .arg(test_package_path(DEBS[0]))
+        .output()?;
+    assert!(output.status.success());
+
+    let summary = summary(&output)?;
+    assert_eq!(versions(&summary), ["4.1.3-1"]);
+    assert_eq!(summary["distributions"], serde_json::json!(["bookworm"]));
+    assert_eq!(summary["snapshots"], serde_json::json!([]));
+    Ok(())
+}
+
+#[test]
+fn test_add_prints_nothing_on_stdout_without_json() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(test_package_path(DEBS[0]))
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn test_add_archive_json_summary_with_aptly() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+    ctx.create_repo("repo-rabbitmq-server-jammy")?;
+    let (archive_path, _temp_dir) = create_tar_archive_with_debs()?;
+
+    let output = Command::new(cargo::cargo_bin!("bellhop"))
+        .env("APTLY_CONFIG", ctx.config_path.to_str().unwrap())
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm,jammy"])
+        .args(["--suffix", "v1", "--json", "-p"])
+        .arg(&archive_path)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let summary = summary(&output)?;
+    assert_eq!(versions(&summary), ["4.1.3-1", "4.1.4-1"]);
+    assert_eq!(
+        summary["distributions"],
+        serde_json::json!(["bookworm", "jammy"])
+    );
+    assert!(ctx.snapshot_exists("snap-rabbitmq-server-jammy-v1")?);
+    Ok(())
+}
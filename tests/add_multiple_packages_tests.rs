@@ -0,0 +1,172 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+#[test]
+fn test_add_multiple_paths_takes_one_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.4-1_all.deb"))
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 2, "Unexpected repo adds: {adds:?}");
+    assert!(adds[0].ends_with("rabbitmq-server_4.1.3-1_all.deb"));
+    assert!(adds[1].ends_with("rabbitmq-server_4.1.4-1_all.deb"));
+    assert_eq!(
+        stub.invocations_starting_with("snapshot create")?,
+        [
+            "snapshot create snap-rabbitmq-server-bookworm-v1 from repo repo-rabbitmq-server-bookworm"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_accepts_several_values_after_one_flag() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1", "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .arg(test_package_path("rabbitmq-server_4.1.4-1_all.deb"))
+        .assert()
+        .success();
+
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_add_multiple_paths_adds_nothing_if_one_is_missing() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .arg("/nonexistent/rabbitmq-server_4.1.4-1_all.deb")
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "/nonexistent/rabbitmq-server_4.1.4-1_all.deb",
+        ));
+
+    assert!(stub.invocations_starting_with("repo add")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_add_multiple_paths_to_aptly() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(["rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.4-1_all.deb"));
+    cmd.assert().success();
+
+    assert_eq!(
+        ctx.list_snapshots("snap-rabbitmq-server-bookworm")?,
+        ["snap-rabbitmq-server-bookworm-v1"]
+    );
+    assert_eq!(
+        ctx.snapshot_package_count("snap-rabbitmq-server-bookworm-v1")?,
+        2
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_reads_paths_from_stdin() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let paths = format!(
+        "{}\n{}\n",
+        test_package_path("rabbitmq-server_4.1.3-1_all.deb").display(),
+        test_package_path("rabbitmq-server_4.1.4-1_all.deb").display()
+    );
+
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "--from-stdin",
+    ]);
+    assert_cmd::Command::from_std(cmd)
+        .write_stdin(paths)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 2, "Unexpected repo adds: {adds:?}");
+    assert!(adds[0].ends_with("rabbitmq-server_4.1.3-1_all.deb"));
+    assert!(adds[1].ends_with("rabbitmq-server_4.1.4-1_all.deb"));
+    assert_eq!(stub.invocations_starting_with("snapshot create")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_add_from_empty_stdin_fails() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    let mut cmd = stub.bellhop_command();
+    cmd.args(["rabbitmq", "deb", "add", "-d", "bookworm", "--from-stdin"]);
+    assert_cmd::Command::from_std(cmd)
+        .write_stdin("\n")
+        .assert()
+        .code(64)
+        .stderr(output_includes("package_file_path"));
+
+    assert!(stub.invocations_starting_with("repo add")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_from_stdin_conflicts_with_package_file_path() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--from-stdin",
+        "-p",
+        "rabbitmq-server_4.1.3-1_all.deb",
+    ])
+    .code(64)
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
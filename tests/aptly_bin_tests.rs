@@ -0,0 +1,74 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use bellhop::aptly::is_aptly_available;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+#[test]
+fn test_aptly_bin_is_used_for_all_aptly_commands() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_BIN", stub.bin_path());
+    cmd.env_remove("APTLY_CONFIG");
+    cmd.args([
+        "rabbitmq", "snapshot", "list", "-d", "bookworm", "--suffix", "v1",
+    ]);
+    cmd.assert().success();
+
+    assert_eq!(
+        stub.invocations()?,
+        vec![
+            "version",
+            "snapshot show -with-packages snap-rabbitmq-server-bookworm-v1"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_missing_aptly_bin_is_reported() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_BIN", "/nonexistent/bin/aptly");
+    cmd.args(["rabbitmq", "snapshot", "list", "-d", "bookworm"]);
+    cmd.assert()
+        .failure()
+        .code(70)
+        .stderr(output_includes("aptly executable not found"));
+    Ok(())
+}
+
+#[test]
+fn test_availability_is_cached_per_binary() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let present = stub.bin_path().to_string_lossy().into_owned();
+    let absent = "/nonexistent/bin/aptly";
+
+    assert!(is_aptly_available(&present));
+    assert!(!is_aptly_available(absent));
+    // a cached answer for one binary does not leak into the other
+    assert!(is_aptly_available(&present));
+    assert!(!is_aptly_available(absent));
+    assert_eq!(
+        stub.invocations()?,
+        vec!["version"],
+        "The result for the stub should be cached"
+    );
+    Ok(())
+}
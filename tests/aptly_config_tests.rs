@@ -0,0 +1,93 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs;
+use test_helpers::*;
+
+#[test]
+fn test_config_flag_is_passed_to_every_aptly_call() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let config_path = stub.temp_dir.path().join("aptly.conf");
+    fs::write(&config_path, "{}")?;
+
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "--config",
+        config_path.to_str().unwrap(),
+        "rabbitmq",
+        "deb",
+        "publish",
+        "-d",
+        "bookworm",
+    ]);
+    cmd.assert().success();
+
+    let expected = format!("-config={}", config_path.display());
+    let invocations = stub.invocations()?;
+    // `aptly version` is the availability check, which does not need a configuration
+    let aptly_calls: Vec<&String> = invocations.iter().filter(|i| *i != "version").collect();
+    assert!(!aptly_calls.is_empty());
+    assert!(aptly_calls.iter().all(|i| i.starts_with(&expected)));
+    Ok(())
+}
+
+#[test]
+fn test_config_flag_takes_precedence_over_the_env_var() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let config_path = stub.temp_dir.path().join("aptly.conf");
+    fs::write(&config_path, "{}")?;
+
+    let mut cmd = stub.bellhop_command();
+    cmd.env("APTLY_CONFIG", "/etc/aptly-from-env.conf");
+    cmd.args([
+        "rabbitmq",
+        "snapshot",
+        "list",
+        "-d",
+        "bookworm",
+        "--config",
+        config_path.to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations
+            .iter()
+            .any(|i| i.starts_with(&format!("-config={} snapshot show", config_path.display())))
+    );
+    assert!(!invocations.iter().any(|i| i.contains("aptly-from-env")));
+    Ok(())
+}
+
+#[test]
+fn test_missing_config_file_is_reported() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "--config",
+        "/nonexistent/aptly.conf",
+        "rabbitmq",
+        "snapshot",
+        "list",
+        "-d",
+        "bookworm",
+    ])
+    .code(65)
+    .stderr(output_includes(
+        "aptly configuration file does not exist at /nonexistent/aptly.conf",
+    ));
+    Ok(())
+}
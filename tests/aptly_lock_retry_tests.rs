@@ -0,0 +1,201 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use test_helpers::*;
+
+/// `publish list` fails with a lock error the given number of times, then succeeds
+fn stub_locked_for(failures: usize) -> Result<AptlyStub, Box<dyn Error>> {
+    AptlyStub::with_script(&format!(
+        r#"if [ "$1 $2" = "publish list" ]; then
+  attempts="$(dirname "$0")/attempts"
+  echo x >> "$attempts"
+  if [ "$(wc -l < "$attempts")" -le {failures} ]; then
+    echo 'ERROR: unable to acquire database lock' >&2
+    exit 1
+  fi
+fi"#
+    ))
+}
+
+fn publish_list_calls(stub: &AptlyStub) -> Result<usize, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .iter()
+        .filter(|line| line.starts_with("publish list"))
+        .count())
+}
+
+#[test]
+fn test_lock_failures_are_retried() -> Result<(), Box<dyn Error>> {
+    let stub = stub_locked_for(1)?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(publish_list_calls(&stub)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_lock_retries_give_up_eventually() -> Result<(), Box<dyn Error>> {
+    let stub = stub_locked_for(10)?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .args(["--aptly-lock-retries", "1"])
+        .assert()
+        .failure()
+        .stderr(output_includes("unable to acquire database lock"));
+
+    assert_eq!(publish_list_calls(&stub)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_lock_retries_can_be_disabled() -> Result<(), Box<dyn Error>> {
+    let stub = stub_locked_for(1)?;
+
+    stub.bellhop_command()
+        .args(["--aptly-lock-retries", "0"])
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .failure();
+
+    assert_eq!(publish_list_calls(&stub)?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_other_failures_are_not_retried() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo 'ERROR: unable to load config' >&2; exit 1; fi",
+    )?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .failure()
+        .stderr(output_includes("unable to load config"));
+
+    assert_eq!(publish_list_calls(&stub)?, 1);
+    Ok(())
+}
+
+/// `publish list` exits with the given code the given number of times, then succeeds
+fn stub_exiting_with(code: u8, failures: usize) -> Result<AptlyStub, Box<dyn Error>> {
+    AptlyStub::with_script(&format!(
+        r#"if [ "$1 $2" = "publish list" ]; then
+  attempts="$(dirname "$0")/attempts"
+  echo x >> "$attempts"
+  if [ "$(wc -l < "$attempts")" -le {failures} ]; then
+    echo 'ERROR: temporary file contention' >&2
+    exit {code}
+  fi
+fi"#
+    ))
+}
+
+#[test]
+fn test_configured_exit_codes_are_retried() -> Result<(), Box<dyn Error>> {
+    let stub = stub_exiting_with(2, 2)?;
+
+    stub.bellhop_command()
+        .args(["--retry-aptly-exit-codes", "1,2"])
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .success()
+        .stderr(output_includes("aptly exited with code 2, retrying"));
+
+    assert_eq!(publish_list_calls(&stub)?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_exit_codes_are_not_retried_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = stub_exiting_with(2, 1)?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .failure()
+        .stderr(output_includes("temporary file contention"));
+
+    assert_eq!(publish_list_calls(&stub)?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_other_exit_codes_are_not_retried() -> Result<(), Box<dyn Error>> {
+    let stub = stub_exiting_with(3, 1)?;
+
+    stub.bellhop_command()
+        .args(["--retry-aptly-exit-codes", "1,2"])
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .failure();
+
+    assert_eq!(publish_list_calls(&stub)?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_exit_code_retries_share_the_retry_count() -> Result<(), Box<dyn Error>> {
+    let stub = stub_exiting_with(2, 10)?;
+
+    stub.bellhop_command()
+        .args(["--retry-aptly-exit-codes", "2", "--aptly-lock-retries", "1"])
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .failure()
+        .stderr(output_includes("temporary file contention"));
+
+    assert_eq!(publish_list_calls(&stub)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_exit_code_zero_cannot_be_retried() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["--retry-aptly-exit-codes", "0,1"])
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .failure()
+        .code(64);
+    Ok(())
+}
@@ -0,0 +1,97 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use test_helpers::*;
+
+#[test]
+fn test_add_with_arch_map_adds_a_rewritten_copy() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--arch-map",
+            "all=amd64",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1);
+    assert!(
+        adds[0].ends_with("rabbitmq-server_4.1.3-1_amd64.deb"),
+        "{adds:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_with_a_non_matching_arch_map_adds_the_original() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let deb_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--arch-map",
+            "arm64=amd64",
+            "-p",
+        ])
+        .arg(&deb_path)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1);
+    assert!(
+        adds[0].ends_with(&deb_path.display().to_string()),
+        "{adds:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_rejects_a_malformed_arch_map() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--arch-map",
+            "all",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .failure()
+        .code(64);
+    Ok(())
+}
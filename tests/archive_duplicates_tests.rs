@@ -0,0 +1,53 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs::File;
+use tar::Builder;
+use tempfile::TempDir;
+use test_helpers::*;
+
+const DEB: &str = "rabbitmq-server_4.1.7-1_all.deb";
+
+#[test]
+fn test_duplicate_debs_in_an_archive_are_added_once() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    builder.append_path_with_name(test_package_path(DEB), DEB)?;
+    builder.append_path_with_name(test_package_path(DEB), format!("nested/{DEB}"))?;
+    builder.finish()?;
+
+    let stub = AptlyStub::new()?;
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(&archive_path)
+        .assert()
+        .success()
+        .stderr(output_includes("Skipping duplicate package"));
+
+    let adds: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo add"))
+        .collect();
+    assert_eq!(adds.len(), 1);
+    assert!(
+        !adds[0].contains("/nested/"),
+        "The top level copy should be kept"
+    );
+    Ok(())
+}
@@ -112,7 +112,7 @@ fn test_remove_preserves_existing_version_flag() -> Result<(), Box<dyn Error>> {
     let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
     cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
     cmd.args([
-        "rabbitmq", "deb", "remove", "-v", "4.1.3-1", "-d", "bookworm",
+        "rabbitmq", "deb", "remove", "-V", "4.1.3-1", "-d", "bookworm",
     ]);
     cmd.assert().success();
 
@@ -137,7 +137,7 @@ fn test_remove_version_and_path_conflict() -> Result<(), Box<dyn Error>> {
         "rabbitmq",
         "deb",
         "remove",
-        "-v",
+        "-V",
         "4.1.3-1",
         "-p",
         package_path.to_str().unwrap(),
@@ -0,0 +1,111 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::aptly;
+use bellhop::common::Project;
+use bellhop::deb::DistributionAlias;
+use bellhop::errors::BellhopError;
+use std::error::Error;
+use test_helpers::*;
+
+// The channel is process-wide, so this module must not contain other tests that set it
+#[test]
+fn test_channel_flows_into_all_derived_names() {
+    aptly::set_channel("nightly").expect("channel should be valid");
+
+    assert_eq!(
+        aptly::repo_name(&Project::RabbitMQ, &DistributionAlias::Bookworm),
+        "repo-rabbitmq-server-nightly-bookworm"
+    );
+    assert_eq!(
+        aptly::snapshot_name_with_suffix(&Project::Erlang, &DistributionAlias::Noble, "16-Dec-25"),
+        "snap-rabbitmq-erlang-nightly-noble-16-Dec-25"
+    );
+    assert_eq!(
+        aptly::rel_path_with_prefix(
+            &Project::CliTools,
+            &DistributionAlias::Trixie,
+            DistributionAlias::Trixie.family()
+        ),
+        "rabbitmq-cli-nightly/debian/trixie"
+    );
+}
+
+#[test]
+fn test_invalid_channels_are_rejected() {
+    for channel in ["", "night/ly", "night ly", "../nightly"] {
+        assert!(matches!(
+            aptly::set_channel(channel),
+            Err(BellhopError::InvalidChannel { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_commands_use_the_channel() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "--channel", "nightly", "deb", "add"])
+        .args(["-d", "bookworm", "--suffix", "v1", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .args(["--channel", "nightly"])
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    for expected in [
+        "repo add -architectures=amd64,arm64,armel,armhf,i386 repo-rabbitmq-server-nightly-bookworm ",
+        "snapshot create snap-rabbitmq-server-nightly-bookworm-v1 from repo repo-rabbitmq-server-nightly-bookworm",
+    ] {
+        assert!(
+            invocations.iter().any(|line| line.starts_with(expected)),
+            "Expected '{expected}' in {invocations:?}"
+        );
+    }
+    assert!(
+        invocations.iter().any(|line| {
+            line.starts_with("publish snapshot")
+            && line.ends_with(
+                "snap-rabbitmq-server-nightly-bookworm-v1 rabbitmq-server-nightly/debian/bookworm"
+            )
+        }),
+        "Expected a nightly publication in {invocations:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_invalid_channel_fails_with_data_error() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "--channel",
+        "night/ly",
+        "snapshot",
+        "list",
+        "-d",
+        "bookworm",
+    ])
+    .code(65)
+    .stderr(output_includes("Invalid channel"));
+    Ok(())
+}
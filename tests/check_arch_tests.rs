@@ -0,0 +1,165 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tar::{Builder, Header};
+use tempfile::TempDir;
+use test_helpers::*;
+
+#[test]
+fn test_check_arch_rejects_an_incompatible_package() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let deb_path = temp_dir.path().join("rabbitmqadmin_2.0.0-1_arm64.deb");
+    fs::write(&deb_path, minimal_deb())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["cli-tools", "deb", "add", "-d", "bookworm", "--check-arch", "-p"])
+        .arg(&deb_path)
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "rabbitmqadmin_2.0.0-1_arm64.deb is built for arm64, which is not one of the repository architectures (amd64)",
+        ));
+
+    assert!(
+        stub.invocations_starting_with("repo add")?.is_empty(),
+        "Nothing should be added"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_check_arch_accepts_a_compatible_package() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let deb_path = temp_dir.path().join("rabbitmqadmin_2.0.0-1_amd64.deb");
+    fs::write(&deb_path, minimal_deb())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "cli-tools",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--check-arch",
+            "-p",
+        ])
+        .arg(&deb_path)
+        .assert()
+        .success();
+
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_architecture_is_not_checked_by_default() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let deb_path = temp_dir.path().join("rabbitmqadmin_2.0.0-1_arm64.deb");
+    fs::write(&deb_path, minimal_deb())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["cli-tools", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(&deb_path)
+        .assert()
+        .success();
+
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 1);
+    Ok(())
+}
+
+fn mixed_architecture_tarball(dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let path = dir.join("rabbitmqadmin-2.0.0.tar");
+    let mut builder = Builder::new(File::create(&path)?);
+    for name in [
+        "rabbitmqadmin_2.0.0-1_amd64.deb",
+        "rabbitmqadmin_2.0.0-1_arm64.deb",
+        "rabbitmqadmin-doc_2.0.0-1_all.deb",
+    ] {
+        let data = minimal_deb();
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, &data[..])?;
+    }
+    builder.finish()?;
+    Ok(path)
+}
+
+#[test]
+fn test_only_arch_skips_packages_for_other_architectures() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive_path = mixed_architecture_tarball(temp_dir.path())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "cli-tools",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--only-arch",
+            "amd64",
+            "-p",
+        ])
+        .arg(&archive_path)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 2, "Unexpected repo adds: {adds:?}");
+    assert!(adds.iter().any(|line| line.ends_with("_amd64.deb")));
+    assert!(adds.iter().any(|line| line.ends_with("_all.deb")));
+    assert!(!adds.iter().any(|line| line.ends_with("_arm64.deb")));
+    Ok(())
+}
+
+#[test]
+fn test_only_arch_fails_when_nothing_is_left() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let deb_path = temp_dir.path().join("rabbitmqadmin_2.0.0-1_arm64.deb");
+    fs::write(&deb_path, minimal_deb())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "cli-tools",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--only-arch",
+            "amd64,i386",
+            "-p",
+        ])
+        .arg(&deb_path)
+        .assert()
+        .code(65)
+        .stderr(output_includes("No .deb files found in archive"));
+
+    assert!(
+        stub.invocations_starting_with("repo add")?.is_empty(),
+        "Nothing should be added"
+    );
+    Ok(())
+}
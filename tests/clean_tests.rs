@@ -0,0 +1,117 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const CLEANUP_OUTPUT: &str = "Loading mirrors, local repos, snapshots and published repos...
+Loading list of all packages...
+Deleting unreferenced packages (1)...
+Building list of files referenced by packages...
+Building list of files in package pool...
+Deleting unreferenced files (2)...
+Disk space freed: 12.50 MiB...
+Compacting database...";
+
+#[test]
+fn test_clean_reports_the_reclaimed_space() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"db cleanup\" ]; then echo '{CLEANUP_OUTPUT}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args(["clean", "--yes"])
+        .assert()
+        .success()
+        .stdout(output_includes(
+            "Deleted 1 packages and 2 files, freed 12.50 MiB",
+        ));
+
+    let invocations = stub.invocations()?;
+    assert!(invocations.contains(&"db cleanup".to_string()));
+    assert!(!invocations.contains(&"db recover".to_string()));
+    Ok(())
+}
+
+#[test]
+fn test_clean_recovers_the_database_first_on_request() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["clean", "--recover", "--yes"])
+        .assert()
+        .success();
+
+    let db_commands: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("db "))
+        .collect();
+    assert_eq!(db_commands, ["db recover", "db cleanup"]);
+    Ok(())
+}
+
+#[test]
+fn test_clean_requires_confirmation() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .arg("clean")
+        .assert()
+        .code(64)
+        .stderr(output_includes("not confirmed, pass --yes"));
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("db "))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_clean_after_dropping_a_snapshot() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1", "-p",
+    ])
+    .arg(&package_path);
+    cmd.assert().success();
+
+    ctx.remove_packages("repo-rabbitmq-server-bookworm", "rabbitmq-server")?;
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "snapshot", "delete", "-d", "bookworm", "--suffix", "v1",
+    ]);
+    cmd.assert().success();
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(["clean", "--yes"]);
+    cmd.assert()
+        .success()
+        .stdout(output_includes("Deleted 1 packages"));
+    Ok(())
+}
@@ -106,7 +106,7 @@ fn test_cli_tools_remove_package_by_version() -> Result<(), Box<dyn Error>> {
         "cli-tools",
         "deb",
         "remove",
-        "-v",
+        "-V",
         "4.1.3-1",
         "-d",
         "bookworm",
@@ -173,3 +173,65 @@ fn test_cli_tools_all_flag_uses_all_distributions() -> Result<(), Box<dyn Error>
 
     Ok(())
 }
+
+#[test]
+fn test_cli_tools_commands_dispatch_to_cli_tools_repositories() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "cli-tools",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--suffix",
+            "v1",
+        ])
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+    stub.bellhop_command()
+        .args([
+            "cli-tools",
+            "snapshot",
+            "take",
+            "-d",
+            "noble",
+            "--suffix",
+            "v2",
+        ])
+        .assert()
+        .success();
+    stub.bellhop_command()
+        .args([
+            "cli-tools",
+            "deb",
+            "publish",
+            "-d",
+            "noble",
+            "--suffix",
+            "v2",
+        ])
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    for expected in [
+        "repo add -architectures=amd64 repo-rabbitmq-cli-bookworm ",
+        "snapshot create snap-rabbitmq-cli-bookworm-v1 from repo repo-rabbitmq-cli-bookworm",
+        "snapshot create snap-rabbitmq-cli-noble-v2 from repo repo-rabbitmq-cli-noble",
+    ] {
+        assert!(
+            invocations.iter().any(|line| line.starts_with(expected)),
+            "Expected '{expected}' in {invocations:?}"
+        );
+    }
+    assert!(
+        invocations.iter().any(|line| line.starts_with("publish ")
+            && line.contains("snap-rabbitmq-cli-noble-v2 rabbitmq-cli/ubuntu/noble")),
+        "Expected a cli-tools publication in {invocations:?}"
+    );
+    Ok(())
+}
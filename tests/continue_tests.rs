@@ -0,0 +1,150 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const MISSING_JAMMY_REPO_SCRIPT: &str = r#"if [ "$1 $2" = "repo add" ]; then
+  case "$4" in
+    *jammy*)
+      echo "ERROR: unable to add: local repo with name $4 not found" >&2
+      exit 1
+      ;;
+  esac
+fi
+"#;
+
+fn add(stub: &AptlyStub, extra: &[&str]) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.args(["rabbitmq", "deb", "add", "-d", "bookworm,jammy,noble"])
+        .args(["--suffix", "v1"])
+        .args(extra)
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"));
+    cmd
+}
+
+#[test]
+fn test_continue_applies_the_other_distributions() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(MISSING_JAMMY_REPO_SCRIPT)?;
+
+    add(&stub, &["--continue"])
+        .assert()
+        .failure()
+        .code(70)
+        .stderr(output_includes("1 distribution(s) failed"))
+        .stderr(output_includes("jammy: aptly command failed with status 1"));
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(
+        adds.len(),
+        3,
+        "every distribution should be attempted: {adds:?}"
+    );
+
+    let snapshots = stub.invocations_starting_with("snapshot create")?;
+    assert_eq!(
+        snapshots,
+        vec![
+            "snapshot create snap-rabbitmq-server-bookworm-v1 from repo repo-rabbitmq-server-bookworm",
+            "snapshot create snap-rabbitmq-server-noble-v1 from repo repo-rabbitmq-server-noble",
+        ],
+        "the failed distribution should not be snapshotted"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_first_error_stops_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(MISSING_JAMMY_REPO_SCRIPT)?;
+
+    add(&stub, &[])
+        .assert()
+        .failure()
+        .stderr(output_includes("repo-rabbitmq-server-jammy not found"));
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 2, "noble should not be attempted: {adds:?}");
+    assert!(
+        stub.invocations_starting_with("snapshot create")?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_continue_and_exit_on_first_error_conflict() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    add(&stub, &["--continue", "--exit-on-first-error"])
+        .assert()
+        .failure()
+        .stderr(output_includes("cannot be used with"));
+
+    Ok(())
+}
+
+#[test]
+fn test_continue_with_a_missing_repository() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+    ctx.create_repo("repo-rabbitmq-server-noble")?;
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(["rabbitmq", "deb", "add", "-d", "bookworm,jammy,noble"])
+        .args(["--suffix", "v1", "--continue", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"));
+    cmd.assert()
+        .failure()
+        .stderr(output_includes("1 distribution(s) failed"));
+
+    for rel in ["bookworm", "noble"] {
+        assert!(ctx.snapshot_package_exists(
+            &format!("snap-rabbitmq-server-{rel}-v1"),
+            "rabbitmq-server (= 4.1.3-1)"
+        )?);
+    }
+    assert!(!ctx.snapshot_exists("snap-rabbitmq-server-jammy-v1")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_continue_applies_to_publishing() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        r#"if [ "$1 $2" = "publish snapshot" ] && [ "$4" = "jammy" ]; then
+  echo "ERROR: unable to publish: jammy is broken" >&2
+  exit 1
+fi
+"#,
+    )?;
+
+    add(&stub, &["--continue", "--publish", "--no-gpg-check"])
+        .assert()
+        .failure()
+        .code(70)
+        .stderr(output_includes("jammy"));
+
+    let publishes = stub.invocations_starting_with("publish snapshot")?;
+    assert_eq!(publishes.len(), 3, "{publishes:?}");
+    assert!(publishes.last().is_some_and(|line| line.contains("noble")));
+    Ok(())
+}
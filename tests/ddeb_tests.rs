@@ -0,0 +1,80 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_helpers::*;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+fn archive_with_debug_symbols(dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let path = dir.join("packages.zip");
+    let mut zip = ZipWriter::new(File::create(&path)?);
+    for name in [
+        "erlang-base_27.3-1_amd64.deb",
+        "erlang-base-dbgsym_27.3-1_amd64.ddeb",
+    ] {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(&minimal_deb())?;
+    }
+    zip.finish()?;
+    Ok(path)
+}
+
+#[test]
+fn test_ddeb_is_added_with_include_ddeb() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive = archive_with_debug_symbols(temp_dir.path())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["erlang", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .arg("--include-ddeb")
+        .arg("-p")
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 2, "Unexpected repo adds: {adds:?}");
+    assert!(
+        adds.iter()
+            .any(|line| line.ends_with("erlang-base-dbgsym_27.3-1_amd64.ddeb"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ddeb_is_ignored_by_default() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive = archive_with_debug_symbols(temp_dir.path())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["erlang", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .arg("-p")
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1, "Unexpected repo adds: {adds:?}");
+    assert!(adds[0].ends_with("erlang-base_27.3-1_amd64.deb"));
+    Ok(())
+}
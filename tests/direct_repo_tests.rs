@@ -0,0 +1,116 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const LISTS_CUSTOM_REPO: &str = "if [ \"$1 $2\" = \"repo list\" ]; then echo 'my-custom-repo'; fi";
+
+#[test]
+fn test_add_to_a_repo_given_by_name() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(LISTS_CUSTOM_REPO)?;
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "--repo", "my-custom-repo", "-p"])
+        .arg(&package_path)
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    assert!(invocations.iter().any(|line| line.starts_with("repo add ")
+        && line.contains(" my-custom-repo ")
+        && line.ends_with("rabbitmq-server_4.1.3-1_all.deb")));
+    assert!(
+        !invocations.iter().any(|line| line.starts_with("snapshot")),
+        "No snapshots should be taken: {invocations:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_remove_from_a_repo_given_by_name() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(LISTS_CUSTOM_REPO)?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "remove", "--repo", "my-custom-repo"])
+        .args(["-V", "4.1.3-1"])
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations.contains(&"repo remove my-custom-repo rabbitmq-server (= 4.1.3-1)".to_string())
+    );
+    assert!(!invocations.iter().any(|line| line.starts_with("snapshot")));
+    Ok(())
+}
+
+#[test]
+fn test_a_missing_repo_is_reported() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(LISTS_CUSTOM_REPO)?;
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "--repo", "another-repo", "-p"])
+        .arg(&package_path)
+        .assert()
+        .code(65)
+        .stderr(output_includes("Repository 'another-repo' does not exist"));
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo add"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_repo_conflicts_with_distributions() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-p", "x.deb", "-d", "bookworm"])
+        .args(["--repo", "my-custom-repo"])
+        .assert()
+        .code(64)
+        .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_add_a_deb_to_an_arbitrarily_named_repo() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("my-custom-repo")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(["rabbitmq", "deb", "add", "--repo", "my-custom-repo", "-p"])
+        .arg(&package_path);
+    cmd.assert().success();
+
+    assert!(
+        ctx.package_exists("my-custom-repo", "rabbitmq-server (= 4.1.3-1)")?,
+        "The package should be in the custom repository"
+    );
+    assert!(ctx.list_snapshots("snap-")?.is_empty());
+    Ok(())
+}
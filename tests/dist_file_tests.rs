@@ -0,0 +1,129 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::cli;
+use bellhop::deb::DistributionAlias;
+use bellhop::errors::BellhopError;
+use std::error::Error;
+use std::fs;
+use tempfile::TempDir;
+use test_helpers::*;
+
+#[test]
+fn test_dist_file_is_parsed_into_aliases() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("distributions");
+    fs::write(&path, "bookworm\njammy\n")?;
+
+    assert_eq!(
+        cli::read_distribution_file(&path)?,
+        vec![DistributionAlias::Bookworm, DistributionAlias::Jammy]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dist_file_may_mix_commas_newlines_and_comments() -> Result<(), Box<dyn Error>> {
+    assert_eq!(
+        cli::parse_distribution_list("# production\nnoble, jammy\n\ntrixie\n")?,
+        vec![
+            DistributionAlias::Noble,
+            DistributionAlias::Jammy,
+            DistributionAlias::Trixie
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dist_file_with_an_invalid_alias_errors() {
+    assert!(matches!(
+        cli::parse_distribution_list("bookworm\nbuster\n"),
+        Err(BellhopError::InvalidDistribution { alias }) if alias == "buster"
+    ));
+}
+
+#[test]
+fn test_missing_dist_file_errors() {
+    assert!(matches!(
+        cli::read_distribution_file(std::path::Path::new("/nonexistent/distributions")),
+        Err(BellhopError::DistributionFileNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_add_to_the_distributions_in_a_dist_file() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let dist_file = temp_dir.path().join("distributions");
+    fs::write(&dist_file, "bookworm\njammy\n")?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "--suffix", "v1", "--dist-file"])
+        .arg(&dist_file)
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+
+    let repos: Vec<String> = stub
+        .invocations()?
+        .iter()
+        .filter(|line| line.starts_with("repo add"))
+        .filter_map(|line| {
+            line.split_whitespace()
+                .find(|arg| arg.starts_with("repo-"))
+                .map(String::from)
+        })
+        .collect();
+    assert_eq!(
+        repos,
+        [
+            "repo-rabbitmq-server-bookworm",
+            "repo-rabbitmq-server-jammy"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dist_file_conflicts_with_distributions() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-p", "x.deb", "-d", "bookworm"])
+        .args(["--dist-file", "distributions"])
+        .assert()
+        .code(64)
+        .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_invalid_alias_in_a_dist_file_is_reported() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let dist_file = temp_dir.path().join("distributions");
+    fs::write(&dist_file, "buster\n")?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "list", "--dist-file"])
+        .arg(&dist_file)
+        .assert()
+        .failure()
+        .stderr(output_includes("Invalid distribution alias: buster"));
+    Ok(())
+}
@@ -0,0 +1,133 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::cli;
+use bellhop::deb::DistributionAlias;
+use std::error::Error;
+use test_helpers::*;
+
+fn repo_add_order(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .iter()
+        .filter(|line| line.starts_with("repo add"))
+        .filter_map(|line| {
+            line.split_whitespace()
+                .find(|arg| arg.starts_with("repo-rabbitmq-server-"))
+                .map(|repo| repo.trim_start_matches("repo-rabbitmq-server-").to_string())
+        })
+        .collect())
+}
+
+#[test]
+fn test_order_distributions_puts_listed_distributions_first() {
+    let ordered = cli::order_distributions(
+        DistributionAlias::all().to_vec(),
+        &[DistributionAlias::Bookworm, DistributionAlias::Noble],
+    );
+    assert_eq!(
+        ordered,
+        vec![
+            DistributionAlias::Bookworm,
+            DistributionAlias::Noble,
+            DistributionAlias::Jammy,
+            DistributionAlias::Focal,
+            DistributionAlias::Trixie,
+            DistributionAlias::Bullseye,
+        ]
+    );
+}
+
+#[test]
+fn test_order_distributions_ignores_unselected_distributions() {
+    let ordered = cli::order_distributions(
+        vec![DistributionAlias::Jammy, DistributionAlias::Trixie],
+        &[DistributionAlias::Bookworm, DistributionAlias::Trixie],
+    );
+    assert_eq!(
+        ordered,
+        vec![DistributionAlias::Trixie, DistributionAlias::Jammy]
+    );
+}
+
+#[test]
+fn test_repo_add_follows_the_distribution_order_flag() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-p",
+        package_path.to_str().unwrap(),
+        "--all",
+        "--distribution-order",
+        "trixie,jammy",
+    ]);
+    cmd.assert().success();
+
+    assert_eq!(
+        repo_add_order(&stub)?,
+        vec!["trixie", "jammy", "noble", "focal", "bookworm", "bullseye"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_repo_add_follows_the_distribution_order_env_var() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    let mut cmd = stub.bellhop_command();
+    cmd.env("BELLHOP_DISTRIBUTION_ORDER", "bullseye, bookworm");
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-p",
+        package_path.to_str().unwrap(),
+        "-d",
+        "noble,bookworm,bullseye",
+    ]);
+    cmd.assert().success();
+
+    assert_eq!(
+        repo_add_order(&stub)?,
+        vec!["bullseye", "bookworm", "noble"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unknown_distribution_in_order_is_rejected() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "publish",
+        "--all",
+        "--distribution-order",
+        "buster",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(output_includes("Invalid distribution alias: buster"));
+    Ok(())
+}
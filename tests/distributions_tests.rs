@@ -0,0 +1,67 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use tempfile::TempDir;
+use test_helpers::*;
+
+#[test]
+fn test_distributions_lists_all_aliases() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+
+    // does not need aptly
+    Command::new(cargo::cargo_bin!("bellhop"))
+        .env("APTLY_BIN", temp_dir.path().join("aptly"))
+        .arg("distributions")
+        .assert()
+        .success()
+        .stdout(output_includes("noble        ubuntu   yes"))
+        .stdout(output_includes("jammy        ubuntu   yes"))
+        .stdout(output_includes("focal        ubuntu   no"))
+        .stdout(output_includes("trixie       debian   yes"))
+        .stdout(output_includes("bookworm     debian   yes"))
+        .stdout(output_includes("bullseye     debian   no"));
+    Ok(())
+}
+
+#[test]
+fn test_distributions_as_json() -> Result<(), Box<dyn Error>> {
+    let assert = run_bellhop_succeeds(["distributions", "--format", "json"]);
+
+    let distributions: Vec<serde_json::Value> =
+        serde_json::from_slice(&assert.get_output().stdout)?;
+    let names: Vec<&str> = distributions
+        .iter()
+        .filter_map(|d| d["name"].as_str())
+        .collect();
+    assert_eq!(
+        names,
+        ["noble", "jammy", "focal", "trixie", "bookworm", "bullseye"]
+    );
+
+    let not_erlang_supported: Vec<&str> = distributions
+        .iter()
+        .filter(|d| d["erlang_supported"] == false)
+        .filter_map(|d| d["name"].as_str())
+        .collect();
+    assert_eq!(not_erlang_supported, ["focal", "bullseye"]);
+
+    let focal = &distributions[2];
+    assert_eq!(focal["family"], "ubuntu");
+    Ok(())
+}
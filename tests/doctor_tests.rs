@@ -0,0 +1,112 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+use test_helpers::*;
+
+fn version_stub() -> Result<AptlyStub, Box<dyn Error>> {
+    AptlyStub::with_script("if [ \"$1\" = version ]; then echo 'aptly version: 1.6.2'; fi")
+}
+
+fn write_config(temp_dir: &TempDir, contents: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let path = temp_dir.path().join("aptly.conf");
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+#[test]
+fn test_doctor_passes_with_a_valid_config() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let root_dir = temp_dir.path().join("aptly-root");
+    let config = write_config(
+        &temp_dir,
+        &format!(r#"{{"rootDir": "{}"}}"#, root_dir.display()),
+    )?;
+    let stub = version_stub()?;
+
+    stub.bellhop_command()
+        .env("APTLY_CONFIG", &config)
+        .env("HOME", temp_dir.path())
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(output_includes("[ok]   aptly: aptly version: 1.6.2"))
+        .stdout(output_includes(&format!(
+            "[ok]   configuration: {}",
+            config.display()
+        )))
+        .stdout(output_includes(&format!(
+            "[ok]   root directory: {}",
+            root_dir.display()
+        )))
+        .stdout(output_includes("signing key"));
+    Ok(())
+}
+
+#[test]
+fn test_doctor_fails_with_an_invalid_config() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = write_config(&temp_dir, "{ not json")?;
+    let stub = version_stub()?;
+
+    stub.bellhop_command()
+        .env("APTLY_CONFIG", &config)
+        .env("HOME", temp_dir.path())
+        .arg("doctor")
+        .assert()
+        .code(70)
+        .stdout(output_includes("[ok]   aptly"))
+        .stdout(output_includes("[FAIL] configuration"))
+        .stdout(output_includes("is not valid JSON"))
+        .stderr(output_includes("1 environment check(s) failed"));
+    Ok(())
+}
+
+#[test]
+fn test_doctor_fails_without_a_config_file() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let stub = version_stub()?;
+
+    stub.bellhop_command()
+        .env("HOME", temp_dir.path())
+        .arg("doctor")
+        .assert()
+        .code(70)
+        .stdout(output_includes("[FAIL] configuration: could not read"));
+    Ok(())
+}
+
+#[test]
+fn test_doctor_fails_when_aptly_cannot_be_run() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = write_config(
+        &temp_dir,
+        &format!(r#"{{"rootDir": "{}"}}"#, temp_dir.path().display()),
+    )?;
+    let stub = version_stub()?;
+
+    stub.bellhop_command()
+        .env("APTLY_BIN", temp_dir.path().join("missing-aptly"))
+        .env("APTLY_CONFIG", &config)
+        .arg("doctor")
+        .assert()
+        .code(70)
+        .stdout(output_includes("[FAIL] aptly: could not run"));
+    Ok(())
+}
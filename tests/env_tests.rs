@@ -0,0 +1,82 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+use test_helpers::*;
+
+#[test]
+fn test_env_reports_versions_and_configuration() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = temp_dir.path().join("aptly.conf");
+    fs::write(&config, r#"{"rootDir": "/srv/aptly"}"#)?;
+    let stub =
+        AptlyStub::with_script("if [ \"$1\" = version ]; then echo 'aptly version: 1.6.2'; fi")?;
+
+    stub.bellhop_command()
+        .env("APTLY_CONFIG", &config)
+        .arg("env")
+        .assert()
+        .success()
+        .stdout(output_includes(&format!(
+            "bellhop: {}",
+            env!("CARGO_PKG_VERSION")
+        )))
+        .stdout(output_includes("aptly: aptly version: 1.6.2"))
+        .stdout(output_includes("gpg: "))
+        .stdout(output_includes(&format!(
+            "aptly configuration: {}",
+            config.display()
+        )))
+        .stdout(output_includes("aptly root directory: /srv/aptly"));
+    Ok(())
+}
+
+#[test]
+fn test_env_reports_a_missing_aptly() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+
+    Command::new(cargo::cargo_bin!("bellhop"))
+        .env("APTLY_BIN", temp_dir.path().join("aptly"))
+        .arg("env")
+        .assert()
+        .success()
+        .stdout(output_includes(&format!(
+            "bellhop: {}",
+            env!("CARGO_PKG_VERSION")
+        )))
+        .stdout(output_includes("aptly: not found"));
+    Ok(())
+}
+
+#[test]
+fn test_env_supports_json() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+
+    let assert = Command::new(cargo::cargo_bin!("bellhop"))
+        .env("APTLY_BIN", temp_dir.path().join("aptly"))
+        .args(["env", "--json"])
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    assert_eq!(report["bellhop"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(report["aptly"], "not found");
+    Ok(())
+}
@@ -92,7 +92,7 @@ fn test_erlang_remove_trixie_by_version() -> Result<(), Box<dyn Error>> {
         "erlang",
         "deb",
         "remove",
-        "-v",
+        "-V",
         "1:27.3.4.6-1",
         "-d",
         "trixie",
@@ -107,3 +107,112 @@ fn test_erlang_remove_trixie_by_version() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_erlang_remove_trixie_by_package_name() -> Result<(), Box<dyn Error>> {
+    let archive_path = test_fixture_path("archives/erlang-27.3.4.6-debian-trixie.zip");
+    if !archive_path.exists() {
+        eprintln!("Skipping test: Trixie archive not available");
+        return Ok(());
+    }
+
+    let ctx = AptlyTestContext::new()?;
+    let repo_name = "repo-rabbitmq-erlang-trixie";
+    ctx.create_repo(repo_name)?;
+
+    let mut add_cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    add_cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    add_cmd.args([
+        "erlang",
+        "deb",
+        "add",
+        "-p",
+        archive_path.to_str().unwrap(),
+        "-d",
+        "trixie",
+    ]);
+    add_cmd.assert().success();
+    assert!(ctx.package_exists(repo_name, "Name (= erlang-base)")?);
+    assert!(ctx.package_exists(repo_name, "Name (= erlang-dev)")?);
+
+    let mut remove_cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    remove_cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    remove_cmd.args([
+        "erlang",
+        "deb",
+        "remove",
+        "-V",
+        "1:27.3.4.6-1",
+        "--package-name",
+        "erlang-base",
+        "-d",
+        "trixie",
+    ]);
+    remove_cmd.assert().success();
+
+    assert!(
+        !ctx.package_exists(repo_name, "Name (= erlang-base)")?,
+        "erlang-base should be removed"
+    );
+    assert!(
+        ctx.package_exists(repo_name, "Name (= erlang-dev)")?,
+        "erlang-dev should be kept"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_erlang_remove_by_package_name_query() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "erlang",
+            "deb",
+            "remove",
+            "-V",
+            "1:27.3.4.6-1",
+            "--package-name",
+            "erlang-base",
+            "-d",
+            "trixie",
+        ])
+        .assert()
+        .success();
+
+    assert!(stub.invocations()?.contains(
+        &"repo remove repo-rabbitmq-erlang-trixie Name (= erlang-base), Version (= 1:27.3.4.6-1)"
+            .to_string()
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_erlang_remove_package_name_requires_version() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "erlang",
+        "deb",
+        "remove",
+        "--package-name",
+        "erlang-base",
+        "--all-versions",
+        "erlang",
+        "-d",
+        "trixie",
+    ])
+    .stderr(output_includes("cannot be used with"));
+    run_bellhop_fails([
+        "erlang",
+        "deb",
+        "remove",
+        "--package-name",
+        "erlang-base",
+        "-p",
+        "erlang-base_27.3.4.6-1_amd64.deb",
+        "-d",
+        "trixie",
+    ])
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
@@ -0,0 +1,202 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use tempfile::TempDir;
+use test_helpers::*;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const ERLANG_VERSION: &str = "1:27.3.4.6-1";
+
+fn erlang_archive(dir: &TempDir, packages: &[&str]) -> Result<PathBuf, Box<dyn Error>> {
+    let archive = dir.path().join("erlang.zip");
+    let mut zip = ZipWriter::new(File::create(&archive)?);
+    for package in packages {
+        zip.start_file(*package, SimpleFileOptions::default())?;
+        zip.write_all(&fs::read(test_package_path(package))?)?;
+    }
+    zip.finish()?;
+    Ok(archive)
+}
+
+fn repo_removes(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo remove"))
+        .collect())
+}
+
+#[test]
+fn test_exact_removal_keeps_packages_not_in_the_archive() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    let repo_name = "repo-rabbitmq-erlang-trixie";
+    ctx.create_repo(repo_name)?;
+    for package in [
+        "erlang-base_27.3.4.6-1_amd64.deb",
+        "erlang-asn1_27.3.4.6-1_amd64.deb",
+        "erlang-base-dbgsym_27.3.4.6-1_amd64.deb",
+        "erlang-asn1-dbgsym_27.3.4.6-1_amd64.deb",
+    ] {
+        Command::new("aptly")
+            .arg(ctx.config_arg())
+            .args(["repo", "add", repo_name])
+            .arg(test_package_path(package))
+            .output()?;
+    }
+    let dir = TempDir::new()?;
+    let archive = erlang_archive(
+        &dir,
+        &[
+            "erlang-base_27.3.4.6-1_amd64.deb",
+            "erlang-asn1_27.3.4.6-1_amd64.deb",
+        ],
+    )?;
+
+    Command::new(cargo::cargo_bin!("bellhop"))
+        .env("APTLY_CONFIG", &ctx.config_path)
+        .args(["erlang", "deb", "remove", "-d", "trixie", "--exact", "-p"])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let remaining = ctx.list_packages(repo_name)?;
+    assert!(
+        remaining.iter().all(|p| p.contains("dbgsym")),
+        "{remaining:?}"
+    );
+    assert_eq!(remaining.len(), 2, "{remaining:?}");
+    Ok(())
+}
+
+#[test]
+fn test_exact_removal_queries_the_names_and_versions_of_the_control_files()
+-> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let dir = TempDir::new()?;
+    let archive = erlang_archive(
+        &dir,
+        &[
+            "erlang-base_27.3.4.6-1_amd64.deb",
+            "erlang-asn1_27.3.4.6-1_amd64.deb",
+        ],
+    )?;
+
+    stub.bellhop_command()
+        .args(["erlang", "deb", "remove", "-d", "trixie", "--exact", "-p"])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    assert_eq!(
+        repo_removes(&stub)?,
+        vec![format!(
+            "repo remove repo-rabbitmq-erlang-trixie (Name (= erlang-asn1), Version (= {ERLANG_VERSION})) | (Name (= erlang-base), Version (= {ERLANG_VERSION}))"
+        )]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_removal_without_exact_queries_by_version() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let dir = TempDir::new()?;
+    let archive = erlang_archive(&dir, &["erlang-base_27.3.4.6-1_amd64.deb"])?;
+
+    stub.bellhop_command()
+        .args(["erlang", "deb", "remove", "-d", "trixie", "-p"])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    assert_eq!(
+        repo_removes(&stub)?,
+        vec![
+            "repo remove repo-rabbitmq-erlang-trixie Name (~ ^erlang), Version (= 27.3.4.6-1)"
+                .to_string()
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_exact_removal_of_a_single_deb() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "remove", "-d", "bookworm", "--exact", "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+
+    assert_eq!(
+        repo_removes(&stub)?,
+        vec![
+            "repo remove repo-rabbitmq-server-bookworm (Name (= rabbitmq-server), Version (= 4.1.3-1))"
+                .to_string()
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_exact_requires_a_package_file() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "remove", "-d", "bookworm", "--exact", "-V", "4.1.3-1",
+        ])
+        .assert()
+        .failure()
+        .code(64);
+    assert!(stub.invocations()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_for_exact_removal() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm",
+            "--exact",
+            "--dry-run",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success()
+        .stdout(output_includes(
+            "aptly repo remove repo-rabbitmq-server-bookworm '(Name (= rabbitmq-server), Version (= 4.1.3-1))'",
+        ));
+    assert!(repo_removes(&stub)?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::cli;
+use bellhop::deb::DistributionAlias;
+use bellhop::errors::BellhopError;
+use std::error::Error;
+use test_helpers::*;
+
+fn repo_adds(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .iter()
+        .filter(|line| line.starts_with("repo add"))
+        .filter_map(|line| {
+            line.split_whitespace()
+                .find(|arg| arg.starts_with("repo-"))
+                .map(String::from)
+        })
+        .collect())
+}
+
+#[test]
+fn test_except_distributions_subtracts_from_all() -> Result<(), Box<dyn Error>> {
+    assert_eq!(
+        cli::except_distributions(
+            DistributionAlias::all(),
+            &[DistributionAlias::Focal, DistributionAlias::Bullseye]
+        )?,
+        vec![
+            DistributionAlias::Noble,
+            DistributionAlias::Jammy,
+            DistributionAlias::Trixie,
+            DistributionAlias::Bookworm,
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_excepting_every_distribution_errors() {
+    assert!(matches!(
+        cli::except_distributions(
+            DistributionAlias::erlang_supported(),
+            DistributionAlias::erlang_supported()
+        ),
+        Err(BellhopError::NoDistributionsLeft)
+    ));
+}
+
+#[test]
+fn test_add_to_all_except_focal_and_bullseye() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "--suffix", "v1", "--all"])
+        .args(["--except", "focal,bullseye", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+
+    assert_eq!(
+        repo_adds(&stub)?,
+        [
+            "repo-rabbitmq-server-noble",
+            "repo-rabbitmq-server-jammy",
+            "repo-rabbitmq-server-trixie",
+            "repo-rabbitmq-server-bookworm"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_except_rejects_an_invalid_alias() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "--suffix", "v1", "--all"])
+        .args(["--except", "buster", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .failure()
+        .stderr(output_includes("Invalid distribution alias: buster"));
+    assert!(repo_adds(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_except_everything_is_reported() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["erlang", "deb", "add", "--suffix", "v1", "--all"])
+        .args(["--except", "noble,jammy,trixie,bookworm", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .code(64)
+        .stderr(output_includes("--except leaves none of the distributions"));
+    Ok(())
+}
+
+#[test]
+fn test_except_requires_all() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-p", "x.deb", "-d", "bookworm"])
+        .args(["--except", "focal"])
+        .assert()
+        .code(64);
+    Ok(())
+}
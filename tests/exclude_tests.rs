@@ -0,0 +1,55 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use tempfile::TempDir;
+use test_helpers::*;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+#[test]
+fn test_excluded_debug_packages_are_not_added() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive = temp_dir.path().join("packages.zip");
+    let mut zip = ZipWriter::new(File::create(&archive)?);
+    for name in [
+        "erlang-base_27.3-1_amd64.deb",
+        "erlang-base-dbg_27.3-1_amd64.deb",
+    ] {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(&minimal_deb())?;
+    }
+    zip.finish()?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["erlang", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .args(["--exclude", "*-dbg_*", "-p"])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let adds: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo add"))
+        .collect();
+    assert_eq!(adds.len(), 1, "Unexpected repo adds: {adds:?}");
+    assert!(adds[0].ends_with("erlang-base_27.3-1_amd64.deb"));
+    Ok(())
+}
@@ -0,0 +1,78 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use test_helpers::*;
+
+#[test]
+fn test_missing_required_argument_is_a_usage_error() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails(["rabbitmq", "deb", "add", "-d", "bookworm"])
+        .code(64)
+        .stderr(output_includes("required arguments were not provided"));
+    Ok(())
+}
+
+#[test]
+fn test_invalid_value_is_a_usage_error() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "-p",
+        "rabbitmq-server_4.1.3-1_all.deb",
+        "--input-format",
+        "rpm",
+    ])
+    .code(64)
+    .stderr(output_includes("invalid value"));
+    Ok(())
+}
+
+#[test]
+fn test_unknown_subcommand_is_a_usage_error() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails(["rabbitmq", "deb", "frobnicate"]).code(64);
+    Ok(())
+}
+
+#[test]
+fn test_missing_subcommand_prints_help_and_is_a_usage_error() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails(["rabbitmq"])
+        .code(64)
+        .stderr(output_includes("Usage:"));
+    Ok(())
+}
+
+#[test]
+fn test_help_exits_successfully() -> Result<(), Box<dyn Error>> {
+    run_bellhop_succeeds(["rabbitmq", "deb", "add", "--help"])
+        .code(0)
+        .stdout(output_includes("Usage:"));
+    Ok(())
+}
+
+#[test]
+fn test_bad_distribution_is_a_data_error() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "hamm"])
+        .assert()
+        .code(65)
+        .stderr(output_includes("hamm"));
+    Ok(())
+}
@@ -0,0 +1,154 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::archive::{ExtractionOptions, process_package_file};
+use bellhop::errors::BellhopError;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use tar::{Builder, Header};
+use tempfile::TempDir;
+use test_helpers::*;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const DEB: &str = "example_1.0.0-1_all.deb";
+const BOMB_SIZE: u64 = 4 * 1024 * 1024;
+
+fn append_zeroes<W: Write>(builder: &mut Builder<W>, name: &str, size: u64) -> io::Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, io::repeat(0).take(size))
+}
+
+/// A few kilobytes on disk, megabytes once extracted
+fn create_tar_gz_bomb(temp_dir: &TempDir) -> Result<PathBuf, Box<dyn Error>> {
+    let path = temp_dir.path().join("packages.tar.gz");
+    let encoder = GzEncoder::new(File::create(&path)?, Compression::fast());
+    let mut builder = Builder::new(encoder);
+    append_zeroes(&mut builder, DEB, 1024)?;
+    append_zeroes(&mut builder, "padding.bin", BOMB_SIZE)?;
+    builder.into_inner()?.finish()?;
+    Ok(path)
+}
+
+fn create_zip_bomb(temp_dir: &TempDir) -> Result<PathBuf, Box<dyn Error>> {
+    let path = temp_dir.path().join("packages.zip");
+    let mut zip = ZipWriter::new(File::create(&path)?);
+    zip.start_file("padding.bin", SimpleFileOptions::default())?;
+    io::copy(&mut io::repeat(0).take(BOMB_SIZE), &mut zip)?;
+    zip.finish()?;
+    Ok(path)
+}
+
+fn process_with_limits(
+    path: &Path,
+    max_extracted_bytes: u64,
+    max_entries: u64,
+) -> Result<(), BellhopError> {
+    let options = ExtractionOptions {
+        max_extracted_bytes,
+        max_entries,
+        ..ExtractionOptions::default()
+    };
+    process_package_file(path, &options).map(|_| ())
+}
+
+#[test]
+fn test_tar_gz_over_the_byte_limit_is_rejected() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = create_tar_gz_bomb(&temp_dir)?;
+
+    let result = process_with_limits(&path, 1024 * 1024, 100);
+
+    assert!(
+        matches!(result, Err(BellhopError::ArchiveTooLarge { .. })),
+        "Expected ArchiveTooLarge, got {result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_zip_over_the_byte_limit_is_rejected() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = create_zip_bomb(&temp_dir)?;
+
+    let result = process_with_limits(&path, 1024 * 1024, 100);
+
+    assert!(
+        matches!(result, Err(BellhopError::ArchiveTooLarge { .. })),
+        "Expected ArchiveTooLarge, got {result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_archive_within_the_limits_is_extracted() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = create_tar_gz_bomb(&temp_dir)?;
+
+    process_with_limits(&path, 2 * BOMB_SIZE, 2)?;
+    Ok(())
+}
+
+#[test]
+fn test_archive_over_the_entry_limit_is_rejected() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&path)?);
+    append_zeroes(&mut builder, DEB, 1024)?;
+    for i in 0..10 {
+        append_zeroes(&mut builder, &format!("file-{i}.txt"), 1)?;
+    }
+    builder.finish()?;
+
+    let result = process_with_limits(&path, BOMB_SIZE, 5);
+
+    assert!(
+        matches!(result, Err(BellhopError::ArchiveTooLarge { .. })),
+        "Expected ArchiveTooLarge, got {result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_deb_add_aborts_when_the_byte_limit_is_exceeded() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = create_tar_gz_bomb(&temp_dir)?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm"])
+        .args(["--max-extracted-bytes", "1048576", "-p"])
+        .arg(&path)
+        .assert()
+        .code(65)
+        .stderr(output_includes("extraction limit of 1048576 bytes"));
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo add")),
+        "Nothing should be added from an oversized archive"
+    );
+    Ok(())
+}
@@ -0,0 +1,159 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use bellhop::archive::{ExtractionOptions, PackageSource, process_package_file};
+use bellhop::errors::BellhopError;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tar::Builder;
+use tempfile::TempDir;
+use test_helpers::*;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const DEB: &str = "rabbitmq-server_4.1.7-1_all.deb";
+
+fn extensionless(temp_dir: &TempDir) -> PathBuf {
+    temp_dir.path().join("artifact")
+}
+
+fn append_deb<W: Write>(builder: &mut Builder<W>) -> Result<(), Box<dyn Error>> {
+    builder.append_path_with_name(test_package_path(DEB), DEB)?;
+    Ok(())
+}
+
+fn process(path: &Path) -> Result<PackageSource, BellhopError> {
+    process_package_file(path, &ExtractionOptions::default())
+}
+
+fn assert_extracted_deb(source: PackageSource, expected_name: &str) {
+    match source {
+        PackageSource::Archive { deb_files, .. } => {
+            assert_eq!(deb_files.len(), 1);
+            assert!(
+                deb_files[0].ends_with(expected_name),
+                "Unexpected file: {}",
+                deb_files[0].display()
+            );
+        }
+        PackageSource::SingleDeb(path) => {
+            panic!("Expected extracted packages, got {}", path.display())
+        }
+    }
+}
+
+#[test]
+fn test_gzip_magic_is_routed_to_tar_gz() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = extensionless(&temp_dir);
+    let mut builder = Builder::new(GzEncoder::new(File::create(&path)?, Compression::fast()));
+    append_deb(&mut builder)?;
+    builder.into_inner()?.finish()?;
+
+    assert_extracted_deb(process(&path)?, DEB);
+    Ok(())
+}
+
+#[test]
+fn test_zip_magic_is_routed_to_zip() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = extensionless(&temp_dir);
+    let mut zip = ZipWriter::new(File::create(&path)?);
+    zip.start_file(DEB, SimpleFileOptions::default())?;
+    zip.write_all(&fs::read(test_package_path(DEB))?)?;
+    zip.finish()?;
+
+    assert_extracted_deb(process(&path)?, DEB);
+    Ok(())
+}
+
+#[test]
+fn test_tar_magic_is_routed_to_tar() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = extensionless(&temp_dir);
+    let mut builder = Builder::new(File::create(&path)?);
+    append_deb(&mut builder)?;
+    builder.finish()?;
+
+    assert_extracted_deb(process(&path)?, DEB);
+    Ok(())
+}
+
+#[test]
+fn test_ar_magic_is_routed_to_deb() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = extensionless(&temp_dir);
+    fs::copy(test_package_path(DEB), &path)?;
+
+    // aptly only picks up files with a .deb extension
+    assert_extracted_deb(process(&path)?, "artifact.deb");
+    Ok(())
+}
+
+#[test]
+fn test_xz_magic_is_reported_as_unsupported() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = extensionless(&temp_dir);
+    fs::write(&path, [0xfd, b'7', b'z', b'X', b'Z', 0x00, 0x00, 0x04])?;
+
+    let err = process(&path).err().expect("xz input should be rejected");
+    assert!(
+        matches!(err, BellhopError::UnsupportedArchiveFormat { ref format, .. } if format == "xz")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_zstd_magic_is_reported_as_unsupported() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = extensionless(&temp_dir);
+    fs::write(&path, [0x28, 0xb5, 0x2f, 0xfd, 0x00])?;
+
+    let err = process(&path).err().expect("zstd input should be rejected");
+    assert!(
+        matches!(err, BellhopError::UnsupportedArchiveFormat { ref format, .. } if format == "zstd")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unknown_content_is_passed_through() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = extensionless(&temp_dir);
+    fs::write(&path, b"something else entirely")?;
+
+    match process(&path)? {
+        PackageSource::SingleDeb(result) => assert_eq!(result, path),
+        PackageSource::Archive { .. } => panic!("Unknown content should not be extracted"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_extension_takes_precedence_over_content() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("gzip-looking.deb");
+    fs::write(&path, [0x1f, 0x8b, 0x08, 0x00])?;
+
+    match process(&path)? {
+        PackageSource::SingleDeb(result) => assert_eq!(result, path),
+        PackageSource::Archive { .. } => panic!("A .deb file should not be sniffed"),
+    }
+    Ok(())
+}
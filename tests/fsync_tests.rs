@@ -0,0 +1,112 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use mockito::Server;
+use std::error::Error;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tar::Builder;
+use tempfile::TempDir;
+use test_helpers::*;
+
+const DEB: &str = "rabbitmq-server_4.1.7-1_all.deb";
+
+/// A stub that copies every file passed to `aptly repo add` into `capture_dir`,
+/// since bellhop removes its temporary files once the command completes
+fn capturing_stub(capture_dir: &Path) -> Result<AptlyStub, Box<dyn Error>> {
+    AptlyStub::with_script(&format!(
+        "if [ \"$1\" = repo ] && [ \"$2\" = add ]; then for last; do :; done; cp \"$last\" \"{}\"; fi",
+        capture_dir.display()
+    ))
+}
+
+fn create_tar_archive(temp_dir: &TempDir) -> Result<PathBuf, Box<dyn Error>> {
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    builder.append_path_with_name(test_package_path(DEB), DEB)?;
+    builder.finish()?;
+    Ok(archive_path)
+}
+
+#[test]
+fn test_add_archive_with_fsync() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let capture_dir = TempDir::new()?;
+    let stub = capturing_stub(capture_dir.path())?;
+    let archive_path = create_tar_archive(&temp_dir)?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--fsync", "-p"])
+        .arg(&archive_path)
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read(capture_dir.path().join(DEB))?,
+        fs::read(test_package_path(DEB))?,
+        "Extracted package should be complete"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_import_from_github_with_fsync() -> Result<(), Box<dyn Error>> {
+    let capture_dir = TempDir::new()?;
+    let stub = capturing_stub(capture_dir.path())?;
+    let deb_contents = fs::read(test_package_path(DEB))?;
+
+    let mut server = Server::new();
+    let release = server
+        .mock("GET", "/repos/rabbitmq/rabbitmq-server/releases/tags/v4.1.7")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"tag_name": "v4.1.7", "assets": [{{"name": "{DEB}", "browser_download_url": "{}/download/{DEB}", "size": {}}}]}}"#,
+            server.url(),
+            deb_contents.len()
+        ))
+        .create();
+    let download = server
+        .mock("GET", format!("/download/{DEB}").as_str())
+        .with_status(200)
+        .with_body(&deb_contents)
+        .create();
+
+    stub.bellhop_command()
+        .env("GITHUB_API_URL", server.url())
+        .args([
+            "rabbitmq",
+            "deb",
+            "import-from-github",
+            "--github-release-url",
+            "https://github.com/rabbitmq/rabbitmq-server/releases/tag/v4.1.7",
+            "-d",
+            "bookworm",
+            "--no-cache",
+            "--fsync",
+        ])
+        .assert()
+        .success();
+
+    release.assert();
+    download.assert();
+    assert_eq!(
+        fs::read(capture_dir.path().join(DEB))?,
+        deb_contents,
+        "Downloaded package should be complete"
+    );
+    Ok(())
+}
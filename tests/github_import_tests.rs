@@ -15,8 +15,16 @@ mod test_helpers;
 
 use assert_cmd::assert::OutputAssertExt;
 use assert_cmd::cargo;
+use mockito::{Server, ServerGuard};
 use std::error::Error;
+use std::fs::{self, File};
+use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
 use test_helpers::*;
 
 #[test]
@@ -40,8 +48,13 @@ fn test_cli_tools_import_from_github_help() -> Result<(), Box<dyn Error>> {
 }
 
 #[test]
-fn test_erlang_does_not_have_import_from_github() -> Result<(), Box<dyn Error>> {
-    run_bellhop_fails(["erlang", "deb", "import-from-github", "--help"]);
+fn test_erlang_import_from_github_help() -> Result<(), Box<dyn Error>> {
+    run_bellhop_succeeds(["erlang", "deb", "import-from-github", "--help"])
+        .stdout(output_includes(
+            "Import .deb packages from a GitHub release",
+        ))
+        .stdout(output_includes("--github-release-url"))
+        .stdout(output_includes("*{distribution}*.deb for erlang"));
     Ok(())
 }
 
@@ -75,6 +88,198 @@ fn test_import_from_github_invalid_url() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_erlang_import_from_github_invalid_url() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "erlang",
+            "deb",
+            "import-from-github",
+            "--github-release-url",
+            "https://not-github.com/foo/bar",
+            "-d",
+            "bookworm",
+        ])
+        .assert()
+        .failure()
+        .stderr(output_includes("Invalid GitHub release URL"));
+    Ok(())
+}
+
+#[test]
+fn test_erlang_import_from_github_rejects_unsupported_distributions() -> Result<(), Box<dyn Error>>
+{
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "erlang",
+            "deb",
+            "import-from-github",
+            "--github-release-url",
+            RELEASE_URL,
+            "-d",
+            "bookworm,focal",
+        ])
+        .assert()
+        .code(65)
+        .stderr(output_includes("Erlang packages are not built for focal"));
+    Ok(())
+}
+
+const RELEASE_PATH: &str = "/repos/rabbitmq/rabbitmq-server/releases/tags/v4.2.3";
+const RELEASE_URL: &str = "https://github.com/rabbitmq/rabbitmq-server/releases/tag/v4.2.3";
+
+fn release_json(asset_names: &[&str]) -> String {
+    let assets: Vec<String> = asset_names
+        .iter()
+        .map(|name| {
+            format!(
+                r#"{{"name": "{name}", "browser_download_url": "https://example.com/{name}", "size": 1024}}"#
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"tag_name": "v4.2.3", "assets": [{}]}}"#,
+        assets.join(", ")
+    )
+}
+
+fn check_only(server: &ServerGuard, extra: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("GITHUB_API_URL", server.url());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        RELEASE_URL,
+        "-d",
+        "bookworm",
+        "--check-only",
+        "--no-cache",
+    ]);
+    cmd.args(extra);
+    cmd
+}
+
+#[test]
+fn test_check_only_succeeds_for_a_matching_release() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&[
+            "rabbitmq-server_4.2.3-1_all.deb",
+            "rabbitmq-server-4.2.3.tar.xz",
+        ]))
+        .create();
+
+    check_only(&server, &[])
+        .assert()
+        .success()
+        .stderr(output_includes("rabbitmq-server_4.2.3-1_all.deb"));
+
+    release.assert();
+    Ok(())
+}
+
+#[test]
+fn test_check_only_fails_when_no_asset_matches() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server-4.2.3.tar.xz"]))
+        .create();
+
+    check_only(&server, &[])
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(output_includes("No assets matching pattern '*.deb'"));
+    Ok(())
+}
+
+#[test]
+fn test_check_only_honors_the_pattern() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .create();
+
+    check_only(&server, &["--pattern", "*arm64*.deb"])
+        .assert()
+        .failure()
+        .stderr(output_includes("No assets matching pattern '*arm64*.deb'"));
+    Ok(())
+}
+
+#[test]
+fn test_check_only_honors_only_arch() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&[
+            "erlang_27.3-1_amd64.deb",
+            "erlang_27.3-1_arm64.deb",
+            "erlang-doc_27.3-1_all.deb",
+        ]))
+        .create();
+
+    let assert = check_only(&server, &["--only-arch", "arm64"])
+        .assert()
+        .success();
+    let stderr = String::from_utf8(assert.get_output().stderr.clone())?;
+    assert!(stderr.contains("erlang_27.3-1_arm64.deb"));
+    assert!(stderr.contains("erlang-doc_27.3-1_all.deb"));
+    assert!(!stderr.contains("erlang_27.3-1_amd64.deb"));
+    Ok(())
+}
+
+#[test]
+fn test_check_only_fails_when_only_arch_matches_nothing() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["erlang_27.3-1_amd64.deb"]))
+        .create();
+
+    check_only(&server, &["--only-arch", "arm64"])
+        .assert()
+        .code(65)
+        .stderr(output_includes("No assets matching pattern '*.deb'"));
+    Ok(())
+}
+
+#[test]
+fn test_check_only_fails_for_a_missing_release() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(404)
+        .with_body(r#"{"message": "Not Found"}"#)
+        .create();
+
+    check_only(&server, &[])
+        .assert()
+        .failure()
+        .code(70)
+        .stderr(output_includes("GitHub API returned status 404"));
+    Ok(())
+}
+
 #[test]
 #[ignore]
 fn test_import_rabbitmq_server_from_github() -> Result<(), Box<dyn Error>> {
@@ -132,3 +337,984 @@ fn test_import_cli_tools_from_github() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_release_metadata_is_reported_and_notes_are_written() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "tag_name": "v4.2.3",
+                "name": "RabbitMQ 4.2.3",
+                "body": "RabbitMQ 4.2.3 is a maintenance release.\n\n## Bug Fixes\n",
+                "published_at": "2026-01-20T10:00:00Z",
+                "assets": [{"name": "rabbitmq-server_4.2.3-1_all.deb", "browser_download_url": "https://example.com/rabbitmq-server_4.2.3-1_all.deb", "size": 1024}]
+            }"#,
+        )
+        .create();
+
+    let temp_dir = TempDir::new()?;
+    let notes_path = temp_dir.path().join("release-notes.md");
+
+    check_only(
+        &server,
+        &["--release-notes-file", notes_path.to_str().unwrap()],
+    )
+    .assert()
+    .success()
+    .stderr(output_includes(
+        "Release: RabbitMQ 4.2.3 (rabbitmq/rabbitmq-server v4.2.3)",
+    ))
+    .stderr(output_includes("Published at: 2026-01-20T10:00:00Z"));
+
+    release.assert();
+    assert_eq!(
+        fs::read_to_string(&notes_path)?,
+        "RabbitMQ 4.2.3 is a maintenance release.\n\n## Bug Fixes\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_release_metadata_is_in_the_run_report() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "tag_name": "v4.2.3",
+                "name": "RabbitMQ 4.2.3",
+                "published_at": "2026-01-20T10:00:00Z",
+                "assets": [
+                    {"name": "rabbitmq-server_4.2.3-1_all.deb", "browser_download_url": "https://example.com/rabbitmq-server_4.2.3-1_all.deb", "size": 1024},
+                    {"name": "rabbitmq-server-4.2.3.tar.xz", "browser_download_url": "https://example.com/rabbitmq-server-4.2.3.tar.xz", "size": 2048}
+                ]
+            }"#,
+        )
+        .create();
+
+    let output = check_only(&server, &["--json"]).output()?;
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr)?;
+    let last_line = stderr.lines().last().ok_or("stderr should not be empty")?;
+    let report: serde_json::Value = serde_json::from_str(last_line)?;
+    assert_eq!(
+        report["summary"],
+        serde_json::json!({
+            "owner": "rabbitmq",
+            "repo": "rabbitmq-server",
+            "tag": "v4.2.3",
+            "name": "RabbitMQ 4.2.3",
+            "published_at": "2026-01-20T10:00:00Z",
+            "assets": ["rabbitmq-server_4.2.3-1_all.deb"],
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_release_without_a_name_is_reported_by_tag() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .create();
+
+    let temp_dir = TempDir::new()?;
+    let notes_path = temp_dir.path().join("release-notes.md");
+
+    check_only(
+        &server,
+        &["--release-notes-file", notes_path.to_str().unwrap()],
+    )
+    .assert()
+    .success()
+    .stderr(output_includes(
+        "Release: v4.2.3 (rabbitmq/rabbitmq-server v4.2.3)",
+    ));
+
+    assert_eq!(fs::read_to_string(&notes_path)?, "");
+    Ok(())
+}
+
+const ASSET: &str = "rabbitmq-server_4.2.3-1_all.deb";
+const ASSET_SIZE: usize = 1024;
+
+fn mock_release_with_downloadable_asset(server: &mut ServerGuard) -> mockito::Mock {
+    let body = format!(
+        r#"{{"tag_name": "v4.2.3", "assets": [{{"name": "{ASSET}", "browser_download_url": "{}/download/{ASSET}", "size": {ASSET_SIZE}}}]}}"#,
+        server.url()
+    );
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .create()
+}
+
+fn import_keeping_downloads(
+    stub: &AptlyStub,
+    server: &ServerGuard,
+    download_dir: &Path,
+) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.env("GITHUB_API_URL", server.url());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        RELEASE_URL,
+        "-d",
+        "bookworm",
+        "--no-cache",
+        "--keep-downloads",
+    ]);
+    cmd.arg(download_dir);
+    cmd
+}
+
+#[test]
+fn test_keep_downloads_does_not_download_again() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_with_downloadable_asset(&mut server);
+    let download = server
+        .mock("GET", format!("/download/{ASSET}").as_str())
+        .with_status(200)
+        .with_body(vec![b'x'; ASSET_SIZE])
+        .expect(1)
+        .create();
+
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let download_dir = temp_dir.path().join("downloads");
+
+    import_keeping_downloads(&stub, &server, &download_dir)
+        .assert()
+        .success();
+    assert_eq!(fs::read(download_dir.join(ASSET))?.len(), ASSET_SIZE);
+
+    import_keeping_downloads(&stub, &server, &download_dir)
+        .assert()
+        .success()
+        .stderr(output_includes("is already downloaded, skipping"));
+
+    download.assert();
+    let adds = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo add"))
+        .count();
+    assert_eq!(adds, 2, "Both imports should add the kept package");
+    Ok(())
+}
+
+#[test]
+fn test_keep_downloads_replaces_an_incomplete_file() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_with_downloadable_asset(&mut server);
+    let download = server
+        .mock("GET", format!("/download/{ASSET}").as_str())
+        .with_status(200)
+        .with_body(vec![b'x'; ASSET_SIZE])
+        .expect(1)
+        .create();
+
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join(ASSET), b"partial")?;
+
+    import_keeping_downloads(&stub, &server, temp_dir.path())
+        .assert()
+        .success();
+
+    download.assert();
+    assert_eq!(fs::read(temp_dir.path().join(ASSET))?.len(), ASSET_SIZE);
+    Ok(())
+}
+
+fn check_only_with_cache(server: &ServerGuard, cache_dir: &Path) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("GITHUB_API_URL", server.url());
+    cmd.env("XDG_CACHE_HOME", cache_dir);
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        RELEASE_URL,
+        "-d",
+        "bookworm",
+        "--check-only",
+    ]);
+    cmd
+}
+
+#[test]
+fn test_release_metadata_is_cached() -> Result<(), Box<dyn Error>> {
+    let cache_dir = TempDir::new()?;
+    let mut server = Server::new();
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .expect(1)
+        .create();
+
+    for _ in 0..2 {
+        check_only_with_cache(&server, cache_dir.path())
+            .assert()
+            .success()
+            .stderr(output_includes("rabbitmq-server_4.2.3-1_all.deb"));
+    }
+
+    release.assert();
+    assert!(
+        cache_dir
+            .path()
+            .join("bellhop/releases/rabbitmq/rabbitmq-server/v4.2.3.json")
+            .is_file()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_no_cache_always_calls_the_api() -> Result<(), Box<dyn Error>> {
+    let cache_dir = TempDir::new()?;
+    let mut server = Server::new();
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .expect(2)
+        .create();
+
+    check_only_with_cache(&server, cache_dir.path())
+        .assert()
+        .success();
+    check_only_with_cache(&server, cache_dir.path())
+        .arg("--no-cache")
+        .assert()
+        .success();
+
+    release.assert();
+    Ok(())
+}
+
+#[test]
+fn test_expired_release_metadata_is_fetched_again() -> Result<(), Box<dyn Error>> {
+    let cache_dir = TempDir::new()?;
+    let mut server = Server::new();
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .expect(2)
+        .create();
+
+    check_only_with_cache(&server, cache_dir.path())
+        .assert()
+        .success();
+    let cached = cache_dir
+        .path()
+        .join("bellhop/releases/rabbitmq/rabbitmq-server/v4.2.3.json");
+    File::options()
+        .write(true)
+        .open(&cached)?
+        .set_modified(SystemTime::now() - Duration::from_secs(60 * 60))?;
+    check_only_with_cache(&server, cache_dir.path())
+        .assert()
+        .success();
+
+    release.assert();
+    Ok(())
+}
+
+#[test]
+fn test_a_stalled_api_request_times_out() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_chunked_body(|w| {
+            thread::sleep(Duration::from_secs(3));
+            w.write_all(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]).as_bytes())
+        })
+        .create();
+
+    check_only(&server, &["--http-timeout", "1"])
+        .assert()
+        .failure()
+        .code(70)
+        .stderr(output_includes("timed out"));
+    Ok(())
+}
+
+#[test]
+fn test_http_timeout_must_be_positive() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        RELEASE_URL,
+        "-d",
+        "bookworm",
+        "--http-timeout",
+        "0",
+    ])
+    .stderr(output_includes("--http-timeout"));
+    Ok(())
+}
+
+const MULTI_ARCH_ASSETS: [&str; 4] = [
+    "erlang-base_27.3-1_amd64.deb",
+    "erlang-base_27.3-1_arm64.deb",
+    "erlang-dev_27.3-1_amd64.deb",
+    "erlang-dev_27.3-1_arm64.deb",
+];
+
+fn mock_release_with_assets(server: &mut ServerGuard, names: &[&str]) -> mockito::Mock {
+    let assets: Vec<String> = names
+        .iter()
+        .map(|name| {
+            format!(
+                r#"{{"name": "{name}", "browser_download_url": "{}/download/{name}", "size": {ASSET_SIZE}}}"#,
+                server.url()
+            )
+        })
+        .collect();
+    server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"tag_name": "v4.2.3", "assets": [{}]}}"#,
+            assets.join(", ")
+        ))
+        .create()
+}
+
+#[test]
+fn test_assets_are_downloaded_concurrently() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_with_assets(&mut server, &MULTI_ARCH_ASSETS);
+
+    let active = Arc::new(AtomicUsize::new(0));
+    let max_active = Arc::new(AtomicUsize::new(0));
+    for name in MULTI_ARCH_ASSETS {
+        let active = Arc::clone(&active);
+        let max_active = Arc::clone(&max_active);
+        server
+            .mock("GET", format!("/download/{name}").as_str())
+            .with_status(200)
+            .with_chunked_body(move |w| {
+                let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+                max_active.fetch_max(now_active, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(500));
+                active.fetch_sub(1, Ordering::SeqCst);
+                w.write_all(&[b'x'; ASSET_SIZE])
+            })
+            .expect(1)
+            .create();
+    }
+
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    import_keeping_downloads(&stub, &server, temp_dir.path())
+        .args(["--download-jobs", "4"])
+        .assert()
+        .success();
+
+    for name in MULTI_ARCH_ASSETS {
+        assert_eq!(fs::read(temp_dir.path().join(name))?.len(), ASSET_SIZE);
+    }
+    assert!(
+        max_active.load(Ordering::SeqCst) > 1,
+        "Downloads should overlap"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_a_failed_download_fails_the_import() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_with_assets(&mut server, &MULTI_ARCH_ASSETS);
+    for name in MULTI_ARCH_ASSETS {
+        let status = if name == MULTI_ARCH_ASSETS[1] {
+            404
+        } else {
+            200
+        };
+        server
+            .mock("GET", format!("/download/{name}").as_str())
+            .with_status(status)
+            .with_body(vec![b'x'; ASSET_SIZE])
+            .create();
+    }
+
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    import_keeping_downloads(&stub, &server, temp_dir.path())
+        .args(["--download-jobs", "2"])
+        .assert()
+        .code(70)
+        .stderr(output_includes(&format!(
+            "/download/{}",
+            MULTI_ARCH_ASSETS[1]
+        )))
+        .stderr(output_includes("404"));
+
+    let adds = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo add"))
+        .count();
+    assert_eq!(adds, 0, "Nothing should be added after a failed download");
+    Ok(())
+}
+
+#[test]
+fn test_download_jobs_must_be_positive() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        RELEASE_URL,
+        "-d",
+        "bookworm",
+        "--download-jobs",
+        "0",
+    ])
+    .stderr(output_includes("must be a positive number"));
+    Ok(())
+}
+
+#[test]
+fn test_an_asset_over_the_download_limit_is_not_downloaded() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_with_assets(&mut server, &MULTI_ARCH_ASSETS);
+    let downloads = server
+        .mock("GET", mockito::Matcher::Regex("^/download/".to_string()))
+        .with_status(200)
+        .with_body(vec![b'x'; ASSET_SIZE])
+        .expect(0)
+        .create();
+
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    import_keeping_downloads(&stub, &server, temp_dir.path())
+        .args(["--max-download-size", &(ASSET_SIZE - 1).to_string()])
+        .assert()
+        .code(65)
+        .stderr(output_includes(&format!(
+            "Release asset {} is {ASSET_SIZE} bytes, over the download limit of {} bytes",
+            MULTI_ARCH_ASSETS[0],
+            ASSET_SIZE - 1
+        )));
+
+    downloads.assert();
+    Ok(())
+}
+
+#[test]
+fn test_a_download_exceeding_its_declared_size_is_aborted() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_with_assets(&mut server, &MULTI_ARCH_ASSETS[..1]);
+    server
+        .mock(
+            "GET",
+            format!("/download/{}", MULTI_ARCH_ASSETS[0]).as_str(),
+        )
+        .with_status(200)
+        .with_chunked_body(|w| w.write_all(&[b'x'; 2 * ASSET_SIZE]))
+        .create();
+
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    import_keeping_downloads(&stub, &server, temp_dir.path())
+        .args(["--max-download-size", &(10 * ASSET_SIZE).to_string()])
+        .assert()
+        .code(70)
+        .stderr(output_includes(&format!(
+            "received more than the declared {ASSET_SIZE} bytes"
+        )));
+
+    assert!(!temp_dir.path().join(MULTI_ARCH_ASSETS[0]).exists());
+    Ok(())
+}
+
+#[test]
+fn test_download_only_does_not_use_aptly() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_with_assets(&mut server, &MULTI_ARCH_ASSETS);
+    for name in MULTI_ARCH_ASSETS {
+        server
+            .mock("GET", format!("/download/{name}").as_str())
+            .with_status(200)
+            .with_body(vec![b'x'; ASSET_SIZE])
+            .expect(1)
+            .create();
+    }
+
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let assert = stub
+        .bellhop_command()
+        .env("GITHUB_API_URL", server.url())
+        .args([
+            "rabbitmq",
+            "deb",
+            "import-from-github",
+            "--github-release-url",
+            RELEASE_URL,
+            "-d",
+            "bookworm",
+            "--no-cache",
+            "--download-only",
+            "--dest-dir",
+        ])
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    for name in MULTI_ARCH_ASSETS {
+        let path = temp_dir.path().join(name);
+        assert_eq!(fs::read(&path)?.len(), ASSET_SIZE);
+        assert!(stdout.contains(&path.display().to_string()));
+    }
+    assert!(
+        stub.invocations()?.is_empty(),
+        "aptly should not be invoked"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_download_only_requires_a_destination() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        RELEASE_URL,
+        "-d",
+        "bookworm",
+        "--download-only",
+    ])
+    .stderr(output_includes("--keep-downloads"));
+    Ok(())
+}
+
+#[test]
+fn test_erlang_assets_are_imported_into_their_distribution() -> Result<(), Box<dyn Error>> {
+    const ERLANG_ASSETS: [&str; 3] = [
+        "erlang-base_27.3-1~noble_amd64.deb",
+        "erlang-base_27.3-1~bookworm_amd64.deb",
+        "erlang-base_27.3-1~jammy_amd64.deb",
+    ];
+    let mut server = Server::new();
+    mock_release_with_assets(&mut server, &ERLANG_ASSETS);
+    for name in &ERLANG_ASSETS[..2] {
+        server
+            .mock("GET", format!("/download/{name}").as_str())
+            .with_status(200)
+            .with_body(vec![b'x'; ASSET_SIZE])
+            .expect(1)
+            .create();
+    }
+
+    let stub = AptlyStub::new()?;
+    stub.bellhop_command()
+        .env("GITHUB_API_URL", server.url())
+        .args([
+            "erlang",
+            "deb",
+            "import-from-github",
+            "--github-release-url",
+            RELEASE_URL,
+            "-d",
+            "noble,bookworm",
+            "--suffix",
+            "v1",
+            "--no-cache",
+        ])
+        .assert()
+        .success();
+
+    let adds: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo add"))
+        .collect();
+    assert_eq!(adds.len(), 2, "Unexpected repo adds: {adds:?}");
+    assert!(adds.iter().any(
+        |line| line.contains("repo-rabbitmq-erlang-noble ") && line.ends_with(ERLANG_ASSETS[0])
+    ));
+    assert!(
+        adds.iter()
+            .any(|line| line.contains("repo-rabbitmq-erlang-bookworm ")
+                && line.ends_with(ERLANG_ASSETS[1]))
+    );
+    Ok(())
+}
+
+const RELEASES_PATH: &str = "/repos/rabbitmq/rabbitmq-server/releases";
+const REPOSITORY_URL: &str = "https://github.com/rabbitmq/rabbitmq-server";
+
+fn release_list_json(releases: &[(String, bool)]) -> String {
+    let entries: Vec<String> = releases
+        .iter()
+        .map(|(tag, prerelease)| {
+            format!(r#"{{"tag_name": "{tag}", "prerelease": {prerelease}, "draft": false}}"#)
+        })
+        .collect();
+    format!("[{}]", entries.join(", "))
+}
+
+fn mock_release_list_page(server: &mut ServerGuard, page: &str, body: String) -> mockito::Mock {
+    server
+        .mock("GET", RELEASES_PATH)
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("per_page".into(), "100".into()),
+            mockito::Matcher::UrlEncoded("page".into(), page.into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .create()
+}
+
+fn check_only_by_tag_pattern(server: &ServerGuard, extra: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("GITHUB_API_URL", server.url());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        REPOSITORY_URL,
+        "-d",
+        "bookworm",
+        "--check-only",
+        "--no-cache",
+    ]);
+    cmd.args(extra);
+    cmd
+}
+
+#[test]
+fn test_tag_pattern_selects_the_newest_release_across_pages() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    let first_page: Vec<(String, bool)> = (0..100)
+        .map(|patch| (format!("v3.13.{patch}"), false))
+        .collect();
+    let page_1 = mock_release_list_page(&mut server, "1", release_list_json(&first_page));
+    let page_2 = mock_release_list_page(
+        &mut server,
+        "2",
+        release_list_json(&[
+            ("v4.2.4-rc.1".to_string(), true),
+            ("v4.2.3".to_string(), false),
+            ("v4.2.2".to_string(), false),
+        ]),
+    );
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .create();
+
+    check_only_by_tag_pattern(&server, &["--tag-pattern", "v4.2.*"])
+        .assert()
+        .success()
+        .stderr(output_includes("Selected release v4.2.3"));
+
+    page_1.assert();
+    page_2.assert();
+    release.assert();
+    Ok(())
+}
+
+#[test]
+fn test_tag_pattern_includes_prereleases_on_request() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_list_page(
+        &mut server,
+        "1",
+        release_list_json(&[
+            ("v4.2.4-rc.1".to_string(), true),
+            ("v4.2.3".to_string(), false),
+        ]),
+    );
+    let release = server
+        .mock(
+            "GET",
+            "/repos/rabbitmq/rabbitmq-server/releases/tags/v4.2.4-rc.1",
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.4~rc.1-1_all.deb"]))
+        .create();
+
+    check_only_by_tag_pattern(
+        &server,
+        &["--tag-pattern", "v4.2.*", "--include-prereleases"],
+    )
+    .assert()
+    .success()
+    .stderr(output_includes("Selected release v4.2.4-rc.1"));
+
+    release.assert();
+    Ok(())
+}
+
+#[test]
+fn test_tag_pattern_fails_when_no_release_matches() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_release_list_page(
+        &mut server,
+        "1",
+        release_list_json(&[("v4.2.3".to_string(), false)]),
+    );
+
+    check_only_by_tag_pattern(&server, &["--tag-pattern", "v5.*"])
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(output_includes("No release with a tag matching 'v5.*'"));
+
+    Ok(())
+}
+
+#[test]
+fn test_include_prereleases_requires_a_tag_pattern() -> Result<(), Box<dyn Error>> {
+    let server = Server::new();
+
+    check_only_by_tag_pattern(&server, &["--include-prereleases"])
+        .assert()
+        .failure()
+        .stderr(output_includes("--tag-pattern"));
+
+    Ok(())
+}
+
+fn import_expecting_version(stub: &AptlyStub, server: &ServerGuard, version: &str) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.env("GITHUB_API_URL", server.url()).args([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--github-release-url",
+        RELEASE_URL,
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "--no-cache",
+        "--expect-version",
+        version,
+    ]);
+    cmd
+}
+
+fn mock_downloadable_release(server: &mut ServerGuard) {
+    let name = "rabbitmq-server_4.2.3-1_all.deb";
+    mock_release_with_assets(server, &[name]);
+    server
+        .mock("GET", format!("/download/{name}").as_str())
+        .with_status(200)
+        .with_body(vec![b'x'; ASSET_SIZE])
+        .create();
+}
+
+const REPO_HAS_4_2_3: &str =
+    "if [ \"$1 $2\" = \"repo search\" ]; then echo 'rabbitmq-server_4.2.3-1_all'; fi";
+
+#[test]
+fn test_expected_version_is_verified_after_the_import() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_downloadable_release(&mut server);
+    let stub = AptlyStub::with_script(REPO_HAS_4_2_3)?;
+
+    import_expecting_version(&stub, &server, "4.2.3")
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations
+            .contains(&"repo search repo-rabbitmq-server-bookworm rabbitmq-server".to_string())
+    );
+    assert!(
+        invocations
+            .iter()
+            .any(|line| line.starts_with("snapshot create"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_import_fails_when_the_expected_version_is_missing() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    mock_downloadable_release(&mut server);
+    let stub = AptlyStub::with_script(REPO_HAS_4_2_3)?;
+
+    import_expecting_version(&stub, &server, "4.2.4")
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "No package of version 4.2.4 in repo 'repo-rabbitmq-server-bookworm'",
+        ));
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("snapshot create"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_import_from_github_help_lists_release_components() -> Result<(), Box<dyn Error>> {
+    run_bellhop_succeeds(["rabbitmq", "deb", "import-from-github", "--help"])
+        .stdout(output_includes("--owner <OWNER>"))
+        .stdout(output_includes("--repo <REPO>"))
+        .stdout(output_includes("--tag <TAG>"));
+    Ok(())
+}
+
+fn check_only_by_components(server: &ServerGuard, extra: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("GITHUB_API_URL", server.url());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--owner",
+        "rabbitmq",
+        "--repo",
+        "rabbitmq-server",
+        "-d",
+        "bookworm",
+        "--check-only",
+        "--no-cache",
+    ]);
+    cmd.args(extra);
+    cmd
+}
+
+#[test]
+fn test_check_only_by_owner_repo_and_tag() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .create();
+
+    check_only_by_components(&server, &["--tag", "v4.2.3"])
+        .assert()
+        .success()
+        .stderr(output_includes("rabbitmq-server_4.2.3-1_all.deb"));
+
+    release.assert();
+    Ok(())
+}
+
+#[test]
+fn test_tag_pattern_with_owner_and_repo() -> Result<(), Box<dyn Error>> {
+    let mut server = Server::new();
+    let page = mock_release_list_page(
+        &mut server,
+        "1",
+        release_list_json(&[("v4.2.3".to_string(), false), ("v4.2.2".to_string(), false)]),
+    );
+    let release = server
+        .mock("GET", RELEASE_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(release_json(&["rabbitmq-server_4.2.3-1_all.deb"]))
+        .create();
+
+    check_only_by_components(&server, &["--tag-pattern", "v4.2.*"])
+        .assert()
+        .success()
+        .stderr(output_includes("Selected release v4.2.3"));
+
+    page.assert();
+    release.assert();
+    Ok(())
+}
+
+#[test]
+fn test_owner_and_repo_require_a_tag_or_tag_pattern() -> Result<(), Box<dyn Error>> {
+    let server = Server::new();
+
+    check_only_by_components(&server, &[])
+        .assert()
+        .code(64)
+        .stderr(output_includes("tag or tag_pattern"));
+    Ok(())
+}
+
+#[test]
+fn test_release_components_must_not_be_empty() -> Result<(), Box<dyn Error>> {
+    let server = Server::new();
+
+    check_only_by_components(&server, &["--tag", " "])
+        .assert()
+        .code(64)
+        .stderr(output_includes("must not be empty"));
+    Ok(())
+}
+
+#[test]
+fn test_release_components_conflict_with_the_release_url() -> Result<(), Box<dyn Error>> {
+    let server = Server::new();
+
+    check_only_by_components(
+        &server,
+        &["--tag", "v4.2.3", "--github-release-url", RELEASE_URL],
+    )
+    .assert()
+    .code(64)
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_owner_requires_repo() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "import-from-github",
+        "--owner",
+        "rabbitmq",
+        "--tag",
+        "v4.2.3",
+        "-d",
+        "bookworm",
+    ])
+    .code(64)
+    .stderr(output_includes("--repo <REPO>"));
+    Ok(())
+}
@@ -0,0 +1,105 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+/// Only TESTKEY is in the keyring
+const KEYRING_SCRIPT: &str = r#"[ "$1" = "--list-secret-keys" ] && [ "$2" = "TESTKEY" ]"#;
+
+fn publish(stub: &AptlyStub, key: &str) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.env("BELLHOP_GPG_KEY", key).args([
+        "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+    ]);
+    cmd
+}
+
+fn published(stub: &AptlyStub) -> Result<bool, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .iter()
+        .any(|line| line.starts_with("publish snapshot") || line.starts_with("publish switch")))
+}
+
+#[test]
+fn test_publish_proceeds_when_the_signing_key_is_present() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.set_gpg_script(KEYRING_SCRIPT)?;
+
+    publish(&stub, "TESTKEY").assert().success();
+
+    assert!(published(&stub)?);
+    Ok(())
+}
+
+#[test]
+fn test_publish_fails_fast_when_the_signing_key_is_absent() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.set_gpg_script(KEYRING_SCRIPT)?;
+
+    publish(&stub, "MISSINGKEY")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(output_includes(
+            "GPG signing key MISSINGKEY is not in the keyring",
+        ));
+
+    assert!(!published(&stub)?, "nothing should be published");
+    Ok(())
+}
+
+#[test]
+fn test_no_gpg_check_skips_the_preflight() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.set_gpg_script(KEYRING_SCRIPT)?;
+
+    publish(&stub, "MISSINGKEY")
+        .arg("--no-gpg-check")
+        .assert()
+        .success();
+
+    assert!(published(&stub)?);
+    Ok(())
+}
+
+#[test]
+fn test_promote_latest_fails_fast_when_the_signing_key_is_absent() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.set_gpg_script(KEYRING_SCRIPT)?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "MISSINGKEY")
+        .args(["rabbitmq", "deb", "promote-latest", "-d", "bookworm"])
+        .args(["--suffix", "promoted", "--remove-older"])
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "GPG signing key MISSINGKEY is not in the keyring",
+        ));
+
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations
+            .iter()
+            .all(|line| !line.starts_with("repo remove") && !line.starts_with("snapshot")),
+        "nothing should be changed: {invocations:?}"
+    );
+    assert!(!published(&stub)?, "nothing should be published");
+    Ok(())
+}
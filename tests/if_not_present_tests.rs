@@ -0,0 +1,110 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const REPO: &str = "repo-rabbitmq-server-bookworm";
+
+fn add_if_not_present(stub: &AptlyStub) -> assert_cmd::assert::Assert {
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--if-not-present",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+}
+
+#[test]
+fn test_if_not_present_skips_a_present_package() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1\" = repo ] && [ \"$2\" = search ]; then echo rabbitmq-server_4.1.3-1_all; fi",
+    )?;
+
+    add_if_not_present(&stub).success().stderr(output_includes(
+        "already present in repo 'repo-rabbitmq-server-bookworm'",
+    ));
+
+    assert!(stub.invocations()?.contains(&format!(
+        "repo search {REPO} Name (= rabbitmq-server), Version (= 4.1.3-1), $Architecture (= all)"
+    )));
+    assert!(stub.invocations_starting_with("repo add")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_if_not_present_adds_a_missing_package() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1\" = repo ] && [ \"$2\" = search ]; then echo 'ERROR: no results' >&2; exit 1; fi",
+    )?;
+
+    add_if_not_present(&stub).success();
+
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_if_not_present_conflicts_with_newer_only() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--if-not-present",
+        "--newer-only",
+        "-p",
+        "rabbitmq-server_4.1.3-1_all.deb",
+    ])
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_adding_the_same_package_twice_with_if_not_present() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+
+    for suffix in ["v1", "v2"] {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+        cmd.args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--suffix",
+            suffix,
+            "--if-not-present",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"));
+        cmd.assert().success();
+    }
+
+    assert_eq!(ctx.list_packages(REPO)?, ["rabbitmq-server_4.1.3-1_all"]);
+    Ok(())
+}
@@ -0,0 +1,128 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::error::Error;
+use std::fs::File;
+use std::path::PathBuf;
+use tar::Builder;
+use tempfile::TempDir;
+use test_helpers::*;
+
+const DEB: &str = "rabbitmq-server_4.1.7-1_all.deb";
+
+/// A gzip-compressed tarball with a name that gives no hint of its format
+fn create_blob(temp_dir: &TempDir) -> Result<PathBuf, Box<dyn Error>> {
+    let blob_path = temp_dir.path().join("blob");
+    let encoder = GzEncoder::new(File::create(&blob_path)?, Compression::fast());
+    let mut builder = Builder::new(encoder);
+    builder.append_path_with_name(test_package_path(DEB), DEB)?;
+    builder.into_inner()?.finish()?;
+    Ok(blob_path)
+}
+
+#[test]
+fn test_input_format_forces_tar_gz_extraction() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let blob_path = create_blob(&temp_dir)?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--input-format",
+            "tar.gz",
+            "-p",
+        ])
+        .arg(&blob_path)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1);
+    assert!(
+        adds[0].ends_with(&format!("/{DEB}")),
+        "The package inside the tarball should be added, got: {}",
+        adds[0]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_auto_input_format_sniffs_extensionless_files() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let blob_path = create_blob(&temp_dir)?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(&blob_path)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1);
+    assert!(adds[0].ends_with(&format!("/{DEB}")));
+    Ok(())
+}
+
+#[test]
+fn test_forced_deb_input_format_skips_sniffing() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let blob_path = create_blob(&temp_dir)?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--input-format",
+            "deb",
+            "--no-validate",
+            "-p",
+        ])
+        .arg(&blob_path)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1);
+    assert!(adds[0].ends_with("/blob.deb"));
+    Ok(())
+}
+
+#[test]
+fn test_input_format_rejects_unknown_values() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-p",
+        "blob",
+        "--input-format",
+        "rpm",
+    ])
+    .stderr(output_includes("invalid value 'rpm'"));
+    Ok(())
+}
@@ -169,7 +169,7 @@ fn test_remove_package_from_single_distribution() -> Result<(), Box<dyn Error>>
     let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
     cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
     cmd.args([
-        "rabbitmq", "deb", "remove", "-v", "4.1.3-1", "-d", "bookworm",
+        "rabbitmq", "deb", "remove", "-V", "4.1.3-1", "-d", "bookworm",
     ]);
     cmd.assert().success();
 
@@ -218,7 +218,7 @@ fn test_remove_package_from_multiple_distributions() -> Result<(), Box<dyn Error
         "rabbitmq",
         "deb",
         "remove",
-        "-v",
+        "-V",
         "4.1.3-1",
         "-d",
         "bookworm,jammy",
@@ -252,7 +252,7 @@ fn test_remove_nonexistent_package_succeeds() -> Result<(), Box<dyn Error>> {
         "rabbitmq",
         "deb",
         "remove",
-        "-v",
+        "-V",
         "99.99.99-999",
         "-d",
         "bookworm",
@@ -495,7 +495,7 @@ fn test_add_and_remove_workflow() -> Result<(), Box<dyn Error>> {
         "rabbitmq",
         "deb",
         "remove",
-        "-v",
+        "-V",
         "4.1.3-1",
         "-d",
         "bookworm",
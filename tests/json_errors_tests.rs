@@ -0,0 +1,89 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::errors::BellhopError;
+use serde_json::Value;
+use std::error::Error;
+use std::path::PathBuf;
+use test_helpers::*;
+
+/// The error object, nested in the run report on the last line of stderr
+fn stderr_json(args: &[&str]) -> Result<Value, Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let output = stub.bellhop_command().args(args).assert().failure();
+    let stderr = String::from_utf8(output.get_output().stderr.clone())?;
+    let last_line = stderr.lines().last().ok_or("stderr should not be empty")?;
+    let report: Value = serde_json::from_str(last_line)?;
+    Ok(report["error"].clone())
+}
+
+#[test]
+fn test_package_file_not_found_as_json() -> Result<(), Box<dyn Error>> {
+    let error = stderr_json(&[
+        "--json",
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "-p",
+        "/nonexistent/rabbitmq-server_4.1.3-1_all.deb",
+    ])?;
+
+    assert_eq!(error["error"], "package_file_not_found");
+    assert_eq!(error["exit_code"], 65);
+    assert_eq!(
+        error["message"],
+        "Package file does not exist at /nonexistent/rabbitmq-server_4.1.3-1_all.deb"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_invalid_distribution_as_json() -> Result<(), Box<dyn Error>> {
+    let error = stderr_json(&["rabbitmq", "deb", "publish", "-d", "hamm", "--json"])?;
+
+    assert_eq!(error["error"], "invalid_distribution");
+    assert_eq!(error["exit_code"], 65);
+    assert_eq!(error["message"], "Invalid distribution alias: hamm");
+    Ok(())
+}
+
+#[test]
+fn test_errors_are_plain_text_without_json() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "hamm"])
+        .assert()
+        .code(65)
+        .stderr(output_includes("Error: Invalid distribution alias: hamm"));
+    Ok(())
+}
+
+#[test]
+fn test_error_json_shape() {
+    let error = BellhopError::PackageFileNotFound {
+        path: PathBuf::from("/tmp/missing.deb"),
+    };
+    assert_eq!(
+        error.to_json(),
+        serde_json::json!({
+            "error": "package_file_not_found",
+            "message": "Package file does not exist at /tmp/missing.deb",
+            "exit_code": 65,
+        })
+    );
+}
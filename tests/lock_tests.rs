@@ -0,0 +1,191 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::errors::BellhopError;
+use bellhop::lock;
+use std::error::Error;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use test_helpers::*;
+
+#[test]
+fn test_second_acquisition_times_out_while_the_lock_is_held() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("bellhop.lock");
+
+    let held = lock::acquire(&path, Duration::ZERO)?;
+    let contender = {
+        let path = path.clone();
+        thread::spawn(move || {
+            let started = Instant::now();
+            (
+                lock::acquire(&path, Duration::from_millis(300)),
+                started.elapsed(),
+            )
+        })
+    };
+    let (result, waited) = contender.join().unwrap();
+
+    assert!(matches!(result, Err(BellhopError::AlreadyRunning { .. })));
+    assert!(
+        waited >= Duration::from_millis(300),
+        "gave up after {waited:?}"
+    );
+
+    drop(held);
+    assert!(lock::acquire(&path, Duration::ZERO).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_acquisition_waits_for_the_lock_to_be_released() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("nested").join("bellhop.lock");
+
+    let held = lock::acquire(&path, Duration::ZERO)?;
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        drop(held);
+    });
+
+    assert!(lock::acquire(&path, Duration::from_secs(10)).is_ok());
+    releaser.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn test_changing_commands_fail_while_another_run_holds_the_lock() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("bellhop.lock");
+    let _held = lock::acquire(&path, Duration::ZERO)?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .arg("--lockfile")
+        .arg(&path)
+        .args(["--lock-timeout", "0"])
+        .assert()
+        .failure()
+        .code(70)
+        .stderr(output_includes("Another bellhop run holds the lock"));
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("publish"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_read_only_commands_do_not_take_the_lock() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("bellhop.lock");
+    let _held = lock::acquire(&path, Duration::ZERO)?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "snapshot", "list", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .arg("--lockfile")
+        .arg(&path)
+        .args(["--lock-timeout", "0"])
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_admin_commands_fail_while_another_run_holds_the_lock() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("bellhop.lock");
+    let _held = lock::acquire(&path, Duration::ZERO)?;
+    let stub = AptlyStub::new()?;
+
+    let commands: [&[&str]; 3] = [
+        &["repositories", "set-up"],
+        &[
+            "mirror",
+            "create",
+            "--name",
+            "nodesource",
+            "--url",
+            "https://deb.nodesource.com/node_22.x",
+            "--distribution",
+            "nodistro",
+        ],
+        &["mirror", "update", "--name", "nodesource", "--suffix", "v1"],
+    ];
+    for args in commands {
+        stub.bellhop_command()
+            .args(args)
+            .arg("--lockfile")
+            .arg(&path)
+            .args(["--lock-timeout", "0"])
+            .assert()
+            .failure()
+            .code(70)
+            .stderr(output_includes("Another bellhop run holds the lock"));
+    }
+    assert!(
+        stub.invocations()?
+            .iter()
+            .all(|line| line == "version" || line.ends_with("list -raw")),
+        "nothing should be changed: {:?}",
+        stub.invocations()?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_fails_while_another_run_holds_the_lock() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("bellhop.lock");
+    let _held = lock::acquire(&path, Duration::ZERO)?;
+    let stub = AptlyStub::new()?;
+    let watch_root = temp_dir.path().join("watch");
+    let server_dir = watch_root.join("rabbitmq-server");
+    std::fs::create_dir_all(&server_dir)?;
+    let deb = "rabbitmq-server_4.1.3-1_all.deb";
+    std::fs::copy(test_package_path(deb), server_dir.join(deb))?;
+
+    stub.bellhop_command()
+        .args(["watch", "-d", "bookworm", "--once", "--root"])
+        .arg(&watch_root)
+        .arg("--lockfile")
+        .arg(&path)
+        .args(["--lock-timeout", "0"])
+        .assert()
+        .failure()
+        .code(70)
+        .stderr(output_includes("Another bellhop run holds the lock"));
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo add"))
+    );
+
+    Ok(())
+}
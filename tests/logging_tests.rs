@@ -0,0 +1,92 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use predicates::prelude::*;
+use std::error::Error;
+use test_helpers::*;
+
+const SNAPSHOT_ARGS: [&str; 7] = [
+    "rabbitmq", "snapshot", "take", "-d", "bookworm", "--suffix", "v1",
+];
+
+#[test]
+fn test_info_lines_are_logged_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.bellhop_command()
+        .args(SNAPSHOT_ARGS)
+        .assert()
+        .success()
+        .stderr(output_includes("[INFO]"));
+    Ok(())
+}
+
+#[test]
+fn test_quiet_suppresses_info_lines() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.bellhop_command()
+        .arg("--quiet")
+        .args(SNAPSHOT_ARGS)
+        .assert()
+        .success()
+        .stderr(output_includes("[INFO]").not())
+        .stderr(output_includes("[DEBUG]").not());
+    Ok(())
+}
+
+#[test]
+fn test_short_quiet_after_subcommands() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    stub.bellhop_command()
+        .args(SNAPSHOT_ARGS)
+        .arg("-q")
+        .assert()
+        .success()
+        .stderr(output_includes("[INFO]").not());
+    Ok(())
+}
+
+#[test]
+fn test_quiet_still_reports_errors() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script("echo boom >&2; exit 1")?;
+    stub.bellhop_command()
+        .arg("--quiet")
+        .args(SNAPSHOT_ARGS)
+        .assert()
+        .failure()
+        .stderr(output_includes("Error:"));
+    Ok(())
+}
+
+#[test]
+fn test_quiet_conflicts_with_verbose() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "--quiet",
+        "--verbose",
+        "rabbitmq",
+        "snapshot",
+        "list",
+        "--all",
+    ])
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_short_quiet_conflicts_with_short_verbose() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails(["rabbitmq", "snapshot", "list", "--all", "-q", "-v"])
+        .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
@@ -0,0 +1,156 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const NODESOURCE_URL: &str = "https://deb.nodesource.com/node_22.x";
+
+#[test]
+fn test_mirror_create_passes_components_and_architectures() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "mirror",
+            "create",
+            "--name",
+            "nodesource",
+            "--url",
+            NODESOURCE_URL,
+            "--distribution",
+            "nodistro",
+            "--components",
+            "main",
+            "--architectures",
+            "amd64,arm64",
+        ])
+        .assert()
+        .success();
+
+    assert!(stub.invocations()?.contains(&format!(
+        "mirror create -architectures=amd64,arm64 nodesource {NODESOURCE_URL} nodistro main"
+    )));
+    Ok(())
+}
+
+#[test]
+fn test_mirror_create_requires_a_url() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "mirror",
+        "create",
+        "--name",
+        "nodesource",
+        "--distribution",
+        "nodistro",
+    ])
+    .stderr(output_includes("--url"));
+    Ok(())
+}
+
+#[test]
+fn test_mirror_update_snapshots_the_mirror() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "mirror",
+            "update",
+            "--name",
+            "nodesource",
+            "--suffix",
+            "2026-10-16",
+        ])
+        .assert()
+        .success();
+
+    let invocations: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("mirror") || line.starts_with("snapshot"))
+        .collect();
+    assert_eq!(
+        invocations,
+        [
+            "mirror update nodesource",
+            "snapshot create snap-mirror-nodesource-2026-10-16 from mirror nodesource",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_mirror_update_does_not_snapshot_after_a_failure() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"mirror update\" ]; then echo 'mirror not found' >&2; exit 1; fi",
+    )?;
+
+    stub.bellhop_command()
+        .args(["mirror", "update", "--name", "nodesource"])
+        .assert()
+        .failure()
+        .stderr(output_includes("aptly mirror update nodesource"));
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("snapshot create"))
+    );
+    Ok(())
+}
+
+#[test]
+#[ignore]
+fn test_mirror_nodesource() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "mirror",
+        "create",
+        "--name",
+        "nodesource",
+        "--url",
+        NODESOURCE_URL,
+        "--distribution",
+        "nodistro",
+        "--components",
+        "main",
+        "--architectures",
+        "amd64",
+    ]);
+    cmd.assert().success();
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "mirror",
+        "update",
+        "--name",
+        "nodesource",
+        "--suffix",
+        "seed",
+    ]);
+    cmd.assert().success();
+
+    assert!(ctx.snapshot_exists("snap-mirror-nodesource-seed")?);
+    assert!(ctx.snapshot_package_count("snap-mirror-nodesource-seed")? > 0);
+    Ok(())
+}
@@ -0,0 +1,157 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs::File;
+use tar::{Builder, Header};
+use tempfile::TempDir;
+use test_helpers::*;
+
+/// A stub whose repository already has rabbitmq-server 4.1.5-1
+fn stub_with_existing_version() -> Result<AptlyStub, Box<dyn Error>> {
+    AptlyStub::with_script(
+        "if [ \"$1\" = repo ] && [ \"$2\" = search ]; then echo rabbitmq-server_4.1.5-1_all; fi",
+    )
+}
+
+fn add_newer_only(stub: &AptlyStub, filename: &str) {
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--newer-only",
+            "-p",
+        ])
+        .arg(test_package_path(filename))
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_newer_only_adds_a_newer_version() -> Result<(), Box<dyn Error>> {
+    let stub = stub_with_existing_version()?;
+    add_newer_only(&stub, "rabbitmq-server_4.1.7-1_all.deb");
+
+    let invocations = stub.invocations()?;
+    assert!(invocations.contains(
+        &"repo search repo-rabbitmq-server-bookworm Name (= rabbitmq-server)".to_string()
+    ));
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_newer_only_skips_an_older_version() -> Result<(), Box<dyn Error>> {
+    let stub = stub_with_existing_version()?;
+    add_newer_only(&stub, "rabbitmq-server_4.1.3-1_all.deb");
+
+    assert!(stub.invocations_starting_with("repo add")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_newer_only_skips_the_same_version() -> Result<(), Box<dyn Error>> {
+    let stub = stub_with_existing_version()?;
+    add_newer_only(&stub, "rabbitmq-server_4.1.5-1_all.deb");
+
+    assert!(stub.invocations_starting_with("repo add")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_newer_only_adds_to_an_empty_repository() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1\" = repo ] && [ \"$2\" = search ]; then echo 'ERROR: no results' >&2; exit 1; fi",
+    )?;
+    add_newer_only(&stub, "rabbitmq-server_4.1.3-1_all.deb");
+
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_without_newer_only_the_repository_is_not_searched() -> Result<(), Box<dyn Error>> {
+    let stub = stub_with_existing_version()?;
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo search"))
+    );
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_newer_only_compares_versions_per_architecture() -> Result<(), Box<dyn Error>> {
+    // the repository already has the amd64 package
+    let stub = AptlyStub::with_script(
+        r#"if [ "$1 $2" = "repo search" ]; then
+  case "$*" in
+    *arm64*) ;;
+    *) echo rabbitmqadmin_2.0.0-1_amd64 ;;
+  esac
+fi
+"#,
+    )?;
+    let temp_dir = TempDir::new()?;
+    let archive_path = temp_dir.path().join("rabbitmqadmin-2.0.0.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    for name in [
+        "rabbitmqadmin_2.0.0-1_amd64.deb",
+        "rabbitmqadmin_2.0.0-1_arm64.deb",
+    ] {
+        let data = minimal_deb();
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, &data[..])?;
+    }
+    builder.finish()?;
+
+    stub.bellhop_command()
+        .args([
+            "cli-tools",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--newer-only",
+            "-p",
+        ])
+        .arg(&archive_path)
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    assert!(invocations.contains(
+        &"repo search repo-rabbitmq-cli-bookworm Name (= rabbitmqadmin), ($Architecture (= arm64) | $Architecture (= all))".to_string()
+    ), "{invocations:?}");
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1, "Unexpected repo adds: {adds:?}");
+    assert!(adds[0].ends_with("_arm64.deb"));
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const REPO: &str = "repo-rabbitmq-server-bookworm";
+
+#[test]
+fn test_add_with_no_snapshot_only_adds_to_the_repo() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--no-snapshot",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    assert!(invocations.iter().any(|line| line.starts_with("repo add")));
+    assert!(
+        !invocations.iter().any(|line| line.starts_with("snapshot")),
+        "No snapshot commands expected: {invocations:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_no_snapshot_conflicts_with_suffix() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--no-snapshot",
+        "--suffix",
+        "v1",
+        "-p",
+        "rabbitmq-server_4.1.3-1_all.deb",
+    ])
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_print_plan_with_no_snapshot_has_no_snapshot_steps() -> Result<(), Box<dyn Error>> {
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let assert = Command::new(cargo::cargo_bin!("bellhop"))
+        .args([
+            "--print-plan",
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--repo-only",
+            "-p",
+        ])
+        .arg(&package_path)
+        .assert()
+        .success()
+        .stdout(output_includes("aptly repo add"));
+
+    let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+    assert!(
+        !stdout.contains("aptly snapshot"),
+        "Unexpected plan: {stdout}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_with_no_snapshot_to_aptly() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--no-snapshot",
+        "-p",
+    ])
+    .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"));
+    cmd.assert().success();
+
+    assert!(ctx.package_exists(REPO, "rabbitmq-server (= 4.1.3-1)")?);
+    assert!(
+        ctx.list_snapshots("snap-rabbitmq-server-bookworm")?
+            .is_empty(),
+        "No snapshot should be taken"
+    );
+    Ok(())
+}
@@ -0,0 +1,228 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+use test_helpers::*;
+
+fn renamed_package(dir: &TempDir) -> Result<PathBuf, Box<dyn Error>> {
+    let path = dir.path().join("rabbitmq-server-nightly.deb");
+    fs::copy(test_package_path("rabbitmq-server_4.1.3-1_all.deb"), &path)?;
+    Ok(path)
+}
+
+fn searches(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo search"))
+        .collect())
+}
+
+#[test]
+fn test_add_if_not_present_uses_the_given_name_and_version() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let dir = TempDir::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--if-not-present",
+            "--package-name",
+            "rabbitmq-server",
+            "--version",
+            "4.1.3-1",
+            "-p",
+        ])
+        .arg(renamed_package(&dir)?)
+        .assert()
+        .success();
+
+    assert_eq!(
+        searches(&stub)?,
+        vec![
+            "repo search repo-rabbitmq-server-bookworm Name (= rabbitmq-server), Version (= 4.1.3-1), $Architecture (= all)"
+                .to_string()
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_newer_only_uses_the_given_name() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1\" = repo ] && [ \"$2\" = search ]; then echo rabbitmq-server_4.1.5-1_all; fi",
+    )?;
+    let dir = TempDir::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--newer-only",
+            "--package-name",
+            "rabbitmq-server",
+            "--version",
+            "4.1.3-1",
+            "-p",
+        ])
+        .arg(renamed_package(&dir)?)
+        .assert()
+        .success();
+
+    assert_eq!(
+        searches(&stub)?,
+        vec!["repo search repo-rabbitmq-server-bookworm Name (= rabbitmq-server)".to_string()]
+    );
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo add"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_rejects_a_package_name_without_a_version() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let dir = TempDir::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--package-name",
+            "rabbitmq-server",
+            "-p",
+        ])
+        .arg(renamed_package(&dir)?)
+        .assert()
+        .failure()
+        .code(64);
+    assert!(stub.invocations()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_add_rejects_a_version_without_a_package_name() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let dir = TempDir::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--version",
+            "4.1.3-1",
+            "-p",
+        ])
+        .arg(renamed_package(&dir)?)
+        .assert()
+        .failure()
+        .code(64);
+    Ok(())
+}
+
+#[test]
+fn test_add_rejects_an_identity_for_multiple_packages() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--package-name",
+            "rabbitmq-server",
+            "--version",
+            "4.1.3-1",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .arg("-p")
+        .arg(test_package_path("rabbitmq-server_4.1.7-1_all.deb"))
+        .assert()
+        .failure()
+        .code(64)
+        .stderr(predicates::str::contains("describe a single package"));
+    Ok(())
+}
+
+#[test]
+fn test_remove_with_an_explicit_name_and_version() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm",
+            "--package-name",
+            "rabbitmq-server",
+            "--version",
+            "4.1.3-1",
+        ])
+        .assert()
+        .success();
+
+    assert!(stub.invocations()?.contains(
+        &"repo remove repo-rabbitmq-server-bookworm Name (= rabbitmq-server), Version (= 4.1.3-1)"
+            .to_string()
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_remove_rejects_a_package_name_without_a_version() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm",
+            "--package-name",
+            "rabbitmq-server",
+        ])
+        .assert()
+        .failure()
+        .code(64);
+    assert!(stub.invocations()?.is_empty());
+    Ok(())
+}
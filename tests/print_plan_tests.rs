@@ -0,0 +1,193 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use tempfile::TempDir;
+use test_helpers::*;
+
+/// A bellhop command that cannot find aptly
+fn bellhop_without_aptly() -> Result<(Command, TempDir), Box<dyn Error>> {
+    let empty_dir = TempDir::new()?;
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("PATH", empty_dir.path());
+    cmd.env_remove("APTLY_BIN");
+    cmd.env_remove("APTLY_CONFIG");
+    Ok((cmd, empty_dir))
+}
+
+#[test]
+fn test_aptly_is_required_without_print_plan() -> Result<(), Box<dyn Error>> {
+    let (mut cmd, _dir) = bellhop_without_aptly()?;
+    cmd.args(["rabbitmq", "deb", "publish", "-d", "bookworm"])
+        .assert()
+        .failure()
+        .stderr(output_includes("aptly executable not found"));
+    Ok(())
+}
+
+#[test]
+fn test_print_plan_for_add_without_aptly() -> Result<(), Box<dyn Error>> {
+    let (mut cmd, _dir) = bellhop_without_aptly()?;
+    cmd.args([
+        "--print-plan",
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "-p",
+    ])
+    .arg(test_fixture_path("archives/rabbitmq-multi.zip"))
+    .assert()
+    .success()
+    .stdout(output_includes(
+        "aptly repo add -architectures=amd64,arm64,armel,armhf,i386 repo-rabbitmq-server-bookworm rabbitmq-server_4.1.3-1_all.deb",
+    ))
+    .stdout(output_includes(
+        "repo-rabbitmq-server-bookworm rabbitmq-server_4.1.5-1_all.deb",
+    ))
+    .stdout(output_includes(
+        "aptly snapshot create snap-rabbitmq-server-bookworm-v1 from repo repo-rabbitmq-server-bookworm",
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_for_remove_without_aptly() -> Result<(), Box<dyn Error>> {
+    let (mut cmd, _dir) = bellhop_without_aptly()?;
+    cmd.args([
+        "erlang",
+        "deb",
+        "remove",
+        "-V",
+        "1:27.3.4.6-1",
+        "--package-name",
+        "erlang-base",
+        "-d",
+        "trixie",
+        "--dry-run",
+    ])
+    .assert()
+    .success()
+    .stdout(output_includes(
+        "aptly repo remove repo-rabbitmq-erlang-trixie 'Name (= erlang-base), Version (= 1:27.3.4.6-1)'",
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_print_plan_for_publish_without_aptly() -> Result<(), Box<dyn Error>> {
+    let (mut cmd, _dir) = bellhop_without_aptly()?;
+    cmd.args([
+        "--print-plan",
+        "rabbitmq",
+        "deb",
+        "publish",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+    ])
+    .assert()
+    .success()
+    .stdout(output_includes(
+        "aptly publish switch bookworm rabbitmq-server/debian/bookworm snap-rabbitmq-server-bookworm-v1",
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_print_plan_for_publish_with_cleanup_previous() -> Result<(), Box<dyn Error>> {
+    let (mut cmd, _dir) = bellhop_without_aptly()?;
+    cmd.args([
+        "--print-plan",
+        "rabbitmq",
+        "deb",
+        "publish",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "--cleanup-previous",
+    ])
+    .assert()
+    .success()
+    .stdout(output_includes(
+        "aptly snapshot drop -force <the snapshot previously published at rabbitmq-server/debian/bookworm>",
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_print_plan_for_snapshot_commands_without_aptly() -> Result<(), Box<dyn Error>> {
+    let (mut take, _dir) = bellhop_without_aptly()?;
+    take.args([
+        "--print-plan",
+        "cli-tools",
+        "snapshot",
+        "take",
+        "-d",
+        "noble",
+        "--suffix",
+        "v1",
+    ])
+    .assert()
+    .success()
+    .stdout(output_includes(
+        "aptly snapshot create snap-rabbitmq-cli-noble-v1 from repo repo-rabbitmq-cli-noble",
+    ));
+
+    let (mut delete, _dir) = bellhop_without_aptly()?;
+    delete
+        .args([
+            "--print-plan",
+            "cli-tools",
+            "snapshot",
+            "delete",
+            "-d",
+            "noble",
+            "--suffix",
+            "v1",
+        ])
+        .assert()
+        .success()
+        .stdout(output_includes(
+            "aptly snapshot drop -force snap-rabbitmq-cli-noble-v1",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_print_plan_rejects_unsupported_commands() -> Result<(), Box<dyn Error>> {
+    let (mut cmd, _dir) = bellhop_without_aptly()?;
+    cmd.args([
+        "--print-plan",
+        "rabbitmq",
+        "snapshot",
+        "list",
+        "-d",
+        "bookworm",
+    ])
+    .assert()
+    .failure()
+    .code(64)
+    .stderr(output_includes("not supported for 'snapshot list'"));
+    Ok(())
+}
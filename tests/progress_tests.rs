@@ -0,0 +1,56 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use bellhop::progress;
+use std::error::Error;
+use test_helpers::*;
+
+fn has_progress_control_chars(output: &[u8]) -> bool {
+    output.iter().any(|&b| b == b'\r' || b == 0x1b)
+}
+
+#[test]
+fn test_quiet_disables_progress_bars() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let archive_path = test_fixture_path("archives/rabbitmq-multi.zip");
+
+    let output = stub
+        .bellhop_command()
+        .args(["--quiet", "rabbitmq", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(&archive_path)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    assert!(!has_progress_control_chars(&output.stderr));
+    assert!(!has_progress_control_chars(&output.stdout));
+    Ok(())
+}
+
+#[test]
+fn test_quiet_is_accepted_after_subcommands() -> Result<(), Box<dyn Error>> {
+    run_bellhop_succeeds(["rabbitmq", "deb", "add", "--quiet", "--help"]);
+    Ok(())
+}
+
+#[test]
+fn test_set_quiet_disables_progress() {
+    progress::set_quiet(true);
+    assert!(!progress::is_enabled());
+    assert!(progress::bytes_bar(1024, "asset").is_hidden());
+    assert!(progress::entries_bar(3, "Extracting").is_hidden());
+}
@@ -0,0 +1,207 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const REPO: &str = "repo-rabbitmq-server-bookworm";
+
+fn bellhop(ctx: &AptlyTestContext, args: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(args);
+    cmd
+}
+
+fn seed_three_versions() -> Result<AptlyTestContext, Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+    for (filename, suffix) in [
+        ("rabbitmq-server_4.1.3-1_all.deb", "v1"),
+        ("rabbitmq-server_4.1.7-1_all.deb", "v2"),
+        ("rabbitmq-server_4.1.4-1_all.deb", "v3"),
+    ] {
+        let path = test_package_path(filename);
+        bellhop(
+            &ctx,
+            &[
+                "rabbitmq",
+                "deb",
+                "add",
+                "-p",
+                path.to_str().unwrap(),
+                "-d",
+                "bookworm",
+                "--suffix",
+                suffix,
+            ],
+        )
+        .assert()
+        .success();
+    }
+    Ok(ctx)
+}
+
+#[test]
+fn test_promote_latest_publishes_only_the_newest_version() -> Result<(), Box<dyn Error>> {
+    let ctx = seed_three_versions()?;
+
+    bellhop(
+        &ctx,
+        &[
+            "rabbitmq",
+            "deb",
+            "promote-latest",
+            "-d",
+            "bookworm",
+            "--suffix",
+            "promoted",
+        ],
+    )
+    .assert()
+    .success();
+
+    let snapshot = "snap-rabbitmq-server-bookworm-promoted";
+    assert!(ctx.published_snapshot_is_active("rabbitmq-server", "debian", "bookworm", snapshot)?);
+    assert_eq!(ctx.snapshot_package_count(snapshot)?, 1);
+    assert!(ctx.snapshot_package_exists(snapshot, "rabbitmq-server (= 4.1.7-1)")?);
+
+    // Without --remove-older the repository itself is left alone
+    assert!(ctx.package_exists(REPO, "rabbitmq-server (= 4.1.3-1)")?);
+    assert!(ctx.package_exists(REPO, "rabbitmq-server (= 4.1.4-1)")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_promote_latest_can_remove_older_versions() -> Result<(), Box<dyn Error>> {
+    let ctx = seed_three_versions()?;
+
+    bellhop(
+        &ctx,
+        &[
+            "rabbitmq",
+            "deb",
+            "promote-latest",
+            "-d",
+            "bookworm",
+            "--suffix",
+            "promoted",
+            "--remove-older",
+        ],
+    )
+    .assert()
+    .success();
+
+    assert!(ctx.package_exists(REPO, "rabbitmq-server (= 4.1.7-1)")?);
+    assert!(!ctx.package_exists(REPO, "rabbitmq-server (= 4.1.3-1)")?);
+    assert!(!ctx.package_exists(REPO, "rabbitmq-server (= 4.1.4-1)")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_promote_latest_fails_for_an_empty_repository() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+
+    bellhop(
+        &ctx,
+        &["rabbitmq", "deb", "promote-latest", "-d", "bookworm"],
+    )
+    .assert()
+    .failure()
+    .stderr(output_includes("No packages to promote"));
+
+    Ok(())
+}
+
+#[test]
+fn test_promote_latest_filters_by_dpkg_version_order() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        r#"if [ "$1 $2" = "repo search" ]; then
+  printf 'rabbitmq-server_4.1.7-1_all\nrabbitmq-server_4.1.10-1_all\nrabbitmq-server_4.1.10~rc.1-1_all\n'
+fi"#,
+    )?;
+
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "promote-latest",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "promoted",
+    ]);
+    cmd.assert().success();
+
+    let invocations = stub.invocations()?;
+    assert!(invocations.contains(
+        &"snapshot filter snap-rabbitmq-server-bookworm-promoted-bellhop-repo snap-rabbitmq-server-bookworm-promoted (Name (= rabbitmq-server), Version (= 4.1.10-1))"
+            .to_string()
+    ));
+    assert!(
+        invocations
+            .iter()
+            .any(|i| i.starts_with("publish snapshot"))
+    );
+    assert!(!invocations.iter().any(|i| i.starts_with("repo remove")));
+
+    Ok(())
+}
+
+#[test]
+fn test_promote_latest_switches_a_customized_publication() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        r#"if [ "$1 $2" = "repo search" ]; then
+  echo rabbitmq-server_4.1.3-1_all
+  echo rabbitmq-server_4.1.7-1_all
+fi
+if [ "$1 $2" = "publish list" ]; then
+  echo '  * hotfix/stable [amd64] publishes {rabbitmq: [snap-rabbitmq-server-bookworm-v1]}'
+fi
+"#,
+    )?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args(["rabbitmq", "deb", "promote-latest", "-d", "bookworm"])
+        .args(["--suffix", "promoted", "--publish-prefix", "hotfix"])
+        .args([
+            "--distribution-name",
+            "bookworm=stable",
+            "--component",
+            "rabbitmq",
+        ])
+        .assert()
+        .success();
+
+    let publishes: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("publish snapshot") || line.starts_with("publish switch"))
+        .collect();
+    assert_eq!(
+        publishes,
+        [
+            "publish switch -gpg-key=TESTKEY -component=rabbitmq stable hotfix snap-rabbitmq-server-bookworm-promoted"
+        ]
+    );
+    Ok(())
+}
@@ -86,7 +86,7 @@ proptest! {
         project in project_strategy(),
         dist in distribution_alias_strategy()
     ) {
-        let path = bellhop::aptly::rel_path_with_prefix(&project, &dist);
+        let path = bellhop::aptly::rel_path_with_prefix(&project, &dist, dist.family());
         let parts: Vec<&str> = path.split('/').collect();
         prop_assert_eq!(parts.len(), 3);
         prop_assert!(!path.starts_with('/'));
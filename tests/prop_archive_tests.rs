@@ -50,9 +50,24 @@ proptest! {
     ) {
         let version_str = format!("{epoch}:{version}-{revision}");
         let filename = format!("{name}_{version_str}_{arch}.deb");
-        match extract_version_from_filename(&filename) {
-            Ok(v) => prop_assert_eq!(v, version_str),
-            Err(_) => {}
+        if let Ok(v) = extract_version_from_filename(&filename) {
+            prop_assert_eq!(v, version_str);
         }
     }
+
+    #[test]
+    fn percent_encoded_epochs_are_decoded(
+        name in "[a-z][a-z0-9-]{2,10}",
+        epoch in 0u32..10,
+        version in "[0-9][0-9.]*",
+        revision in 0u32..10,
+        encoded_colon in prop_oneof!["%3a", "%3A"],
+        arch in prop_oneof!["amd64", "all"]
+    ) {
+        let filename = format!("{name}_{epoch}{encoded_colon}{version}-{revision}_{arch}.deb");
+        prop_assert_eq!(
+            extract_version_from_filename(&filename).unwrap(),
+            format!("{epoch}:{version}-{revision}")
+        );
+    }
 }
@@ -228,3 +228,803 @@ fn test_first_time_publish() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+const PUBLISHED: &str = "  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}";
+
+fn publish_invocations(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("publish switch") || line.starts_with("publish snapshot"))
+        .collect())
+}
+
+#[test]
+fn test_publish_passes_force_overwrite_to_aptly() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm", "noble"])
+        .args(["--suffix", "v1", "--force-overwrite"])
+        .assert()
+        .success();
+
+    let publishes = publish_invocations(&stub)?;
+    assert_eq!(publishes.len(), 2);
+    assert!(
+        publishes
+            .iter()
+            .all(|line| line.contains(" -force-overwrite "))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_does_not_force_overwrite_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .success();
+
+    let publishes = publish_invocations(&stub)?;
+    assert_eq!(publishes.len(), 1);
+    assert!(!publishes[0].contains("-force-overwrite"));
+    Ok(())
+}
+
+#[test]
+fn test_failed_initial_publish_names_the_distribution() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish snapshot\" ]; then echo 'file already exists and is different' >&2; exit 1; fi",
+    )?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .code(70)
+        .stderr(output_includes(
+            "Failed to publish 'bookworm' (initial publish)",
+        ))
+        .stderr(output_includes("file already exists and is different"));
+    Ok(())
+}
+
+#[test]
+fn test_failed_switch_names_the_distribution() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi\nif [ \"$1 $2\" = \"publish switch\" ]; then exit 1; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .assert()
+        .code(70)
+        .stderr(output_includes(
+            "Failed to publish 'bookworm' (switching the existing publication)",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_publish_to_an_s3_endpoint() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .args(["--publish-endpoint", "myendpoint"])
+        .assert()
+        .success();
+
+    // the local publication at the same path must not be mistaken for the S3 one
+    assert_eq!(
+        publish_invocations(&stub)?,
+        [
+            "publish snapshot -distribution bookworm -gpg-key=TESTKEY snap-rabbitmq-server-bookworm-v1 s3:myendpoint:rabbitmq-server/debian/bookworm"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_with_the_suffix_used_by_add() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v2", "-p",
+    ])
+    .arg(&package_path);
+    cmd.assert().success();
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v2",
+    ]);
+    cmd.assert().success();
+
+    assert!(
+        ctx.published_snapshot_is_active(
+            "rabbitmq-server",
+            "debian",
+            "bookworm",
+            "snap-rabbitmq-server-bookworm-v2"
+        )?,
+        "The v2 snapshot created by add should be published"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_and_publish_resolve_the_same_suffix() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v2", "-p",
+        ])
+        .arg(&package_path)
+        .assert()
+        .success();
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v2",
+        ])
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations
+            .iter()
+            .any(|line| line.starts_with("snapshot create snap-rabbitmq-server-bookworm-v2 "))
+    );
+    assert!(
+        publish_invocations(&stub)?
+            .iter()
+            .all(|line| line.contains(" snap-rabbitmq-server-bookworm-v2"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_with_publish_makes_the_package_live() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--publish",
+        "-p",
+    ])
+    .arg(&package_path);
+    cmd.assert().success();
+
+    let date = Local::now().format("%d-%b-%y").to_string();
+    let expected_snapshot = format!("snap-rabbitmq-server-bookworm-{date}");
+    assert!(
+        ctx.published_snapshot_is_active(
+            "rabbitmq-server",
+            "debian",
+            "bookworm",
+            &expected_snapshot
+        )?,
+        "{expected_snapshot} should be published by add --publish"
+    );
+
+    let output = Command::new("aptly")
+        .arg(ctx.config_arg())
+        .args(["snapshot", "show", "-with-packages", &expected_snapshot])
+        .output()?;
+    assert!(String::from_utf8_lossy(&output.stdout).contains("rabbitmq-server_4.1.3-1_all"));
+    Ok(())
+}
+
+#[test]
+fn test_add_with_publish_switches_to_the_snapshots_it_took() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm,noble",
+            "--publish",
+            "-p",
+        ])
+        .arg(&package_path)
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    let snapshots: Vec<&str> = invocations
+        .iter()
+        .filter_map(|line| line.strip_prefix("snapshot create "))
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    assert_eq!(snapshots.len(), 2);
+    let publishes = publish_invocations(&stub)?;
+    assert_eq!(publishes.len(), 2);
+    for snapshot in snapshots {
+        assert!(
+            publishes
+                .iter()
+                .any(|line| line.split_whitespace().any(|word| word == snapshot)),
+            "{snapshot} was not published: {publishes:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_add_with_publish_rejects_no_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--publish",
+            "--no-snapshot",
+            "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .failure()
+        .code(64);
+    assert!(publish_invocations(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_add_with_publish_uses_the_publish_flags() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '  * s3:releases:hotfix/stable [amd64] publishes {rabbitmq: [snap-rabbitmq-server-bookworm-v1]}'; fi",
+    )?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v2"])
+        .args(["--publish", "--publish-endpoint", "releases"])
+        .args([
+            "--publish-prefix",
+            "hotfix",
+            "--distribution-name",
+            "bookworm=stable",
+        ])
+        .args(["--component", "rabbitmq", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+
+    assert_eq!(
+        publish_invocations(&stub)?,
+        [
+            "publish switch -gpg-key=TESTKEY -component=rabbitmq stable s3:releases:hotfix snap-rabbitmq-server-bookworm-v2"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_passes_the_component_to_aptly() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{}'; fi",
+        PUBLISHED.replace("{main:", "{rabbitmq:")
+    ))?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm", "noble"])
+        .args(["--suffix", "v1", "--component", "rabbitmq"])
+        .assert()
+        .success();
+
+    let publishes = publish_invocations(&stub)?;
+    assert_eq!(publishes.len(), 2);
+    assert!(publishes[0].starts_with("publish switch"));
+    assert!(publishes[1].starts_with("publish snapshot"));
+    assert!(
+        publishes
+            .iter()
+            .all(|line| line.contains(" -component=rabbitmq "))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_with_component_by_project() -> Result<(), Box<dyn Error>> {
+    for (project, component) in [
+        ("rabbitmq", "rabbitmq"),
+        ("erlang", "erlang"),
+        ("cli-tools", "cli"),
+    ] {
+        let stub = AptlyStub::new()?;
+
+        stub.bellhop_command()
+            .args([project, "deb", "publish", "-d", "noble", "--suffix", "v1"])
+            .arg("--component-by-project")
+            .assert()
+            .success();
+
+        let publishes = publish_invocations(&stub)?;
+        assert_eq!(publishes.len(), 1);
+        assert!(
+            publishes[0].contains(&format!(" -component={component} ")),
+            "{publishes:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_component_overrides_component_by_project() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["erlang", "deb", "publish", "-d", "noble", "--suffix", "v1"])
+        .args(["--component-by-project", "--component", "otp"])
+        .assert()
+        .success();
+
+    let publishes = publish_invocations(&stub)?;
+    assert_eq!(publishes.len(), 1);
+    assert!(publishes[0].contains(" -component=otp "));
+    Ok(())
+}
+
+#[test]
+fn test_switching_a_component_the_publication_lacks_fails() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v2",
+        ])
+        .arg("--component-by-project")
+        .assert()
+        .code(65)
+        .stderr(output_includes("without the 'rabbitmq' component"));
+    assert!(publish_invocations(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_publish_to_a_custom_component() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1", "-p",
+    ])
+    .arg(&package_path);
+    cmd.assert().success();
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "publish",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "--component",
+        "rabbitmq",
+    ]);
+    cmd.assert().success();
+
+    let output = Command::new("aptly")
+        .arg(ctx.config_arg())
+        .args(["publish", "list"])
+        .output()?;
+    let published = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        published.contains("{rabbitmq: [snap-rabbitmq-server-bookworm-v1]"),
+        "Unexpected publications: {published}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_fails_early_for_a_missing_passphrase_file() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .args(["--signing-passphrase-file", "/nonexistent/passphrase"])
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "Signing passphrase file does not exist at /nonexistent/passphrase",
+        ));
+
+    assert!(publish_invocations(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_publish_under_an_overridden_family() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '  * rabbitmq-server/debian/noble/noble [amd64] publishes {main: [snap-rabbitmq-server-noble-v1]}'; fi",
+    )?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm", "noble"])
+        .args(["--suffix", "v2", "--family", "debian"])
+        .assert()
+        .success();
+
+    // noble is already published under the debian prefix, so it is switched
+    assert_eq!(
+        publish_invocations(&stub)?,
+        [
+            "publish snapshot -distribution bookworm -gpg-key=TESTKEY snap-rabbitmq-server-bookworm-v2 rabbitmq-server/debian/bookworm",
+            "publish switch -gpg-key=TESTKEY noble rabbitmq-server/debian/noble snap-rabbitmq-server-noble-v2"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_at_the_root_prefix() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '  * ./bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}'; fi",
+    )?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm", "noble"])
+        .args(["--suffix", "v2", "--publish-prefix", "."])
+        .assert()
+        .success();
+
+    assert_eq!(
+        publish_invocations(&stub)?,
+        [
+            "publish switch -gpg-key=TESTKEY bookworm . snap-rabbitmq-server-bookworm-v2",
+            "publish snapshot -distribution noble -gpg-key=TESTKEY snap-rabbitmq-server-noble-v2 ."
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_rejects_a_prefix_with_traversal() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .args(["--publish-prefix", "../elsewhere"])
+        .assert()
+        .code(65)
+        .stderr(output_includes("Invalid publish prefix '../elsewhere'"));
+
+    assert!(publish_invocations(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_publish_rejects_an_unknown_family() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .args(["--family", "fedora"])
+        .assert()
+        .code(64)
+        .stderr(output_includes("Unsupported Debian family: fedora"));
+    Ok(())
+}
+
+#[test]
+fn test_publish_under_a_mapped_distribution_name() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '  * rabbitmq-server/debian/bookworm/stable [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}'; fi",
+    )?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm", "trixie"])
+        .args(["--suffix", "v2"])
+        .args(["--distribution-name", "bookworm=stable"])
+        .args(["--distribution-name", "trixie=testing"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        publish_invocations(&stub)?,
+        [
+            "publish switch -gpg-key=TESTKEY stable rabbitmq-server/debian/bookworm snap-rabbitmq-server-bookworm-v2",
+            "publish snapshot -distribution testing -gpg-key=TESTKEY snap-rabbitmq-server-trixie-v2 rabbitmq-server/debian/trixie"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_under_an_additional_distribution_name() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1", "-p",
+    ])
+    .arg(&package_path);
+    cmd.assert().success();
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "publish",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "--also-distribution",
+        "stable",
+    ]);
+    cmd.assert().success();
+
+    let output = Command::new("aptly")
+        .arg(ctx.config_arg())
+        .args(["publish", "list"])
+        .output()?;
+    let published = String::from_utf8_lossy(&output.stdout);
+    for location in [
+        "rabbitmq-server/debian/bookworm/bookworm",
+        "rabbitmq-server/debian/bookworm/stable",
+    ] {
+        assert!(
+            published
+                .lines()
+                .any(|line| line.contains(location)
+                    && line.contains("snap-rabbitmq-server-bookworm-v1")),
+            "{location} is not published: {published}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_publish_switches_each_distribution_name_separately() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm"])
+        .args(["--suffix", "v2", "--also-distribution", "stable"])
+        .assert()
+        .success();
+
+    // bookworm is already published and is switched, stable is not yet
+    assert_eq!(
+        publish_invocations(&stub)?,
+        [
+            "publish switch -gpg-key=TESTKEY bookworm rabbitmq-server/debian/bookworm snap-rabbitmq-server-bookworm-v2",
+            "publish snapshot -distribution stable -gpg-key=TESTKEY snap-rabbitmq-server-bookworm-v2 rabbitmq-server/debian/bookworm"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_failed_additional_publication_names_the_distribution() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2 $4\" = \"publish snapshot stable\" ]; then exit 1; fi",
+    )?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm"])
+        .args(["--suffix", "v1", "--also-distribution", "stable"])
+        .assert()
+        .code(70)
+        .stderr(output_includes(
+            "Failed to publish 'stable' (initial publish)",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_publish_rejects_a_mapping_for_an_unknown_distribution() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .args(["--distribution-name", "buster=oldstable"])
+        .assert()
+        .code(64)
+        .stderr(output_includes("Invalid distribution alias: buster"));
+    Ok(())
+}
+
+fn snapshot_drops(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("snapshot drop"))
+        .collect())
+}
+
+#[test]
+fn test_publish_with_cleanup_previous_drops_the_replaced_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v2",
+        ])
+        .arg("--cleanup-previous")
+        .assert()
+        .success();
+
+    assert_eq!(
+        snapshot_drops(&stub)?,
+        ["snapshot drop -force snap-rabbitmq-server-bookworm-v1"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_publish_keeps_the_replaced_snapshot_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v2",
+        ])
+        .assert()
+        .success();
+
+    assert!(snapshot_drops(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_cleanup_previous_never_drops_the_active_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ])
+        .arg("--cleanup-previous")
+        .assert()
+        .success();
+
+    assert!(snapshot_drops(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_cleanup_previous_keeps_a_snapshot_published_elsewhere() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then\n  echo '{PUBLISHED}'\n  echo '  * rabbitmq-server/debian/bookworm/stable [amd64] publishes {{main: [snap-rabbitmq-server-bookworm-v1]}}'\nfi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v2",
+        ])
+        .arg("--cleanup-previous")
+        .assert()
+        .success();
+
+    assert!(snapshot_drops(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_failed_cleanup_does_not_fail_the_publish() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi\nif [ \"$1 $2\" = \"snapshot drop\" ]; then exit 1; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v2",
+        ])
+        .arg("--cleanup-previous")
+        .assert()
+        .success();
+
+    assert_eq!(snapshot_drops(&stub)?.len(), 1);
+    assert_eq!(publish_invocations(&stub)?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_publish_twice_with_cleanup_previous() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    for (suffix, package) in [
+        ("v1", "rabbitmq-server_4.1.3-1_all.deb"),
+        ("v2", "rabbitmq-server_4.1.4-1_all.deb"),
+    ] {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+        cmd.args([
+            "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", suffix, "-p",
+        ])
+        .arg(test_package_path(package));
+        cmd.assert().success();
+
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+        cmd.args([
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", suffix,
+        ])
+        .arg("--cleanup-previous");
+        cmd.assert().success();
+    }
+
+    assert!(!ctx.snapshot_exists("snap-rabbitmq-server-bookworm-v1")?);
+    assert!(ctx.snapshot_exists("snap-rabbitmq-server-bookworm-v2")?);
+    assert!(ctx.published_snapshot_is_active(
+        "rabbitmq-server",
+        "debian",
+        "bookworm",
+        "snap-rabbitmq-server-bookworm-v2"
+    )?);
+    Ok(())
+}
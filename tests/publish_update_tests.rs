@@ -0,0 +1,141 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const PUBLISHED: &str = "  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}";
+
+fn publish_invocations(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("publish ") && !line.starts_with("publish list"))
+        .collect())
+}
+
+#[test]
+fn test_update_only_keeps_the_published_snapshot() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let bellhop = |args: &[&str]| {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", &ctx.config_path)
+            .args(["rabbitmq", "deb"])
+            .args(args)
+            .args(["-d", "bookworm"]);
+        cmd
+    };
+    bellhop(&["add", "--suffix", "v1", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .assert()
+        .success();
+    bellhop(&["publish", "--suffix", "v1"]).assert().success();
+    bellhop(&["add", "--suffix", "v2", "-p"])
+        .arg(test_package_path("rabbitmq-server_4.1.4-1_all.deb"))
+        .assert()
+        .success();
+
+    bellhop(&["publish", "--update-only"]).assert().success();
+
+    assert!(ctx.published_snapshot_is_active(
+        "rabbitmq-server",
+        "debian",
+        "bookworm",
+        "snap-rabbitmq-server-bookworm-v1"
+    )?);
+    Ok(())
+}
+
+#[test]
+fn test_update_only_runs_publish_update() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "publish",
+            "-d",
+            "bookworm",
+            "--update-only",
+        ])
+        .env("BELLHOP_GPG_KEY", "ABCD1234")
+        .assert()
+        .success();
+
+    assert_eq!(
+        publish_invocations(&stub)?,
+        ["publish update -gpg-key=ABCD1234 bookworm rabbitmq-server/debian/bookworm"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_update_only_fails_when_nothing_is_published() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "publish",
+            "-d",
+            "bookworm,noble",
+            "--update-only",
+        ])
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "Nothing is published for 'noble' at 'rabbitmq-server/ubuntu/noble'",
+        ));
+
+    assert!(publish_invocations(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_update_only_conflicts_with_cleanup_previous() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm"])
+        .args(["--update-only", "--cleanup-previous"])
+        .assert()
+        .code(64);
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_for_update_only() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm"])
+        .args(["--update-only", "--force-overwrite", "--dry-run"])
+        .assert()
+        .success()
+        .stdout("aptly publish update -force-overwrite bookworm rabbitmq-server/debian/bookworm\n");
+    assert!(stub.invocations()?.is_empty());
+    Ok(())
+}
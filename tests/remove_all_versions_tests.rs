@@ -0,0 +1,167 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const REPO: &str = "repo-rabbitmq-server-bookworm";
+
+fn bellhop(ctx: &AptlyTestContext, args: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn test_remove_all_versions() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+
+    for filename in [
+        "rabbitmq-server_4.1.3-1_all.deb",
+        "rabbitmq-server_4.1.4-1_all.deb",
+    ] {
+        let path = test_package_path(filename);
+        bellhop(
+            &ctx,
+            &[
+                "rabbitmq",
+                "deb",
+                "add",
+                "-d",
+                "bookworm",
+                "-p",
+                path.to_str().unwrap(),
+            ],
+        )
+        .assert()
+        .success();
+    }
+    assert!(ctx.package_exists(REPO, "rabbitmq-server (= 4.1.3-1)")?);
+    assert!(ctx.package_exists(REPO, "rabbitmq-server (= 4.1.4-1)")?);
+
+    bellhop(
+        &ctx,
+        &[
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm",
+            "--all-versions",
+            "rabbitmq-server",
+        ],
+    )
+    .assert()
+    .success();
+
+    assert!(!ctx.package_exists(REPO, "rabbitmq-server (= 4.1.3-1)")?);
+    assert!(!ctx.package_exists(REPO, "rabbitmq-server (= 4.1.4-1)")?);
+    assert!(ctx.list_packages(REPO)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_remove_all_versions_issues_a_name_only_query() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm,noble",
+            "--all-versions",
+            "rabbitmq-server",
+            "--suffix",
+            "v1",
+        ])
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    for rel in ["bookworm", "noble"] {
+        assert!(invocations.contains(&format!(
+            "repo remove repo-rabbitmq-server-{rel} Name (= rabbitmq-server)"
+        )));
+        assert!(invocations.contains(&format!(
+            "snapshot create snap-rabbitmq-server-{rel}-v1 from repo repo-rabbitmq-server-{rel}"
+        )));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_remove_all_versions_rejects_an_invalid_name() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm",
+            "--all-versions",
+            "rabbitmq-server | Name (~ .)",
+        ])
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(output_includes("Invalid package name"));
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo remove"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_all_versions_conflicts_with_version() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "remove",
+        "--all-versions",
+        "rabbitmq-server",
+        "-V",
+        "4.1.3-1",
+    ])
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_all_versions_conflicts_with_package_file_path() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "deb",
+        "remove",
+        "--all-versions",
+        "rabbitmq-server",
+        "-p",
+        "rabbitmq-server_4.1.3-1_all.deb",
+    ])
+    .stderr(output_includes("cannot be used with"));
+    Ok(())
+}
@@ -0,0 +1,184 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const REPO: &str = "repo-rabbitmq-server-bookworm";
+
+fn bellhop(ctx: &AptlyTestContext, args: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(args);
+    cmd
+}
+
+fn publishes(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("publish snapshot") || line.starts_with("publish switch"))
+        .collect())
+}
+
+#[test]
+fn test_remove_with_republish_takes_effect_immediately() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+
+    let path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    bellhop(
+        &ctx,
+        &[
+            "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "before", "-p",
+        ],
+    )
+    .arg(&path)
+    .assert()
+    .success();
+    bellhop(
+        &ctx,
+        &[
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "before",
+        ],
+    )
+    .assert()
+    .success();
+
+    bellhop(
+        &ctx,
+        &[
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm",
+            "--suffix",
+            "after",
+            "-V",
+            "4.1.3-1",
+            "--republish",
+        ],
+    )
+    .assert()
+    .success();
+
+    let snapshot = "snap-rabbitmq-server-bookworm-after";
+    assert!(ctx.published_snapshot_is_active("rabbitmq-server", "debian", "bookworm", snapshot)?);
+    assert_eq!(ctx.snapshot_package_count(snapshot)?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_remove_with_republish_publishes_the_recreated_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "remove",
+            "-d",
+            "bookworm",
+            "--suffix",
+            "after",
+            "-V",
+            "4.1.3-1",
+            "--republish",
+        ])
+        .assert()
+        .success();
+
+    let publishes = publishes(&stub)?;
+    assert_eq!(publishes.len(), 1, "Unexpected publishes: {publishes:?}");
+    assert!(publishes[0].contains("snap-rabbitmq-server-bookworm-after"));
+    Ok(())
+}
+
+#[test]
+fn test_remove_does_not_publish_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "remove", "-d", "bookworm", "-V", "4.1.3-1",
+        ])
+        .assert()
+        .success();
+
+    assert!(publishes(&stub)?.is_empty(), "Nothing should be published");
+    Ok(())
+}
+
+#[test]
+fn test_remove_with_republish_switches_a_customized_publication() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '  * hotfix/stable [amd64] publishes {rabbitmq: [snap-rabbitmq-server-bookworm-before]}'; fi",
+    )?;
+
+    stub.bellhop_command()
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args([
+            "rabbitmq", "deb", "remove", "-d", "bookworm", "-V", "4.1.3-1",
+        ])
+        .args([
+            "--suffix",
+            "after",
+            "--republish",
+            "--publish-prefix",
+            "hotfix",
+        ])
+        .args([
+            "--distribution-name",
+            "bookworm=stable",
+            "--component",
+            "rabbitmq",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        publishes(&stub)?,
+        [
+            "publish switch -gpg-key=TESTKEY -component=rabbitmq stable hotfix snap-rabbitmq-server-bookworm-after"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_remove_with_republish_checks_the_publish_flags_first() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "remove", "-d", "bookworm", "-V", "4.1.3-1",
+        ])
+        .args(["--republish", "--keyring", "/nonexistent/signing.kbx"])
+        .assert()
+        .code(65)
+        .stderr(output_includes("GPG keyring does not exist"));
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo remove"))
+    );
+    Ok(())
+}
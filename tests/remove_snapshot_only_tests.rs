@@ -0,0 +1,173 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const SNAPSHOTS_SCRIPT: &str = r#"if [ "$1 $2" = "snapshot list" ]; then
+  echo snap-rabbitmq-server-bookworm-v1
+  echo snap-rabbitmq-server-noble-v1
+  echo snap-rabbitmq-server-noble-v1-hotfix
+fi
+"#;
+
+fn remove_from_snapshots(stub: &AptlyStub, distributions: &str) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq",
+        "deb",
+        "remove",
+        "-V",
+        "4.1.3-1",
+        "-d",
+        distributions,
+    ])
+    .args(["--snapshot-only", "--suffix", "v1", "--to", "v1-hotfix"]);
+    cmd
+}
+
+fn mutating_invocations(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line != "version" && line != "snapshot list -raw")
+        .collect())
+}
+
+#[test]
+fn test_snapshot_only_removal_keeps_the_version_in_the_repo() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    let repo_name = "repo-rabbitmq-server-bookworm";
+    ctx.create_repo(repo_name)?;
+
+    let bellhop = |args: &[&str]| {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", &ctx.config_path)
+            .args(["rabbitmq", "deb"])
+            .args(args)
+            .args(["-d", "bookworm"]);
+        cmd
+    };
+    for package in [
+        "rabbitmq-server_4.1.3-1_all.deb",
+        "rabbitmq-server_4.1.4-1_all.deb",
+    ] {
+        bellhop(&["add", "--suffix", "v1", "-p"])
+            .arg(test_package_path(package))
+            .assert()
+            .success();
+    }
+
+    bellhop(&["remove", "-V", "4.1.3-1", "--snapshot-only"])
+        .args(["--suffix", "v1", "--to", "v1-hotfix"])
+        .assert()
+        .success();
+
+    let hotfix = "snap-rabbitmq-server-bookworm-v1-hotfix";
+    assert!(!ctx.snapshot_package_exists(hotfix, "rabbitmq-server (= 4.1.3-1)")?);
+    assert!(ctx.snapshot_package_exists(hotfix, "rabbitmq-server (= 4.1.4-1)")?);
+    assert!(ctx.snapshot_package_exists(
+        "snap-rabbitmq-server-bookworm-v1",
+        "rabbitmq-server (= 4.1.3-1)"
+    )?);
+    assert!(ctx.package_exists(repo_name, "rabbitmq-server (= 4.1.3-1)")?);
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_only_removal_filters_the_snapshots() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOTS_SCRIPT)?;
+
+    remove_from_snapshots(&stub, "bookworm").assert().success();
+
+    assert_eq!(
+        mutating_invocations(&stub)?,
+        [
+            "snapshot filter snap-rabbitmq-server-bookworm-v1 snap-rabbitmq-server-bookworm-v1-hotfix !(rabbitmq-server (= 4.1.3-1))"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_only_fails_without_the_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOTS_SCRIPT)?;
+
+    remove_from_snapshots(&stub, "bookworm,trixie")
+        .assert()
+        .code(65)
+        .stderr(output_includes("snap-rabbitmq-server-trixie-v1"));
+
+    assert!(mutating_invocations(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_only_keeps_an_existing_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOTS_SCRIPT)?;
+
+    remove_from_snapshots(&stub, "bookworm,noble")
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "Snapshot 'snap-rabbitmq-server-noble-v1-hotfix' already exists",
+        ));
+
+    assert!(mutating_invocations(&stub)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_only_requires_a_suffix() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "remove", "-V", "4.1.3-1", "-d", "bookworm",
+        ])
+        .args(["--snapshot-only", "--to", "v1-hotfix"])
+        .assert()
+        .code(64);
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_for_snapshot_only_removal() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    remove_from_snapshots(&stub, "bookworm")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout("aptly snapshot filter snap-rabbitmq-server-bookworm-v1 snap-rabbitmq-server-bookworm-v1-hotfix '!(rabbitmq-server (= 4.1.3-1))'\n");
+    assert!(stub.invocations()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_lowercase_v_is_verbose_for_remove() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "remove", "-v", "4.1.3-1", "-d", "bookworm",
+        ])
+        .assert()
+        .code(64);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use bellhop::aptly;
+use bellhop::cli;
+use bellhop::common::Project;
+use bellhop::deb::DistributionAlias;
+use bellhop::errors::BellhopError;
+use std::error::Error;
+use test_helpers::*;
+
+// The prefix is process-wide, so this module must not contain other tests that set it
+#[test]
+fn test_repo_prefix_flows_into_all_derived_names() {
+    aptly::set_repo_prefix("acme-broker").expect("prefix should be valid");
+
+    for project in [Project::RabbitMQ, Project::Erlang, Project::CliTools] {
+        assert_eq!(aptly::project_prefix(&project), "acme-broker");
+        assert_eq!(
+            aptly::repo_name(&project, &DistributionAlias::Bookworm),
+            "repo-acme-broker-bookworm"
+        );
+        assert_eq!(
+            aptly::snapshot_name_with_suffix(&project, &DistributionAlias::Noble, "16-Dec-25"),
+            "snap-acme-broker-noble-16-Dec-25"
+        );
+        assert_eq!(
+            aptly::rel_path_with_prefix(
+                &project,
+                &DistributionAlias::Trixie,
+                DistributionAlias::Trixie.family()
+            ),
+            "acme-broker/debian/trixie"
+        );
+    }
+}
+
+#[test]
+fn test_invalid_repo_prefixes_are_rejected() {
+    for prefix in ["", "acme/broker", "acme broker", "../acme"] {
+        assert!(matches!(
+            aptly::set_repo_prefix(prefix),
+            Err(BellhopError::InvalidRepoPrefix { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_repo_prefix_is_accepted_before_and_after_subcommands() {
+    for args in [
+        &[
+            "bellhop",
+            "rabbitmq",
+            "--repo-prefix",
+            "acme",
+            "deb",
+            "publish",
+            "-a",
+        ][..],
+        &[
+            "bellhop",
+            "rabbitmq",
+            "deb",
+            "publish",
+            "-a",
+            "--repo-prefix",
+            "acme",
+        ][..],
+    ] {
+        let mut matches = cli::parser()
+            .try_get_matches_from(args)
+            .expect("arguments should parse");
+        while let Some((_, sub)) = matches.remove_subcommand() {
+            matches = sub;
+        }
+        assert_eq!(cli::repo_prefix(&matches), Some("acme"));
+    }
+}
+
+#[test]
+fn test_invalid_repo_prefix_fails_with_data_error() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq",
+        "--repo-prefix",
+        "acme/broker",
+        "snapshot",
+        "list",
+        "-d",
+        "bookworm",
+    ])
+    .code(65)
+    .stderr(output_includes("Invalid repository prefix"));
+    Ok(())
+}
@@ -17,6 +17,7 @@ use assert_cmd::assert::OutputAssertExt;
 use assert_cmd::cargo;
 use std::error::Error;
 use std::process::Command;
+use std::str;
 use test_helpers::*;
 
 #[test]
@@ -134,3 +135,83 @@ fn test_repositories_setup_creates_only_missing() -> Result<(), Box<dyn Error>>
 
     Ok(())
 }
+
+const ABSENT: [&str; 2] = ["repo-rabbitmq-erlang-noble", "repo-rabbitmq-server-focal"];
+
+fn expected_repos() -> Vec<String> {
+    let mut repos = Vec::new();
+    for dist in ["noble", "jammy", "focal", "trixie", "bookworm", "bullseye"] {
+        repos.push(format!("repo-rabbitmq-server-{dist}"));
+        repos.push(format!("repo-rabbitmq-cli-{dist}"));
+    }
+    for dist in ["noble", "jammy", "trixie", "bookworm"] {
+        repos.push(format!("repo-rabbitmq-erlang-{dist}"));
+    }
+    repos
+}
+
+fn repository_status(stdout: &[u8]) -> Result<serde_json::Value, Box<dyn Error>> {
+    Ok(serde_json::from_str(str::from_utf8(stdout)?)?)
+}
+
+#[test]
+fn test_repositories_list_reports_missing_repos_as_json() -> Result<(), Box<dyn Error>> {
+    let existing: Vec<String> = expected_repos()
+        .into_iter()
+        .filter(|repo| !ABSENT.contains(&repo.as_str()))
+        .chain(["repo-something-else".to_string()])
+        .collect();
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"repo list\" ]; then printf '%s\\n' {}; fi",
+        existing.join(" ")
+    ))?;
+
+    let output = stub
+        .bellhop_command()
+        .args(["repositories", "list", "--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+
+    let status = repository_status(&output.stdout)?;
+    assert_eq!(status["missing"], serde_json::json!(ABSENT));
+    assert_eq!(status["expected"].as_array().unwrap().len(), 16);
+    assert_eq!(status["present"].as_array().unwrap().len(), 14);
+    assert_eq!(
+        status["unexpected"],
+        serde_json::json!(["repo-something-else"])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_repositories_status_alias_lists_missing_repos() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["repositories", "status"])
+        .assert()
+        .success()
+        .stdout(output_includes("repo-rabbitmq-server-bookworm (missing)"));
+    Ok(())
+}
+
+#[test]
+fn test_repositories_list_after_creating_a_subset() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    for repo in expected_repos() {
+        if !ABSENT.contains(&repo.as_str()) {
+            ctx.create_repo(&repo)?;
+        }
+    }
+
+    let output = Command::new(cargo::cargo_bin!("bellhop"))
+        .env("APTLY_CONFIG", ctx.config_path.to_str().unwrap())
+        .args(["repositories", "list", "--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+
+    let status = repository_status(&output.stdout)?;
+    assert_eq!(status["missing"], serde_json::json!(ABSENT));
+    assert_eq!(status["unexpected"], serde_json::json!([]));
+    Ok(())
+}
@@ -0,0 +1,219 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const SNAPSHOTS_SCRIPT: &str = r#"if [ "$1 $2" = "snapshot list" ]; then
+  echo snap-rabbitmq-server-bookworm-v1
+  echo snap-rabbitmq-server-bookworm-v2
+fi
+if [ "$1 $2" = "publish list" ]; then
+  echo '  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v2]}'
+fi
+"#;
+
+fn rollback(stub: &AptlyStub, to: &str) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.args(["rabbitmq", "deb", "rollback", "-d", "bookworm", "--to", to]);
+    cmd
+}
+
+#[test]
+fn test_rollback_switches_to_the_earlier_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOTS_SCRIPT)?;
+
+    rollback(&stub, "v1").arg("--yes").assert().success();
+
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations.iter().any(|i| i.starts_with("publish switch")
+            && i.ends_with(
+                "bookworm rabbitmq-server/debian/bookworm snap-rabbitmq-server-bookworm-v1"
+            )),
+        "expected a switch to the v1 snapshot, got: {invocations:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rollback_switches_a_customized_publication() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        r#"if [ "$1 $2" = "snapshot list" ]; then
+  echo snap-rabbitmq-server-bookworm-v1
+  echo snap-rabbitmq-server-bookworm-v2
+fi
+if [ "$1 $2" = "publish list" ]; then
+  echo '  * hotfix/bookworm [amd64] publishes {rabbitmq: [snap-rabbitmq-server-bookworm-v2]}'
+fi
+"#,
+    )?;
+
+    rollback(&stub, "v1")
+        .env("BELLHOP_GPG_KEY", "TESTKEY")
+        .args([
+            "--yes",
+            "--publish-prefix",
+            "hotfix",
+            "--component",
+            "rabbitmq",
+        ])
+        .assert()
+        .success();
+
+    let publishes: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("publish snapshot") || line.starts_with("publish switch"))
+        .collect();
+    assert_eq!(
+        publishes,
+        [
+            "publish switch -gpg-key=TESTKEY -component=rabbitmq bookworm hotfix snap-rabbitmq-server-bookworm-v1"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rollback_plan_uses_the_publish_flags() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    rollback(&stub, "v1")
+        .args(["--print-plan", "--publish-prefix", "hotfix"])
+        .assert()
+        .success()
+        .stdout(output_includes(
+            "aptly publish switch bookworm hotfix snap-rabbitmq-server-bookworm-v1",
+        ));
+    assert!(stub.invocations()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_rollback_fails_when_the_snapshot_does_not_exist() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOTS_SCRIPT)?;
+
+    rollback(&stub, "v0")
+        .arg("--yes")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(output_includes(
+            "Snapshot 'snap-rabbitmq-server-bookworm-v0' does not exist",
+        ));
+
+    let invocations = stub.invocations()?;
+    assert!(
+        !invocations.iter().any(|i| i.starts_with("publish")),
+        "nothing should be published, got: {invocations:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rollback_requires_confirmation() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOTS_SCRIPT)?;
+
+    rollback(&stub, "v1")
+        .assert()
+        .failure()
+        .code(64)
+        .stderr(output_includes("not confirmed, pass --yes"));
+
+    assert!(
+        stub.invocations()?
+            .iter()
+            .all(|i| !i.starts_with("publish switch"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rollback_requires_a_suffix() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "rollback", "-d", "bookworm", "--yes"])
+        .assert()
+        .failure()
+        .stderr(output_includes("--to <SUFFIX>"));
+
+    Ok(())
+}
+
+#[test]
+fn test_rollback_restores_the_earlier_publication() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let bellhop = |args: &[&str]| {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+        cmd.args(args);
+        cmd
+    };
+    let add_and_publish = |filename: &str, suffix: &str| {
+        let path = test_package_path(filename);
+        bellhop(&[
+            "rabbitmq",
+            "deb",
+            "add",
+            "-d",
+            "bookworm",
+            "--suffix",
+            suffix,
+            "-p",
+            path.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+        bellhop(&[
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", suffix,
+        ])
+        .assert()
+        .success();
+    };
+
+    add_and_publish("rabbitmq-server_4.1.3-1_all.deb", "a");
+    add_and_publish("rabbitmq-server_4.1.4-1_all.deb", "b");
+    assert!(ctx.published_snapshot_is_active(
+        "rabbitmq-server",
+        "debian",
+        "bookworm",
+        "snap-rabbitmq-server-bookworm-b"
+    )?);
+
+    bellhop(&[
+        "rabbitmq", "deb", "rollback", "-d", "bookworm", "--to", "a", "--yes",
+    ])
+    .assert()
+    .success();
+
+    assert!(ctx.published_snapshot_is_active(
+        "rabbitmq-server",
+        "debian",
+        "bookworm",
+        "snap-rabbitmq-server-bookworm-a"
+    )?);
+
+    Ok(())
+}
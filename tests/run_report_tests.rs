@@ -0,0 +1,143 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use serde_json::{Value, json};
+use std::error::Error;
+use std::process::Output;
+use test_helpers::*;
+
+const FAILING_REPO_ADD_SCRIPT: &str = r#"if [ "$1 $2" = "repo add" ]; then
+  echo "ERROR: unable to add: local repo with name $4 not found" >&2
+  exit 1
+fi
+"#;
+
+fn run_report(output: &Output) -> Result<Value, Box<dyn Error>> {
+    let stderr = String::from_utf8(output.stderr.clone())?;
+    let last_line = stderr.lines().last().ok_or("stderr should not be empty")?;
+    Ok(serde_json::from_str(last_line)?)
+}
+
+#[test]
+fn test_run_report_for_a_snapshot_take() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    let assert = stub
+        .bellhop_command()
+        .args(["rabbitmq", "snapshot", "take", "-d", "bookworm,noble"])
+        .args(["--suffix", "v1", "--json"])
+        .assert()
+        .success();
+
+    let report = run_report(assert.get_output())?;
+    assert_eq!(report["command"], "rabbitmq snapshot take");
+    assert_eq!(report["status"], "succeeded");
+    assert_eq!(report["distributions"], json!(["bookworm", "noble"]));
+    assert_eq!(report["error"], Value::Null);
+    assert_eq!(report["summary"], Value::Null);
+    assert_eq!(report["exit_code"], 0);
+
+    let actions: Vec<&str> = report["actions"]
+        .as_array()
+        .ok_or("actions should be an array")?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    let expected: Vec<String> = stub
+        .invocations()?
+        .iter()
+        .filter(|line| *line != "version")
+        .map(|line| format!("aptly {line}"))
+        .collect();
+    assert_eq!(actions, expected);
+    assert!(actions.contains(
+        &"aptly snapshot create snap-rabbitmq-server-noble-v1 from repo repo-rabbitmq-server-noble"
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_run_report_for_a_failing_add() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(FAILING_REPO_ADD_SCRIPT)?;
+    let deb = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    let assert = stub
+        .bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--json", "-p"])
+        .arg(&deb)
+        .assert()
+        .failure()
+        .code(70);
+
+    let report = run_report(assert.get_output())?;
+    assert_eq!(report["command"], "rabbitmq deb add");
+    assert_eq!(report["status"], "failed");
+    assert_eq!(report["distributions"], json!(["bookworm"]));
+    assert_eq!(report["exit_code"], 70);
+    assert_eq!(report["error"]["error"], "aptly_non_zero_exit");
+    assert_eq!(report["error"]["exit_code"], 70);
+    assert!(report["error"]["message"].as_str().is_some());
+    assert_eq!(report["summary"], Value::Null);
+    let last_invocation = stub
+        .invocations()?
+        .pop()
+        .ok_or("aptly should have been run")?;
+    assert!(last_invocation.starts_with("repo add"));
+    assert_eq!(
+        report["actions"].as_array().and_then(|a| a.last()),
+        Some(&json!(format!("aptly {last_invocation}")))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_run_report_for_an_error_before_any_aptly_command() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    let assert = stub
+        .bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "hamm", "--json"])
+        .assert()
+        .failure()
+        .code(65);
+
+    let report = run_report(assert.get_output())?;
+    assert_eq!(report["status"], "failed");
+    assert_eq!(
+        report["error"],
+        json!({
+            "error": "invalid_distribution",
+            "message": "Invalid distribution alias: hamm",
+            "exit_code": 65,
+        })
+    );
+    assert_eq!(report["distributions"], json!([]));
+    assert_eq!(report["actions"], json!([]));
+    Ok(())
+}
+
+#[test]
+fn test_no_run_report_without_json() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "take", "-d", "bookworm"])
+        .args(["--suffix", "v1", "--quiet"])
+        .assert()
+        .success()
+        .stderr("");
+    Ok(())
+}
@@ -185,7 +185,7 @@ fn test_remove_refuses_to_replace_a_published_stale_snapshot() -> Result<(), Box
     bellhop(
         &ctx,
         &[
-            "rabbitmq", "deb", "remove", "-v", "4.1.3-1", "-d", "bookworm",
+            "rabbitmq", "deb", "remove", "-V", "4.1.3-1", "-d", "bookworm",
         ],
     )
     .assert()
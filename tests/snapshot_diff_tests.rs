@@ -0,0 +1,117 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+/// A stub with two snapshots of bookworm, v2 adds rabbitmq-server 4.1.4-1 and upgrades erlang-base
+fn stub_with_two_snapshots() -> Result<AptlyStub, Box<dyn Error>> {
+    AptlyStub::with_script(
+        r#"if [ "$1 $2" = "snapshot show" ]; then
+  echo "Name: $4"
+  echo "Packages:"
+  echo "  rabbitmq-server_4.1.3-1_all"
+  case "$4" in
+    *-v1) echo "  erlang-base_1:27.3-1_amd64" ;;
+    *-v2) echo "  rabbitmq-server_4.1.4-1_all"; echo "  erlang-base_1:27.3.1-1_amd64" ;;
+  esac
+fi"#,
+    )
+}
+
+#[test]
+fn test_snapshot_diff_lists_the_delta() -> Result<(), Box<dyn Error>> {
+    let stub = stub_with_two_snapshots()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "snapshot", "diff", "-d", "bookworm", "--from", "v1", "--to", "v2",
+        ])
+        .assert()
+        .success()
+        .stdout(output_includes(
+            "snap-rabbitmq-server-bookworm-v1 -> snap-rabbitmq-server-bookworm-v2",
+        ))
+        .stdout(output_includes(
+            "~ rabbitmq-server (all): 4.1.3-1 -> 4.1.3-1, 4.1.4-1",
+        ))
+        .stdout(output_includes(
+            "~ erlang-base (amd64): 1:27.3-1 -> 1:27.3.1-1",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_diff_as_json() -> Result<(), Box<dyn Error>> {
+    let stub = stub_with_two_snapshots()?;
+
+    let assert = stub
+        .bellhop_command()
+        .args([
+            "rabbitmq", "snapshot", "diff", "-d", "bookworm", "--from", "v2", "--to", "v1",
+            "--format", "json",
+        ])
+        .assert()
+        .success();
+
+    let diffs: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    assert_eq!(diffs[0]["distribution"], "bookworm");
+    assert_eq!(diffs[0]["from"], "snap-rabbitmq-server-bookworm-v2");
+    assert_eq!(diffs[0]["added"], serde_json::json!([]));
+    assert_eq!(diffs[0]["removed"], serde_json::json!([]));
+    assert_eq!(diffs[0]["changed"].as_array().map(Vec::len), Some(2));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_diff_requires_both_suffixes() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails([
+        "rabbitmq", "snapshot", "diff", "-d", "bookworm", "--from", "v1",
+    ])
+    .stderr(output_includes("--to"));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_diff_of_real_snapshots() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    for (suffix, package) in [
+        ("v1", "rabbitmq-server_4.1.3-1_all.deb"),
+        ("v2", "rabbitmq-server_4.1.4-1_all.deb"),
+    ] {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+        cmd.args([
+            "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", suffix, "-p",
+        ])
+        .arg(test_package_path(package));
+        cmd.assert().success();
+    }
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "snapshot", "diff", "-d", "bookworm", "--from", "v1", "--to", "v2",
+    ]);
+    cmd.assert().success().stdout(output_includes(
+        "~ rabbitmq-server (all): 4.1.3-1 -> 4.1.3-1, 4.1.4-1",
+    ));
+    Ok(())
+}
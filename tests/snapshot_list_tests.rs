@@ -0,0 +1,168 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use predicates::prelude::*;
+use serde_json::Value;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const SNAPSHOT_SHOW_SCRIPT: &str = r#"if [ "$1 $2" = "snapshot show" ]; then
+  echo "Name: $4"
+  echo "Number of packages: 3"
+  echo "Packages:"
+  echo "  rabbitmq-server_4.1.3-1_all"
+  echo "  rabbitmq-server_4.1.4-1_all"
+  echo "  rabbitmq-server_4.2.0-1_all"
+fi
+"#;
+
+fn list(stub: &AptlyStub, extra: &[&str]) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq", "snapshot", "list", "-d", "bookworm", "--suffix", "v1",
+    ]);
+    cmd.args(extra);
+    cmd
+}
+
+#[test]
+fn test_snapshot_list_prints_all_packages_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOT_SHOW_SCRIPT)?;
+
+    list(&stub, &[])
+        .assert()
+        .success()
+        .stdout(output_includes("Name: snap-rabbitmq-server-bookworm-v1"))
+        .stdout(output_includes("rabbitmq-server_4.1.3-1_all"))
+        .stdout(output_includes("rabbitmq-server_4.2.0-1_all"));
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_list_grep_prints_only_matching_packages() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOT_SHOW_SCRIPT)?;
+
+    list(&stub, &["--grep", "4.1."])
+        .assert()
+        .success()
+        .stdout(output_includes("Name: snap-rabbitmq-server-bookworm-v1"))
+        .stdout(output_includes("rabbitmq-server_4.1.3-1_all"))
+        .stdout(output_includes("rabbitmq-server_4.1.4-1_all"))
+        .stdout(predicate::str::contains("rabbitmq-server_4.2.0-1_all").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_list_grep_filters_json_packages() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOT_SHOW_SCRIPT)?;
+
+    let output = list(&stub, &["--grep", "4.2", "--format", "json"]).output()?;
+    assert!(output.status.success());
+
+    let snapshots: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        snapshots,
+        serde_json::json!([{
+            "distribution": "bookworm",
+            "snapshot": "snap-rabbitmq-server-bookworm-v1",
+            "packages": ["rabbitmq-server_4.2.0-1_all"],
+        }])
+    );
+
+    Ok(())
+}
+
+const DATED_SNAPSHOTS_SCRIPT: &str = r#"case "$1 $2" in
+  "snapshot list")
+    echo snap-rabbitmq-server-bookworm-01-Dec-25
+    echo snap-rabbitmq-server-bookworm-16-Dec-25
+    echo snap-rabbitmq-server-bookworm-02-Jan-26
+    echo snap-rabbitmq-server-bookworm-v1
+    echo snap-rabbitmq-server-noble-16-Dec-25
+    ;;
+  "snapshot show")
+    echo "Name: $4"
+    echo "Number of packages: 1"
+    echo "Packages:"
+    echo "  rabbitmq-server_4.1.3-1_all"
+    ;;
+esac
+"#;
+
+fn listed_snapshots(output: &[u8]) -> Result<Vec<String>, Box<dyn Error>> {
+    let snapshots: Value = serde_json::from_slice(output)?;
+    Ok(snapshots
+        .as_array()
+        .ok_or("expected an array")?
+        .iter()
+        .filter_map(|snapshot| snapshot["snapshot"].as_str().map(String::from))
+        .collect())
+}
+
+#[test]
+fn test_snapshot_list_within_a_date_range() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(DATED_SNAPSHOTS_SCRIPT)?;
+
+    let output = stub
+        .bellhop_command()
+        .args(["rabbitmq", "snapshot", "list", "-d", "bookworm,noble"])
+        .args(["--after", "01-Dec-25", "--before", "2026-01-02"])
+        .args(["--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+
+    assert_eq!(
+        listed_snapshots(&output.stdout)?,
+        [
+            "snap-rabbitmq-server-bookworm-16-Dec-25",
+            "snap-rabbitmq-server-noble-16-Dec-25",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_list_after_a_date_in_text_format() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(DATED_SNAPSHOTS_SCRIPT)?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "list", "-d", "bookworm"])
+        .args(["--after", "2025-12-16"])
+        .assert()
+        .success()
+        .stdout(output_includes(
+            "Name: snap-rabbitmq-server-bookworm-02-Jan-26",
+        ))
+        .stdout(predicate::str::contains("16-Dec-25").not())
+        .stdout(predicate::str::contains("bookworm-v1").not());
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_list_date_range_conflicts_with_suffix() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(DATED_SNAPSHOTS_SCRIPT)?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "list", "-d", "bookworm"])
+        .args(["--suffix", "v1", "--before", "2026-01-02"])
+        .assert()
+        .failure()
+        .code(64);
+    Ok(())
+}
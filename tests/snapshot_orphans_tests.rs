@@ -0,0 +1,120 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use predicates::prelude::*;
+use std::error::Error;
+use test_helpers::*;
+
+/// A mix of the latest dated (current), published and stale snapshots for bookworm
+const STUB_SCRIPT: &str = r#"
+case "$1 $2" in
+  "snapshot list")
+    echo snap-rabbitmq-server-bookworm-01-Dec-25
+    echo snap-rabbitmq-server-bookworm-16-Dec-25
+    echo snap-rabbitmq-server-bookworm-02-Jan-26
+    echo snap-rabbitmq-server-bookworm-v1
+    echo snap-rabbitmq-erlang-bookworm-01-Dec-25
+    ;;
+  "publish list")
+    echo "Published repositories:"
+    echo "  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-16-Dec-25]: Snapshot from local repo [repo-rabbitmq-server-bookworm]}"
+    ;;
+esac
+"#;
+
+#[test]
+fn test_snapshot_orphans_lists_only_stale_snapshots() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(STUB_SCRIPT)?;
+
+    let output = stub
+        .bellhop_command()
+        .args(["rabbitmq", "snapshot", "orphans", "-d", "bookworm"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(
+        String::from_utf8(output)?,
+        "snap-rabbitmq-server-bookworm-01-Dec-25\nsnap-rabbitmq-server-bookworm-v1\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_orphans_json_format() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(STUB_SCRIPT)?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "snapshot", "orphans", "-d", "bookworm", "--format", "json",
+        ])
+        .assert()
+        .success()
+        .stdout(output_includes(
+            r#""name": "snap-rabbitmq-server-bookworm-01-Dec-25""#,
+        ))
+        .stdout(output_includes(r#""distribution": "bookworm""#))
+        .stdout(output_includes("snap-rabbitmq-server-bookworm-v1"))
+        .stdout(output_includes("16-Dec-25").not())
+        .stdout(output_includes("02-Jan-26").not())
+        .stdout(output_includes("rabbitmq-erlang").not());
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_orphans_honors_the_global_json_flag() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(STUB_SCRIPT)?;
+
+    stub.bellhop_command()
+        .args([
+            "--json", "rabbitmq", "snapshot", "orphans", "-d", "bookworm",
+        ])
+        .assert()
+        .success()
+        .stdout(output_includes(
+            r#""name": "snap-rabbitmq-server-bookworm-01-Dec-25""#,
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_orphans_rejects_unknown_format() -> Result<(), Box<dyn Error>> {
+    run_bellhop_fails(["rabbitmq", "snapshot", "orphans", "--format", "yaml"]);
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_orphans_within_a_date_range() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(STUB_SCRIPT)?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "orphans", "-d", "bookworm"])
+        .args(["--before", "2025-12-02"])
+        .assert()
+        .success()
+        .stdout("snap-rabbitmq-server-bookworm-01-Dec-25\n");
+
+    // snapshots without a date suffix are left out of date ranges
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "orphans", "-d", "bookworm"])
+        .args(["--after", "01-Dec-25"])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
@@ -0,0 +1,116 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use serde_json::Value;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const SNAPSHOT_SHOW_SCRIPT: &str = r#"if [ "$1 $2" = "snapshot show" ]; then
+  echo "Name: $4"
+  echo "Created At: 2025-08-04 10:00:00 UTC"
+  echo "Description: Snapshot from local repo [repo-rabbitmq-server-bookworm]"
+  echo "Number of packages: 2"
+  echo "Packages:"
+  echo "  rabbitmq-server_4.1.3-1_all"
+  echo "  rabbitmq-server_4.1.4-1_all"
+fi
+"#;
+
+#[test]
+fn test_snapshot_show_prints_the_named_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOT_SHOW_SCRIPT)?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "show", "snap-pruned-by-hand"])
+        .assert()
+        .success()
+        .stdout(output_includes("Name: snap-pruned-by-hand"))
+        .stdout(output_includes("Number of packages: 2"))
+        .stdout(output_includes("rabbitmq-server_4.1.4-1_all"));
+
+    assert!(
+        stub.invocations()?
+            .contains(&"snapshot show -with-packages snap-pruned-by-hand".to_string())
+    );
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_show_prints_json() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(SNAPSHOT_SHOW_SCRIPT)?;
+
+    let output = stub
+        .bellhop_command()
+        .args(["rabbitmq", "snapshot", "show", "snap-pruned-by-hand"])
+        .args(["--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+
+    let details: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        details,
+        serde_json::json!({
+            "name": "snap-pruned-by-hand",
+            "created_at": "2025-08-04 10:00:00 UTC",
+            "description": "Snapshot from local repo [repo-rabbitmq-server-bookworm]",
+            "packages": ["rabbitmq-server_4.1.3-1_all", "rabbitmq-server_4.1.4-1_all"],
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_show_requires_a_name() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "snapshot", "show"])
+        .assert()
+        .failure()
+        .code(64);
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_show_prints_the_package_count() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1", "-p",
+    ])
+    .arg(&package_path);
+    cmd.assert().success();
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args([
+        "rabbitmq",
+        "snapshot",
+        "show",
+        "snap-rabbitmq-server-bookworm-v1",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(output_includes("Number of packages: 1"))
+        .stdout(output_includes("rabbitmq-server_4.1.3-1_all"));
+    Ok(())
+}
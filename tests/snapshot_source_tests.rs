@@ -0,0 +1,146 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const REPO: &str = "repo-rabbitmq-server-bookworm";
+
+fn bellhop(ctx: &AptlyTestContext, args: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(args);
+    cmd
+}
+
+fn add(ctx: &AptlyTestContext, filename: &str, extra: &[&str]) -> Command {
+    let path = test_package_path(filename);
+    let mut args = vec!["rabbitmq", "deb", "add", "-p", path.to_str().unwrap()];
+    args.extend_from_slice(extra);
+    bellhop(ctx, &args)
+}
+
+fn setup_published_repo() -> Result<AptlyTestContext, Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+    add(
+        &ctx,
+        "rabbitmq-server_4.1.3-1_all.deb",
+        &["-d", "bookworm", "--suffix", "v1"],
+    )
+    .assert()
+    .success();
+    bellhop(
+        &ctx,
+        &[
+            "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+        ],
+    )
+    .assert()
+    .success();
+    Ok(ctx)
+}
+
+#[test]
+fn test_snapshot_from_published_keeps_published_packages() -> Result<(), Box<dyn Error>> {
+    let ctx = setup_published_repo()?;
+
+    // With the package gone from the repository, only the published snapshot still has it
+    ctx.remove_packages(REPO, "rabbitmq-server (= 4.1.3-1)")?;
+
+    add(
+        &ctx,
+        "rabbitmq-server_4.1.4-1_all.deb",
+        &[
+            "-d",
+            "bookworm",
+            "--suffix",
+            "v2",
+            "--snapshot-from",
+            "published",
+        ],
+    )
+    .assert()
+    .success();
+
+    let snapshot = "snap-rabbitmq-server-bookworm-v2";
+    assert!(
+        ctx.snapshot_package_exists(snapshot, "rabbitmq-server (= 4.1.3-1)")?,
+        "The previously published package should be in the new snapshot"
+    );
+    assert!(
+        ctx.snapshot_package_exists(snapshot, "rabbitmq-server (= 4.1.4-1)")?,
+        "The newly added package should be in the new snapshot"
+    );
+    assert!(
+        !ctx.snapshot_exists("snap-rabbitmq-server-bookworm-v2-bellhop-repo")?,
+        "The intermediate repository snapshot should be cleaned up"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_from_repo_drops_removed_packages() -> Result<(), Box<dyn Error>> {
+    let ctx = setup_published_repo()?;
+
+    ctx.remove_packages(REPO, "rabbitmq-server (= 4.1.3-1)")?;
+
+    add(
+        &ctx,
+        "rabbitmq-server_4.1.4-1_all.deb",
+        &["-d", "bookworm", "--suffix", "v2"],
+    )
+    .assert()
+    .success();
+
+    let snapshot = "snap-rabbitmq-server-bookworm-v2";
+    assert!(!ctx.snapshot_package_exists(snapshot, "rabbitmq-server (= 4.1.3-1)")?);
+    assert!(ctx.snapshot_package_exists(snapshot, "rabbitmq-server (= 4.1.4-1)")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_from_published_falls_back_to_repo_when_unpublished() -> Result<(), Box<dyn Error>>
+{
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo(REPO)?;
+
+    add(
+        &ctx,
+        "rabbitmq-server_4.1.3-1_all.deb",
+        &[
+            "-d",
+            "bookworm",
+            "--suffix",
+            "v1",
+            "--snapshot-from",
+            "published",
+        ],
+    )
+    .assert()
+    .success();
+
+    assert!(ctx.snapshot_package_exists(
+        "snap-rabbitmq-server-bookworm-v1",
+        "rabbitmq-server (= 4.1.3-1)"
+    )?);
+
+    Ok(())
+}
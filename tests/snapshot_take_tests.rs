@@ -0,0 +1,161 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::Command;
+use test_helpers::*;
+
+const PUBLISHED_SCRIPT: &str = r#"if [ "$1 $2" = "publish list" ]; then
+  echo '  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}'
+fi
+"#;
+
+fn take_from_published(stub: &AptlyStub) -> Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq",
+        "snapshot",
+        "take",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "bookmark",
+        "--from-published",
+    ]);
+    cmd
+}
+
+#[test]
+fn test_take_from_published_copies_the_published_snapshot() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(PUBLISHED_SCRIPT)?;
+
+    take_from_published(&stub).assert().success();
+
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations.iter().any(|i| i
+            == "snapshot merge snap-rabbitmq-server-bookworm-bookmark snap-rabbitmq-server-bookworm-v1"),
+        "expected a copy of the published snapshot, got: {invocations:?}"
+    );
+    assert!(
+        !invocations.iter().any(|i| i.starts_with("snapshot create")),
+        "the repository should not be snapshotted, got: {invocations:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_take_from_published_fails_when_nothing_is_published() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    take_from_published(&stub)
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(output_includes("Nothing is published for 'bookworm'"));
+
+    let invocations = stub.invocations()?;
+    assert!(
+        !invocations.iter().any(|i| i.starts_with("snapshot merge")),
+        "no snapshot should be taken, got: {invocations:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_take_from_published_conflicts_with_snapshot_from() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    take_from_published(&stub)
+        .args(["--snapshot-from", "published"])
+        .assert()
+        .failure()
+        .stderr(output_includes("cannot be used with"));
+
+    Ok(())
+}
+
+#[test]
+fn test_take_from_published_dry_run_lists_the_copy() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+
+    take_from_published(&stub)
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(output_includes(
+            "aptly snapshot merge snap-rabbitmq-server-bookworm-bookmark <the snapshot published at rabbitmq-server/debian/bookworm/bookworm>",
+        ));
+
+    assert!(stub.invocations()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_take_from_published_with_aptly() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let bellhop = |args: &[&str]| {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+        cmd.args(args);
+        cmd
+    };
+
+    let path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    bellhop(&[
+        "rabbitmq",
+        "deb",
+        "add",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "-p",
+        path.to_str().unwrap(),
+    ])
+    .assert()
+    .success();
+    bellhop(&[
+        "rabbitmq", "deb", "publish", "-d", "bookworm", "--suffix", "v1",
+    ])
+    .assert()
+    .success();
+    bellhop(&[
+        "rabbitmq",
+        "snapshot",
+        "take",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "bookmark",
+        "--from-published",
+    ])
+    .assert()
+    .success();
+
+    assert!(ctx.snapshot_package_exists(
+        "snap-rabbitmq-server-bookworm-bookmark",
+        "rabbitmq-server (= 4.1.3-1)"
+    )?);
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_helpers::*;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const DSC: &str = "Format: 3.0 (quilt)
+Source: rabbitmq-server
+Version: 4.1.3-1
+Files:
+ 0123456789abcdef0123456789abcdef 6 rabbitmq-server_4.1.3.orig.tar.xz
+ 0123456789abcdef0123456789abcdef 6 rabbitmq-server_4.1.3-1.debian.tar.xz
+";
+
+fn source_package_archive(dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let archive = dir.join("packages.zip");
+    let mut zip = ZipWriter::new(File::create(&archive)?);
+    let deb = minimal_deb();
+    let files: [(&str, &[u8]); 4] = [
+        ("rabbitmq-server_4.1.3-1_all.deb", &deb),
+        ("rabbitmq-server_4.1.3-1.dsc", DSC.as_bytes()),
+        ("rabbitmq-server_4.1.3.orig.tar.xz", b"source"),
+        ("rabbitmq-server_4.1.3-1.debian.tar.xz", b"debian"),
+    ];
+    for (name, contents) in files {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(contents)?;
+    }
+    zip.finish()?;
+    Ok(archive)
+}
+
+#[test]
+fn test_source_package_is_added_with_the_flag() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive = source_package_archive(temp_dir.path())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .args(["--with-source", "-p"])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 2, "Unexpected repo adds: {adds:?}");
+    assert!(
+        adds.iter()
+            .any(|line| line.ends_with("rabbitmq-server_4.1.3-1.dsc"))
+    );
+    assert!(
+        adds.iter()
+            .any(|line| line.ends_with("rabbitmq-server_4.1.3-1_all.deb"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_source_package_is_skipped_without_the_flag() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive = source_package_archive(temp_dir.path())?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .arg("-p")
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let adds = stub.invocations_starting_with("repo add")?;
+    assert_eq!(adds.len(), 1, "Unexpected repo adds: {adds:?}");
+    assert!(adds[0].ends_with("rabbitmq-server_4.1.3-1_all.deb"));
+    Ok(())
+}
+
+#[test]
+fn test_source_package_is_looked_up_by_its_dsc_fields() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive = source_package_archive(temp_dir.path())?;
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"repo search\" ]; then echo 'ERROR: no results' >&2; exit 1; fi",
+    )?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .args(["--with-source", "--if-not-present", "--check-arch", "-p"])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    assert!(invocations.iter().any(|line| {
+        line.starts_with("repo search")
+            && line.ends_with(
+                "Name (= rabbitmq-server), Version (= 4.1.3-1), $Architecture (= source)",
+            )
+    }));
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use predicates::prelude::*;
+use std::error::Error;
+use test_helpers::*;
+
+const PROGRESS_SCRIPT: &str = r#"if [ "$1 $2" = "repo add" ]; then
+  echo "Loading packages..."
+  echo "[+] rabbitmq-server_4.1.3-1_all added"
+  echo "Warning: slow disk" >&2
+fi
+"#;
+
+fn add(stub: &AptlyStub) -> std::process::Command {
+    let mut cmd = stub.bellhop_command();
+    cmd.args([
+        "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1", "-p",
+    ])
+    .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"));
+    cmd
+}
+
+#[test]
+fn test_stream_aptly_forwards_progress() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(PROGRESS_SCRIPT)?;
+
+    add(&stub)
+        .arg("--stream-aptly")
+        .assert()
+        .success()
+        .stderr(output_includes("Loading packages..."))
+        .stderr(output_includes("[+] rabbitmq-server_4.1.3-1_all added"))
+        .stderr(output_includes("Warning: slow disk"))
+        .stdout(predicate::str::contains("Loading packages...").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_aptly_output_is_buffered_by_default() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(PROGRESS_SCRIPT)?;
+
+    add(&stub)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Loading packages...").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_aptly_still_reports_failures() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        r#"if [ "$1 $2" = "repo add" ]; then
+  echo "ERROR: unable to add package" >&2
+  exit 1
+fi
+"#,
+    )?;
+
+    add(&stub)
+        .arg("--stream-aptly")
+        .assert()
+        .failure()
+        .stderr(output_includes("aptly command failed with status 1"))
+        .stderr(output_includes("Stderr: ERROR: unable to add package"));
+
+    Ok(())
+}
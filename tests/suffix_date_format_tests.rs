@@ -0,0 +1,56 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use chrono::Local;
+use std::error::Error;
+use test_helpers::*;
+
+#[test]
+fn test_snapshot_create_and_publish_agree_on_a_custom_suffix() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm"])
+        .args(["--suffix-date-format", "%Y-%m-%d", "-p"])
+        .arg(&package_path)
+        .assert()
+        .success();
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "publish", "-d", "bookworm"])
+        .args(["--suffix-date-format", "%Y-%m-%d"])
+        .assert()
+        .success();
+
+    let snapshot = format!(
+        "snap-rabbitmq-server-bookworm-{}",
+        Local::now().format("%Y-%m-%d")
+    );
+    let invocations = stub.invocations()?;
+    assert!(
+        invocations
+            .iter()
+            .any(|line| line.starts_with(&format!("snapshot create {snapshot} "))),
+        "Expected a snapshot named {snapshot}: {invocations:?}"
+    );
+    assert!(
+        invocations
+            .iter()
+            .any(|line| line.starts_with("publish snapshot") && line.contains(&snapshot)),
+        "Expected {snapshot} to be published: {invocations:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,189 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use bellhop::archive::{ExtractionOptions, PackageSource, process_package_file};
+use bellhop::errors::BellhopError;
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tar::{Builder, EntryType, Header};
+use tempfile::TempDir;
+use test_helpers::*;
+
+const DEB: &str = "rabbitmq-server_4.1.7-1_all.deb";
+
+/// `tar::Builder` refuses to write unsafe paths, so the entry name is set on the raw header
+fn append_raw_entry(
+    builder: &mut Builder<File>,
+    name: &str,
+    data: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    let mut header = Header::new_gnu();
+    header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+    header.set_entry_type(EntryType::Regular);
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append(&header, data)?;
+    Ok(())
+}
+
+fn extracted_debs(archive_path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    match process_package_file(archive_path, &ExtractionOptions::default())? {
+        PackageSource::Archive { deb_files, .. } => Ok(deb_files),
+        PackageSource::SingleDeb(path) => {
+            Err(format!("Expected an archive: {}", path.display()).into())
+        }
+    }
+}
+
+fn following_symlinks() -> ExtractionOptions {
+    ExtractionOptions {
+        follow_symlinks: true,
+        ..ExtractionOptions::default()
+    }
+}
+
+fn archive_with_symlink(temp_dir: &TempDir, target: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    builder.append_path_with_name(test_package_path(DEB), format!("pool/{DEB}"))?;
+
+    let mut header = Header::new_gnu();
+    header.set_entry_type(EntryType::Symlink);
+    header.set_size(0);
+    builder.append_link(&mut header, "latest/rabbitmq-server_latest_all.deb", target)?;
+    builder.finish()?;
+    Ok(archive_path)
+}
+
+fn unique_name(temp_dir: &TempDir) -> String {
+    format!(
+        "{}-evil",
+        temp_dir.path().file_name().unwrap().to_string_lossy()
+    )
+}
+
+#[test]
+fn test_tar_entry_with_parent_dir_is_skipped() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let evil = unique_name(&temp_dir);
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    append_raw_entry(&mut builder, &format!("../{evil}"), b"evil")?;
+    builder.append_path_with_name(test_package_path(DEB), DEB)?;
+    builder.finish()?;
+
+    let deb_files = extracted_debs(&archive_path)?;
+
+    assert_eq!(deb_files.len(), 1);
+    assert!(deb_files[0].ends_with(DEB));
+    assert!(
+        !env::temp_dir().join(&evil).exists(),
+        "Entry must not be written outside of the extraction directory"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tar_entry_with_absolute_path_is_skipped() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let evil = env::temp_dir().join(unique_name(&temp_dir));
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    append_raw_entry(&mut builder, &evil.to_string_lossy(), b"evil")?;
+    builder.append_path_with_name(test_package_path(DEB), DEB)?;
+    builder.finish()?;
+
+    let deb_files = extracted_debs(&archive_path)?;
+
+    assert_eq!(deb_files.len(), 1);
+    assert!(!evil.exists(), "Absolute entry paths must not be honored");
+    Ok(())
+}
+
+#[test]
+fn test_tar_symlinks_and_hard_links_are_skipped() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path)?);
+    builder.append_path_with_name(test_package_path(DEB), DEB)?;
+
+    let mut header = Header::new_gnu();
+    header.set_entry_type(EntryType::Symlink);
+    header.set_size(0);
+    builder.append_link(
+        &mut header,
+        "symlinked_1.0-1_all.deb",
+        test_package_path(DEB),
+    )?;
+
+    let mut header = Header::new_gnu();
+    header.set_entry_type(EntryType::Link);
+    header.set_size(0);
+    builder.append_link(&mut header, "hardlinked_1.0-1_all.deb", DEB)?;
+    builder.finish()?;
+
+    let deb_files = extracted_debs(&archive_path)?;
+
+    assert_eq!(
+        deb_files.len(),
+        1,
+        "Only the regular file should be extracted: {deb_files:?}"
+    );
+    assert!(deb_files[0].ends_with(DEB));
+    Ok(())
+}
+
+#[test]
+fn test_tar_symlink_is_copied_with_follow_symlinks() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let archive_path = archive_with_symlink(&temp_dir, &format!("../pool/{DEB}"))?;
+
+    assert_eq!(extracted_debs(&archive_path)?.len(), 1);
+
+    let source = process_package_file(&archive_path, &following_symlinks())?;
+    let PackageSource::Archive { deb_files, .. } = &source else {
+        return Err("Expected an archive".into());
+    };
+    assert_eq!(deb_files.len(), 2, "{deb_files:?}");
+    let copy = deb_files
+        .iter()
+        .find(|path| path.ends_with("rabbitmq-server_latest_all.deb"))
+        .ok_or("The symlink should be extracted")?;
+    assert!(
+        !copy.is_symlink(),
+        "Symlinks are extracted as regular files"
+    );
+    assert_eq!(std::fs::read(copy)?, std::fs::read(test_package_path(DEB))?);
+    Ok(())
+}
+
+#[test]
+fn test_tar_symlink_outside_of_the_archive_is_rejected() -> Result<(), Box<dyn Error>> {
+    for target in ["../../etc/passwd", "/etc/passwd"] {
+        let temp_dir = TempDir::new()?;
+        let archive_path = archive_with_symlink(&temp_dir, target)?;
+
+        let result = process_package_file(&archive_path, &following_symlinks());
+        assert!(
+            matches!(result, Err(BellhopError::SymlinkOutsideArchive { .. })),
+            "{target} must be rejected"
+        );
+        assert_eq!(extracted_debs(&archive_path)?.len(), 1);
+    }
+    Ok(())
+}
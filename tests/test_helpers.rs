@@ -17,9 +17,11 @@ use assert_cmd::assert::Assert;
 use assert_cmd::cargo;
 use assert_cmd::prelude::*;
 use predicates::prelude::predicate;
+use std::env;
 use std::error::Error;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fs;
+use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
 use std::process::Command;
 use tempfile::TempDir;
@@ -140,6 +142,44 @@ impl AptlyTestContext {
         Ok(output.status.success() && !output.stdout.is_empty())
     }
 
+    /// Remove packages matching a query from a repository, bypassing bellhop
+    pub fn remove_packages(&self, repo_name: &str, package_query: &str) -> CommandRunResult {
+        let output = Command::new("aptly")
+            .arg(self.config_arg())
+            .arg("repo")
+            .arg("remove")
+            .arg(repo_name)
+            .arg(package_query)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to remove packages: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Check if a package exists in a snapshot
+    pub fn snapshot_package_exists(
+        &self,
+        snapshot_name: &str,
+        package_query: &str,
+    ) -> Result<bool, Box<dyn Error>> {
+        let output = Command::new("aptly")
+            .arg(self.config_arg())
+            .arg("snapshot")
+            .arg("search")
+            .arg(snapshot_name)
+            .arg(package_query)
+            .output()?;
+
+        Ok(output.status.success() && !output.stdout.is_empty())
+    }
+
     /// List snapshots matching a pattern
     pub fn list_snapshots(&self, pattern: &str) -> Result<Vec<String>, Box<dyn Error>> {
         let output = Command::new("aptly")
@@ -290,6 +330,116 @@ impl AptlyTestContext {
 
         Ok(false)
     }
+
+    /// Check if a repository is published to the given prefix, with any snapshot
+    pub fn is_published(
+        &self,
+        prefix: &str,
+        family: &str,
+        distribution: &str,
+    ) -> Result<bool, Box<dyn Error>> {
+        let output = Command::new("aptly")
+            .arg(self.config_arg())
+            .arg("publish")
+            .arg("list")
+            .output()?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to list published repositories: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )
+            .into());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let publish_prefix = format!("{prefix}/{family}/{distribution}/{distribution} ");
+        Ok(stdout.lines().any(|line| line.contains(&publish_prefix)))
+    }
+}
+
+/// A fake `aptly` executable that records every invocation, one line of arguments per call
+pub struct AptlyStub {
+    pub temp_dir: TempDir,
+    pub log_path: PathBuf,
+}
+
+impl AptlyStub {
+    /// Create a stub that succeeds with no output for every command
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Self::with_script("exit 0")
+    }
+
+    /// Create a stub that runs the given shell snippet after recording the invocation
+    pub fn with_script(script: &str) -> Result<Self, Box<dyn Error>> {
+        let temp_dir = TempDir::new()?;
+        let log_path = temp_dir.path().join("invocations.log");
+        let bin_path = temp_dir.path().join("aptly");
+
+        fs::write(
+            &bin_path,
+            format!(
+                "#!/bin/sh\necho \"$@\" >> \"{}\"\n{script}\n",
+                log_path.display()
+            ),
+        )?;
+        fs::set_permissions(&bin_path, fs::Permissions::from_mode(0o755))?;
+
+        let stub = AptlyStub { temp_dir, log_path };
+        // Publishing checks for the signing key first, the stub keyring has every key
+        stub.set_gpg_script("exit 0")?;
+        Ok(stub)
+    }
+
+    /// Replace the `gpg` shim that commands run against this stub find on `PATH`
+    pub fn set_gpg_script(&self, script: &str) -> Result<(), Box<dyn Error>> {
+        let gpg_path = self.temp_dir.path().join("gpg");
+        fs::write(&gpg_path, format!("#!/bin/sh\n{script}\n"))?;
+        fs::set_permissions(&gpg_path, fs::Permissions::from_mode(0o755))?;
+        Ok(())
+    }
+
+    /// Path to the stub executable, e.g. for `APTLY_BIN`
+    pub fn bin_path(&self) -> PathBuf {
+        self.temp_dir.path().join("aptly")
+    }
+
+    /// A `PATH` value that resolves `aptly` to this stub first
+    pub fn path_env(&self) -> OsString {
+        let mut paths = vec![self.temp_dir.path().to_path_buf()];
+        if let Some(path) = env::var_os("PATH") {
+            paths.extend(env::split_paths(&path));
+        }
+        env::join_paths(paths).expect("PATH entries should be valid")
+    }
+
+    /// A bellhop command that will run against this stub
+    pub fn bellhop_command(&self) -> Command {
+        let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+        cmd.env("PATH", self.path_env());
+        cmd.env_remove("APTLY_CONFIG");
+        cmd
+    }
+
+    /// Arguments of every recorded invocation, in order
+    pub fn invocations(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        if !self.log_path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(fs::read_to_string(&self.log_path)?
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    /// Recorded invocations of a subcommand, e.g. `repo add`, in order
+    pub fn invocations_starting_with(&self, prefix: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(self
+            .invocations()?
+            .into_iter()
+            .filter(|line| line.starts_with(prefix))
+            .collect())
+    }
 }
 
 /// Run bellhop command and expect success
@@ -333,3 +483,13 @@ pub fn test_fixture_path(filename: &str) -> PathBuf {
         .join("fixtures")
         .join(filename)
 }
+
+/// The smallest file that passes the `.deb` checks done before a package is handed to aptly:
+/// an `ar` archive with empty `debian-binary`, `control.tar.gz` and `data.tar.gz` members
+pub fn minimal_deb() -> Vec<u8> {
+    let mut deb = b"!<arch>\n".to_vec();
+    for name in ["debian-binary", "control.tar.gz", "data.tar.gz"] {
+        deb.extend(format!("{name:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n", 0, 0, 0, 644, 0).bytes());
+    }
+    deb
+}
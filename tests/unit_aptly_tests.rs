@@ -12,8 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use bellhop::aptly::{
+    ChangedPackage, CleanupSummary, DateRange, PublishMethod, PublishOptions, SnapshotDetails,
+    filter_snapshot_packages, matches_expected_version, parse_db_cleanup_output,
+    parse_snapshot_details,
+};
+use bellhop::cli::DEFAULT_SUFFIX_DATE_FORMAT;
 use bellhop::common::Project;
-use bellhop::deb::DistributionAlias;
+use bellhop::deb::{DebianFamily, DistributionAlias};
+use bellhop::errors::BellhopError;
+use chrono::NaiveDate;
+use std::collections::HashSet;
+use std::path::PathBuf;
 
 #[test]
 fn test_repo_name_rabbitmq() {
@@ -74,7 +84,11 @@ fn test_snapshot_name_with_suffix_erlang() {
 #[test]
 fn test_rel_path_with_prefix_debian() {
     assert_eq!(
-        bellhop::aptly::rel_path_with_prefix(&Project::RabbitMQ, &DistributionAlias::Bookworm),
+        bellhop::aptly::rel_path_with_prefix(
+            &Project::RabbitMQ,
+            &DistributionAlias::Bookworm,
+            DebianFamily::Debian
+        ),
         "rabbitmq-server/debian/bookworm"
     );
 }
@@ -82,11 +96,61 @@ fn test_rel_path_with_prefix_debian() {
 #[test]
 fn test_rel_path_with_prefix_ubuntu() {
     assert_eq!(
-        bellhop::aptly::rel_path_with_prefix(&Project::Erlang, &DistributionAlias::Noble),
+        bellhop::aptly::rel_path_with_prefix(
+            &Project::Erlang,
+            &DistributionAlias::Noble,
+            DebianFamily::Ubuntu
+        ),
         "rabbitmq-erlang/ubuntu/noble"
     );
 }
 
+#[test]
+fn test_rel_path_with_an_overridden_family() {
+    assert_eq!(
+        bellhop::aptly::rel_path_with_prefix(
+            &Project::RabbitMQ,
+            &DistributionAlias::Noble,
+            DebianFamily::Debian
+        ),
+        "rabbitmq-server/debian/noble"
+    );
+    assert_eq!(
+        bellhop::aptly::rel_path_with_prefix(
+            &Project::RabbitMQ,
+            &DistributionAlias::Bookworm,
+            DebianFamily::Ubuntu
+        ),
+        "rabbitmq-server/ubuntu/bookworm"
+    );
+}
+
+#[test]
+fn test_publish_options_family_of() {
+    let natural = PublishOptions::default();
+    assert_eq!(
+        natural.family_of(&DistributionAlias::Jammy),
+        DebianFamily::Ubuntu
+    );
+    assert_eq!(
+        natural.family_of(&DistributionAlias::Trixie),
+        DebianFamily::Debian
+    );
+
+    let overridden = PublishOptions {
+        family: Some(DebianFamily::Debian),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        overridden.family_of(&DistributionAlias::Jammy),
+        DebianFamily::Debian
+    );
+    assert_eq!(
+        overridden.family_of(&DistributionAlias::Trixie),
+        DebianFamily::Debian
+    );
+}
+
 #[test]
 fn test_repo_name_cli_tools() {
     assert_eq!(
@@ -120,11 +184,19 @@ fn test_snapshot_name_with_suffix_cli_tools() {
 #[test]
 fn test_rel_path_with_prefix_cli_tools() {
     assert_eq!(
-        bellhop::aptly::rel_path_with_prefix(&Project::CliTools, &DistributionAlias::Noble),
+        bellhop::aptly::rel_path_with_prefix(
+            &Project::CliTools,
+            &DistributionAlias::Noble,
+            DebianFamily::Ubuntu
+        ),
         "rabbitmq-cli/ubuntu/noble"
     );
     assert_eq!(
-        bellhop::aptly::rel_path_with_prefix(&Project::CliTools, &DistributionAlias::Trixie),
+        bellhop::aptly::rel_path_with_prefix(
+            &Project::CliTools,
+            &DistributionAlias::Trixie,
+            DebianFamily::Debian
+        ),
         "rabbitmq-cli/debian/trixie"
     );
 }
@@ -164,3 +236,1136 @@ fn test_all_distributions_have_valid_repo_names() {
         }
     }
 }
+
+#[test]
+fn test_published_snapshot_name() {
+    let published: HashSet<String> = [
+        "Published repositories:",
+        "  * rabbitmq-server/debian/bookworm/bookworm [amd64, arm64] publishes {main: [snap-rabbitmq-server-bookworm-16-Dec-25]: Snapshot from local repo [repo-rabbitmq-server-bookworm]}",
+        "  * rabbitmq-server/ubuntu/noble/noble [amd64] publishes {main: [snap-rabbitmq-server-noble-v2]: Merged from sources: 'a', 'b'}",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    assert_eq!(
+        bellhop::aptly::published_snapshot_name(
+            &published,
+            "rabbitmq-server/debian/bookworm",
+            "bookworm"
+        ),
+        Some("snap-rabbitmq-server-bookworm-16-Dec-25".to_string())
+    );
+    assert_eq!(
+        bellhop::aptly::published_snapshot_name(
+            &published,
+            "rabbitmq-server/ubuntu/noble",
+            "noble"
+        ),
+        Some("snap-rabbitmq-server-noble-v2".to_string())
+    );
+    assert_eq!(
+        bellhop::aptly::published_snapshot_name(
+            &published,
+            "rabbitmq-erlang/debian/bookworm",
+            "bookworm"
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_parse_package_keys() {
+    let output = "rabbitmq-server_4.1.3-1_all\nerlang-base_1:27.3.4.6-1_amd64\n\n";
+    assert_eq!(
+        bellhop::aptly::parse_package_keys(output),
+        vec![
+            ("rabbitmq-server".to_string(), "4.1.3-1".to_string()),
+            ("erlang-base".to_string(), "1:27.3.4.6-1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_newest_versions_picks_the_newest_version_per_package() {
+    let packages: Vec<(String, String)> = [
+        ("rabbitmqadmin", "2.9.0-1"),
+        ("rabbitmqadmin", "2.10.0-1"),
+        ("rabbitmq-lqt", "0.5.0-1"),
+        ("rabbitmqadmin", "2.10.0~rc.1-1"),
+    ]
+    .into_iter()
+    .map(|(n, v)| (n.to_string(), v.to_string()))
+    .collect();
+
+    let newest = bellhop::aptly::newest_versions(&packages);
+    assert_eq!(newest.len(), 2);
+    assert_eq!(newest["rabbitmqadmin"], "2.10.0-1");
+    assert_eq!(newest["rabbitmq-lqt"], "0.5.0-1");
+}
+
+#[test]
+fn test_orphan_snapshot_names() {
+    let snapshots: HashSet<String> = [
+        "snap-rabbitmq-server-bookworm-01-Dec-25",
+        "snap-rabbitmq-server-bookworm-16-Dec-25",
+        "snap-rabbitmq-server-bookworm-02-Jan-26",
+        "snap-rabbitmq-server-bookworm-v1",
+        "snap-rabbitmq-server-bookworm-v2",
+        "snap-rabbitmq-server-noble-01-Dec-25",
+        "snap-rabbitmq-erlang-bookworm-01-Dec-25",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let published: HashSet<String> = [
+        "  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-16-Dec-25]: Snapshot from local repo [repo-rabbitmq-server-bookworm]}",
+        "  * staging/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v2]: Snapshot from local repo [repo-rabbitmq-server-bookworm]}",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    assert_eq!(
+        bellhop::aptly::orphan_snapshot_names(
+            &snapshots,
+            &published,
+            &Project::RabbitMQ,
+            &DistributionAlias::Bookworm,
+            DEFAULT_SUFFIX_DATE_FORMAT
+        ),
+        vec![
+            "snap-rabbitmq-server-bookworm-01-Dec-25".to_string(),
+            "snap-rabbitmq-server-bookworm-v1".to_string(),
+        ]
+    );
+    assert!(
+        bellhop::aptly::orphan_snapshot_names(
+            &snapshots,
+            &published,
+            &Project::RabbitMQ,
+            &DistributionAlias::Noble,
+            DEFAULT_SUFFIX_DATE_FORMAT
+        )
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_orphan_snapshot_names_with_a_custom_date_format() {
+    let snapshots: HashSet<String> = [
+        "snap-rabbitmq-server-bookworm-2025-12-01",
+        "snap-rabbitmq-server-bookworm-2026-01-02",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    assert_eq!(
+        bellhop::aptly::orphan_snapshot_names(
+            &snapshots,
+            &HashSet::new(),
+            &Project::RabbitMQ,
+            &DistributionAlias::Bookworm,
+            "%Y-%m-%d"
+        ),
+        vec!["snap-rabbitmq-server-bookworm-2025-12-01".to_string()]
+    );
+}
+
+#[test]
+fn test_snapshot_date() {
+    let date = |name: &str, format: &str| {
+        bellhop::aptly::snapshot_date(
+            name,
+            &Project::RabbitMQ,
+            &DistributionAlias::Bookworm,
+            format,
+        )
+    };
+
+    assert_eq!(
+        date(
+            "snap-rabbitmq-server-bookworm-16-Dec-25",
+            DEFAULT_SUFFIX_DATE_FORMAT
+        ),
+        NaiveDate::from_ymd_opt(2025, 12, 16)
+    );
+    assert_eq!(
+        date("snap-rabbitmq-server-bookworm-2025-12-16", "%Y-%m-%d"),
+        NaiveDate::from_ymd_opt(2025, 12, 16)
+    );
+    assert_eq!(
+        date(
+            "snap-rabbitmq-server-bookworm-v1",
+            DEFAULT_SUFFIX_DATE_FORMAT
+        ),
+        None
+    );
+    assert_eq!(
+        date(
+            "snap-rabbitmq-server-bookworm-2025-12-16",
+            DEFAULT_SUFFIX_DATE_FORMAT
+        ),
+        None
+    );
+    // another distribution's snapshot
+    assert_eq!(
+        date(
+            "snap-rabbitmq-server-trixie-16-Dec-25",
+            DEFAULT_SUFFIX_DATE_FORMAT
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_date_range_bounds_are_exclusive() {
+    let day = |d| NaiveDate::from_ymd_opt(2025, 12, d).unwrap();
+    let range = DateRange {
+        after: Some(day(1)),
+        before: Some(day(16)),
+    };
+
+    assert!(!range.contains(day(1)));
+    assert!(range.contains(day(2)));
+    assert!(range.contains(day(15)));
+    assert!(!range.contains(day(16)));
+    assert!(!range.is_unbounded());
+    assert!(DateRange::default().is_unbounded());
+    assert!(DateRange::default().contains(day(1)));
+}
+
+#[test]
+fn test_dated_snapshot_names_are_in_date_order() {
+    let snapshots: HashSet<String> = [
+        "snap-rabbitmq-server-bookworm-02-Jan-26",
+        "snap-rabbitmq-server-bookworm-16-Dec-25",
+        "snap-rabbitmq-server-bookworm-01-Dec-25",
+        "snap-rabbitmq-server-bookworm-v1",
+        "snap-rabbitmq-server-noble-16-Dec-25",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let names = |range: DateRange| {
+        bellhop::aptly::dated_snapshot_names(
+            &snapshots,
+            &Project::RabbitMQ,
+            &DistributionAlias::Bookworm,
+            DEFAULT_SUFFIX_DATE_FORMAT,
+            &range,
+        )
+    };
+
+    assert_eq!(
+        names(DateRange::default()),
+        [
+            "snap-rabbitmq-server-bookworm-01-Dec-25",
+            "snap-rabbitmq-server-bookworm-16-Dec-25",
+            "snap-rabbitmq-server-bookworm-02-Jan-26",
+        ]
+    );
+    assert_eq!(
+        names(DateRange {
+            after: NaiveDate::from_ymd_opt(2025, 12, 1),
+            before: None,
+        }),
+        [
+            "snap-rabbitmq-server-bookworm-16-Dec-25",
+            "snap-rabbitmq-server-bookworm-02-Jan-26",
+        ]
+    );
+}
+
+#[test]
+fn test_all_versions_query() {
+    assert_eq!(
+        bellhop::aptly::all_versions_query(&Project::RabbitMQ, "rabbitmq-server").unwrap(),
+        "Name (= rabbitmq-server)"
+    );
+    assert_eq!(
+        bellhop::aptly::all_versions_query(&Project::CliTools, "rabbitmqadmin").unwrap(),
+        "Name (= rabbitmqadmin)"
+    );
+    assert_eq!(
+        bellhop::aptly::all_versions_query(&Project::Erlang, "erlang-base").unwrap(),
+        "Name (~ ^erlang-base)"
+    );
+    assert_eq!(
+        bellhop::aptly::all_versions_query(&Project::Erlang, "libfoo2.0+").unwrap(),
+        "Name (~ ^libfoo2\\.0\\+)"
+    );
+}
+
+#[test]
+fn test_all_versions_query_rejects_query_syntax() {
+    for name in ["", "rabbitmq-server (= 4.1.3-1)", "a|b", "Name"] {
+        assert!(bellhop::aptly::all_versions_query(&Project::RabbitMQ, name).is_err());
+    }
+}
+
+#[test]
+fn test_version_query() {
+    assert_eq!(
+        bellhop::aptly::version_query(&Project::RabbitMQ, "4.1.3-1", None).unwrap(),
+        "rabbitmq-server (= 4.1.3-1)"
+    );
+    assert_eq!(
+        bellhop::aptly::version_query(&Project::Erlang, "1:27.3.4.6-1", None).unwrap(),
+        "Name (~ ^erlang), Version (= 1:27.3.4.6-1)"
+    );
+    assert_eq!(
+        bellhop::aptly::version_query(&Project::Erlang, "1:27.3.4.6-1", Some("erlang-base"))
+            .unwrap(),
+        "Name (= erlang-base), Version (= 1:27.3.4.6-1)"
+    );
+    assert!(bellhop::aptly::version_query(&Project::Erlang, "1", Some("erlang | x")).is_err());
+}
+
+#[test]
+fn test_publish_args_for_switch() {
+    let args = |force_overwrite| {
+        bellhop::aptly::publish_args(
+            PublishMethod::Switch,
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1",
+            "-skip-signing",
+            &PublishOptions {
+                force_overwrite,
+                ..PublishOptions::default()
+            },
+        )
+    };
+    assert_eq!(
+        args(false),
+        [
+            "publish",
+            "switch",
+            "-skip-signing",
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1"
+        ]
+    );
+    assert_eq!(
+        args(true),
+        [
+            "publish",
+            "switch",
+            "-skip-signing",
+            "-force-overwrite",
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_update_args_leave_out_the_component() {
+    let options = PublishOptions {
+        force_overwrite: true,
+        component: Some("erlang".to_string()),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        bellhop::aptly::publish_update_args(
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "-skip-signing",
+            &options,
+        ),
+        [
+            "publish",
+            "update",
+            "-skip-signing",
+            "-force-overwrite",
+            "bookworm",
+            "rabbitmq-server/debian/bookworm"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_args_with_a_custom_prefix() {
+    let options = PublishOptions {
+        prefix: Some("apt/rabbitmq".to_string()),
+        ..PublishOptions::default()
+    };
+    let rel_path = options.rel_path_of(&Project::RabbitMQ, &DistributionAlias::Bookworm);
+    assert_eq!(rel_path, "apt/rabbitmq");
+    assert_eq!(
+        bellhop::aptly::publish_args(
+            PublishMethod::Snapshot,
+            "bookworm",
+            &rel_path,
+            "snap-rabbitmq-server-bookworm-v1",
+            "-skip-signing",
+            &options,
+        ),
+        [
+            "publish",
+            "snapshot",
+            "-distribution",
+            "bookworm",
+            "-skip-signing",
+            "snap-rabbitmq-server-bookworm-v1",
+            "apt/rabbitmq"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_args_with_the_root_prefix() {
+    let options = PublishOptions {
+        prefix: Some(".".to_string()),
+        ..PublishOptions::default()
+    };
+    let rel_path = bellhop::aptly::publish_target(
+        &options.rel_path_of(&Project::RabbitMQ, &DistributionAlias::Noble),
+        None,
+    );
+    assert_eq!(
+        bellhop::aptly::publish_args(
+            PublishMethod::Switch,
+            "noble",
+            &rel_path,
+            "snap-rabbitmq-server-noble-v1",
+            "-skip-signing",
+            &options,
+        ),
+        [
+            "publish",
+            "switch",
+            "-skip-signing",
+            "noble",
+            ".",
+            "snap-rabbitmq-server-noble-v1"
+        ]
+    );
+
+    let published = HashSet::from([
+        "  * ./noble [amd64] publishes {main: [snap-rabbitmq-server-noble-v0]}".to_string(),
+    ]);
+    assert!(bellhop::aptly::publication_exists(&published, ".", "noble"));
+    assert!(!bellhop::aptly::publication_exists(
+        &published, ".", "bookworm"
+    ));
+}
+
+#[test]
+fn test_distribution_names_of_a_release() {
+    let mut options = PublishOptions {
+        also_distribution: Some("stable".to_string()),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        options.distribution_names_of(&DistributionAlias::Bookworm),
+        ["bookworm", "stable"]
+    );
+
+    options.also_distribution = Some("bookworm".to_string());
+    assert_eq!(
+        options.distribution_names_of(&DistributionAlias::Bookworm),
+        ["bookworm"]
+    );
+
+    options.also_distribution = None;
+    assert_eq!(
+        options.distribution_names_of(&DistributionAlias::Bookworm),
+        ["bookworm"]
+    );
+}
+
+#[test]
+fn test_publish_prefix_defaults_to_the_computed_one() {
+    let options = PublishOptions::default();
+    assert_eq!(
+        options.rel_path_of(&Project::RabbitMQ, &DistributionAlias::Bookworm),
+        "rabbitmq-server/debian/bookworm"
+    );
+}
+
+#[test]
+fn test_publish_prefix_validation() {
+    for prefix in [
+        ".",
+        "apt",
+        "apt/rabbitmq",
+        "rabbitmq-server/debian/bookworm",
+        "v1.2",
+    ] {
+        assert!(bellhop::aptly::is_valid_publish_prefix(prefix), "{prefix}");
+    }
+    for prefix in [
+        "",
+        "..",
+        "../apt",
+        "apt/../..",
+        "/apt",
+        "apt/",
+        "apt//x",
+        "./apt",
+        "s3:x:apt",
+        "a b",
+    ] {
+        assert!(!bellhop::aptly::is_valid_publish_prefix(prefix), "{prefix}");
+    }
+
+    let options = PublishOptions {
+        prefix: Some("../outside".to_string()),
+        ..PublishOptions::default()
+    };
+    assert!(matches!(
+        options.validate(),
+        Err(BellhopError::InvalidPublishPrefix { prefix }) if prefix == "../outside"
+    ));
+}
+
+#[test]
+fn test_publish_args_for_initial_publish() {
+    let args = |force_overwrite| {
+        bellhop::aptly::publish_args(
+            PublishMethod::Snapshot,
+            "noble",
+            "rabbitmq-server/ubuntu/noble",
+            "snap-rabbitmq-server-noble-v1",
+            "-skip-signing",
+            &PublishOptions {
+                force_overwrite,
+                ..PublishOptions::default()
+            },
+        )
+    };
+    assert_eq!(
+        args(false),
+        [
+            "publish",
+            "snapshot",
+            "-distribution",
+            "noble",
+            "-skip-signing",
+            "snap-rabbitmq-server-noble-v1",
+            "rabbitmq-server/ubuntu/noble"
+        ]
+    );
+    assert_eq!(
+        args(true),
+        [
+            "publish",
+            "snapshot",
+            "-distribution",
+            "noble",
+            "-skip-signing",
+            "-force-overwrite",
+            "snap-rabbitmq-server-noble-v1",
+            "rabbitmq-server/ubuntu/noble"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_args_with_a_component() {
+    let options = PublishOptions {
+        component: Some("rabbitmq".to_string()),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        bellhop::aptly::publish_args(
+            PublishMethod::Switch,
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1",
+            "-skip-signing",
+            &options,
+        ),
+        [
+            "publish",
+            "switch",
+            "-skip-signing",
+            "-component=rabbitmq",
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1"
+        ]
+    );
+    assert_eq!(
+        bellhop::aptly::publish_args(
+            PublishMethod::Snapshot,
+            "noble",
+            "rabbitmq-server/ubuntu/noble",
+            "snap-rabbitmq-server-noble-v1",
+            "-skip-signing",
+            &options,
+        ),
+        [
+            "publish",
+            "snapshot",
+            "-distribution",
+            "noble",
+            "-skip-signing",
+            "-component=rabbitmq",
+            "snap-rabbitmq-server-noble-v1",
+            "rabbitmq-server/ubuntu/noble"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_args_with_a_passphrase_file() {
+    let options = PublishOptions {
+        passphrase_file: Some(PathBuf::from("/etc/bellhop/passphrase")),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        bellhop::aptly::publish_args(
+            PublishMethod::Switch,
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1",
+            "-gpg-key=0A9AF2115F4687BD29803A206B73A36E6026DFCA",
+            &options,
+        ),
+        [
+            "publish",
+            "switch",
+            "-gpg-key=0A9AF2115F4687BD29803A206B73A36E6026DFCA",
+            "-passphrase-file=/etc/bellhop/passphrase",
+            "-batch",
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_flags_only_include_the_passphrase_file_when_set() {
+    assert!(PublishOptions::default().flags().is_empty());
+
+    let options = PublishOptions {
+        passphrase_file: Some(PathBuf::from("passphrase.txt")),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        options.flags(),
+        ["-passphrase-file=passphrase.txt", "-batch"]
+    );
+}
+
+#[test]
+fn test_publish_options_reject_a_missing_passphrase_file() {
+    let options = PublishOptions {
+        passphrase_file: Some(PathBuf::from("/nonexistent/passphrase")),
+        ..PublishOptions::default()
+    };
+    assert!(matches!(
+        options.validate(),
+        Err(BellhopError::PassphraseFileNotFound { .. })
+    ));
+    assert!(PublishOptions::default().validate().is_ok());
+}
+
+#[test]
+fn test_publish_args_only_include_the_keyring_when_set() {
+    let args = |options: &PublishOptions| {
+        bellhop::aptly::publish_args(
+            PublishMethod::Snapshot,
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1",
+            "-gpg-key=0A9AF2115F4687BD29803A206B73A36E6026DFCA",
+            options,
+        )
+    };
+
+    let without_keyring = args(&PublishOptions::default());
+    assert!(
+        without_keyring
+            .iter()
+            .all(|arg| !arg.starts_with("-keyring="))
+    );
+    assert!(
+        without_keyring.contains(&"-gpg-key=0A9AF2115F4687BD29803A206B73A36E6026DFCA".to_string())
+    );
+
+    let options = PublishOptions {
+        keyring: Some(PathBuf::from("/ci/signing.kbx")),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        args(&options),
+        [
+            "publish",
+            "snapshot",
+            "-distribution",
+            "bookworm",
+            "-gpg-key=0A9AF2115F4687BD29803A206B73A36E6026DFCA",
+            "-keyring=/ci/signing.kbx",
+            "snap-rabbitmq-server-bookworm-v1",
+            "rabbitmq-server/debian/bookworm"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_update_args_include_the_keyring() {
+    let options = PublishOptions {
+        keyring: Some(PathBuf::from("/ci/signing.kbx")),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        bellhop::aptly::publish_update_args(
+            "bookworm",
+            "rabbitmq-server/debian/bookworm",
+            "-gpg-key=ABCD1234",
+            &options,
+        ),
+        [
+            "publish",
+            "update",
+            "-gpg-key=ABCD1234",
+            "-keyring=/ci/signing.kbx",
+            "bookworm",
+            "rabbitmq-server/debian/bookworm"
+        ]
+    );
+}
+
+#[test]
+fn test_publish_options_reject_a_missing_keyring() {
+    let options = PublishOptions {
+        keyring: Some(PathBuf::from("/nonexistent/signing.kbx")),
+        ..PublishOptions::default()
+    };
+    assert!(matches!(
+        options.validate(),
+        Err(BellhopError::KeyringNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_publication_exists_with_a_custom_component() {
+    let published: HashSet<String> = [
+        "  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {rabbitmq: [snap-rabbitmq-server-bookworm-v1]: Snapshot from local repo [repo-rabbitmq-server-bookworm]}",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    assert!(bellhop::aptly::publication_exists(
+        &published,
+        "rabbitmq-server/debian/bookworm",
+        "bookworm"
+    ));
+    assert_eq!(
+        bellhop::aptly::published_snapshot_name(
+            &published,
+            "rabbitmq-server/debian/bookworm",
+            "bookworm"
+        )
+        .as_deref(),
+        Some("snap-rabbitmq-server-bookworm-v1")
+    );
+}
+
+#[test]
+fn test_published_component_snapshot_of_a_shared_publication() {
+    let published: HashSet<String> = [
+        "  * packages/debian/bookworm/bookworm [amd64] publishes {erlang: [snap-rabbitmq-erlang-bookworm-v3]: Snapshot from local repo [repo-rabbitmq-erlang-bookworm]}, {rabbitmq: [snap-rabbitmq-server-bookworm-v1]: Snapshot from local repo [repo-rabbitmq-server-bookworm]}",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let snapshot = |component| {
+        bellhop::aptly::published_component_snapshot(
+            &published,
+            "packages/debian/bookworm",
+            "bookworm",
+            component,
+        )
+    };
+
+    assert_eq!(
+        snapshot(Some("rabbitmq")).as_deref(),
+        Some("snap-rabbitmq-server-bookworm-v1")
+    );
+    assert_eq!(
+        snapshot(Some("erlang")).as_deref(),
+        Some("snap-rabbitmq-erlang-bookworm-v3")
+    );
+    assert_eq!(snapshot(Some("cli")), None);
+    assert_eq!(
+        snapshot(None).as_deref(),
+        Some("snap-rabbitmq-erlang-bookworm-v3")
+    );
+
+    assert!(bellhop::aptly::publication_has_component(
+        &published,
+        "packages/debian/bookworm",
+        "bookworm",
+        "erlang"
+    ));
+    assert!(!bellhop::aptly::publication_has_component(
+        &published,
+        "packages/debian/bookworm",
+        "bookworm",
+        "main"
+    ));
+    assert!(!bellhop::aptly::publication_has_component(
+        &published,
+        "packages/debian/trixie",
+        "trixie",
+        "erlang"
+    ));
+}
+
+#[test]
+fn test_publish_target() {
+    assert_eq!(
+        bellhop::aptly::publish_target("rabbitmq-server/debian/bookworm", None),
+        "rabbitmq-server/debian/bookworm"
+    );
+    assert_eq!(
+        bellhop::aptly::publish_target("rabbitmq-server/debian/bookworm", Some("myendpoint")),
+        "s3:myendpoint:rabbitmq-server/debian/bookworm"
+    );
+}
+
+#[test]
+fn test_publication_exists_distinguishes_endpoints() {
+    let published: HashSet<String> = [
+        "Published repositories:",
+        "  * s3:myendpoint:rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]: Snapshot from local repo [repo-rabbitmq-server-bookworm]}",
+        "  * rabbitmq-server/ubuntu/noble/noble [amd64] publishes {main: [snap-rabbitmq-server-noble-v1]: Snapshot from local repo [repo-rabbitmq-server-noble]}",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    let exists = |prefix: &str, distribution| {
+        bellhop::aptly::publication_exists(&published, prefix, distribution)
+    };
+    assert!(exists(
+        "s3:myendpoint:rabbitmq-server/debian/bookworm",
+        "bookworm"
+    ));
+    assert!(!exists("rabbitmq-server/debian/bookworm", "bookworm"));
+    assert!(!exists(
+        "s3:otherendpoint:rabbitmq-server/debian/bookworm",
+        "bookworm"
+    ));
+    assert!(exists("rabbitmq-server/ubuntu/noble", "noble"));
+    assert!(!exists(
+        "s3:myendpoint:rabbitmq-server/ubuntu/noble",
+        "noble"
+    ));
+}
+
+#[test]
+fn test_check_package_architecture() {
+    let check = |project, file_name: &str| {
+        bellhop::aptly::check_package_architecture(&project, &PathBuf::from(file_name))
+    };
+
+    assert!(check(Project::RabbitMQ, "rabbitmq-server_4.1.3-1_all.deb").is_ok());
+    assert!(check(Project::CliTools, "rabbitmqadmin_2.0.0-1_amd64.deb").is_ok());
+    assert!(check(Project::CliTools, "rabbitmqadmin_2.0.0-1_all.deb").is_ok());
+    assert!(matches!(
+        check(Project::CliTools, "rabbitmqadmin_2.0.0-1_arm64.deb"),
+        Err(BellhopError::IncompatibleArchitecture { .. })
+    ));
+    assert!(matches!(
+        check(Project::RabbitMQ, "rabbitmq-server_4.1.3-1_riscv64.deb"),
+        Err(BellhopError::IncompatibleArchitecture { .. })
+    ));
+    // aptly decides for projects without an explicit architecture list
+    assert!(check(Project::Erlang, "erlang-base_27.3.4.6-1_riscv64.deb").is_ok());
+}
+
+#[test]
+fn test_mirror_create_args() {
+    assert_eq!(
+        bellhop::aptly::mirror_create_args(
+            "nodesource",
+            "https://deb.nodesource.com/node_22.x",
+            "nodistro",
+            &[],
+            &[],
+        ),
+        [
+            "mirror",
+            "create",
+            "nodesource",
+            "https://deb.nodesource.com/node_22.x",
+            "nodistro"
+        ]
+    );
+    assert_eq!(
+        bellhop::aptly::mirror_create_args(
+            "nodesource",
+            "https://deb.nodesource.com/node_22.x",
+            "nodistro",
+            &["main".to_string(), "contrib".to_string()],
+            &["amd64".to_string()],
+        ),
+        [
+            "mirror",
+            "create",
+            "-architectures=amd64",
+            "nodesource",
+            "https://deb.nodesource.com/node_22.x",
+            "nodistro",
+            "main",
+            "contrib"
+        ]
+    );
+}
+
+#[test]
+fn test_package_key_query() {
+    assert_eq!(
+        bellhop::aptly::package_key_query("erlang-base", "1:27.3.4.6-1", "arm64"),
+        "Name (= erlang-base), Version (= 1:27.3.4.6-1), $Architecture (= arm64)"
+    );
+}
+
+#[test]
+fn test_diff_package_sets() {
+    let from: Vec<String> = [
+        "rabbitmq-server_4.1.3-1_all",
+        "erlang-base_1:27.3-1_amd64",
+        "erlang-base_1:27.3-1_arm64",
+        "erlang-doc_1:27.3-1_all",
+    ]
+    .map(String::from)
+    .to_vec();
+    let to: Vec<String> = [
+        "rabbitmq-server_4.1.3-1_all",
+        "rabbitmq-server_4.1.4-1_all",
+        "erlang-base_1:27.3-1_amd64",
+        "erlang-base_1:27.3.1-1_arm64",
+        "erlang-dev_1:27.3-1_amd64",
+    ]
+    .map(String::from)
+    .to_vec();
+
+    let diff = bellhop::aptly::diff_package_sets(&from, &to);
+    assert_eq!(diff.added, ["erlang-dev_1:27.3-1_amd64"]);
+    assert_eq!(diff.removed, ["erlang-doc_1:27.3-1_all"]);
+    assert_eq!(
+        diff.changed,
+        [
+            ChangedPackage {
+                name: "erlang-base".to_string(),
+                architecture: "arm64".to_string(),
+                from: vec!["1:27.3-1".to_string()],
+                to: vec!["1:27.3.1-1".to_string()],
+            },
+            ChangedPackage {
+                name: "rabbitmq-server".to_string(),
+                architecture: "all".to_string(),
+                from: vec!["4.1.3-1".to_string()],
+                to: vec!["4.1.3-1".to_string(), "4.1.4-1".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diff_of_identical_package_sets_is_empty() {
+    let packages: Vec<String> = ["rabbitmq-server_4.1.3-1_all"].map(String::from).to_vec();
+    assert!(bellhop::aptly::diff_package_sets(&packages, &packages).is_empty());
+}
+
+#[test]
+fn test_is_database_lock_failure() {
+    assert!(bellhop::aptly::is_database_lock_failure(
+        "ERROR: unable to acquire database lock"
+    ));
+    assert!(bellhop::aptly::is_database_lock_failure(
+        "Unable to acquire database lock: resource temporarily unavailable"
+    ));
+    assert!(!bellhop::aptly::is_database_lock_failure(
+        "ERROR: local repo with name repo-rabbitmq-server-bookworm not found"
+    ));
+}
+
+#[test]
+fn test_publish_args_with_a_distribution_name_mapping() {
+    let options = PublishOptions {
+        distribution_names: [(DistributionAlias::Bookworm, "stable".to_string())].into(),
+        ..PublishOptions::default()
+    };
+    assert_eq!(
+        options.distribution_name_of(&DistributionAlias::Bookworm),
+        "stable"
+    );
+    assert_eq!(
+        options.distribution_name_of(&DistributionAlias::Noble),
+        "noble"
+    );
+
+    let distribution = options.distribution_name_of(&DistributionAlias::Bookworm);
+    assert_eq!(
+        bellhop::aptly::publish_args(
+            PublishMethod::Switch,
+            distribution,
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1",
+            "-skip-signing",
+            &options,
+        ),
+        [
+            "publish",
+            "switch",
+            "-skip-signing",
+            "stable",
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1"
+        ]
+    );
+    assert_eq!(
+        bellhop::aptly::publish_args(
+            PublishMethod::Snapshot,
+            distribution,
+            "rabbitmq-server/debian/bookworm",
+            "snap-rabbitmq-server-bookworm-v1",
+            "-skip-signing",
+            &options,
+        ),
+        [
+            "publish",
+            "snapshot",
+            "-distribution",
+            "stable",
+            "-skip-signing",
+            "snap-rabbitmq-server-bookworm-v1",
+            "rabbitmq-server/debian/bookworm"
+        ]
+    );
+}
+
+#[test]
+fn test_names_without_a_channel_are_unchanged() {
+    assert_eq!(
+        bellhop::aptly::repo_name(&Project::RabbitMQ, &DistributionAlias::Bookworm),
+        "repo-rabbitmq-server-bookworm"
+    );
+    assert_eq!(
+        bellhop::aptly::snapshot_name_with_suffix(
+            &Project::Erlang,
+            &DistributionAlias::Noble,
+            "v1"
+        ),
+        "snap-rabbitmq-erlang-noble-v1"
+    );
+    assert_eq!(
+        bellhop::aptly::rel_path_with_prefix(
+            &Project::CliTools,
+            &DistributionAlias::Trixie,
+            DebianFamily::Debian
+        ),
+        "rabbitmq-cli/debian/trixie"
+    );
+}
+
+const SNAPSHOT_SHOW_OUTPUT: &str = "Name: snap-rabbitmq-server-bookworm-v1
+Created At: 2025-08-04 10:00:00 UTC
+Description: Snapshot from local repo [repo-rabbitmq-server-bookworm]
+Number of packages: 3
+Packages:
+  rabbitmq-server_4.1.3-1_all
+  rabbitmq-server_4.1.4-1_all
+  erlang-base_1:27.3.4.6-1_amd64
+";
+
+#[test]
+fn test_parse_snapshot_details() {
+    assert_eq!(
+        parse_snapshot_details(SNAPSHOT_SHOW_OUTPUT),
+        SnapshotDetails {
+            name: "snap-rabbitmq-server-bookworm-v1".to_string(),
+            created_at: Some("2025-08-04 10:00:00 UTC".to_string()),
+            description: Some(
+                "Snapshot from local repo [repo-rabbitmq-server-bookworm]".to_string()
+            ),
+            packages: vec![
+                "rabbitmq-server_4.1.3-1_all".to_string(),
+                "rabbitmq-server_4.1.4-1_all".to_string(),
+                "erlang-base_1:27.3.4.6-1_amd64".to_string(),
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_parse_snapshot_details_of_an_empty_snapshot() {
+    let details = parse_snapshot_details("Name: snap-empty\nNumber of packages: 0\n");
+    assert_eq!(details.name, "snap-empty");
+    assert_eq!(details.created_at, None);
+    assert!(details.packages.is_empty());
+}
+
+#[test]
+fn test_filter_snapshot_packages_keeps_details_and_matching_packages() {
+    assert_eq!(
+        filter_snapshot_packages(SNAPSHOT_SHOW_OUTPUT, "4.1.4"),
+        "Name: snap-rabbitmq-server-bookworm-v1
+Created At: 2025-08-04 10:00:00 UTC
+Description: Snapshot from local repo [repo-rabbitmq-server-bookworm]
+Number of packages: 3
+Packages:
+  rabbitmq-server_4.1.4-1_all
+"
+    );
+}
+
+#[test]
+fn test_filter_snapshot_packages_only_filters_package_lines() {
+    let filtered = filter_snapshot_packages(SNAPSHOT_SHOW_OUTPUT, "erlang");
+    assert!(filtered.contains("Name: snap-rabbitmq-server-bookworm-v1"));
+    assert!(filtered.contains("erlang-base_1:27.3.4.6-1_amd64"));
+    assert!(!filtered.contains("rabbitmq-server_4.1.3-1_all"));
+
+    let nothing = filter_snapshot_packages(SNAPSHOT_SHOW_OUTPUT, "no-such-package");
+    assert!(nothing.ends_with("Packages:\n"));
+}
+
+#[test]
+fn test_parse_db_cleanup_output() {
+    let output = "Loading mirrors, local repos, snapshots and published repos...
+Deleting unreferenced packages (3)...
+Building list of files in package pool...
+Deleting unreferenced files (7)...
+Disk space freed: 1.02 GiB...
+Compacting database...";
+    assert_eq!(
+        parse_db_cleanup_output(output),
+        CleanupSummary {
+            packages_deleted: 3,
+            files_deleted: 7,
+            space_freed: Some("1.02 GiB".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_parse_db_cleanup_output_with_nothing_to_delete() {
+    let output = "Loading list of all packages...
+Deleting unreferenced packages (0)...
+Building list of files in package pool...
+Compacting database...";
+    assert_eq!(parse_db_cleanup_output(output), CleanupSummary::default());
+}
+
+#[test]
+fn test_matches_expected_version() {
+    assert!(matches_expected_version("4.2.3-1", "4.2.3"));
+    assert!(matches_expected_version("4.2.3-1", "4.2.3-1"));
+    assert!(matches_expected_version("27.3-1~noble", "27.3"));
+    assert!(!matches_expected_version("4.2.30-1", "4.2.3"));
+    assert!(!matches_expected_version("4.2.3-1", "4.2.3-2"));
+}
@@ -12,8 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use bellhop::archive::{extract_version_from_filename, extract_versions_from_debs};
-use std::path::PathBuf;
+use bellhop::archive::{
+    ExtractionOptions, InputFormat, PackageSource, exclude_matching,
+    extract_name_and_version_from_filename, extract_package_key_from_deb, extract_version_from_deb,
+    extract_version_from_filename, extract_versions_from_debs, filter_by_architecture,
+    is_allowed_architecture, process_package_file, read_name_and_version_from_dsc,
+    resolve_symlink_target, validate_deb,
+};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use tar::{Builder, Header};
+use tempfile::TempDir;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
 
 #[test]
 fn test_extract_version_standard_format() {
@@ -31,6 +45,26 @@ fn test_extract_version_with_epoch() {
     );
 }
 
+#[test]
+fn test_extract_version_with_a_percent_encoded_epoch() {
+    assert_eq!(
+        extract_version_from_filename("erlang-base_1%3a27.3.4.6-1_amd64.deb").unwrap(),
+        "1:27.3.4.6-1"
+    );
+    assert_eq!(
+        extract_version_from_filename("erlang-base_1%3A27.3.4.6-1_amd64.deb").unwrap(),
+        "1:27.3.4.6-1"
+    );
+}
+
+#[test]
+fn test_extract_name_and_version_with_a_percent_encoded_epoch() {
+    assert_eq!(
+        extract_name_and_version_from_filename("erlang-base_1%3a27.3.4.6-1_amd64.deb").unwrap(),
+        ("erlang-base".to_string(), "1:27.3.4.6-1".to_string())
+    );
+}
+
 #[test]
 fn test_extract_version_complex_package_name() {
     assert_eq!(
@@ -72,6 +106,19 @@ fn test_extract_version_missing_architecture() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_extract_name_and_version() {
+    assert_eq!(
+        extract_name_and_version_from_filename("erlang-base_1:27.3.4.6-1_amd64.deb").unwrap(),
+        ("erlang-base".to_string(), "1:27.3.4.6-1".to_string())
+    );
+}
+
+#[test]
+fn test_extract_name_and_version_missing_name() {
+    assert!(extract_name_and_version_from_filename("_1.2.3-1_amd64.deb").is_err());
+}
+
 #[test]
 fn test_extract_versions_from_multiple_debs() {
     let paths = vec![
@@ -81,3 +128,781 @@ fn test_extract_versions_from_multiple_debs() {
     let versions = extract_versions_from_debs(&paths).unwrap();
     assert_eq!(versions, vec!["4.1.3-1", "4.1.4-1"]);
 }
+
+#[test]
+fn test_input_format_from_str() {
+    assert_eq!("auto".parse::<InputFormat>().unwrap(), InputFormat::Auto);
+    assert_eq!("deb".parse::<InputFormat>().unwrap(), InputFormat::Deb);
+    assert_eq!("tar".parse::<InputFormat>().unwrap(), InputFormat::Tar);
+    assert_eq!("tar.gz".parse::<InputFormat>().unwrap(), InputFormat::TarGz);
+    assert_eq!("tgz".parse::<InputFormat>().unwrap(), InputFormat::TarGz);
+    assert_eq!("zip".parse::<InputFormat>().unwrap(), InputFormat::Zip);
+    assert!("rpm".parse::<InputFormat>().is_err());
+}
+
+#[test]
+fn test_forced_deb_input_format_gets_a_deb_extension() {
+    let temp_dir = TempDir::new().unwrap();
+    let blob_path = temp_dir.path().join("blob");
+    fs::write(&blob_path, b"not really a package").unwrap();
+
+    let options = ExtractionOptions {
+        input_format: InputFormat::Deb,
+        ..ExtractionOptions::default()
+    };
+    match process_package_file(&blob_path, &options).unwrap() {
+        PackageSource::Archive { deb_files, .. } => {
+            assert_eq!(deb_files.len(), 1);
+            assert!(deb_files[0].ends_with("blob.deb"));
+            assert_eq!(fs::read(&deb_files[0]).unwrap(), b"not really a package");
+        }
+        PackageSource::SingleDeb(path) => panic!("Expected a renamed copy, got {}", path.display()),
+    }
+}
+
+#[test]
+fn test_forced_zip_input_format_rejects_other_content() {
+    let temp_dir = TempDir::new().unwrap();
+    let blob_path = temp_dir.path().join("blob.deb");
+    fs::write(&blob_path, b"not really a zip").unwrap();
+
+    let options = ExtractionOptions {
+        input_format: InputFormat::Zip,
+        ..ExtractionOptions::default()
+    };
+    assert!(process_package_file(&blob_path, &options).is_err());
+}
+
+#[test]
+fn test_dedup_deb_files_keeps_the_top_level_copy() {
+    let deduped = bellhop::archive::dedup_deb_files(vec![
+        PathBuf::from("/tmp/x/nested/dir/rabbitmq-server_4.1.3-1_all.deb"),
+        PathBuf::from("/tmp/x/rabbitmq-server_4.1.4-1_all.deb"),
+        PathBuf::from("/tmp/x/rabbitmq-server_4.1.3-1_all.deb"),
+        PathBuf::from("/tmp/x/nested/erlang-base_27.3.4.6-1_amd64.deb"),
+        PathBuf::from("/tmp/x/nested/erlang-base_27.3.4.6-1_arm64.deb"),
+    ]);
+    assert_eq!(
+        deduped,
+        vec![
+            PathBuf::from("/tmp/x/rabbitmq-server_4.1.3-1_all.deb"),
+            PathBuf::from("/tmp/x/rabbitmq-server_4.1.4-1_all.deb"),
+            PathBuf::from("/tmp/x/nested/erlang-base_27.3.4.6-1_amd64.deb"),
+            PathBuf::from("/tmp/x/nested/erlang-base_27.3.4.6-1_arm64.deb"),
+        ]
+    );
+}
+
+#[test]
+fn test_dedup_deb_files_keeps_unparseable_names() {
+    let files = vec![
+        PathBuf::from("/tmp/x/odd.deb"),
+        PathBuf::from("/tmp/x/nested/odd.deb"),
+    ];
+    assert_eq!(bellhop::archive::dedup_deb_files(files.clone()), files);
+}
+
+#[test]
+fn test_extract_package_key() {
+    assert_eq!(
+        bellhop::archive::extract_package_key_from_filename("erlang-base_1:27.3.4.6-1_amd64.deb")
+            .unwrap(),
+        (
+            "erlang-base".to_string(),
+            "1:27.3.4.6-1".to_string(),
+            "amd64".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_nested_tar_gz_in_a_subdirectory_is_expanded() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut tar_gz = Vec::new();
+    {
+        let encoder = GzEncoder::new(&mut tar_gz, Compression::fast());
+        let mut builder = Builder::new(encoder);
+        for deb in [
+            "rabbitmq-server_4.1.3-1_all.deb",
+            "rabbitmq-server_4.1.4-1_all.deb",
+        ] {
+            let mut header = Header::new_gnu();
+            header.set_size(3);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, deb, &b"deb"[..]).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    let zip_path = temp_dir.path().join("bundle.zip");
+    let mut zip = ZipWriter::new(File::create(&zip_path).unwrap());
+    zip.start_file("artifacts/packages.tar.gz", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(&tar_gz).unwrap();
+    zip.finish().unwrap();
+
+    match process_package_file(&zip_path, &ExtractionOptions::default()).unwrap() {
+        PackageSource::Archive { deb_files, .. } => {
+            let mut names: Vec<_> = deb_files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            assert_eq!(
+                names,
+                vec![
+                    "rabbitmq-server_4.1.3-1_all.deb",
+                    "rabbitmq-server_4.1.4-1_all.deb"
+                ]
+            );
+            assert!(
+                deb_files
+                    .iter()
+                    .all(|p| p.parent().unwrap().ends_with("artifacts"))
+            );
+        }
+        PackageSource::SingleDeb(path) => panic!("Expected an archive, got {}", path.display()),
+    }
+}
+
+fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_nested_zip_archives_are_expanded() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let bookworm = zip_bytes(&[("rabbitmq-server_4.1.3-1_all.deb", b"deb")]);
+    let noble = zip_bytes(&[("rabbitmq-server_4.1.4-1_all.deb", b"deb")]);
+    let zip_path = temp_dir.path().join("bundle.zip");
+    fs::write(
+        &zip_path,
+        zip_bytes(&[("bookworm.zip", &bookworm), ("dists/noble.zip", &noble)]),
+    )
+    .unwrap();
+
+    match process_package_file(&zip_path, &ExtractionOptions::default()).unwrap() {
+        PackageSource::Archive { deb_files, .. } => {
+            let mut names: Vec<_> = deb_files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            assert_eq!(
+                names,
+                vec![
+                    "rabbitmq-server_4.1.3-1_all.deb",
+                    "rabbitmq-server_4.1.4-1_all.deb"
+                ]
+            );
+            // the inner zips are removed once they are expanded
+            for deb in &deb_files {
+                let dir = deb.parent().unwrap();
+                assert!(!dir.join("bookworm.zip").exists());
+                assert!(!dir.join("noble.zip").exists());
+            }
+        }
+        PackageSource::SingleDeb(path) => panic!("Expected an archive, got {}", path.display()),
+    }
+}
+
+#[test]
+fn test_zip_archives_nested_too_deeply_are_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut nested = zip_bytes(&[("rabbitmq-server_4.1.3-1_all.deb", b"deb")]);
+    for level in 0..4 {
+        nested = zip_bytes(&[(&format!("level-{level}.zip"), &nested)]);
+    }
+    let zip_path = temp_dir.path().join("bundle.zip");
+    fs::write(&zip_path, zip_bytes(&[("outer.zip", &nested)])).unwrap();
+
+    assert!(matches!(
+        process_package_file(&zip_path, &ExtractionOptions::default()),
+        Err(bellhop::errors::BellhopError::ArchiveNestedTooDeeply { limit: 3, .. })
+    ));
+}
+
+#[test]
+fn test_extract_architecture_from_filename() {
+    assert_eq!(
+        bellhop::archive::extract_architecture_from_filename("erlang-base_1:27.3.4.6-1_arm64.deb")
+            .unwrap(),
+        "arm64"
+    );
+    assert_eq!(
+        bellhop::archive::extract_architecture_from_filename("rabbitmq-server_4.1.3-1_all.deb")
+            .unwrap(),
+        "all"
+    );
+    assert!(
+        bellhop::archive::extract_architecture_from_filename("rabbitmq-server_4.1.3-1.deb")
+            .is_err()
+    );
+    assert!(
+        bellhop::archive::extract_architecture_from_filename("rabbitmq-server_4.1.3-1_all.rpm")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_is_allowed_architecture() {
+    let allowed = vec!["amd64".to_string(), "arm64".to_string()];
+    assert!(is_allowed_architecture(
+        "erlang-base_1:27.3.4.6-1_arm64.deb",
+        &allowed
+    ));
+    assert!(is_allowed_architecture(
+        "rabbitmq-server_4.1.3-1_all.deb",
+        &allowed
+    ));
+    assert!(!is_allowed_architecture(
+        "erlang-base_1:27.3.4.6-1_i386.deb",
+        &allowed
+    ));
+    assert!(!is_allowed_architecture(
+        "rabbitmq-server-4.1.3.tar.xz",
+        &allowed
+    ));
+}
+
+#[test]
+fn test_filter_by_architecture_keeps_allowed_and_all() {
+    let debs = vec![
+        PathBuf::from("/tmp/debs/erlang-base_27.3-1_amd64.deb"),
+        PathBuf::from("/tmp/debs/erlang-base_27.3-1_arm64.deb"),
+        PathBuf::from("/tmp/debs/erlang-doc_27.3-1_all.deb"),
+    ];
+
+    let kept = filter_by_architecture(debs, &["amd64".to_string()]);
+    assert_eq!(
+        kept,
+        vec![
+            PathBuf::from("/tmp/debs/erlang-base_27.3-1_amd64.deb"),
+            PathBuf::from("/tmp/debs/erlang-doc_27.3-1_all.deb"),
+        ]
+    );
+}
+
+#[test]
+fn test_only_architectures_filters_archive_contents() {
+    let temp_dir = TempDir::new().unwrap();
+    let archive_path = temp_dir.path().join("packages.tar");
+    let mut builder = Builder::new(File::create(&archive_path).unwrap());
+    for name in [
+        "erlang-base_27.3-1_amd64.deb",
+        "erlang-base_27.3-1_arm64.deb",
+        "erlang-doc_27.3-1_all.deb",
+    ] {
+        let data = b"not really a package";
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, &data[..]).unwrap();
+    }
+    builder.finish().unwrap();
+
+    let arm64_only = ExtractionOptions {
+        only_architectures: Some(vec!["arm64".to_string()]),
+        ..ExtractionOptions::default()
+    };
+    match process_package_file(&archive_path, &arm64_only).unwrap() {
+        PackageSource::Archive { deb_files, .. } => {
+            let mut names: Vec<String> = deb_files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            assert_eq!(
+                names,
+                vec!["erlang-base_27.3-1_arm64.deb", "erlang-doc_27.3-1_all.deb"]
+            );
+        }
+        PackageSource::SingleDeb(path) => panic!("Expected an archive, got {}", path.display()),
+    }
+}
+
+#[test]
+fn test_only_architectures_rejects_a_single_deb_for_another_architecture() {
+    let temp_dir = TempDir::new().unwrap();
+    let deb_path = temp_dir.path().join("erlang-base_27.3-1_i386.deb");
+    fs::write(&deb_path, b"not really a package").unwrap();
+
+    let options = ExtractionOptions {
+        only_architectures: Some(vec!["amd64".to_string()]),
+        ..ExtractionOptions::default()
+    };
+    let result = process_package_file(&deb_path, &options);
+    assert!(matches!(
+        result,
+        Err(bellhop::errors::BellhopError::NoDebFilesInArchive { .. })
+    ));
+}
+
+fn zip_with_files(path: &std::path::Path, names: &[&str]) {
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    for name in names {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"package").unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn test_archive_with_only_rpm_files_names_the_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("packages.zip");
+    zip_with_files(
+        &zip_path,
+        &[
+            "rabbitmq-server-4.1.3-1.el8.noarch.rpm",
+            "rabbitmq-server-4.1.3-1.el9.noarch.rpm",
+            "erlang-base-dbgsym_27.3-1_amd64.ddeb",
+        ],
+    );
+
+    match process_package_file(&zip_path, &ExtractionOptions::default()) {
+        Err(bellhop::errors::BellhopError::OnlyUnsupportedPackagesInArchive { found, .. }) => {
+            assert_eq!(found, vec![".ddeb", ".rpm"]);
+        }
+        Err(e) => panic!("Expected OnlyUnsupportedPackagesInArchive, got {e:?}"),
+        Ok(_) => panic!("Expected OnlyUnsupportedPackagesInArchive, got packages"),
+    }
+}
+
+#[test]
+fn test_empty_archive_has_no_deb_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("empty.zip");
+    zip_with_files(&zip_path, &[]);
+
+    assert!(matches!(
+        process_package_file(&zip_path, &ExtractionOptions::default()),
+        Err(bellhop::errors::BellhopError::NoDebFilesInArchive { .. })
+    ));
+}
+
+fn package_names(source: PackageSource) -> Vec<String> {
+    match source {
+        PackageSource::Archive { deb_files, .. } => {
+            let mut names: Vec<String> = deb_files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        }
+        PackageSource::SingleDeb(path) => panic!("Expected an archive, got {}", path.display()),
+    }
+}
+
+#[test]
+fn test_ddeb_files_are_skipped_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("packages.zip");
+    zip_with_files(
+        &zip_path,
+        &[
+            "erlang-base_27.3-1_amd64.deb",
+            "erlang-base-dbgsym_27.3-1_amd64.ddeb",
+        ],
+    );
+
+    let source = process_package_file(&zip_path, &ExtractionOptions::default()).unwrap();
+    assert_eq!(package_names(source), vec!["erlang-base_27.3-1_amd64.deb"]);
+}
+
+#[test]
+fn test_ddeb_files_are_included_on_request() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("packages.zip");
+    zip_with_files(
+        &zip_path,
+        &[
+            "erlang-base_27.3-1_amd64.deb",
+            "erlang-base-dbgsym_27.3-1_amd64.ddeb",
+        ],
+    );
+
+    let options = ExtractionOptions {
+        include_ddeb: true,
+        ..ExtractionOptions::default()
+    };
+    let source = process_package_file(&zip_path, &options).unwrap();
+    assert_eq!(
+        package_names(source),
+        vec![
+            "erlang-base-dbgsym_27.3-1_amd64.ddeb",
+            "erlang-base_27.3-1_amd64.deb"
+        ]
+    );
+}
+
+#[test]
+fn test_extract_from_ddeb_filenames() {
+    assert_eq!(
+        extract_version_from_filename("erlang-base-dbgsym_1:27.3-1_amd64.ddeb").unwrap(),
+        "1:27.3-1"
+    );
+    assert_eq!(
+        bellhop::archive::extract_package_key_from_filename("erlang-base-dbgsym_27.3-1_arm64.ddeb")
+            .unwrap(),
+        (
+            "erlang-base-dbgsym".to_string(),
+            "27.3-1".to_string(),
+            "arm64".to_string()
+        )
+    );
+    assert_eq!(
+        bellhop::archive::extract_architecture_from_filename(
+            "erlang-base-dbgsym_27.3-1_arm64.ddeb"
+        )
+        .unwrap(),
+        "arm64"
+    );
+}
+
+#[test]
+fn test_exclude_matching_drops_matching_file_names() {
+    let deb_files = vec![
+        PathBuf::from("/tmp/x/erlang-base_27.3-1_amd64.deb"),
+        PathBuf::from("/tmp/x/erlang-base-dbg_27.3-1_amd64.deb"),
+        PathBuf::from("/tmp/x/erlang-doc_27.3-1_all.deb"),
+    ];
+    let patterns = vec!["*-dbg_*".to_string(), "erlang-doc*".to_string()];
+
+    assert_eq!(
+        exclude_matching(deb_files, &patterns),
+        vec![PathBuf::from("/tmp/x/erlang-base_27.3-1_amd64.deb")]
+    );
+}
+
+#[test]
+fn test_excluded_packages_are_dropped_from_archives() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("packages.zip");
+    zip_with_files(
+        &zip_path,
+        &[
+            "erlang-base_27.3-1_amd64.deb",
+            "erlang-base-dbg_27.3-1_amd64.deb",
+        ],
+    );
+
+    let options = ExtractionOptions {
+        exclude: vec!["*-dbg_*".to_string()],
+        ..ExtractionOptions::default()
+    };
+    let source = process_package_file(&zip_path, &options).unwrap();
+    assert_eq!(package_names(source), vec!["erlang-base_27.3-1_amd64.deb"]);
+}
+
+#[test]
+fn test_excluding_every_package_leaves_no_deb_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("packages.zip");
+    zip_with_files(&zip_path, &["erlang-base-dbg_27.3-1_amd64.deb"]);
+
+    let options = ExtractionOptions {
+        exclude: vec!["*-dbg_*".to_string()],
+        ..ExtractionOptions::default()
+    };
+    assert!(matches!(
+        process_package_file(&zip_path, &options),
+        Err(bellhop::errors::BellhopError::NoDebFilesInArchive { .. })
+    ));
+}
+
+fn tar_gz_with_package() -> Vec<u8> {
+    let contents: Vec<u8> = (0..64 * 1024).map(|i| (i * 7919 % 251) as u8).collect();
+    let mut tar_gz = Vec::new();
+    {
+        let encoder = GzEncoder::new(&mut tar_gz, Compression::fast());
+        let mut builder = Builder::new(encoder);
+        let mut header = Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(
+                &mut header,
+                "rabbitmq-server_4.1.3-1_all.deb",
+                &contents[..],
+            )
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+    tar_gz
+}
+
+fn is_empty_or_truncated(path: &std::path::Path) -> bool {
+    matches!(
+        process_package_file(path, &ExtractionOptions::default()),
+        Err(bellhop::errors::BellhopError::EmptyOrTruncatedArchive { .. })
+    )
+}
+
+#[test]
+fn test_zero_byte_archives_are_reported_as_empty() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["packages.tar.gz", "packages.tar", "packages.zip"] {
+        let path = temp_dir.path().join(name);
+        File::create(&path).unwrap();
+        assert!(is_empty_or_truncated(&path), "{name}");
+    }
+}
+
+#[test]
+fn test_truncated_gzip_stream_is_reported_as_truncated() {
+    let temp_dir = TempDir::new().unwrap();
+    let tar_gz = tar_gz_with_package();
+
+    let complete = temp_dir.path().join("complete.tar.gz");
+    fs::write(&complete, &tar_gz).unwrap();
+    assert!(process_package_file(&complete, &ExtractionOptions::default()).is_ok());
+
+    for len in [10, tar_gz.len() / 2] {
+        let truncated = temp_dir.path().join(format!("truncated-{len}.tar.gz"));
+        fs::write(&truncated, &tar_gz[..len]).unwrap();
+        assert!(
+            is_empty_or_truncated(&truncated),
+            "truncated to {len} bytes"
+        );
+    }
+}
+
+#[test]
+fn test_corrupted_gzip_stream_is_an_extraction_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("corrupted.tar.gz");
+    fs::write(
+        &path,
+        b"This is not a gzip stream, only some text in its place",
+    )
+    .unwrap();
+
+    assert!(matches!(
+        process_package_file(&path, &ExtractionOptions::default()),
+        Err(bellhop::errors::BellhopError::ArchiveExtractionFailed(_))
+    ));
+}
+
+const DSC: &str = "-----BEGIN PGP SIGNED MESSAGE-----
+Hash: SHA512
+
+Format: 3.0 (quilt)
+Source: rabbitmq-server
+Binary: rabbitmq-server
+Architecture: all
+Version: 4.1.3-1
+Files:
+ 0123456789abcdef0123456789abcdef 42 rabbitmq-server_4.1.3.orig.tar.gz
+ 0123456789abcdef0123456789abcdef 21 rabbitmq-server_4.1.3-1.debian.tar.xz
+
+-----BEGIN PGP SIGNATURE-----
+-----END PGP SIGNATURE-----
+";
+
+fn zip_with_source_package(path: &std::path::Path) {
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    let files: [(&str, &[u8]); 4] = [
+        ("rabbitmq-server_4.1.3-1_all.deb", b"package"),
+        ("rabbitmq-server_4.1.3-1.dsc", DSC.as_bytes()),
+        ("rabbitmq-server_4.1.3.orig.tar.gz", &tar_gz_with_package()),
+        ("rabbitmq-server_4.1.3-1.debian.tar.xz", b"debian"),
+    ];
+    for (name, contents) in files {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn test_source_packages_are_included_on_request() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("packages.zip");
+    zip_with_source_package(&zip_path);
+
+    let options = ExtractionOptions {
+        with_source: true,
+        ..ExtractionOptions::default()
+    };
+    match process_package_file(&zip_path, &options).unwrap() {
+        PackageSource::Archive { deb_files, .. } => {
+            let mut names: Vec<String> = deb_files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            assert_eq!(
+                names,
+                vec![
+                    "rabbitmq-server_4.1.3-1.dsc",
+                    "rabbitmq-server_4.1.3-1_all.deb"
+                ]
+            );
+            // the tarballs listed in the .dsc must stay next to it for aptly
+            let dir = deb_files[0].parent().unwrap();
+            assert!(dir.join("rabbitmq-server_4.1.3.orig.tar.gz").is_file());
+            assert!(dir.join("rabbitmq-server_4.1.3-1.debian.tar.xz").is_file());
+        }
+        PackageSource::SingleDeb(path) => panic!("Expected an archive, got {}", path.display()),
+    }
+}
+
+#[test]
+fn test_source_packages_are_skipped_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("packages.zip");
+    zip_with_source_package(&zip_path);
+
+    let source = process_package_file(&zip_path, &ExtractionOptions::default()).unwrap();
+    assert!(
+        package_names(source)
+            .iter()
+            .all(|name| name.ends_with(".deb"))
+    );
+}
+
+#[test]
+fn test_read_name_and_version_from_a_signed_dsc() {
+    let temp_dir = TempDir::new().unwrap();
+    let dsc_path = temp_dir.path().join("rabbitmq-server_4.1.3-1.dsc");
+    fs::write(&dsc_path, DSC).unwrap();
+
+    assert_eq!(
+        read_name_and_version_from_dsc(&dsc_path).unwrap(),
+        ("rabbitmq-server".to_string(), "4.1.3-1".to_string())
+    );
+    assert_eq!(extract_version_from_deb(&dsc_path).unwrap(), "4.1.3-1");
+    assert_eq!(
+        extract_package_key_from_deb(&dsc_path).unwrap(),
+        (
+            "rabbitmq-server".to_string(),
+            "4.1.3-1".to_string(),
+            "source".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_dsc_without_a_version_is_malformed() {
+    let temp_dir = TempDir::new().unwrap();
+    let dsc_path = temp_dir.path().join("rabbitmq-server_4.1.3-1.dsc");
+    fs::write(&dsc_path, "Format: 3.0 (quilt)\nSource: rabbitmq-server\n").unwrap();
+
+    assert!(matches!(
+        read_name_and_version_from_dsc(&dsc_path),
+        Err(bellhop::errors::BellhopError::MalformedDscFile { .. })
+    ));
+}
+
+#[test]
+fn test_filter_by_architecture_keeps_source_packages() {
+    let kept = filter_by_architecture(
+        vec![
+            PathBuf::from("rabbitmq-server_4.1.3-1.dsc"),
+            PathBuf::from("erlang-base_27.3-1_arm64.deb"),
+        ],
+        &["amd64".to_string()],
+    );
+    assert_eq!(kept, vec![PathBuf::from("rabbitmq-server_4.1.3-1.dsc")]);
+}
+
+fn fixture_deb(filename: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("debs")
+        .join(filename)
+}
+
+fn invalid_deb_reason(path: &std::path::Path) -> String {
+    match validate_deb(path) {
+        Err(bellhop::errors::BellhopError::InvalidDebFile { reason, .. }) => reason,
+        other => panic!("Expected an invalid .deb file, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_validate_deb_accepts_a_real_package() {
+    validate_deb(&fixture_deb("rabbitmq-server_4.1.3-1_all.deb")).unwrap();
+    validate_deb(&fixture_deb("erlang-base_27.3.4.6-1_amd64.deb")).unwrap();
+}
+
+#[test]
+fn test_validate_deb_rejects_an_html_error_page() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("rabbitmq-server_4.1.3-1_all.deb");
+    fs::write(&path, "<html><body>404 Not Found</body></html>").unwrap();
+
+    assert_eq!(invalid_deb_reason(&path), "not an ar archive");
+}
+
+#[test]
+fn test_validate_deb_rejects_a_truncated_download() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("rabbitmq-server_4.1.3-1_all.deb");
+    let contents = fs::read(fixture_deb("rabbitmq-server_4.1.3-1_all.deb")).unwrap();
+    fs::write(&path, &contents[..contents.len() / 2]).unwrap();
+
+    assert!(invalid_deb_reason(&path).ends_with("is truncated"));
+}
+
+#[test]
+fn test_validate_deb_requires_the_data_member() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("rabbitmq-server_4.1.3-1_all.deb");
+    let mut contents = b"!<arch>\n".to_vec();
+    for (name, data) in [("debian-binary", "2.0\n"), ("control.tar.xz", "")] {
+        contents.extend(
+            format!(
+                "{name:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+                0,
+                0,
+                0,
+                644,
+                data.len()
+            )
+            .bytes(),
+        );
+        contents.extend(data.bytes());
+    }
+    fs::write(&path, contents).unwrap();
+
+    assert_eq!(invalid_deb_reason(&path), "no data.tar.* member");
+}
+
+#[test]
+fn test_resolve_symlink_target() {
+    assert_eq!(
+        resolve_symlink_target(Path::new("latest/a.deb"), Path::new("../pool/a.deb")),
+        Some(PathBuf::from("pool/a.deb"))
+    );
+    assert_eq!(
+        resolve_symlink_target(Path::new("a.deb"), Path::new("./pool/./a.deb")),
+        Some(PathBuf::from("pool/a.deb"))
+    );
+    assert_eq!(
+        resolve_symlink_target(Path::new("latest/a.deb"), Path::new("../../a.deb")),
+        None
+    );
+    assert_eq!(
+        resolve_symlink_target(Path::new("a.deb"), Path::new("/etc/passwd")),
+        None
+    );
+    assert_eq!(
+        resolve_symlink_target(Path::new("a/b.deb"), Path::new("..")),
+        None
+    );
+}
@@ -13,8 +13,11 @@
 // limitations under the License.
 
 use bellhop::cli;
-use chrono::Local;
+use bellhop::common::{Project, SnapshotSource};
+use bellhop::deb::DistributionAlias;
+use chrono::{Local, NaiveDate};
 use clap::ArgMatches;
+use log::LevelFilter;
 
 // Drills down to the leaf subcommand's matches, e.g. rabbitmq -> deb -> publish.
 fn leaf_matches(args: &[&str]) -> ArgMatches {
@@ -49,6 +52,56 @@ fn test_publish_suffix_defaults_to_today() {
     assert_eq!(cli::suffix(&matches), expected);
 }
 
+#[test]
+fn test_publish_suffix_with_a_custom_date_format() {
+    let matches = leaf_matches(&[
+        "bellhop",
+        "rabbitmq",
+        "deb",
+        "publish",
+        "-d",
+        "bookworm",
+        "--suffix-date-format",
+        "%Y-%m-%d",
+    ]);
+    let expected = Local::now().format("%Y-%m-%d").to_string();
+    assert_eq!(cli::suffix(&matches), expected);
+}
+
+#[test]
+fn test_suffix_date_format_is_validated() {
+    for format in ["%Q", ""] {
+        let result = cli::parser().try_get_matches_from([
+            "bellhop",
+            "rabbitmq",
+            "deb",
+            "publish",
+            "-d",
+            "bookworm",
+            "--suffix-date-format",
+            format,
+        ]);
+        assert!(result.is_err(), "'{format}' should be rejected");
+    }
+}
+
+#[test]
+fn test_suffix_date_format_conflicts_with_suffix() {
+    let result = cli::parser().try_get_matches_from([
+        "bellhop",
+        "rabbitmq",
+        "deb",
+        "publish",
+        "-d",
+        "bookworm",
+        "--suffix",
+        "v1",
+        "--suffix-date-format",
+        "%Y-%m-%d",
+    ]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_add_accepts_suffix() {
     let matches = leaf_matches(&[
@@ -65,3 +118,199 @@ fn test_publish_still_requires_a_distribution() {
         "publish must still require a distribution selector"
     );
 }
+
+#[test]
+fn test_snapshot_source_defaults_to_repo() {
+    let matches = leaf_matches(&[
+        "bellhop", "rabbitmq", "deb", "add", "-p", "pkg.deb", "-d", "bookworm",
+    ]);
+    assert_eq!(cli::snapshot_source(&matches), SnapshotSource::Repo);
+}
+
+#[test]
+fn test_snapshot_take_accepts_snapshot_from_published() {
+    let matches = leaf_matches(&[
+        "bellhop",
+        "rabbitmq",
+        "snapshot",
+        "take",
+        "-d",
+        "bookworm",
+        "--snapshot-from",
+        "published",
+    ]);
+    assert_eq!(cli::snapshot_source(&matches), SnapshotSource::Published);
+}
+
+#[test]
+fn test_snapshot_from_rejects_unknown_sources() {
+    let result = cli::parser().try_get_matches_from([
+        "bellhop",
+        "rabbitmq",
+        "snapshot",
+        "take",
+        "-d",
+        "bookworm",
+        "--snapshot-from",
+        "mirror",
+    ]);
+    assert!(result.is_err());
+}
+
+fn top_level_matches(args: &[&str]) -> ArgMatches {
+    cli::parser()
+        .try_get_matches_from(args)
+        .expect("arguments should parse")
+}
+
+#[test]
+fn test_log_levels_default() {
+    let matches = top_level_matches(&["bellhop", "rabbitmq", "snapshot", "list", "--all"]);
+    assert_eq!(
+        cli::log_levels(&matches),
+        (LevelFilter::Info, LevelFilter::Debug)
+    );
+}
+
+#[test]
+fn test_log_levels_quiet() {
+    let matches = top_level_matches(&["bellhop", "-q", "rabbitmq", "snapshot", "list", "--all"]);
+    assert_eq!(
+        cli::log_levels(&matches),
+        (LevelFilter::Warn, LevelFilter::Warn)
+    );
+}
+
+#[test]
+fn test_repeated_verbose_bumps_log_levels() {
+    let once = top_level_matches(&[
+        "bellhop",
+        "--verbose",
+        "rabbitmq",
+        "snapshot",
+        "list",
+        "--all",
+    ]);
+    assert_eq!(
+        cli::log_levels(&once),
+        (LevelFilter::Info, LevelFilter::Trace)
+    );
+
+    let twice = top_level_matches(&[
+        "bellhop",
+        "rabbitmq",
+        "snapshot",
+        "list",
+        "--all",
+        "--verbose",
+        "--verbose",
+    ]);
+    assert_eq!(
+        cli::log_levels(&twice),
+        (LevelFilter::Debug, LevelFilter::Trace)
+    );
+
+    let thrice = top_level_matches(&[
+        "bellhop",
+        "--verbose",
+        "--verbose",
+        "--verbose",
+        "rabbitmq",
+        "snapshot",
+        "list",
+        "--all",
+    ]);
+    assert_eq!(
+        cli::log_levels(&thrice),
+        (LevelFilter::Trace, LevelFilter::Trace)
+    );
+}
+
+#[test]
+fn test_short_verbose_flags_are_counted() {
+    let matches = top_level_matches(&["bellhop", "-vv", "rabbitmq", "snapshot", "list", "--all"]);
+    assert_eq!(
+        cli::log_levels(&matches),
+        (LevelFilter::Debug, LevelFilter::Trace)
+    );
+
+    let matches = top_level_matches(&[
+        "bellhop", "rabbitmq", "snapshot", "list", "--all", "-v", "-vv",
+    ]);
+    assert_eq!(
+        cli::log_levels(&matches),
+        (LevelFilter::Trace, LevelFilter::Trace)
+    );
+}
+
+#[test]
+fn test_read_package_file_paths_skips_blank_lines() {
+    let input = "/tmp/a.deb\n\n  /tmp/b.tar.gz  \n";
+    assert_eq!(
+        cli::read_package_file_paths(input.as_bytes()).unwrap(),
+        ["/tmp/a.deb", "/tmp/b.tar.gz"]
+    );
+    assert!(cli::read_package_file_paths(&b""[..]).unwrap().is_empty());
+}
+
+#[test]
+fn test_parse_distribution_name() {
+    assert_eq!(
+        cli::parse_distribution_name("bookworm=stable"),
+        Ok((DistributionAlias::Bookworm, "stable".to_string()))
+    );
+    assert!(cli::parse_distribution_name("bookworm").is_err());
+    assert!(cli::parse_distribution_name("bookworm=").is_err());
+    assert!(
+        cli::parse_distribution_name("buster=oldstable")
+            .unwrap_err()
+            .contains("buster")
+    );
+}
+
+#[test]
+fn test_parse_snapshot_date() {
+    let expected = NaiveDate::from_ymd_opt(2025, 8, 4).unwrap();
+    assert_eq!(cli::parse_snapshot_date("04-Aug-25"), Ok(expected));
+    assert_eq!(cli::parse_snapshot_date("2025-08-04"), Ok(expected));
+    assert!(cli::parse_snapshot_date("yesterday").is_err());
+    assert!(cli::parse_snapshot_date("2025-13-01").is_err());
+}
+
+#[test]
+fn test_parse_watch_map() {
+    assert_eq!(
+        cli::parse_watch_map("server=rabbitmq"),
+        Ok(("server".to_string(), Project::RabbitMQ))
+    );
+    assert_eq!(
+        cli::parse_watch_map("otp=erlang"),
+        Ok(("otp".to_string(), Project::Erlang))
+    );
+    assert!(cli::parse_watch_map("server").is_err());
+    assert!(cli::parse_watch_map("=rabbitmq").is_err());
+    assert!(cli::parse_watch_map("../server=rabbitmq").is_err());
+    assert!(cli::parse_watch_map("a/b=rabbitmq").is_err());
+    assert!(
+        cli::parse_watch_map("server=rabbitmq-server")
+            .unwrap_err()
+            .contains("rabbitmq-server")
+    );
+}
+
+#[test]
+fn test_parse_arch_map() {
+    assert_eq!(
+        cli::parse_arch_map("all=amd64"),
+        Ok(("all".to_string(), "amd64".to_string()))
+    );
+    assert_eq!(
+        cli::parse_arch_map("armhf=arm64"),
+        Ok(("armhf".to_string(), "arm64".to_string()))
+    );
+    assert!(cli::parse_arch_map("all").is_err());
+    assert!(cli::parse_arch_map("all=").is_err());
+    assert!(cli::parse_arch_map("=amd64").is_err());
+    assert!(cli::parse_arch_map("all=amd64=arm64").is_err());
+    assert!(cli::parse_arch_map("all=amd 64").is_err());
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use bellhop::common::Project;
+use bellhop::common::{Project, SnapshotSource};
 
 #[test]
 fn test_project_display() {
@@ -21,12 +21,36 @@ fn test_project_display() {
     assert_eq!(Project::CliTools.to_string(), "cli-tools");
 }
 
+#[test]
+fn test_project_round_trip() {
+    for project in [Project::RabbitMQ, Project::Erlang, Project::CliTools] {
+        assert_eq!(project.to_string().parse::<Project>(), Ok(project));
+    }
+    assert!("rabbitmq-server".parse::<Project>().is_err());
+}
+
+#[test]
+fn test_project_component() {
+    assert_eq!(Project::RabbitMQ.component(), "rabbitmq");
+    assert_eq!(Project::Erlang.component(), "erlang");
+    assert_eq!(Project::CliTools.component(), "cli");
+}
+
 #[test]
 fn test_project_copy_clone() {
     let p1 = Project::RabbitMQ;
     let p2 = p1;
     assert_eq!(p1, p2);
 
-    let p3 = p1.clone();
+    let p3 = Clone::clone(&p1);
     assert_eq!(p1, p3);
 }
+
+#[test]
+fn test_snapshot_source_round_trip() {
+    for source in [SnapshotSource::Repo, SnapshotSource::Published] {
+        assert_eq!(source.to_string().parse::<SnapshotSource>(), Ok(source));
+    }
+    assert!("mirror".parse::<SnapshotSource>().is_err());
+    assert_eq!(SnapshotSource::default(), SnapshotSource::Repo);
+}
@@ -0,0 +1,225 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use bellhop::debedit::{control_field, field_value, map_architecture, replace_field};
+use bellhop::errors::BellhopError;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::path::Path;
+use tar::{Builder, Header};
+use tempfile::TempDir;
+use test_helpers::*;
+
+const CONTROL: &str = "Package: example\nVersion: 1.0-1\nArchitecture: amd64\nDescription: An example\n more description\n";
+
+fn ar_members(deb: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut members = Vec::new();
+    let mut offset = 8;
+    while offset < deb.len() {
+        let header = &deb[offset..offset + 60];
+        let name = String::from_utf8_lossy(&header[..16])
+            .trim_end()
+            .trim_end_matches('/')
+            .to_string();
+        let size: usize = String::from_utf8_lossy(&header[48..58])
+            .trim()
+            .parse()
+            .unwrap();
+        let start = offset + 60;
+        members.push((name, deb[start..start + size].to_vec()));
+        offset = start + size + size % 2;
+    }
+    members
+}
+
+fn ar_archive(members: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut deb = b"!<arch>\n".to_vec();
+    for (name, data) in members {
+        deb.extend(
+            format!(
+                "{name:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+                0,
+                0,
+                0,
+                100644,
+                data.len()
+            )
+            .bytes(),
+        );
+        deb.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            deb.push(b'\n');
+        }
+    }
+    deb
+}
+
+fn gz_control_deb(path: &Path) {
+    let mut control_tar = Vec::new();
+    {
+        let encoder = GzEncoder::new(&mut control_tar, Compression::fast());
+        let mut builder = Builder::new(encoder);
+        for (name, contents) in [
+            ("./control", CONTROL),
+            ("./md5sums", "abc  usr/bin/example\n"),
+        ] {
+            let mut header = Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+    fs::write(
+        path,
+        ar_archive(&[
+            ("debian-binary", b"2.0\n"),
+            ("control.tar.gz", &control_tar),
+            ("data.tar.gz", b"data"),
+        ]),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_map_architecture_rewrites_a_fixture_deb() {
+    let temp_dir = TempDir::new().unwrap();
+    let original = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+
+    let rewritten = map_architecture(
+        &original,
+        &[("all".to_string(), "amd64".to_string())],
+        temp_dir.path(),
+    )
+    .unwrap()
+    .expect("the mapping should apply");
+
+    assert_eq!(
+        rewritten.file_name().unwrap(),
+        "rabbitmq-server_4.1.3-1_amd64.deb"
+    );
+    assert_eq!(
+        control_field(&rewritten, "Architecture")
+            .unwrap()
+            .as_deref(),
+        Some("amd64")
+    );
+    assert_eq!(
+        control_field(&rewritten, "Version").unwrap().as_deref(),
+        Some("4.1.3-1")
+    );
+    bellhop::archive::validate_deb(&rewritten).unwrap();
+
+    // the package contents are copied as they are
+    let before = ar_members(&fs::read(&original).unwrap());
+    let after = ar_members(&fs::read(&rewritten).unwrap());
+    let names: Vec<&str> = after.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["debian-binary", "control.tar.xz", "data.tar.xz"]);
+    assert_eq!(before[0], after[0]);
+    assert_eq!(before[2], after[2]);
+}
+
+#[test]
+fn test_map_architecture_leaves_other_architectures_alone() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let rewritten = map_architecture(
+        &test_package_path("rabbitmq-server_4.1.3-1_all.deb"),
+        &[("amd64".to_string(), "arm64".to_string())],
+        temp_dir.path(),
+    )
+    .unwrap();
+
+    assert_eq!(rewritten, None);
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_map_architecture_rewrites_a_gzip_control_archive() {
+    let temp_dir = TempDir::new().unwrap();
+    let original = temp_dir.path().join("example_1.0-1_amd64.deb");
+    gz_control_deb(&original);
+    let dest_dir = temp_dir.path().join("rewritten");
+    fs::create_dir(&dest_dir).unwrap();
+
+    let rewritten = map_architecture(
+        &original,
+        &[
+            ("arm64".to_string(), "amd64".to_string()),
+            ("amd64".to_string(), "all".to_string()),
+        ],
+        &dest_dir,
+    )
+    .unwrap()
+    .expect("the mapping should apply");
+
+    assert_eq!(rewritten, dest_dir.join("example_1.0-1_all.deb"));
+    assert_eq!(
+        control_field(&rewritten, "Architecture")
+            .unwrap()
+            .as_deref(),
+        Some("all")
+    );
+    assert_eq!(
+        control_field(&rewritten, "Description").unwrap().as_deref(),
+        Some("An example")
+    );
+    assert_eq!(ar_members(&fs::read(&rewritten).unwrap())[2].1, b"data");
+}
+
+#[test]
+fn test_map_architecture_rejects_a_file_that_is_not_a_deb() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("example_1.0-1_all.deb");
+    fs::write(&path, "<html>Not Found</html>").unwrap();
+
+    assert!(matches!(
+        map_architecture(
+            &path,
+            &[("all".to_string(), "amd64".to_string())],
+            temp_dir.path()
+        ),
+        Err(BellhopError::DebRewriteFailed { .. })
+    ));
+}
+
+#[test]
+fn test_field_value() {
+    assert_eq!(field_value(CONTROL, "Architecture"), Some("amd64"));
+    assert_eq!(field_value(CONTROL, "architecture"), Some("amd64"));
+    assert_eq!(field_value(CONTROL, "Depends"), None);
+    // continuation lines are not fields
+    assert_eq!(field_value(" Architecture: all\n", "Architecture"), None);
+}
+
+#[test]
+fn test_replace_field_keeps_other_lines() {
+    assert_eq!(
+        replace_field(CONTROL, "Architecture", "all"),
+        "Package: example\nVersion: 1.0-1\nArchitecture: all\nDescription: An example\n more description\n"
+    );
+    assert_eq!(
+        replace_field(
+            "Package: example\nArchitecture: amd64",
+            "Architecture",
+            "all"
+        ),
+        "Package: example\nArchitecture: all"
+    );
+}
@@ -130,3 +130,28 @@ fn test_parse_release_url_with_whitespace() {
     assert_eq!(result.owner, "owner");
     assert_eq!(result.tag, "v1.0");
 }
+
+#[test]
+fn test_parse_repository_url() {
+    for url in [
+        "https://github.com/rabbitmq/rabbitmq-server",
+        "https://github.com/rabbitmq/rabbitmq-server/",
+        "https://github.com/rabbitmq/rabbitmq-server/releases",
+    ] {
+        assert_eq!(
+            gh::parse_repository_url(url).unwrap(),
+            ("rabbitmq".to_string(), "rabbitmq-server".to_string())
+        );
+    }
+}
+
+#[test]
+fn test_parse_repository_url_rejects_other_urls() {
+    for url in [
+        "https://gitlab.com/rabbitmq/rabbitmq-server",
+        "https://github.com/rabbitmq",
+        "https://github.com/rabbitmq/rabbitmq-server/releases/tag/v4.2.3",
+    ] {
+        assert!(gh::parse_repository_url(url).is_err(), "{url}");
+    }
+}
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use bellhop::gh::releases::{ReleaseAsset, filter_assets, glob_match};
+use bellhop::gh::releases::{
+    ReleaseAsset, ReleaseSummary, filter_assets, glob_match, is_per_distribution_pattern,
+    pattern_for_distribution, select_release, version_from_tag,
+};
 
 #[test]
 fn test_glob_match_star_deb() {
@@ -97,3 +100,90 @@ fn test_filter_assets_no_matches() {
     let filtered = filter_assets(assets, "*.deb");
     assert!(filtered.is_empty());
 }
+
+#[test]
+fn test_pattern_for_distribution() {
+    assert!(is_per_distribution_pattern("*{distribution}*.deb"));
+    assert!(!is_per_distribution_pattern("*.deb"));
+    assert_eq!(
+        pattern_for_distribution("*{distribution}*.deb", "noble"),
+        "*noble*.deb"
+    );
+    assert_eq!(pattern_for_distribution("*.deb", "noble"), "*.deb");
+}
+
+fn release(tag: &str, prerelease: bool, draft: bool) -> ReleaseSummary {
+    ReleaseSummary {
+        tag_name: tag.to_string(),
+        prerelease,
+        draft,
+    }
+}
+
+fn mixed_releases() -> Vec<ReleaseSummary> {
+    vec![
+        release("v4.2.9", false, false),
+        release("v4.2.10", false, false),
+        release("v4.2.11-rc.1", true, false),
+        release("v4.2.12", false, true),
+        release("v4.3.0-beta.2", true, false),
+        release("v4.1.7", false, false),
+    ]
+}
+
+#[test]
+fn test_select_release_picks_the_highest_matching_tag() {
+    let releases = mixed_releases();
+    let selected = select_release(&releases, "v4.2.*", false).unwrap();
+    assert_eq!(selected.tag_name, "v4.2.10");
+}
+
+#[test]
+fn test_select_release_skips_drafts() {
+    let releases = mixed_releases();
+    let selected = select_release(&releases, "v4.2.1*", true).unwrap();
+    assert_eq!(selected.tag_name, "v4.2.11-rc.1");
+}
+
+#[test]
+fn test_select_release_includes_prereleases_on_request() {
+    let releases = mixed_releases();
+    assert_eq!(
+        select_release(&releases, "v4.*", true).unwrap().tag_name,
+        "v4.3.0-beta.2"
+    );
+    assert_eq!(
+        select_release(&releases, "v4.*", false).unwrap().tag_name,
+        "v4.2.10"
+    );
+}
+
+#[test]
+fn test_select_release_sorts_prereleases_before_their_release() {
+    let releases = vec![
+        release("v4.2.0-rc.2", true, false),
+        release("v4.2.0", false, false),
+        release("v4.2.0-rc.1", true, false),
+    ];
+    assert_eq!(
+        select_release(&releases, "v4.2.0*", true).unwrap().tag_name,
+        "v4.2.0"
+    );
+}
+
+#[test]
+fn test_select_release_without_a_match() {
+    let releases = mixed_releases();
+    assert!(select_release(&releases, "v5.*", true).is_none());
+    assert!(select_release(&releases, "v4.3.*", false).is_none());
+    assert!(select_release(&[], "*", true).is_none());
+}
+
+#[test]
+fn test_version_from_tag() {
+    assert_eq!(version_from_tag("v4.2.3"), "4.2.3");
+    assert_eq!(version_from_tag("4.2.3"), "4.2.3");
+    assert_eq!(version_from_tag("v4.2.0-rc.1"), "4.2.0-rc.1");
+    assert_eq!(version_from_tag("OTP-27.3.4"), "OTP-27.3.4");
+    assert_eq!(version_from_tag("vendor-1.0"), "vendor-1.0");
+}
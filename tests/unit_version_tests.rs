@@ -0,0 +1,135 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bellhop::version::compare_versions;
+use std::cmp::Ordering;
+
+#[test]
+fn test_numeric_components_compare_numerically() {
+    assert_eq!(compare_versions("4.1.10-1", "4.1.7-1"), Ordering::Greater);
+    assert_eq!(compare_versions("4.1.3-1", "4.1.4-1"), Ordering::Less);
+    assert_eq!(
+        compare_versions("27.3.4.6-1", "27.3.4.6-1"),
+        Ordering::Equal
+    );
+}
+
+#[test]
+fn test_revision_breaks_ties() {
+    assert_eq!(compare_versions("4.1.3-2", "4.1.3-1"), Ordering::Greater);
+}
+
+#[test]
+fn test_epoch_wins_over_upstream_version() {
+    assert_eq!(compare_versions("1:1.0-1", "9.9-1"), Ordering::Greater);
+}
+
+#[test]
+fn test_tilde_sorts_before_release() {
+    assert_eq!(compare_versions("4.2.0~rc.1-1", "4.2.0-1"), Ordering::Less);
+}
+
+#[test]
+fn test_missing_epoch_is_zero() {
+    assert_eq!(compare_versions("0:1.0-1", "1.0-1"), Ordering::Equal);
+    assert_eq!(compare_versions("1:0.1-1", "0:9.9-1"), Ordering::Greater);
+    assert_eq!(compare_versions("2:1.0", "10:0.1"), Ordering::Less);
+}
+
+#[test]
+fn test_missing_revision_sorts_before_any_revision() {
+    assert_eq!(compare_versions("1.0", "1.0-0"), Ordering::Equal);
+    assert_eq!(compare_versions("1.0", "1.0-1"), Ordering::Less);
+}
+
+#[test]
+fn test_leading_zeroes_are_ignored() {
+    assert_eq!(compare_versions("1.001", "1.1"), Ordering::Equal);
+    assert_eq!(compare_versions("1.010", "1.9"), Ordering::Greater);
+}
+
+#[test]
+fn test_long_digit_runs_do_not_overflow() {
+    assert_eq!(
+        compare_versions(
+            "1.123456789012345678901234567890",
+            "1.123456789012345678901234567891"
+        ),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn test_policy_tilde_examples() {
+    // From the Debian Policy: ~~ < ~~a < ~ < (empty) < a
+    let ordered = ["1.0~~", "1.0~~a", "1.0~", "1.0", "1.0a"];
+    for pair in ordered.windows(2) {
+        assert_eq!(
+            compare_versions(pair[0], pair[1]),
+            Ordering::Less,
+            "{} < {}",
+            pair[0],
+            pair[1]
+        );
+        assert_eq!(compare_versions(pair[1], pair[0]), Ordering::Greater);
+    }
+}
+
+#[test]
+fn test_tilde_in_revision() {
+    assert_eq!(compare_versions("1.0-1~bpo1", "1.0-1"), Ordering::Less);
+    assert_eq!(compare_versions("1.0-1~bpo1", "1.0-0"), Ordering::Greater);
+}
+
+#[test]
+fn test_letters_sort_before_non_letters() {
+    assert_eq!(compare_versions("1.0a", "1.0+"), Ordering::Less);
+    assert_eq!(compare_versions("1.0+b1", "1.0.1"), Ordering::Less);
+    assert_eq!(compare_versions("1.0+dfsg", "1.0"), Ordering::Greater);
+}
+
+#[test]
+fn test_hyphens_in_upstream_version() {
+    // Only the last hyphen separates the Debian revision
+    assert_eq!(
+        compare_versions("1.0-beta-2", "1.0-beta-1"),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions("1.0-rc-1", "1.0-beta-1"),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn test_rabbitmq_and_erlang_versions() {
+    assert_eq!(compare_versions("4.2.0-1", "4.1.10-1"), Ordering::Greater);
+    assert_eq!(
+        compare_versions("1:27.3.4.6-1", "1:27.3.4.10-1"),
+        Ordering::Less
+    );
+    assert_eq!(compare_versions("1:26.2.5-1", "27.0-1"), Ordering::Greater);
+}
+
+#[test]
+fn test_comparison_is_antisymmetric() {
+    let versions = [
+        "1.0~rc1", "1.0", "1.0-1", "1:0.9", "1.0+b1", "1.0.1", "2.0~~",
+    ];
+    for a in versions {
+        for b in versions {
+            assert_eq!(compare_versions(a, b), compare_versions(b, a).reverse());
+        }
+    }
+}
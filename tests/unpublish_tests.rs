@@ -0,0 +1,147 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use assert_cmd::cargo;
+use std::error::Error;
+use std::process::{Command, Stdio};
+use test_helpers::*;
+
+const PUBLISHED: &str = "  * rabbitmq-server/debian/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}";
+
+fn drops(stub: &AptlyStub) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("publish drop"))
+        .collect())
+}
+
+#[test]
+fn test_unpublish_drops_the_publication() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    ctx.create_repo("repo-rabbitmq-server-bookworm")?;
+
+    let package_path = test_package_path("rabbitmq-server_4.1.3-1_all.deb");
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(["rabbitmq", "deb", "add", "-d", "bookworm", "-p"])
+        .arg(&package_path);
+    cmd.assert().success();
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(["rabbitmq", "deb", "publish", "-d", "bookworm"]);
+    cmd.assert().success();
+    assert!(ctx.is_published("rabbitmq-server", "debian", "bookworm")?);
+
+    let mut cmd = Command::new(cargo::cargo_bin!("bellhop"));
+    cmd.env("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    cmd.args(["rabbitmq", "deb", "unpublish", "-d", "bookworm", "--yes"]);
+    cmd.assert().success();
+
+    assert!(
+        !ctx.is_published("rabbitmq-server", "debian", "bookworm")?,
+        "Bookworm should no longer be published"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unpublish_skips_distributions_that_are_not_published() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq",
+            "deb",
+            "unpublish",
+            "-d",
+            "bookworm,noble",
+            "--yes",
+        ])
+        .assert()
+        .success()
+        .stderr(output_includes("'noble' is not published"));
+
+    assert_eq!(
+        drops(&stub)?,
+        ["publish drop bookworm rabbitmq-server/debian/bookworm"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unpublish_requires_confirmation() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(&format!(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '{PUBLISHED}'; fi"
+    ))?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "unpublish", "-d", "bookworm"])
+        .stdin(Stdio::null())
+        .assert()
+        .code(64)
+        .stderr(output_includes("pass --yes"));
+
+    assert!(drops(&stub)?.is_empty(), "Nothing should be dropped");
+    Ok(())
+}
+
+#[test]
+fn test_unpublish_drops_a_publication_with_a_custom_prefix() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        r#"if [ "$1 $2" = "publish list" ]; then
+  echo '  * hotfix/stable [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}'
+  echo '  * hotfix/testing [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}'
+fi
+"#,
+    )?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "unpublish", "-d", "bookworm", "--yes"])
+        .args(["--publish-prefix", "hotfix"])
+        .args(["--distribution-name", "bookworm=stable"])
+        .args(["--also-distribution", "testing"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        drops(&stub)?,
+        ["publish drop stable hotfix", "publish drop testing hotfix"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unpublish_drops_a_publication_on_an_endpoint() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::with_script(
+        "if [ \"$1 $2\" = \"publish list\" ]; then echo '  * s3:mirror:rabbitmq-server/ubuntu/bookworm/bookworm [amd64] publishes {main: [snap-rabbitmq-server-bookworm-v1]}'; fi",
+    )?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "unpublish", "-d", "bookworm", "--yes"])
+        .args(["--publish-endpoint", "mirror", "--family", "ubuntu"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        drops(&stub)?,
+        ["publish drop bookworm s3:mirror:rabbitmq-server/ubuntu/bookworm"]
+    );
+    Ok(())
+}
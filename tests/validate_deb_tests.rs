@@ -0,0 +1,62 @@
+// Copyright (C) 2025-2026 Michael S. Klishin and Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+mod test_helpers;
+
+use assert_cmd::assert::OutputAssertExt;
+use std::error::Error;
+use std::fs;
+use tempfile::TempDir;
+use test_helpers::*;
+
+#[test]
+fn test_a_bogus_deb_is_rejected_before_anything_is_added() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let bogus = temp_dir.path().join("rabbitmq-server_4.1.4-1_all.deb");
+    fs::write(&bogus, "<html><body>404 Not Found</body></html>")?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args([
+            "rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1", "-p",
+        ])
+        .arg(test_package_path("rabbitmq-server_4.1.3-1_all.deb"))
+        .arg(&bogus)
+        .assert()
+        .code(65)
+        .stderr(output_includes(
+            "Not a valid Debian package (not an ar archive)",
+        ))
+        .stderr(output_includes("rabbitmq-server_4.1.4-1_all.deb"));
+
+    assert!(stub.invocations_starting_with("repo add")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_no_validate_hands_the_file_to_aptly() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let bogus = temp_dir.path().join("rabbitmq-server_4.1.4-1_all.deb");
+    fs::write(&bogus, "<html><body>404 Not Found</body></html>")?;
+    let stub = AptlyStub::new()?;
+
+    stub.bellhop_command()
+        .args(["rabbitmq", "deb", "add", "-d", "bookworm", "--suffix", "v1"])
+        .args(["--no-validate", "-p"])
+        .arg(&bogus)
+        .assert()
+        .success();
+
+    assert_eq!(stub.invocations_starting_with("repo add")?.len(), 1);
+    Ok(())
+}
@@ -13,12 +13,14 @@
 // limitations under the License.
 mod test_helpers;
 
+use assert_cmd::assert::OutputAssertExt;
 use bellhop::common::Project;
 use bellhop::deb::DistributionAlias;
-use bellhop::watcher;
+use bellhop::watcher::{self, WatchOptions, WatchedDirectories};
 use std::env;
 use std::error::Error;
 use std::fs;
+use std::process::Stdio;
 use std::thread;
 use std::time::{Duration, Instant};
 use tempfile::TempDir;
@@ -28,7 +30,8 @@ use test_helpers::*;
 fn test_watch_help() -> Result<(), Box<dyn Error>> {
     run_bellhop_succeeds(["watch", "--help"])
         .stdout(output_includes("Watch directories for .deb files"))
-        .stdout(output_includes("--root"));
+        .stdout(output_includes("--root"))
+        .stdout(output_includes("--watch-removals"));
     Ok(())
 }
 
@@ -69,6 +72,32 @@ fn test_project_for_directory_unknown() {
     assert_eq!(watcher::project_for_directory(""), None);
 }
 
+#[test]
+fn test_watched_directories_default_to_the_built_in_ones() {
+    let directories = WatchedDirectories::default();
+    assert_eq!(
+        directories.names().collect::<Vec<_>>(),
+        ["rabbitmq-server", "rabbitmq-erlang", "rabbitmq-cli"]
+    );
+    assert_eq!(
+        directories.project_for("rabbitmq-erlang"),
+        Some(Project::Erlang)
+    );
+}
+
+#[test]
+fn test_watched_directories_with_a_custom_mapping() {
+    let directories = WatchedDirectories::new([
+        ("server".to_string(), Project::RabbitMQ),
+        ("otp".to_string(), Project::CliTools),
+        ("otp".to_string(), Project::Erlang),
+    ]);
+    assert_eq!(directories.names().collect::<Vec<_>>(), ["server", "otp"]);
+    assert_eq!(directories.project_for("server"), Some(Project::RabbitMQ));
+    assert_eq!(directories.project_for("otp"), Some(Project::Erlang));
+    assert_eq!(directories.project_for("rabbitmq-server"), None);
+}
+
 #[test]
 fn test_releases_for_project_filters_erlang() {
     let all = DistributionAlias::all().to_vec();
@@ -100,7 +129,14 @@ fn test_watch_creates_subdirectories() -> Result<(), Box<dyn Error>> {
 
     let dists = vec![DistributionAlias::Bookworm];
 
-    watcher::watch_directory(&watch_root, &dists, Some(0))?;
+    watcher::watch_directory(
+        &watch_root,
+        &dists,
+        WatchOptions {
+            max_events: Some(0),
+            ..WatchOptions::default()
+        },
+    )?;
 
     assert!(watch_root.join("rabbitmq-server").exists());
     assert!(watch_root.join("rabbitmq-erlang").exists());
@@ -127,7 +163,14 @@ fn test_watch_imports_deb_on_create() -> Result<(), Box<dyn Error>> {
         unsafe {
             env::set_var("APTLY_CONFIG", config_path.to_str().unwrap());
         }
-        watcher::watch_directory(&watch_root_clone, &dists, Some(1))
+        watcher::watch_directory(
+            &watch_root_clone,
+            &dists,
+            WatchOptions {
+                max_events: Some(1),
+                ..WatchOptions::default()
+            },
+        )
     });
 
     thread::sleep(Duration::from_millis(500));
@@ -157,3 +200,335 @@ fn test_watch_imports_deb_on_create() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn wait_for_watcher(handle: &thread::JoinHandle<Result<(), bellhop::errors::BellhopError>>) {
+    let timeout = Duration::from_secs(10);
+    let start = Instant::now();
+    while !handle.is_finished() {
+        if start.elapsed() > timeout {
+            panic!("Watcher thread did not finish within timeout");
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_watch_removes_deb_on_delete() -> Result<(), Box<dyn Error>> {
+    let ctx = AptlyTestContext::new()?;
+    let watch_root = ctx.temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_root)?;
+
+    let repo_name = "repo-rabbitmq-server-bookworm";
+    ctx.create_repo(repo_name)?;
+    unsafe {
+        env::set_var("APTLY_CONFIG", ctx.config_path.to_str().unwrap());
+    }
+
+    let dest = watch_root
+        .join("rabbitmq-server")
+        .join("rabbitmq-server_4.1.3-1_all.deb");
+
+    let root = watch_root.clone();
+    let handle = thread::spawn(move || {
+        watcher::watch_directory(
+            &root,
+            &[DistributionAlias::Bookworm],
+            WatchOptions {
+                watch_removals: true,
+                max_events: Some(1),
+                ..WatchOptions::default()
+            },
+        )
+    });
+    thread::sleep(Duration::from_millis(500));
+    fs::copy(test_package_path("rabbitmq-server_4.1.3-1_all.deb"), &dest)?;
+    wait_for_watcher(&handle);
+    handle.join().unwrap()?;
+    assert!(ctx.package_exists(repo_name, "rabbitmq-server")?);
+
+    let root = watch_root.clone();
+    let handle = thread::spawn(move || {
+        watcher::watch_directory(
+            &root,
+            &[DistributionAlias::Bookworm],
+            WatchOptions {
+                watch_removals: true,
+                max_events: Some(1),
+                ..WatchOptions::default()
+            },
+        )
+    });
+    thread::sleep(Duration::from_millis(500));
+    fs::remove_file(&dest)?;
+    wait_for_watcher(&handle);
+    handle.join().unwrap()?;
+
+    assert!(!ctx.package_exists(repo_name, "rabbitmq-server")?);
+    Ok(())
+}
+
+fn wait_for_invocation(
+    stub: &AptlyStub,
+    prefix: &str,
+    timeout: Duration,
+) -> Result<bool, Box<dyn Error>> {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with(prefix))
+        {
+            return Ok(true);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    Ok(false)
+}
+
+#[test]
+fn test_watch_takes_one_snapshot_for_files_dropped_together() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let watch_root = temp_dir.path().join("watch");
+
+    let mut child = stub
+        .bellhop_command()
+        .args(["watch", "-d", "bookworm", "--root"])
+        .arg(&watch_root)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    thread::sleep(Duration::from_millis(500));
+
+    let debs = [
+        "rabbitmq-server_4.1.3-1_all.deb",
+        "rabbitmq-server_4.1.4-1_all.deb",
+    ];
+    for deb in debs {
+        fs::copy(
+            test_package_path(deb),
+            watch_root.join("rabbitmq-server").join(deb),
+        )?;
+    }
+
+    let snapshot_taken = wait_for_invocation(&stub, "snapshot create", Duration::from_secs(10))?;
+    // long enough for a second quiet period to pass
+    thread::sleep(Duration::from_secs(3));
+    child.kill()?;
+    child.wait()?;
+    assert!(snapshot_taken, "no snapshot was taken");
+
+    let invocations = stub.invocations()?;
+    let snapshots: Vec<usize> = invocations
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.starts_with("snapshot create"))
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(snapshots.len(), 1, "{invocations:?}");
+    assert!(invocations[snapshots[0]].contains("repo-rabbitmq-server-bookworm"));
+    for deb in debs {
+        assert!(
+            invocations[..snapshots[0]]
+                .iter()
+                .any(|line| line.starts_with("repo add") && line.ends_with(deb)),
+            "{deb} was not added before the snapshot was taken: {invocations:?}"
+        );
+    }
+    assert!(
+        !invocations
+            .iter()
+            .any(|line| line.starts_with("publish snapshot") || line.starts_with("publish switch")),
+        "{invocations:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_watch_removes_only_the_architecture_of_the_deleted_file() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let watch_root = temp_dir.path().join("watch");
+    let server_dir = watch_root.join("rabbitmq-server");
+    fs::create_dir_all(&server_dir)?;
+    for deb in [
+        "rabbitmq-server_4.1.3-1_amd64.deb",
+        "rabbitmq-server_4.1.3-1_arm64.deb",
+    ] {
+        fs::write(server_dir.join(deb), minimal_deb())?;
+    }
+
+    let mut child = stub
+        .bellhop_command()
+        .args(["watch", "-d", "bookworm", "--watch-removals", "--root"])
+        .arg(&watch_root)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    thread::sleep(Duration::from_millis(500));
+
+    fs::remove_file(server_dir.join("rabbitmq-server_4.1.3-1_amd64.deb"))?;
+    let removed = wait_for_invocation(&stub, "repo remove", Duration::from_secs(10))?;
+    child.kill()?;
+    child.wait()?;
+    assert!(removed, "{:?}", stub.invocations()?);
+
+    let removals: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo remove"))
+        .collect();
+    assert_eq!(
+        removals,
+        [
+            "repo remove repo-rabbitmq-server-bookworm Name (= rabbitmq-server), Version (= 4.1.3-1), $Architecture (= amd64)"
+        ]
+    );
+    assert!(
+        server_dir
+            .join("rabbitmq-server_4.1.3-1_arm64.deb")
+            .exists()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_watch_publishes_refreshed_snapshots() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let watch_root = temp_dir.path().join("watch");
+
+    let mut child = stub
+        .bellhop_command()
+        .args(["watch", "-d", "bookworm", "--watch-publish", "--root"])
+        .arg(&watch_root)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    thread::sleep(Duration::from_millis(500));
+
+    let deb = "rabbitmq-server_4.1.3-1_all.deb";
+    fs::copy(
+        test_package_path(deb),
+        watch_root.join("rabbitmq-server").join(deb),
+    )?;
+
+    let published = wait_for_invocation(&stub, "publish snapshot", Duration::from_secs(10))?;
+    child.kill()?;
+    child.wait()?;
+    assert!(published, "{:?}", stub.invocations()?);
+    Ok(())
+}
+
+#[test]
+fn test_watch_once_imports_existing_files_and_exits() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let watch_root = temp_dir.path().join("watch");
+    let server_dir = watch_root.join("rabbitmq-server");
+    fs::create_dir_all(&server_dir)?;
+    let deb = "rabbitmq-server_4.1.3-1_all.deb";
+    fs::copy(test_package_path(deb), server_dir.join(deb))?;
+
+    stub.bellhop_command()
+        .args(["watch", "-d", "bookworm", "--once", "--root"])
+        .arg(&watch_root)
+        .assert()
+        .success();
+
+    let invocations = stub.invocations()?;
+    let added = invocations
+        .iter()
+        .position(|line| line.starts_with("repo add") && line.ends_with(deb));
+    let snapshot = invocations.iter().position(|line| {
+        line.starts_with("snapshot create") && line.contains("repo-rabbitmq-server-bookworm")
+    });
+    assert!(added.is_some(), "{invocations:?}");
+    assert!(snapshot.is_some(), "{invocations:?}");
+    assert!(added < snapshot, "{invocations:?}");
+    assert!(watch_root.join("rabbitmq-erlang").exists());
+    Ok(())
+}
+
+#[test]
+fn test_watch_once_with_empty_directories_does_nothing() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let watch_root = temp_dir.path().join("watch");
+
+    stub.bellhop_command()
+        .args(["watch", "-d", "bookworm", "--once", "--root"])
+        .arg(&watch_root)
+        .assert()
+        .success();
+
+    assert!(
+        !stub
+            .invocations()?
+            .iter()
+            .any(|line| line.starts_with("repo add") || line.starts_with("snapshot create"))
+    );
+    assert!(watch_root.join("rabbitmq-server").exists());
+    Ok(())
+}
+
+#[test]
+fn test_watch_map_imports_from_custom_directories() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+    let watch_root = temp_dir.path().join("watch");
+    let packages = [
+        ("server-nightly", "rabbitmq-server_4.1.3-1_all.deb"),
+        ("otp", "erlang-base_27.3.4.6-1_amd64.deb"),
+        ("rabbitmq-server", "rabbitmq-server_4.1.4-1_all.deb"),
+    ];
+    for (dir, deb) in packages {
+        fs::create_dir_all(watch_root.join(dir))?;
+        fs::copy(test_package_path(deb), watch_root.join(dir).join(deb))?;
+    }
+
+    stub.bellhop_command()
+        .args(["watch", "-d", "bookworm", "--once"])
+        .args(["--watch-map", "server-nightly=rabbitmq"])
+        .args(["--watch-map", "otp=erlang", "--root"])
+        .arg(&watch_root)
+        .assert()
+        .success();
+
+    let adds: Vec<String> = stub
+        .invocations()?
+        .into_iter()
+        .filter(|line| line.starts_with("repo add"))
+        .collect();
+    assert_eq!(adds.len(), 2, "{adds:?}");
+    assert!(
+        adds.iter()
+            .any(|line| line.contains(" repo-rabbitmq-server-bookworm ")
+                && line.ends_with(packages[0].1)),
+        "{adds:?}"
+    );
+    assert!(
+        adds.iter()
+            .any(|line| line.contains(" repo-rabbitmq-erlang-bookworm ")
+                && line.ends_with(packages[1].1)),
+        "{adds:?}"
+    );
+    assert!(!watch_root.join("rabbitmq-erlang").exists());
+    Ok(())
+}
+
+#[test]
+fn test_watch_map_rejects_an_unknown_project() -> Result<(), Box<dyn Error>> {
+    let stub = AptlyStub::new()?;
+    let temp_dir = TempDir::new()?;
+
+    stub.bellhop_command()
+        .args(["watch", "-d", "bookworm", "--once"])
+        .args(["--watch-map", "server=rabbitmq-server", "--root"])
+        .arg(temp_dir.path())
+        .assert()
+        .code(64);
+    assert!(stub.invocations()?.is_empty());
+    Ok(())
+}